@@ -50,7 +50,9 @@ pub struct MiningStats {
 	rate: f64,
 }
 
+mod loan;
 mod task;
+pub use loan::{Loan, LoanApiServer};
 pub use task::{Task, TaskApiServer};
 
 #[cfg(test)]
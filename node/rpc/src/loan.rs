@@ -0,0 +1,80 @@
+use super::Error;
+use core::marker::PhantomData;
+use creditcoin_node_runtime::{DealOrderId, LoanBalance};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits;
+use std::sync::Arc;
+use task_scheduler_runtime_api::CreditcoinApi;
+
+use jsonrpsee::{
+	core::{async_trait, Error as JsonRpseeError, RpcResult},
+	proc_macros::rpc,
+	types::{
+		error::{CallError, ErrorCode},
+		ErrorObject,
+	},
+};
+
+#[rpc(client, server)]
+pub trait LoanApi<BlockHash> {
+	#[method(name = "creditcoin_loanBalance")]
+	async fn loan_balance(
+		&self,
+		deal_order_id: DealOrderId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<LoanBalance>>;
+}
+
+pub struct Loan<C, B> {
+	client: Arc<C>,
+	_p: PhantomData<B>,
+}
+
+impl<C, B> Loan<C, B> {
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _p: Default::default() }
+	}
+}
+
+#[async_trait]
+impl<C, B> LoanApiServer<B::Hash> for Loan<C, B>
+where
+	C: sp_api::ProvideRuntimeApi<B>,
+	C: HeaderBackend<B>,
+	C: Send + Sync + 'static,
+	C::Api: CreditcoinApi<B, DealOrderId, LoanBalance>,
+	B: traits::Block,
+{
+	async fn loan_balance(
+		&self,
+		deal_order_id: DealOrderId,
+		at: Option<B::Hash>,
+	) -> RpcResult<Option<LoanBalance>> {
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		api.loan_balance(at, deal_order_id).map_err(|e| {
+			JsonRpseeError::Call(CallError::Custom(ErrorObject::owned(
+				ErrorCode::ServerError(Error::RuntimeError.into()).code(),
+				"Unable to query loan balance.",
+				Some(format!("{e:?}")),
+			)))
+		})
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+	use super::*;
+	use creditcoin_node_runtime::{Block, Hash, Runtime};
+
+	#[tokio::test]
+	async fn loan_balance_should_be_none_for_unknown_deal_order() {
+		let client = Arc::new(test_client::new());
+		let l = Loan::<_, Block>::new(client);
+
+		let deal_order_id = DealOrderId::with_expiration_hash::<Runtime>(1, Hash::zero());
+
+		assert_eq!(l.loan_balance(deal_order_id, None).await.unwrap(), None);
+	}
+}
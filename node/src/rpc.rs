@@ -7,7 +7,7 @@
 
 use std::sync::Arc;
 
-use creditcoin_node_runtime::{opaque::Block, AccountId, Balance, Index};
+use creditcoin_node_runtime::{opaque::Block, AccountId, Balance, DealOrderId, Index, LoanBalance};
 use jsonrpsee::RpcModule;
 pub use sc_rpc_api::DenyUnsafe;
 use sc_transaction_pool_api::TransactionPool;
@@ -39,9 +39,12 @@ where
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
 	C::Api: BlockBuilder<Block>,
 	C::Api: task_scheduler_runtime_api::TaskApi<Block, AccountId>,
+	C::Api: task_scheduler_runtime_api::CreditcoinApi<Block, DealOrderId, LoanBalance>,
 	P: TransactionPool + 'static,
 {
-	use creditcoin_node_rpc::{Creditcoin, CreditcoinApiServer, Task, TaskApiServer};
+	use creditcoin_node_rpc::{
+		Creditcoin, CreditcoinApiServer, Loan, LoanApiServer, Task, TaskApiServer,
+	};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
 	use substrate_frame_rpc_system::{System, SystemApiServer};
 
@@ -51,6 +54,7 @@ where
 	module.merge(System::new(client.clone(), pool, deny_unsafe).into_rpc())?;
 	module.merge(TransactionPayment::new(client.clone()).into_rpc())?;
 	module.merge(Creditcoin::new(mining_metrics).into_rpc())?;
+	module.merge(Loan::new(client.clone()).into_rpc())?;
 	module.merge(Task::new(client, deny_unsafe).into_rpc())?;
 
 	Ok(module)
@@ -10,11 +10,11 @@ use crate::{
 	types::{Blockchain, OwnershipProof},
 	Duration,
 };
-use crate::{AskOrderId, InterestRate, InterestType, LoanTerms};
+use crate::{AskOrderId, InterestRate, InterestType, LoanCurrency, LoanTerms};
 use frame_benchmarking::{account, benchmarks, whitelist_account, Zero};
 use frame_support::{
 	pallet_prelude::*,
-	traits::{Currency, Get},
+	traits::{Currency, Get, ReservableCurrency},
 };
 use frame_system::pallet_prelude::*;
 use frame_system::Config as SystemConfig;
@@ -72,15 +72,21 @@ benchmarks! {
 
 	on_initialize {
 		//insert a askorders
-		let a in 0..255;
+		let a in 0..(MAX_EXPIRY_SWEEP_ENTRIES / 7);
 		//insert b bidorders
-		let b in 0..255;
+		let b in 0..(MAX_EXPIRY_SWEEP_ENTRIES / 7);
 		//insert o offers
-		let o in 0..255;
+		let o in 0..(MAX_EXPIRY_SWEEP_ENTRIES / 7);
 		//insert d dealorders
-		let d in 0..255;
+		let d in 0..(MAX_EXPIRY_SWEEP_ENTRIES / 7);
 		//insert f fundedorders
-		let f in 0..255;
+		let f in 0..(MAX_EXPIRY_SWEEP_ENTRIES / 7);
+		//insert p terms proposals
+		let p in 0..(MAX_EXPIRY_SWEEP_ENTRIES / 7);
+		//insert r repayment orders
+		let r in 0..(MAX_EXPIRY_SWEEP_ENTRIES / 7);
+		//sweep s expiration blocks, the entries expire in the last one
+		let s in 1..MAX_EXPIRY_SWEEP_BLOCKS;
 
 		<Timestamp<T>>::set_timestamp(1u32.into());
 
@@ -89,7 +95,11 @@ benchmarks! {
 
 		let terms = get_all_fit_terms();
 
-		let expiration_block = T::BlockNumber::one();
+		let expiration_block: T::BlockNumber = s.into();
+		crate::ExpiryCursor::<T>::put(ExpirySweep {
+			block: T::BlockNumber::one(),
+			last_deal_order: None,
+		});
 		//generate this many filler asks
 		for i in 0..a {
 			insert_fake_ask::<T>(&borrower, expiration_block, i);
@@ -102,16 +112,44 @@ benchmarks! {
 		for i in 0..o {
 			insert_fake_offer::<T>(&lender, expiration_block, i);
 		}
-		//generate this many matching deals,offers,asks,bids
+		//generate this many matching deals,offers,asks,bids, each one syndicated
 		for i in 0..d {
 			insert_fake_deal::<T>(&lender, expiration_block, DealKind::Unfunded, i);
+			syndicate_fake_deal::<T>(expiration_block, i);
 		}
 		//generate this many matching funded_deals with its deal,transfer,offer,ask and bid.
 		for i in d..d + f{
 			insert_fake_deal::<T>(&lender, expiration_block, DealKind::Funded, i);
 		}
+		//generate this many terms proposals
+		for i in 0..p {
+			insert_fake_terms_proposal::<T>(&lender, expiration_block, i);
+		}
+		//generate this many repayment orders
+		for i in 0..r {
+			insert_fake_repayment_order::<T>(&borrower, expiration_block, i);
+		}
+
+	// the other steps of the hook are benchmarked on their own
+	}: { Creditcoin::<T>::sweep_expired_entries(expiration_block) }
+	verify {}
+
+	mark_defaulted_deal_orders {
+		let d in 0..MAX_DEFAULT_CHECKS_PER_BLOCK;
+
+		<Timestamp<T>>::set_timestamp(1u32.into());
+
+		// every deal order has its own borrower, so each default updates another credit record
+		let expiration_block = T::BlockNumber::one();
+		for i in 0..d {
+			let borrower: T::AccountId = account("borrower", i, 0);
+			insert_fake_deal::<T>(&borrower, expiration_block, DealKind::Funded, i);
+		}
 
-	}: { Creditcoin::<T>::on_initialize(expiration_block) }
+		// every funded deal order is past its term
+		<Timestamp<T>>::set_timestamp(1_000_000u32.into());
+
+	}: { Creditcoin::<T>::mark_defaulted_deal_orders() }
 	verify {}
 
 	register_address {
@@ -151,16 +189,21 @@ benchmarks! {
 
 	add_ask_order {
 		<Timestamp<T>>::set_timestamp(1u32.into());
+		crate::OrderMatchingEnabled::<T>::put(true);
+		fill_order_books::<T>();
 		let who:T::AccountId = lender_account::<T>(true);
 		let terms = get_all_fit_terms();
 		let expiration_block = T::BlockNumber::one();
 
 		let (address_id,ask_id,guid) = generate_ask::<T>(&who,&terms,&expiration_block,false,0).unwrap();
+		let terms = AskTerms::try_from(terms).unwrap();
 
 	}: _(RawOrigin::Signed(who),address_id,terms,expiration_block,guid.into_bounded())
 
 	add_bid_order {
 		<Timestamp<T>>::set_timestamp(1u32.into());
+		crate::OrderMatchingEnabled::<T>::put(true);
+		fill_order_books::<T>();
 		let who:T::AccountId = borrower_account::<T>(true);
 
 		let loan_terms = get_all_fit_terms();
@@ -168,11 +211,13 @@ benchmarks! {
 		let expiration_block = T::BlockNumber::one();
 
 		let (address_id,bid_id,guid) = generate_bid::<T>(&who,&loan_terms,&expiration_block,false,0).unwrap();
+		let terms = BidTerms::try_from(loan_terms).unwrap();
 
-	}:_(RawOrigin::Signed(who),address_id,loan_terms,expiration_block,guid.into_bounded())
+	}:_(RawOrigin::Signed(who),address_id,terms,expiration_block,guid.into_bounded())
 
 	add_offer {
 		<Timestamp<T>>::set_timestamp(1u32.into());
+		fill_order_books::<T>();
 		let lender: T::AccountId = lender_account::<T>(true);
 		let loan_terms = get_all_fit_terms();
 		let expiration_block = T::BlockNumber::one();
@@ -225,6 +270,14 @@ benchmarks! {
 		let deal_id = generate_deal::<T>(true,0u8).unwrap();
 		let (transfer_id,_) = generate_transfer::<T>(deal_id.clone(),true,false,0u8);
 
+		let fee = <Balances<T> as Currency<T::AccountId>>::minimum_balance();
+		<Balances<T> as Currency<T::AccountId>>::make_free_balance_be(&lender, fee + fee + fee);
+		crate::OriginationFeeParameters::<T>::put(crate::OriginationFee {
+			rate: crate::OriginationFeeRate::Flat(fee),
+			payer: crate::FeePayer::Lender,
+			collector: account("collector", 1, 1),
+		});
+
 	}: _(RawOrigin::Signed(lender), deal_id, transfer_id)
 
 	lock_deal_order {
@@ -254,6 +307,10 @@ benchmarks! {
 		<Timestamp<T>>::set_timestamp(1u32.into());
 		let borrower: T::AccountId = borrower_account::<T>(true);
 		let (deal_id,_) = generate_locked_deal::<T>(true).unwrap();
+		crate::DealOrders::<T>::mutate(deal_id.expiration(), deal_id.hash(), |deal_order| {
+			let deal_order = deal_order.as_mut().unwrap();
+			deal_order.repaid_amount = deal_order.amount_owed(Creditcoin::<T>::timestamp());
+		});
 		let (transfer_id, _) = generate_transfer::<T>(deal_id.clone(),true,true,0u8);
 
 	}: _(RawOrigin::Signed(borrower),deal_id,transfer_id)
@@ -261,8 +318,9 @@ benchmarks! {
 	exempt {
 		<Timestamp<T>>::set_timestamp(1u32.into());
 		let lender = lender_account::<T>(true);
-		let deal_id = generate_deal::<T>(true,0u8).unwrap();
-	}:_(RawOrigin::Signed(lender),deal_id)
+		let (deal_id,_) = generate_funded_deal::<T>(true,0u8).unwrap();
+		let reason = crate::ExemptionReason::try_from([b'x'; 256].to_vec()).unwrap();
+	}:_(RawOrigin::Signed(lender),deal_id,None,Some(reason))
 
 	register_deal_order {
 		<Timestamp<T>>::set_timestamp(1u32.into());
@@ -348,12 +406,333 @@ benchmarks! {
 		let signature = ecdsa_sign(ktypeid, &pkey, &message).expect("ecdsa signature");
 		let proof = OwnershipProof::EthSign(signature);
 	}: _(RawOrigin::Signed(who), Blockchain::Ethereum, address, proof)
+
+	cancel_ask_order {
+		<Timestamp<T>>::set_timestamp(1u32.into());
+		fill_order_books::<T>();
+		let who: T::AccountId = lender_account::<T>(true);
+		let terms = get_all_fit_terms();
+		let expiration_block = T::BlockNumber::one();
+
+		let (_, ask_id, _) = generate_ask::<T>(&who, &terms, &expiration_block, true, 0).unwrap();
+
+	}: _(RawOrigin::Signed(who), ask_id)
+
+	cancel_bid_order {
+		<Timestamp<T>>::set_timestamp(1u32.into());
+		fill_order_books::<T>();
+		let who: T::AccountId = borrower_account::<T>(true);
+		let terms = get_all_fit_terms();
+		let expiration_block = T::BlockNumber::one();
+
+		let (_, bid_id, _) = generate_bid::<T>(&who, &terms, &expiration_block, true, 0).unwrap();
+
+	}: _(RawOrigin::Signed(who), bid_id)
+
+	cancel_offer {
+		<Timestamp<T>>::set_timestamp(1u32.into());
+		let lender: T::AccountId = lender_account::<T>(true);
+		let loan_terms = get_all_fit_terms();
+		let expiration_block = T::BlockNumber::one();
+
+		let (offer_id, _, _) = generate_offer::<T>(&lender, &loan_terms, &expiration_block, true, 0u8).unwrap();
+
+	}: _(RawOrigin::Signed(lender), offer_id)
+
+	transfer_deal_order {
+		<Timestamp<T>>::set_timestamp(1u32.into());
+		let lender: T::AccountId = lender_account::<T>(true);
+		let (deal_id, _) = generate_funded_deal::<T>(true, 0u8).unwrap();
+
+		let ktypeid = KeyTypeId(*b"dumy");
+		let pkey = ecdsa_generate(ktypeid, None);
+		let new_lender = T::Signer::from(pkey).into_account();
+		let new_lender_addr_id = register_eth_addr::<T>(&new_lender, "new_lender");
+
+		let lender_addr_id = Creditcoin::<T>::deal_orders(deal_id.expiration(), deal_id.hash()).unwrap().lender_address_id;
+		let payload = crate::helpers::transfer_deal_order_message::<T>(&deal_id, &lender_addr_id, &new_lender_addr_id, 0);
+		let signature = ecdsa_sign(ktypeid, &pkey, &payload[..]).expect("ecdsa signature");
+
+	}: _(RawOrigin::Signed(lender), deal_id, new_lender_addr_id, pkey.into(), signature.into())
+
+	claim_collateral {
+		<Timestamp<T>>::set_timestamp(1u32.into());
+		let lender: T::AccountId = lender_account::<T>(true);
+		let borrower: T::AccountId = borrower_account::<T>(false);
+		let (deal_id, _) = generate_funded_deal::<T>(true, 0u8).unwrap();
+
+		let collateral = <Balances<T> as Currency<T::AccountId>>::minimum_balance();
+		<Balances<T> as Currency<T::AccountId>>::make_free_balance_be(&borrower, collateral + collateral);
+		<Balances<T> as ReservableCurrency<T::AccountId>>::reserve(&borrower, collateral).unwrap();
+		crate::DealOrderCollateral::<T>::insert(deal_id.expiration(), deal_id.hash(), collateral);
+
+		// the loan is past its term
+		<Timestamp<T>>::set_timestamp(1_000_000u32.into());
+
+	}: _(RawOrigin::Signed(lender), deal_id)
+
+	set_order_matching {
+		let root = RawOrigin::Root;
+	}: _(root, true)
+
+	match_pending_orders {
+		let m in 1..MAX_MATCHES_PER_BLOCK;
+		let c in 0..MAX_MATCH_CANDIDATES;
+
+		<Timestamp<T>>::set_timestamp(1u32.into());
+		crate::OrderMatchingEnabled::<T>::put(true);
+
+		// the candidates belong to the borrower, so every one of them is read and none matches
+		let borrower = borrower_account::<T>(false);
+		let expiration_block = 1_000u32.into();
+		let terms = get_all_fit_terms();
+		for i in 0..c {
+			insert_fake_ask::<T>(&borrower, expiration_block, i);
+			crate::AskOrderBook::<T>::try_append(
+				Blockchain::Ethereum,
+				&terms.currency,
+				(terms.interest_rate.clone(), fake_ask_id::<T>(i, expiration_block)),
+			).unwrap();
+		}
+
+		// only the first pending bid order is in the currency of the candidates
+		for i in 0..m {
+			insert_fake_bid::<T>(&borrower, expiration_block, i);
+			let bid_id = fake_bid_id::<T>(i, expiration_block);
+			let mut bid = crate::BidOrders::<T>::try_get_id(&bid_id).unwrap();
+			if i > 0 {
+				bid.terms.currency = LoanCurrency::Native;
+				crate::BidOrders::<T>::insert_id(bid_id.clone(), bid.clone());
+			}
+			Creditcoin::<T>::enlist_bid_order(&bid_id, &bid);
+		}
+
+	}: { Creditcoin::<T>::match_pending_orders() }
+	verify {
+		assert!(crate::PendingMatches::<T>::get().is_empty());
+	}
+
+	propose_terms {
+		<Timestamp<T>>::set_timestamp(1u32.into());
+		let lender: T::AccountId = lender_account::<T>(true);
+		let (deal_id, _) = generate_funded_deal::<T>(true, 0u8).unwrap();
+		let terms = LoanTerms { term_length: Duration::new(2u64, 0u32), ..get_all_fit_terms() };
+		let expiration_block = 1_000u32.into();
+
+	}: _(RawOrigin::Signed(lender), deal_id, terms, expiration_block)
+
+	accept_terms {
+		<Timestamp<T>>::set_timestamp(1u32.into());
+		let lender: T::AccountId = lender_account::<T>(true);
+		let borrower: T::AccountId = borrower_account::<T>(true);
+		let (deal_id, _) = generate_funded_deal::<T>(true, 0u8).unwrap();
+		let terms = LoanTerms { term_length: Duration::new(2u64, 0u32), ..get_all_fit_terms() };
+		let expiration_block = 1_000u32.into();
+		let proposal_id = TermsProposalId::new::<T>(expiration_block, &deal_id, &terms);
+		Creditcoin::<T>::propose_terms(
+			RawOrigin::Signed(lender).into(),
+			deal_id,
+			terms,
+			expiration_block,
+		)
+		.unwrap();
+
+	}: _(RawOrigin::Signed(borrower), proposal_id)
+
+	add_deal_order_share {
+		<Timestamp<T>>::set_timestamp(1u32.into());
+		let lender: T::AccountId = lender_account::<T>(true);
+		let deal_id = generate_deal::<T>(true, 0u8).unwrap();
+		let deal = Creditcoin::<T>::deal_orders(deal_id.expiration(), deal_id.hash()).unwrap();
+
+		// the deal order already has every lender but one
+		crate::DealOrderShares::<T>::insert(
+			&deal_id,
+			&deal.lender_address_id,
+			LenderShare::new(ExternalAmount::from(1_000u64)),
+		);
+		for i in 2..MAX_LENDERS_PER_DEAL {
+			crate::DealOrderShares::<T>::insert(
+				&deal_id,
+				fake_address_id::<T>(i),
+				LenderShare::new(ExternalAmount::from(1u64)),
+			);
+		}
+
+		let ktypeid = KeyTypeId(*b"dumy");
+		let pkey = ecdsa_generate(ktypeid, None);
+		let co_lender = T::Signer::from(pkey).into_account();
+		let co_lender_addr_id = register_eth_addr::<T>(&co_lender, "co_lender");
+		let amount = ExternalAmount::from(1u64);
+
+		let payload = {
+			deal_id.encode().into_iter()
+				.chain(co_lender_addr_id.encode())
+				.chain(amount.encode())
+				.collect::<Vec<u8>>()
+		};
+		let signature = ecdsa_sign(ktypeid, &pkey, &payload[..]).expect("ecdsa signature");
+
+	}: _(RawOrigin::Signed(lender), deal_id, co_lender_addr_id, amount, pkey.into(), signature.into())
+
+	register_share_repayment_transfer {
+		<Timestamp<T>>::set_timestamp(1u32.into());
+		let borrower: T::AccountId = borrower_account::<T>(true);
+		let repayment_amount = ExternalAmount::from(1);
+		let (deal_id, _) = generate_funded_deal::<T>(true, 0u8).unwrap();
+		let deal = Creditcoin::<T>::deal_orders(deal_id.expiration(), deal_id.hash()).unwrap();
+		crate::DealOrderShares::<T>::insert(
+			&deal_id,
+			&deal.lender_address_id,
+			LenderShare::new(deal.terms.amount),
+		);
+		let (_, transfer) = generate_transfer::<T>(deal_id.clone(), false, true, 1u8);
+
+	}: _(RawOrigin::Signed(borrower), transfer.kind, repayment_amount, deal_id, deal.lender_address_id, transfer.tx_id)
+
+	set_deal_order_retention {
+		let root = RawOrigin::Root;
+	}: _(root, Some(T::BlockNumber::one()))
+
+	archive_deal_orders {
+		//move through s closing blocks, the deal orders were closed in the last one
+		let s in 1..MAX_ARCHIVE_SWEEP_BLOCKS;
+		//archive d deal orders
+		let d in 1..(MAX_ARCHIVED_PER_BLOCK / 2);
+		//remove t transfers of the first deal order
+		let t in 0..(MAX_ARCHIVED_PER_BLOCK / 2);
+
+		<Timestamp<T>>::set_timestamp(1u32.into());
+
+		let lender = lender_account::<T>(false);
+		let expiration_block = T::BlockNumber::one();
+		let closed_at: T::BlockNumber = s.into();
+		crate::DealOrderRetention::<T>::put(T::BlockNumber::one());
+		crate::ArchiveCursor::<T>::put(T::BlockNumber::one());
+
+		let deal_ids: Vec<_> = (0..d)
+			.map(|i| {
+				insert_fake_deal::<T>(&lender, expiration_block, DealKind::Funded, i);
+				let ask_id = fake_ask_id::<T>(i, expiration_block);
+				let bid_id = fake_bid_id::<T>(i, expiration_block);
+				let offer_id = fake_offer_id::<T>(expiration_block, &ask_id, &bid_id);
+				let deal_id = fake_deal_id::<T>(expiration_block, &offer_id);
+				crate::ClosedDealOrders::<T>::insert(closed_at, &deal_id, ());
+				deal_id
+			})
+			.collect();
+		for i in 0..t {
+			let transfer = generate_fake_unverified_transfer::<T>(&lender, expiration_block, i + 1).transfer;
+			let transfer_id = TransferId::new::<T>(&transfer.blockchain, &transfer.tx_id);
+			crate::DealOrderTransfers::<T>::insert(&deal_ids[0], &transfer_id, ());
+			Transfers::<T>::insert(&transfer_id, transfer);
+		}
+
+	}: { Creditcoin::<T>::archive_closed_deal_orders(closed_at + T::BlockNumber::one()) }
+	verify {}
+
+	add_repayment_order {
+		<Timestamp<T>>::set_timestamp(1u32.into());
+		let (deal_id, _) = generate_funded_deal::<T>(true, 0u8).unwrap();
+		let payer = payer_account::<T>(true);
+		let address_id = register_eth_addr::<T>(&payer, "payer");
+		let expiration_block = T::BlockNumber::one();
+		let guid = "repayment_guid".as_bytes().into_bounded();
+	}: _(RawOrigin::Signed(payer), deal_id, address_id, ExternalAmount::from(1), expiration_block, guid)
+
+	cancel_repayment_order {
+		<Timestamp<T>>::set_timestamp(1u32.into());
+		let (deal_id, _) = generate_funded_deal::<T>(true, 0u8).unwrap();
+		let (payer, repayment_order_id) = generate_repayment_order::<T>(deal_id);
+	}: _(RawOrigin::Signed(payer), repayment_order_id)
+
+	register_repayment_order_transfer {
+		<Timestamp<T>>::set_timestamp(1u32.into());
+		let (deal_id, _) = generate_funded_deal::<T>(true, 0u8).unwrap();
+		let (payer, repayment_order_id) = generate_repayment_order::<T>(deal_id);
+		let contract = "0x0ad1439a0e0bfdcd49939f9722866651a4aa9b3c".as_bytes().into_bounded();
+		let tx = "0xcb13b65dd4d9d7f3cb8fcddeb442dfdf767403f8a9e5fe8587859225f8a62200".as_bytes().into_bounded();
+	}: _(RawOrigin::Signed(payer), TransferKind::Ethless(contract), repayment_order_id, tx)
+
+	set_origination_fee {
+		let root = RawOrigin::Root;
+		let fee = crate::OriginationFee {
+			rate: crate::OriginationFeeRate::Flat(<Balances<T> as Currency<T::AccountId>>::minimum_balance()),
+			payer: crate::FeePayer::Borrower,
+			collector: account("collector", 1, 1),
+		};
+	}: _(root, Some(fee))
+
+	unregister_address {
+		//look at o deal orders of the owner that don't involve the address
+		let o in 0..MAX_ADDRESS_ORDERS_SCANNED;
+		let who: T::AccountId = lender_account::<T>(true);
+		let address_id = register_eth_addr::<T>(&who, "owner");
+		index_fake_deals::<T>(&who, o);
+	}: _(RawOrigin::Signed(who), address_id)
+
+	transfer_address {
+		//look at o deal orders of the owner that don't involve the address
+		let o in 0..MAX_ADDRESS_ORDERS_SCANNED;
+		let who: T::AccountId = lender_account::<T>(true);
+		let address_id = register_eth_addr::<T>(&who, "owner");
+		index_fake_deals::<T>(&who, o);
+		let new_owner: T::AccountId = account("new_owner", 1, 1);
+
+		let ktypeid = KeyTypeId(*b"dumy");
+		let pkey = ecdsa_generate(ktypeid, Some("//owner".as_bytes().to_vec()));
+		let message = crate::helpers::transfer_address_message::<T>(&address_id, &who, &new_owner, 0);
+		let message = sp_io::hashing::sha2_256(&message);
+		let signature = ecdsa_sign(ktypeid, &pkey, &message).expect("ecdsa signature");
+		let proof = OwnershipProof::EthSign(signature);
+	}: _(RawOrigin::Signed(who), address_id, new_owner, proof)
+
+	register_contract_wallet {
+		let who: T::AccountId = lender_account::<T>(true);
+		let address: ExternalAddress = vec![0x41u8; 20].try_into().unwrap();
+		let signature = ContractWalletSignature::try_from(vec![0xabu8; 1024]).unwrap();
+	}: _(RawOrigin::Signed(who), CHAIN, address, signature)
+
+	persist_contract_wallet {
+		let authority = authority_account::<T>(true);
+		<Creditcoin<T>>::add_authority(RawOrigin::Root.into(), authority.clone()).unwrap();
+		let owner: T::AccountId = lender_account::<T>(true);
+		let value: ExternalAddress = vec![0x41u8; 20].try_into().unwrap();
+		let address_id = AddressId::new::<T>(&CHAIN, &value);
+		let address = crate::Address { blockchain: CHAIN, value, owner };
+		let deadline = System::<T>::block_number() + <<T as crate::Config>::UnverifiedTaskTimeout as Get<T::BlockNumber>>::get();
+		let task_output = crate::TaskOutput::from((address_id, address));
+	}: persist_task_output(RawOrigin::Signed(authority), deadline, task_output)
+
+	fail_contract_wallet {
+		let authority = authority_account::<T>(true);
+		<Creditcoin<T>>::add_authority(RawOrigin::Root.into(), authority.clone()).unwrap();
+		let value: ExternalAddress = vec![0x41u8; 20].try_into().unwrap();
+		let owner: T::AccountId = lender_account::<T>(true);
+		let address_id = AddressId::new::<T>(&CHAIN, &value);
+		let deadline = System::<T>::block_number() + <<T as crate::Config>::UnverifiedTaskTimeout as Get<T::BlockNumber>>::get();
+		let task_id = crate::TaskId::from(crate::ContractWalletId::new::<T>(&address_id, &owner));
+	}: fail_task(RawOrigin::Signed(authority), deadline, task_id, Cause::InvalidSignature)
+
+	set_external_chain {
+		let root = RawOrigin::Root;
+		let blockchain = Blockchain::Other(b"kaia".to_vec().try_into().unwrap());
+		let chain = ExternalChain {
+			address_format: AddressFormat::Evm,
+			evm_chain_id: Some(8217),
+			transfer_kinds: SupportedTransferKinds::ALL,
+			deprecated: false,
+		};
+	}: _(root, blockchain, chain)
 }
 
 //impl_benchmark_test_suite!(Creditcoin, crate::mock::new_test_ext(), crate::mock::Test);
 fn get_all_fit_terms() -> LoanTerms {
 	LoanTerms {
 		amount: 10u64.into(),
+		currency: LoanCurrency::Token {
+			contract: "0x0ad1439a0e0bfdcd49939f9722866651a4aa9b3c".as_bytes().into_bounded(),
+		},
 		interest_rate: InterestRate {
 			rate_per_period: 1,
 			decimals: 1,
@@ -361,6 +740,7 @@ fn get_all_fit_terms() -> LoanTerms {
 			interest_type: InterestType::Simple,
 		},
 		term_length: Duration::new(1u64, 0u32),
+		collateral: 0,
 	}
 }
 
@@ -479,6 +859,36 @@ fn lender_account<T: Config>(whitelist: bool) -> T::AccountId {
 	lender
 }
 
+fn payer_account<T: Config>(whitelist: bool) -> T::AccountId {
+	let payer = account("payer", 1, 1);
+	if whitelist {
+		whitelist_account!(payer);
+	}
+	payer
+}
+
+fn generate_repayment_order<T: Config>(
+	deal_id: DealOrderId<T::BlockNumber, T::Hash>,
+) -> (T::AccountId, RepaymentOrderId<T::BlockNumber, T::Hash>) {
+	let payer = payer_account::<T>(true);
+	let address_id = register_eth_addr::<T>(&payer, "payer");
+	let expiration_block = T::BlockNumber::one();
+	let guid: Guid = "repayment_guid".as_bytes().into_bounded();
+	let repayment_order_id = RepaymentOrderId::new::<T>(expiration_block, &guid);
+
+	Creditcoin::<T>::add_repayment_order(
+		RawOrigin::Signed(payer.clone()).into(),
+		deal_id,
+		address_id,
+		ExternalAmount::from(1),
+		expiration_block,
+		guid,
+	)
+	.unwrap();
+
+	(payer, repayment_order_id)
+}
+
 fn generate_deal<T: Config>(
 	insert: bool,
 	seed: u8,
@@ -569,7 +979,7 @@ fn generate_ask<T: Config>(
 		Creditcoin::<T>::add_ask_order(
 			origin.into(),
 			address_id.clone(),
-			loan_terms.clone(),
+			loan_terms.clone().try_into().unwrap(),
 			*expiration_block,
 			guid.into_bounded(),
 		)
@@ -598,7 +1008,7 @@ fn generate_bid<T: Config>(
 		Creditcoin::<T>::add_bid_order(
 			origin.into(),
 			address_id.clone(),
-			loan_terms.clone(),
+			loan_terms.clone().try_into().unwrap(),
 			*expiration_block,
 			guid.into_bounded(),
 		)
@@ -659,6 +1069,25 @@ fn insert_fake_bid<T: Config>(who: &T::AccountId, expiration_block: BlockNumberF
 	crate::BidOrders::<T>::insert_id(bid_id, bid);
 }
 
+/// Fills the order books of `get_all_fit_terms` to one entry short of their bound, with ask
+/// orders at a higher rate, so a new ask order goes ahead of every entry and taking an order off
+/// a book goes through all of them.
+fn fill_order_books<T: Config>() {
+	let terms = get_all_fit_terms();
+	let rate = InterestRate { rate_per_period: 9, ..terms.interest_rate };
+	let expiration_block = 1_000u32.into();
+
+	let ask_book: Vec<_> = (0..MAX_ORDER_BOOK_DEPTH - 1)
+		.map(|i| (rate.clone(), fake_ask_id::<T>(i, expiration_block)))
+		.collect();
+	crate::AskOrderBook::<T>::insert(CHAIN, &terms.currency, BoundedVec::truncate_from(ask_book));
+
+	let bid_book: Vec<_> = (0..MAX_ORDER_BOOK_DEPTH - 1)
+		.map(|i| fake_bid_id::<T>(i, expiration_block))
+		.collect();
+	crate::BidOrderBook::<T>::insert(CHAIN, &terms.currency, BoundedVec::truncate_from(bid_book));
+}
+
 fn fake_offer_id<T: SystemConfig>(
 	expiration_block: BlockNumberFor<T>,
 	ask_id: &AskOrderId<T::BlockNumber, T::Hash>,
@@ -688,6 +1117,51 @@ fn insert_fake_offer<T: Config>(
 	crate::Offers::<T>::insert_id(offer_id, offer);
 }
 
+fn insert_fake_terms_proposal<T: Config>(
+	who: &T::AccountId,
+	expiration_block: BlockNumberFor<T>,
+	seed: u32,
+) {
+	let ask_id = fake_ask_id::<T>(seed, expiration_block);
+	let bid_id = fake_bid_id::<T>(seed, expiration_block);
+	let offer_id = fake_offer_id::<T>(expiration_block, &ask_id, &bid_id);
+	let deal_order_id = fake_deal_id::<T>(expiration_block, &offer_id);
+	let terms = get_all_fit_terms();
+
+	let terms_proposal_id = TermsProposalId::new::<T>(expiration_block, &deal_order_id, &terms);
+	let terms_proposal = crate::TermsProposal {
+		deal_order_id,
+		terms,
+		expiration_block,
+		block: System::<T>::block_number(),
+		proposer: who.clone(),
+	};
+
+	crate::TermsProposals::<T>::insert_id(terms_proposal_id, terms_proposal);
+}
+
+fn insert_fake_repayment_order<T: Config>(
+	who: &T::AccountId,
+	expiration_block: BlockNumberFor<T>,
+	seed: u32,
+) {
+	let ask_id = fake_ask_id::<T>(seed, expiration_block);
+	let bid_id = fake_bid_id::<T>(seed, expiration_block);
+	let offer_id = fake_offer_id::<T>(expiration_block, &ask_id, &bid_id);
+	let guid = format!("repayment_guid{seed}");
+	let repayment_order_id = RepaymentOrderId::new::<T>(expiration_block, guid.as_bytes());
+	let repayment_order = crate::RepaymentOrder {
+		deal_order_id: fake_deal_id::<T>(expiration_block, &offer_id),
+		payer_address_id: fake_address_id::<T>(seed),
+		amount: ExternalAmount::from(1),
+		expiration_block,
+		block: System::<T>::block_number(),
+		payer: who.clone(),
+	};
+
+	crate::RepaymentOrders::<T>::insert_id(repayment_order_id, repayment_order);
+}
+
 pub(crate) fn fake_deal_id<T: SystemConfig>(
 	expiration_block: BlockNumberFor<T>,
 	offer_id: &OfferId<T::BlockNumber, T::Hash>,
@@ -725,13 +1199,61 @@ fn insert_fake_deal<T: Config>(
 		},
 		offer_id,
 		repayment_transfer_id: None,
+		repaid_amount: ExternalAmount::zero(),
+		status: match kind {
+			DealKind::Funded => DealOrderStatus::Funded,
+			_ => DealOrderStatus::Pending,
+		},
 		terms: get_all_fit_terms(),
 		timestamp: pallet_timestamp::Pallet::<T>::now(),
+		exemption: None,
 	};
 
 	crate::DealOrders::<T>::insert_id(deal_id, deal);
 }
 
+/// Registers the lender address of a fake deal order and shares the deal order among
+/// `MAX_LENDERS_PER_DEAL` lenders of their own, the most there is to remove with it.
+fn syndicate_fake_deal<T: Config>(expiration_block: BlockNumberFor<T>, seed: u32) {
+	let ask_id = fake_ask_id::<T>(seed, expiration_block);
+	let bid_id = fake_bid_id::<T>(seed, expiration_block);
+	let offer_id = fake_offer_id::<T>(expiration_block, &ask_id, &bid_id);
+	let deal_id = fake_deal_id::<T>(expiration_block, &offer_id);
+	for lender in 0..MAX_LENDERS_PER_DEAL {
+		let value = match lender {
+			0 => format!("somefakeaddress{seed}"),
+			_ => format!("somefakecolender{seed}-{lender}"),
+		};
+		let address_id = crate::AddressId::new::<T>(&Blockchain::Ethereum, value.as_bytes());
+		let owner: T::AccountId = account("lender", lender, seed);
+		let address = crate::Address {
+			blockchain: Blockchain::Ethereum,
+			value: value.as_bytes().into_bounded(),
+			owner: owner.clone(),
+		};
+		crate::Addresses::<T>::insert(&address_id, address);
+		crate::AccountDealOrders::<T>::insert(&owner, &deal_id, ());
+		crate::DealOrderShares::<T>::insert(
+			&deal_id,
+			&address_id,
+			LenderShare::new(ExternalAmount::from(1u64)),
+		);
+	}
+}
+
+/// Inserts `count` funded deal orders of other addresses and indexes them under `who`.
+fn index_fake_deals<T: Config>(who: &T::AccountId, count: u32) {
+	let expiration_block = T::BlockNumber::one();
+	for seed in 0..count {
+		insert_fake_deal::<T>(who, expiration_block, DealKind::Funded, seed);
+		let ask_id = fake_ask_id::<T>(seed, expiration_block);
+		let bid_id = fake_bid_id::<T>(seed, expiration_block);
+		let offer_id = fake_offer_id::<T>(expiration_block, &ask_id, &bid_id);
+		let deal_id = fake_deal_id::<T>(expiration_block, &offer_id);
+		crate::AccountDealOrders::<T>::insert(who, deal_id, ());
+	}
+}
+
 pub(crate) fn generate_fake_unverified_transfer<T: SystemConfig + TimestampConfig + Config>(
 	who: &T::AccountId,
 	deadline: BlockNumberFor<T>,
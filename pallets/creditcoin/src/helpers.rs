@@ -1,17 +1,30 @@
+mod address;
+mod archive;
+mod collateral;
+mod credit_history;
+mod expiry;
 mod external_address;
+mod external_chain;
+mod fee;
+mod matching;
 mod register_transfer;
+mod syndication;
 
-pub use external_address::{address_is_well_formed, generate_external_address};
+pub use external_address::{
+	address_is_well_formed, canonical_address, generate_external_address, normalize_address,
+};
 #[cfg(any(test, feature = "runtime-benchmarks"))]
 pub use external_address::{EVMAddress, PublicToAddress};
 
 use crate::{
 	pallet::*,
-	types::{Address, AddressId, OwnershipProof},
-	Blockchain, DealOrderId, Error, ExternalAddress, Guid, Id, TransferId,
+	types::{Address, AddressFormat, AddressId, OwnershipProof},
+	Blockchain, DealOrderId, DealOrderStatus, DoubleMapExt, Error, ExternalAddress, ExternalAmount,
+	Guid, Id, LoanBalance, OrderId, TransferId, MAX_DEFAULT_CHECKS_PER_BLOCK,
 };
-use frame_support::ensure;
+use frame_support::{ensure, weights::Weight};
 use frame_system::pallet_prelude::*;
+use parity_scale_codec::Encode;
 use sp_std::prelude::*;
 
 #[allow(unused_macros)]
@@ -71,6 +84,10 @@ impl<T: Config> Pallet<T> {
 			deal_order_id.hash(),
 			|value| {
 				let deal_order = value.as_mut().ok_or(crate::Error::<T>::NonExistentDealOrder)?;
+				ensure!(
+					!deal_order.has_expired(Self::block_number()),
+					crate::Error::<T>::DealOrderExpired
+				);
 				let deal_event = mutate_deal(deal_order)?;
 
 				let transfer_event = Transfers::<T>::try_mutate(transfer_id, |value| {
@@ -97,6 +114,277 @@ impl<T: Config> Pallet<T> {
 		}
 	}
 
+	/// Credits a freshly verified transfer towards its deal order if it is a repayment, i.e. it
+	/// goes from the borrower, or the payer of a repayment order, to a funded deal order that is
+	/// still open. The recipient was checked against the lender when the transfer was
+	/// registered, so a repayment sent to the lender before the deal order was transferred to
+	/// another lender still counts. A repayment order is settled by its transfer, even if it
+	/// expired or was cancelled after the transfer was registered. Once the deal
+	/// order is repaid it is closed right away if the borrower locked it. A deal order that is
+	/// repaid in full while unlocked stays open, with its collateral held, until the borrower
+	/// locks it and closes it with `close_deal_order` or `close_repaid_deal_order`.
+	/// Transfers that don't qualify are left untouched.
+	pub fn record_repayment(transfer_id: &TransferId<T::Hash>) {
+		let registered_order = RepaymentOrderTransfers::<T>::take(transfer_id);
+		let result = Transfers::<T>::try_mutate(transfer_id, |value| -> Result<_, ()> {
+			let transfer = value.as_mut().ok_or(())?;
+			ensure!(!transfer.is_processed, ());
+			let (deal_order_id, repayment_order_id) = match &transfer.order_id {
+				OrderId::Deal(id) => (id.clone(), None),
+				OrderId::Repayment(id) => {
+					let repayment_order = match registered_order {
+						Some(repayment_order) => repayment_order,
+						None => RepaymentOrders::<T>::try_get_id(id)?,
+					};
+					ensure!(transfer.from == repayment_order.payer_address_id, ());
+					(repayment_order.deal_order_id, Some(id.clone()))
+				},
+			};
+
+			let (closed, repaid_amount, borrower) = DealOrders::<T>::try_mutate(
+				deal_order_id.expiration(),
+				deal_order_id.hash(),
+				|value| -> Result<_, ()> {
+					let deal_order = value.as_mut().ok_or(())?;
+					ensure!(deal_order.funding_transfer_id.is_some(), ());
+					ensure!(!deal_order.is_closed(), ());
+					ensure!(
+						repayment_order_id.is_some()
+							|| transfer.from == deal_order.borrower_address_id,
+						()
+					);
+					if Self::is_syndicated(&deal_order_id, deal_order) {
+						Self::credit_share_repayment(&deal_order_id, &transfer.to, transfer.amount)
+							.map_err(|_| ())?;
+					}
+					deal_order.repaid_amount =
+						deal_order.repaid_amount.saturating_add(transfer.amount);
+					deal_order.repayment_transfer_id = Some(transfer_id.clone());
+
+					let closed =
+						deal_order.lock.is_some() && Self::is_repaid(&deal_order_id, deal_order);
+					if closed {
+						let previous_status = deal_order.status;
+						deal_order.status = DealOrderStatus::Closed;
+						Self::record_loan_outcome(deal_order, previous_status);
+					}
+
+					Ok((closed, deal_order.repaid_amount, deal_order.borrower.clone()))
+				},
+			)?;
+
+			transfer.is_processed = true;
+			Ok((deal_order_id, repayment_order_id, closed, repaid_amount, borrower))
+		});
+
+		if let Ok((deal_order_id, repayment_order_id, closed, repaid_amount, borrower)) = result {
+			Self::deposit_event(crate::Event::<T>::TransferProcessed(transfer_id.clone()));
+			Self::deposit_event(crate::Event::<T>::RepaymentRecorded(
+				deal_order_id.clone(),
+				transfer_id.clone(),
+				repaid_amount,
+			));
+			if let Some(repayment_order_id) = repayment_order_id {
+				RepaymentOrders::<T>::remove(
+					repayment_order_id.expiration(),
+					repayment_order_id.hash(),
+				);
+				Self::deposit_event(crate::Event::<T>::RepaymentOrderSettled(
+					repayment_order_id,
+					transfer_id.clone(),
+				));
+			}
+			if closed {
+				Self::release_collateral(&deal_order_id, &borrower);
+				Self::schedule_archival(&deal_order_id);
+				Self::deposit_event(crate::Event::<T>::DealOrderClosed(deal_order_id));
+			}
+		}
+	}
+
+	/// Closes a locked deal order on behalf of its borrower once the repayments credited towards
+	/// it cover what is due, then releases its collateral and schedules its archival.
+	pub fn close_repaid(
+		who: &T::AccountId,
+		deal_order_id: DealOrderId<T::BlockNumber, T::Hash>,
+	) -> Result<(), Error<T>> {
+		let borrower = DealOrders::<T>::try_mutate(
+			deal_order_id.expiration(),
+			deal_order_id.hash(),
+			|value| -> Result<_, Error<T>> {
+				let deal_order = value.as_mut().ok_or(Error::<T>::NonExistentDealOrder)?;
+				let borrower =
+					try_get!(Addresses<T>, &deal_order.borrower_address_id, NonExistentAddress)?;
+
+				ensure!(borrower.owner == *who, Error::<T>::NotBorrower);
+
+				let now = Self::timestamp();
+				ensure!(now >= deal_order.timestamp, Error::<T>::MalformedDealOrder);
+
+				ensure!(!deal_order.is_closed(), Error::<T>::DealOrderAlreadyClosed);
+
+				ensure!(deal_order.lock.is_some(), Error::<T>::DealOrderMustBeLocked);
+
+				ensure!(
+					Self::is_repaid(&deal_order_id, deal_order),
+					Error::<T>::InsufficientRepayment
+				);
+
+				let previous_status = deal_order.status;
+				deal_order.status = DealOrderStatus::Closed;
+				Self::record_loan_outcome(deal_order, previous_status);
+
+				Ok(deal_order.borrower.clone())
+			},
+		)?;
+
+		Self::release_collateral(&deal_order_id, &borrower);
+		Self::schedule_archival(&deal_order_id);
+		Self::deposit_event(crate::Event::<T>::DealOrderClosed(deal_order_id));
+
+		Ok(())
+	}
+
+	/// Whether the repayments credited towards a deal order cover what the borrower owes by now.
+	/// A syndicated deal order is repaid once every lender has been repaid its share.
+	pub fn is_repaid(
+		deal_order_id: &DealOrderId<T::BlockNumber, T::Hash>,
+		deal_order: &DealOrderFor<T>,
+	) -> bool {
+		if Self::is_syndicated(deal_order_id, deal_order) {
+			Self::shares_repaid(deal_order_id, deal_order)
+		} else {
+			deal_order.repaid_amount >= deal_order.amount_due(Self::timestamp())
+		}
+	}
+
+	/// The deal order a transfer pays into, directly or through a repayment order.
+	pub fn deal_order_of_transfer(
+		transfer_id: &TransferId<T::Hash>,
+		transfer: &TransferFor<T>,
+	) -> Option<DealOrderId<T::BlockNumber, T::Hash>> {
+		match &transfer.order_id {
+			OrderId::Deal(deal_order_id) => Some(deal_order_id.clone()),
+			OrderId::Repayment(repayment_order_id) => {
+				RepaymentOrders::<T>::try_get_id(repayment_order_id)
+					.ok()
+					.or_else(|| RepaymentOrderTransfers::<T>::get(transfer_id))
+					.map(|repayment_order| repayment_order.deal_order_id)
+			},
+		}
+	}
+
+	/// The balance of a deal order right now. Interest only accrues once the deal order has
+	/// been funded; for a closed deal order it is whatever was repaid beyond the principal.
+	pub fn loan_balance(
+		deal_order_id: &DealOrderId<T::BlockNumber, T::Hash>,
+	) -> Option<LoanBalance<T::Moment>> {
+		let deal_order = Self::try_get_deal_order(deal_order_id).ok()?;
+		let principal = deal_order.terms.amount;
+
+		let interest = if deal_order.funding_transfer_id.is_none() {
+			ExternalAmount::zero()
+		} else if deal_order.is_closed() {
+			deal_order.repaid_amount.saturating_sub(principal)
+		} else {
+			deal_order.amount_owed(Self::timestamp()).saturating_sub(principal)
+		};
+
+		Some(LoanBalance {
+			principal,
+			interest,
+			repaid: deal_order.repaid_amount,
+			due: deal_order.due_timestamp(),
+		})
+	}
+
+	/// Marks funded deal orders that are still unpaid after their term ended as defaulted.
+	/// At most `MAX_DEFAULT_CHECKS_PER_BLOCK` deal orders are checked per call, the next call
+	/// resumes where this one stopped so that every deal order is checked eventually.
+	pub fn mark_defaulted_deal_orders() -> Weight {
+		let now = Self::timestamp();
+		let mut deal_orders = match DealOrderDefaultCursor::<T>::take() {
+			Some(cursor) => DealOrders::<T>::iter_from(DealOrders::<T>::hashed_key_for(
+				cursor.expiration(),
+				cursor.hash(),
+			)),
+			None => DealOrders::<T>::iter(),
+		};
+
+		let mut checked = 0u32;
+		let mut last_checked = None;
+		let mut overdue = Vec::new();
+		while checked < MAX_DEFAULT_CHECKS_PER_BLOCK {
+			let (expiration, hash, deal_order) = match deal_orders.next() {
+				Some(entry) => entry,
+				None => break,
+			};
+			checked = checked.saturating_add(1);
+
+			let deal_order_id = DealOrderId::with_expiration_hash::<T>(expiration, hash);
+			if deal_order.is_overdue(now) {
+				overdue.push(deal_order_id.clone());
+			}
+			last_checked = Some(deal_order_id);
+		}
+
+		if checked == MAX_DEFAULT_CHECKS_PER_BLOCK {
+			if let Some(deal_order_id) = last_checked {
+				DealOrderDefaultCursor::<T>::put(deal_order_id);
+			}
+		}
+
+		for deal_order_id in overdue {
+			DealOrders::<T>::mutate(deal_order_id.expiration(), deal_order_id.hash(), |value| {
+				if let Some(deal_order) = value {
+					let previous_status = deal_order.status;
+					deal_order.status = DealOrderStatus::Defaulted;
+					Self::record_loan_outcome(deal_order, previous_status);
+				}
+			});
+			Self::deposit_event(crate::Event::<T>::DealOrderDefaulted(deal_order_id));
+		}
+
+		<T as Config>::WeightInfo::mark_defaulted_deal_orders(checked)
+	}
+
+	/// Drops a deal order from the index of its borrower and of the owner of its lender address.
+	pub fn unindex_deal_order(
+		deal_order_id: &DealOrderId<T::BlockNumber, T::Hash>,
+		deal_order: &DealOrderFor<T>,
+	) {
+		AccountDealOrders::<T>::remove(&deal_order.borrower, deal_order_id);
+		if let Some(lender) = Self::addresses(&deal_order.lender_address_id) {
+			AccountDealOrders::<T>::remove(&lender.owner, deal_order_id);
+		}
+	}
+
+	/// Ensures `who` is the borrower of the deal order or owns its lender address.
+	pub fn ensure_deal_order_party(
+		deal_order: &DealOrderFor<T>,
+		who: &T::AccountId,
+	) -> Result<(), Error<T>> {
+		if deal_order.borrower == *who {
+			return Ok(());
+		}
+		let lender = Self::get_address(&deal_order.lender_address_id)?;
+		ensure!(lender.owner == *who, Error::<T>::NotDealOrderParty);
+		Ok(())
+	}
+
+	/// Ensures the terms of the deal order may still be renegotiated, i.e. it has been funded
+	/// and is neither closed, locked for closing nor defaulted.
+	pub fn ensure_terms_negotiable(deal_order: &DealOrderFor<T>) -> Result<(), Error<T>> {
+		ensure!(!deal_order.is_closed(), Error::<T>::DealOrderAlreadyClosed);
+		ensure!(deal_order.funding_transfer_id.is_some(), Error::<T>::DealNotFunded);
+		ensure!(deal_order.lock.is_none(), Error::<T>::DealOrderAlreadyLocked);
+		ensure!(
+			deal_order.status != DealOrderStatus::Defaulted,
+			Error::<T>::DealOrderAlreadyDefaulted
+		);
+		Ok(())
+	}
+
 	pub fn use_guid(guid: &Guid) -> Result<(), Error<T>> {
 		ensure!(!<UsedGuids<T>>::contains_key(guid.clone()), Error::<T>::GuidAlreadyUsed);
 		UsedGuids::<T>::insert(guid, ());
@@ -150,24 +438,37 @@ pub mod extensions {
 	}
 }
 
-use sp_io::crypto::secp256k1_ecdsa_recover_compressed;
+use sp_io::crypto::{secp256k1_ecdsa_recover, secp256k1_ecdsa_recover_compressed};
+use sp_io::hashing::keccak_256;
+use sp_runtime::traits::Zero;
 
 /// Try to extract an external address for a particular blockchain through a signature and an account id which acts as a message.
 /// This function supports the older and insecure EthSign signing method and the new PersonalSign standard that is supported by Metamask.
+/// It also supports EIP-712 typed data and, for Bitcoin addresses, the signed messages of bitcoin wallets.
+/// The address is derived in the `format` the registry of external chains holds for `blockchain`.
 pub fn try_extract_address<T: Config>(
 	ownership_proof: OwnershipProof,
 	account_id: &[u8],
 	blockchain: &Blockchain,
+	format: &AddressFormat,
 	address: &ExternalAddress,
 ) -> Result<ExternalAddress, crate::Error<T>> {
 	match ownership_proof {
 		// Old insecure signing method
 		OwnershipProof::EthSign(signature) => {
-			extract_public_key_eth_sign(signature.into(), account_id, blockchain, address)
+			extract_public_key_eth_sign(signature.into(), account_id, format, address)
 		},
 		// New Way
 		OwnershipProof::PersonalSign(signature) => {
-			extract_public_key_personal_sign(signature.into(), account_id, blockchain, address)
+			extract_public_key_personal_sign(signature.into(), account_id, format, address)
+		},
+		// EIP-712 typed data, e.g. eth_signTypedData_v4
+		OwnershipProof::TypedData(signature) => {
+			extract_public_key_typed_data(signature.into(), account_id, blockchain, format, address)
+		},
+		// Bitcoin wallets' signmessage
+		OwnershipProof::BitcoinSign(signature) => {
+			extract_address_bitcoin_sign(signature, account_id, format, address)
 		},
 	}
 }
@@ -175,7 +476,7 @@ pub fn try_extract_address<T: Config>(
 fn extract_public_key_eth_sign<T: Config>(
 	signature: [u8; 65],
 	account_id: &[u8],
-	blockchain: &Blockchain,
+	format: &AddressFormat,
 	address: &ExternalAddress,
 ) -> Result<ExternalAddress, Error<T>> {
 	let message = sp_io::hashing::sha2_256(account_id);
@@ -184,7 +485,7 @@ fn extract_public_key_eth_sign<T: Config>(
 	match secp256k1_ecdsa_recover_compressed(&signature, message) {
 		Ok(public_key) => {
 			match generate_external_address(
-				blockchain,
+				format,
 				address,
 				sp_core::ecdsa::Public::from_raw(public_key),
 			) {
@@ -206,10 +507,40 @@ pub fn eth_message(message: &[u8; 32]) -> [u8; 32] {
 	sp_io::hashing::keccak_256(&bytes)
 }
 
+/// The hash a smart contract wallet validates through EIP-1271 to prove it belongs to
+/// `account_id`. It is the same message an externally owned account signs with PersonalSign.
+pub fn contract_wallet_message_hash(account_id: &[u8]) -> [u8; 32] {
+	eth_message(&sp_io::hashing::blake2_256(account_id))
+}
+
+/// The message the incoming lender signs to consent to taking over the lender's side of a deal
+/// order from `lender_address_id`. `nonce` is the number of times the deal order has changed hands
+/// before.
+pub fn transfer_deal_order_message<T: Config>(
+	deal_order_id: &DealOrderId<T::BlockNumber, T::Hash>,
+	lender_address_id: &AddressId<T::Hash>,
+	new_lender_address_id: &AddressId<T::Hash>,
+	nonce: u64,
+) -> Vec<u8> {
+	(b"transfer_deal_order", deal_order_id, lender_address_id, new_lender_address_id, nonce)
+		.encode()
+}
+
+/// The message the key of an external address signs to consent to the address moving from
+/// `owner` to `new_owner`. `nonce` is the number of times the address has been moved before.
+pub fn transfer_address_message<T: Config>(
+	address_id: &AddressId<T::Hash>,
+	owner: &T::AccountId,
+	new_owner: &T::AccountId,
+	nonce: u64,
+) -> Vec<u8> {
+	(b"transfer_address", address_id, owner, new_owner, nonce).encode()
+}
+
 pub fn extract_public_key_personal_sign<T: Config>(
 	signature: [u8; 65],
 	account_id: &[u8],
-	blockchain: &Blockchain,
+	format: &AddressFormat,
 	address: &ExternalAddress,
 ) -> Result<ExternalAddress, Error<T>> {
 	let message = sp_io::hashing::blake2_256(account_id);
@@ -218,7 +549,7 @@ pub fn extract_public_key_personal_sign<T: Config>(
 	match secp256k1_ecdsa_recover_compressed(&signature, &message) {
 		Ok(public_key) => {
 			match generate_external_address(
-				blockchain,
+				format,
 				address,
 				sp_core::ecdsa::Public::from_raw(public_key),
 			) {
@@ -230,6 +561,260 @@ pub fn extract_public_key_personal_sign<T: Config>(
 	}
 }
 
+const EIP712_DOMAIN_NAME: &[u8] = b"Creditcoin";
+const EIP712_DOMAIN_VERSION: &[u8] = b"1";
+const EIP712_REGISTRATION_TYPE: &[u8] = b"AddressRegistration(bytes account)";
+
+/// The fields of an EIP-712 domain, in the order the standard encodes them.
+struct Eip712Domain<'a> {
+	name: &'a [u8],
+	version: &'a [u8],
+	chain_id: u64,
+	verifying_contract: Option<[u8; 20]>,
+	salt: Option<[u8; 32]>,
+}
+
+impl Eip712Domain<'_> {
+	/// `hashStruct(EIP712Domain)`, leaving out the optional fields that are not set.
+	fn separator(&self) -> [u8; 32] {
+		let mut domain_type = b"EIP712Domain(string name,string version,uint256 chainId".to_vec();
+		// uint256 is big endian, padded on the left
+		let mut chain_id = [0u8; 32];
+		chain_id[24..].copy_from_slice(&self.chain_id.to_be_bytes());
+		let mut encoded = [keccak_256(self.name), keccak_256(self.version), chain_id].concat();
+
+		if let Some(verifying_contract) = self.verifying_contract {
+			domain_type.extend_from_slice(b",address verifyingContract");
+			// address is padded on the left
+			let mut word = [0u8; 32];
+			word[12..].copy_from_slice(&verifying_contract);
+			encoded.extend_from_slice(&word);
+		}
+		if let Some(salt) = self.salt {
+			domain_type.extend_from_slice(b",bytes32 salt");
+			encoded.extend_from_slice(&salt);
+		}
+		domain_type.push(b')');
+
+		keccak_256(&[&keccak_256(&domain_type)[..], &encoded].concat())
+	}
+
+	/// The hash a wallet signs for the typed data whose `hashStruct` is `message_hash`.
+	fn hash(&self, message_hash: &[u8; 32]) -> [u8; 32] {
+		keccak_256(&[&b"\x19\x01"[..], &self.separator(), message_hash].concat())
+	}
+}
+
+/// The EIP-712 hash of the typed data signed to prove ownership of an address on `blockchain` to
+/// `account_id`. The domain names Creditcoin at version 1, the EVM chain id the registry of
+/// external chains holds for `blockchain` and, as the salt, the genesis hash of this chain. The
+/// message is an `AddressRegistration` holding the account id.
+pub fn typed_data_hash<T: Config>(
+	account_id: &[u8],
+	blockchain: &Blockchain,
+) -> Result<[u8; 32], Error<T>> {
+	let chain_id = Pallet::<T>::external_chain(blockchain)?
+		.evm_chain_id
+		.ok_or(Error::<T>::TypedDataChainIdUnknown)?;
+
+	let genesis_hash = <frame_system::Pallet<T>>::block_hash(T::BlockNumber::zero());
+	let genesis_hash = genesis_hash.as_ref();
+	// bytes32 is padded on the right
+	let mut salt = [0u8; 32];
+	let len = genesis_hash.len().min(salt.len());
+	salt[..len].copy_from_slice(&genesis_hash[..len]);
+
+	let domain = Eip712Domain {
+		name: EIP712_DOMAIN_NAME,
+		version: EIP712_DOMAIN_VERSION,
+		chain_id,
+		verifying_contract: None,
+		salt: Some(salt),
+	};
+	let message_hash =
+		keccak_256(&[keccak_256(EIP712_REGISTRATION_TYPE), keccak_256(account_id)].concat());
+
+	Ok(domain.hash(&message_hash))
+}
+
+fn extract_public_key_typed_data<T: Config>(
+	signature: [u8; 65],
+	account_id: &[u8],
+	blockchain: &Blockchain,
+	format: &AddressFormat,
+	address: &ExternalAddress,
+) -> Result<ExternalAddress, Error<T>> {
+	let message = typed_data_hash::<T>(account_id, blockchain)?;
+
+	match secp256k1_ecdsa_recover_compressed(&signature, &message) {
+		Ok(public_key) => {
+			match generate_external_address(
+				format,
+				address,
+				sp_core::ecdsa::Public::from_raw(public_key),
+			) {
+				Some(s) => Ok(s),
+				None => Err(Error::TypedDataExternalAddressGenerationFailed),
+			}
+		},
+		Err(_) => Err(Error::TypedDataPublicKeyRecoveryFailed),
+	}
+}
+
+/// The text a bitcoin wallet signs to prove ownership of an address to `account_id`, the hex
+/// encoded blake2 hash of the account id.
+pub fn bitcoin_sign_message(account_id: &[u8]) -> Vec<u8> {
+	hex::encode(sp_io::hashing::blake2_256(account_id)).into_bytes()
+}
+
+/// The hash bitcoin wallets sign for `message`, see `signmessage` of Bitcoin Core.
+pub fn bitcoin_message_hash(message: &[u8]) -> [u8; 32] {
+	let mut bytes: Vec<u8> = vec![];
+	let magic = b"Bitcoin Signed Message:\n";
+
+	push_compact_size(&mut bytes, magic.len());
+	bytes.extend_from_slice(magic);
+	push_compact_size(&mut bytes, message.len());
+	bytes.extend_from_slice(message);
+
+	sp_io::hashing::sha2_256(&sp_io::hashing::sha2_256(&bytes))
+}
+
+fn push_compact_size(bytes: &mut Vec<u8>, size: usize) {
+	match size {
+		0..=0xfc => bytes.push(size as u8),
+		0xfd..=0xffff => {
+			bytes.push(0xfd);
+			bytes.extend_from_slice(&(size as u16).to_le_bytes());
+		},
+		_ => {
+			bytes.push(0xfe);
+			bytes.extend_from_slice(&(size as u32).to_le_bytes());
+		},
+	}
+}
+
+/// Recovers the key of a signature in the compact format of `signmessage`, a header byte followed
+/// by r and s. The header holds the recovery id and whether the key is compressed, see BIP 137.
+fn extract_address_bitcoin_sign<T: Config>(
+	signature: [u8; 65],
+	account_id: &[u8],
+	format: &AddressFormat,
+	address: &ExternalAddress,
+) -> Result<ExternalAddress, Error<T>> {
+	let header = signature[0];
+	if !(27..=42).contains(&header) {
+		return Err(Error::BitcoinSignPublicKeyRecoveryFailed);
+	}
+
+	let mut recoverable = [0u8; 65];
+	recoverable[..64].copy_from_slice(&signature[1..]);
+	recoverable[64] = (header - 27) % 4;
+	let message = bitcoin_message_hash(&bitcoin_sign_message(account_id));
+
+	let public_key = if header < 31 {
+		secp256k1_ecdsa_recover(&recoverable, &message).map(|key| [&[0x04][..], &key[..]].concat())
+	} else {
+		secp256k1_ecdsa_recover_compressed(&recoverable, &message).map(|key| key.to_vec())
+	}
+	.map_err(|_| Error::BitcoinSignPublicKeyRecoveryFailed)?;
+
+	match format {
+		AddressFormat::Bitcoin => {
+			external_address::BTCAddress::from_public_key(address, &public_key)
+		},
+		AddressFormat::Evm => None,
+	}
+	.ok_or(Error::BitcoinSignExternalAddressGenerationFailed)
+}
+
+#[test]
+fn test_typed_data_hash() {
+	crate::mock::ExtBuilder::default().build_and_execute(|| {
+		crate::ExternalChains::<crate::mock::Test>::mutate(&Blockchain::Ethereum, |chain| {
+			chain.as_mut().unwrap().evm_chain_id = Some(1);
+		});
+		// the genesis hash of the mock chain is 0x4545...45
+		let expected_hash =
+			hex::decode("5e77186d84b5370f0770b85dd780eb5b0a59516a59f058040a1d9fb0c73886ba")
+				.unwrap();
+
+		let hash = typed_data_hash::<crate::mock::Test>(&[1u8; 32], &Blockchain::Ethereum).unwrap();
+
+		assert_eq!(hash.as_slice(), expected_hash.as_slice());
+	});
+}
+
+#[test]
+fn test_typed_data_hash_without_chain_id() {
+	crate::mock::ExtBuilder::default().build_and_execute(|| {
+		assert_eq!(
+			typed_data_hash::<crate::mock::Test>(&[1u8; 32], &Blockchain::Ethereum),
+			Err(Error::<crate::mock::Test>::TypedDataChainIdUnknown)
+		);
+	});
+}
+
+#[test]
+fn test_eip712_domain_matches_the_reference_signer() {
+	// the example of the EIP-712 specification, signed by Cow with the key keccak256("cow")
+	let domain = Eip712Domain {
+		name: b"Ether Mail",
+		version: b"1",
+		chain_id: 1,
+		verifying_contract: Some(
+			hex::decode("cccccccccccccccccccccccccccccccccccccccc")
+				.unwrap()
+				.try_into()
+				.unwrap(),
+		),
+		salt: None,
+	};
+	let person = |name: &[u8], wallet: &str| {
+		let mut word = [0u8; 32];
+		word[12..].copy_from_slice(&hex::decode(wallet).unwrap());
+		keccak_256(
+			&[keccak_256(b"Person(string name,address wallet)"), keccak_256(name), word].concat(),
+		)
+	};
+	let mail = keccak_256(
+		&[
+			keccak_256(
+				b"Mail(Person from,Person to,string contents)Person(string name,address wallet)",
+			),
+			person(b"Cow", "cd2a3d9f938e13cd947ec05abc7fe734df8dd826"),
+			person(b"Bob", "bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb"),
+			keccak_256(b"Hello, Bob!"),
+		]
+		.concat(),
+	);
+
+	assert_eq!(
+		hex::encode(domain.separator()),
+		"f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"
+	);
+	let hash = domain.hash(&mail);
+	assert_eq!(
+		hex::encode(hash),
+		"be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"
+	);
+
+	// r ‖ s ‖ v, with v = 28 - 27
+	let signature: [u8; 65] = hex::decode(
+		"4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d\
+		 07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b91562\
+		 01",
+	)
+	.unwrap()
+	.try_into()
+	.unwrap();
+	let public_key = secp256k1_ecdsa_recover(&signature, &hash).unwrap();
+	assert_eq!(
+		hex::encode(&keccak_256(&public_key)[12..]),
+		"cd2a3d9f938e13cd947ec05abc7fe734df8dd826"
+	);
+}
+
 #[test]
 fn test_extract_public_key_personal_sign() {
 	let expected_hash =
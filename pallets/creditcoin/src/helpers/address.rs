@@ -0,0 +1,70 @@
+use crate::{
+	pallet::*, AddressId, DealOrderId, DealOrderStatus, DoubleMapExt, Error,
+	MAX_ADDRESS_ORDERS_SCANNED,
+};
+use frame_support::ensure;
+use sp_std::prelude::*;
+
+impl<T: Config> Pallet<T> {
+	/// Ensures no open ask or bid order and no funded or locked deal order of `owner` references
+	/// the address, so it can be unregistered or moved to another account. Returns the deal orders that are indexed under
+	/// `owner` only because they were lent from the address, e.g. repaid or exempted ones, so
+	/// their index entries can follow the address, along with the number of orders looked at.
+	/// Gives up once more than `MAX_ADDRESS_ORDERS_SCANNED` orders would have to be looked at.
+	pub fn ensure_address_unused(
+		owner: &T::AccountId,
+		address_id: &AddressId<T::Hash>,
+	) -> Result<(Vec<DealOrderId<T::BlockNumber, T::Hash>>, u32), Error<T>> {
+		let head = Self::block_number();
+		let mut scanned = 0u32;
+		let mut scan_next = || -> Result<(), Error<T>> {
+			scanned = scanned.saturating_add(1);
+			ensure!(scanned <= MAX_ADDRESS_ORDERS_SCANNED, Error::<T>::TooManyOrdersToScan);
+			Ok(())
+		};
+
+		for ask_order_id in AccountAskOrders::<T>::iter_key_prefix(owner) {
+			scan_next()?;
+			let is_open = AskOrders::<T>::try_get_id(&ask_order_id).map_or(false, |ask_order| {
+				&ask_order.lender_address_id == address_id && ask_order.expiration_block >= head
+			});
+			ensure!(!is_open, Error::<T>::AddressInUse);
+		}
+
+		for bid_order_id in AccountBidOrders::<T>::iter_key_prefix(owner) {
+			scan_next()?;
+			let is_open = BidOrders::<T>::try_get_id(&bid_order_id).map_or(false, |bid_order| {
+				&bid_order.borrower_address_id == address_id && bid_order.expiration_block >= head
+			});
+			ensure!(!is_open, Error::<T>::AddressInUse);
+		}
+
+		let mut lent_deal_orders = Vec::new();
+		for deal_order_id in AccountDealOrders::<T>::iter_key_prefix(owner) {
+			scan_next()?;
+			let deal_order = match DealOrders::<T>::try_get_id(&deal_order_id) {
+				Ok(deal_order) => deal_order,
+				Err(()) => continue,
+			};
+
+			let lent_from_address = &deal_order.lender_address_id == address_id
+				|| DealOrderShares::<T>::contains_key(&deal_order_id, address_id);
+			if !lent_from_address && &deal_order.borrower_address_id != address_id {
+				continue;
+			}
+			// a pending deal order is funded by whoever owns the lender address by then and a
+			// defaulted one is settled, only a running loan needs the address
+			ensure!(
+				!matches!(deal_order.status, DealOrderStatus::Funded | DealOrderStatus::Locked),
+				Error::<T>::AddressInUse
+			);
+
+			// the borrower keeps its entry, it doesn't depend on who owns the lender address
+			if lent_from_address && &deal_order.borrower != owner {
+				lent_deal_orders.push(deal_order_id);
+			}
+		}
+
+		Ok((lent_deal_orders, scanned))
+	}
+}
@@ -0,0 +1,126 @@
+use crate::{
+	pallet::*, ArchivedDealOrder, DealOrderId, DoubleMapExt, Id, TransferId,
+	MAX_ARCHIVED_PER_BLOCK, MAX_ARCHIVE_SWEEP_BLOCKS,
+};
+use frame_support::weights::Weight;
+use sp_runtime::traits::{CheckedSub, Hash, One};
+use sp_std::prelude::*;
+
+impl<T: Config> Pallet<T> {
+	/// Queues a deal order that was just closed or exempted, it is archived once it is older
+	/// than the retention period.
+	pub fn schedule_archival(deal_order_id: &DealOrderId<T::BlockNumber, T::Hash>) {
+		let now = Self::block_number();
+		ClosedDealOrders::<T>::insert(now, deal_order_id, ());
+		if !ArchiveCursor::<T>::exists() {
+			ArchiveCursor::<T>::put(now);
+		}
+	}
+
+	/// Whether a transfer with this id has been registered, even if it was removed since
+	/// together with its archived deal order.
+	pub fn is_transfer_registered(transfer_id: &TransferId<T::Hash>) -> bool {
+		Transfers::<T>::contains_key(transfer_id)
+			|| ArchivedTransfers::<T>::contains_key(transfer_id)
+	}
+
+	/// Archives the deal orders that were closed or exempted at least `DealOrderRetention`
+	/// blocks before `head`. At most `MAX_ARCHIVED_PER_BLOCK` deal orders and transfers are
+	/// removed per call, the next call resumes where this one stopped, even halfway through the
+	/// transfers of a deal order.
+	pub fn archive_closed_deal_orders(head: T::BlockNumber) -> Weight {
+		let (retention, mut cursor) =
+			match (Self::deal_order_retention(), ArchiveCursor::<T>::get()) {
+				(Some(retention), Some(cursor)) => (retention, cursor),
+				_ => return <T as Config>::WeightInfo::archive_deal_orders(0, 0, 0),
+			};
+		let closed_before = match head.checked_sub(&retention) {
+			Some(block) => block,
+			None => return <T as Config>::WeightInfo::archive_deal_orders(0, 0, 0),
+		};
+
+		let (mut blocks, mut deals, mut transfers) = (0u32, 0u32, 0u32);
+		while cursor <= closed_before
+			&& blocks < MAX_ARCHIVE_SWEEP_BLOCKS
+			&& deals.saturating_add(transfers) < MAX_ARCHIVED_PER_BLOCK
+		{
+			blocks = blocks.saturating_add(1);
+
+			let mut swept_block = true;
+			while let Some(deal_order_id) = ClosedDealOrders::<T>::iter_key_prefix(cursor).next() {
+				// one unit of the budget is kept for the deal order itself
+				let budget = MAX_ARCHIVED_PER_BLOCK
+					.saturating_sub(deals.saturating_add(transfers))
+					.saturating_sub(1);
+				transfers = transfers
+					.saturating_add(Self::archive_deal_order_transfers(&deal_order_id, budget));
+				if DealOrderTransfers::<T>::iter_key_prefix(&deal_order_id).next().is_some() {
+					swept_block = false;
+					break;
+				}
+
+				ClosedDealOrders::<T>::remove(cursor, &deal_order_id);
+				deals = deals.saturating_add(1);
+				Self::archive_deal_order(&deal_order_id, head);
+			}
+
+			if swept_block {
+				cursor = cursor + One::one();
+			}
+		}
+
+		ArchiveCursor::<T>::put(cursor);
+
+		<T as Config>::WeightInfo::archive_deal_orders(blocks, deals, transfers)
+	}
+
+	/// Removes up to `limit` of the transfers registered for a deal order that is being archived,
+	/// remembering their ids. Returns the number of transfers removed.
+	fn archive_deal_order_transfers(
+		deal_order_id: &DealOrderId<T::BlockNumber, T::Hash>,
+		limit: u32,
+	) -> u32 {
+		let transfer_ids: Vec<_> = DealOrderTransfers::<T>::iter_key_prefix(deal_order_id)
+			.take(limit as usize)
+			.collect();
+		for transfer_id in &transfer_ids {
+			DealOrderTransfers::<T>::remove(deal_order_id, transfer_id);
+			if let Some(transfer) = Transfers::<T>::take(transfer_id) {
+				ArchivedTransfers::<T>::insert(transfer_id, ());
+				Self::deposit_event(Event::<T>::TransferArchived(transfer_id.clone(), transfer));
+			}
+		}
+		transfer_ids.len() as u32
+	}
+
+	/// Replaces a deal order, whose transfers have already been removed, with its archived
+	/// record.
+	fn archive_deal_order(
+		deal_order_id: &DealOrderId<T::BlockNumber, T::Hash>,
+		head: T::BlockNumber,
+	) {
+		let deal_order =
+			match DealOrders::<T>::take(deal_order_id.expiration(), deal_order_id.hash()) {
+				Some(deal_order) => deal_order,
+				None => return,
+			};
+
+		Self::unindex_deal_order(deal_order_id, &deal_order);
+		Self::remove_deal_order_shares(deal_order_id);
+		DealOrderTransferNonces::<T>::remove(deal_order_id.expiration(), deal_order_id.hash());
+
+		ArchivedDealOrders::<T>::insert_id(
+			deal_order_id.clone(),
+			ArchivedDealOrder {
+				borrower: deal_order.borrower.clone(),
+				lender_address_id: deal_order.lender_address_id.clone(),
+				status: deal_order.status,
+				principal: deal_order.terms.amount,
+				repaid_amount: deal_order.repaid_amount,
+				deal_order_hash: T::Hashing::hash_of(&deal_order),
+				archived_at: head,
+			},
+		);
+		Self::deposit_event(Event::<T>::DealOrderArchived(deal_order_id.clone(), deal_order));
+	}
+}
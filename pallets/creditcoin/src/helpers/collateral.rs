@@ -0,0 +1,39 @@
+use crate::{pallet::*, DealOrderId, Error, Id, LoanTerms};
+use frame_support::traits::ReservableCurrency;
+use sp_runtime::traits::{UniqueSaturatedFrom, Zero};
+
+impl<T: Config> Pallet<T> {
+	/// Reserves the native collateral required by `terms` from the borrower of a new deal order.
+	pub fn reserve_collateral(
+		deal_order_id: &DealOrderId<T::BlockNumber, T::Hash>,
+		borrower: &T::AccountId,
+		terms: &LoanTerms,
+	) -> Result<(), Error<T>> {
+		let amount = T::Balance::unique_saturated_from(terms.collateral);
+		if amount.is_zero() {
+			return Ok(());
+		}
+
+		<pallet_balances::Pallet<T> as ReservableCurrency<T::AccountId>>::reserve(borrower, amount)
+			.map_err(|_| Error::<T>::InsufficientCollateral)?;
+		DealOrderCollateral::<T>::insert(deal_order_id.expiration(), deal_order_id.hash(), amount);
+
+		Ok(())
+	}
+
+	/// Hands the collateral of a deal order back to its borrower, unless there is none left
+	/// because it was never posted or the lender already claimed it.
+	pub fn release_collateral(
+		deal_order_id: &DealOrderId<T::BlockNumber, T::Hash>,
+		borrower: &T::AccountId,
+	) {
+		if let Some(amount) =
+			DealOrderCollateral::<T>::take(deal_order_id.expiration(), deal_order_id.hash())
+		{
+			<pallet_balances::Pallet<T> as ReservableCurrency<T::AccountId>>::unreserve(
+				borrower, amount,
+			);
+			Self::deposit_event(Event::<T>::CollateralReleased(deal_order_id.clone(), amount));
+		}
+	}
+}
@@ -0,0 +1,42 @@
+use super::DealOrderFor;
+use crate::{pallet::*, Blockchain, CreditRecord, DealOrderStatus};
+use sp_std::prelude::*;
+
+impl<T: Config> Pallet<T> {
+	/// Updates the credit record of the borrower of a deal order that was just closed, exempted
+	/// or defaulted. A defaulted loan is already counted, so repaying it later only records a
+	/// late repayment. Deal orders that were never funded are not loans and are ignored.
+	pub fn record_loan_outcome(deal_order: &DealOrderFor<T>, previous_status: DealOrderStatus) {
+		if deal_order.funding_transfer_id.is_none() {
+			return;
+		}
+
+		let already_counted = previous_status == DealOrderStatus::Defaulted;
+		let on_time = !already_counted && Self::timestamp() <= deal_order.due_timestamp();
+
+		CreditHistory::<T>::mutate(&deal_order.borrower, &deal_order.blockchain, |record| {
+			if !already_counted {
+				record.loans = record.loans.saturating_add(1);
+				record.volume = record.volume.saturating_add(deal_order.terms.amount);
+			}
+
+			match deal_order.status {
+				DealOrderStatus::Closed if on_time => {
+					record.on_time_repayments = record.on_time_repayments.saturating_add(1);
+				},
+				DealOrderStatus::Closed => {
+					record.late_repayments = record.late_repayments.saturating_add(1);
+				},
+				DealOrderStatus::Defaulted => {
+					record.defaults = record.defaults.saturating_add(1);
+				},
+				_ => {},
+			}
+		});
+	}
+
+	/// The credit records of an account, one for each blockchain it borrowed on.
+	pub fn credit_history(account_id: &T::AccountId) -> Vec<(Blockchain, CreditRecord)> {
+		CreditHistory::<T>::iter_prefix(account_id).collect()
+	}
+}
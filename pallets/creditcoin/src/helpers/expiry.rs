@@ -0,0 +1,128 @@
+use super::DealOrderFor;
+use crate::{
+	pallet::*, AskOrderId, BidOrderId, DealOrderId, Error, ExpirySweep, Id, OfferId,
+	MAX_EXPIRY_SWEEP_BLOCKS, MAX_EXPIRY_SWEEP_ENTRIES,
+};
+use frame_support::{ensure, weights::Weight};
+use sp_runtime::traits::One;
+use sp_std::prelude::*;
+
+impl<T: Config> Pallet<T> {
+	/// Removes orders, offers, terms proposals, repayment orders and unfunded deal orders whose
+	/// expiration block has been reached. At most `MAX_EXPIRY_SWEEP_ENTRIES` entries are handled
+	/// per call, the next call resumes where this one stopped, catching up on earlier expiration
+	/// blocks first.
+	pub fn sweep_expired_entries(head: T::BlockNumber) -> Weight {
+		let mut cursor =
+			ExpiryCursor::<T>::get().unwrap_or(ExpirySweep { block: head, last_deal_order: None });
+
+		let (mut asks, mut bids, mut offers, mut proposals) = (0u32, 0u32, 0u32, 0u32);
+		let mut repayments = 0u32;
+		let (mut deals, mut funded_deals, mut blocks) = (0u32, 0u32, 0u32);
+		let mut used = 0u32;
+
+		while cursor.block <= head
+			&& blocks < MAX_EXPIRY_SWEEP_BLOCKS
+			&& used < MAX_EXPIRY_SWEEP_ENTRIES
+		{
+			blocks = blocks.saturating_add(1);
+			let block = cursor.block;
+
+			for (hash, ask_order) in
+				AskOrders::<T>::drain_prefix(block).take((MAX_EXPIRY_SWEEP_ENTRIES - used) as usize)
+			{
+				asks = asks.saturating_add(1);
+				used = used.saturating_add(1);
+				let ask_order_id = AskOrderId::with_expiration_hash::<T>(block, hash);
+				AccountAskOrders::<T>::remove(&ask_order.lender, &ask_order_id);
+			}
+
+			for (hash, bid_order) in
+				BidOrders::<T>::drain_prefix(block).take((MAX_EXPIRY_SWEEP_ENTRIES - used) as usize)
+			{
+				bids = bids.saturating_add(1);
+				used = used.saturating_add(1);
+				let bid_order_id = BidOrderId::with_expiration_hash::<T>(block, hash);
+				AccountBidOrders::<T>::remove(&bid_order.borrower, &bid_order_id);
+			}
+
+			for (hash, offer) in
+				Offers::<T>::drain_prefix(block).take((MAX_EXPIRY_SWEEP_ENTRIES - used) as usize)
+			{
+				offers = offers.saturating_add(1);
+				used = used.saturating_add(1);
+				let offer_id = OfferId::with_expiration_hash::<T>(block, hash);
+				AccountOffers::<T>::remove(&offer.lender, &offer_id);
+			}
+
+			let removed = TermsProposals::<T>::drain_prefix(block)
+				.take((MAX_EXPIRY_SWEEP_ENTRIES - used) as usize)
+				.count() as u32;
+			proposals = proposals.saturating_add(removed);
+			used = used.saturating_add(removed);
+
+			let removed = RepaymentOrders::<T>::drain_prefix(block)
+				.take((MAX_EXPIRY_SWEEP_ENTRIES - used) as usize)
+				.count() as u32;
+			repayments = repayments.saturating_add(removed);
+			used = used.saturating_add(removed);
+
+			let limit = (MAX_EXPIRY_SWEEP_ENTRIES - used) as usize;
+			let deal_orders: Vec<(T::Hash, DealOrderFor<T>)> = match cursor.last_deal_order {
+				Some(last) => {
+					DealOrders::<T>::iter_from(DealOrders::<T>::hashed_key_for(block, last))
+						.take_while(|(expiration, _, _)| *expiration == block)
+						.map(|(_, hash, deal_order)| (hash, deal_order))
+						.take(limit)
+						.collect()
+				},
+				None => DealOrders::<T>::iter_prefix(block).take(limit).collect(),
+			};
+			let swept_block = deal_orders.len() < limit;
+
+			for (hash, deal_order) in deal_orders {
+				used = used.saturating_add(1);
+				cursor.last_deal_order = Some(hash);
+				if deal_order.funding_transfer_id.is_some() {
+					funded_deals = funded_deals.saturating_add(1);
+					continue;
+				}
+
+				deals = deals.saturating_add(1);
+				let deal_order_id = DealOrderId::with_expiration_hash::<T>(block, hash);
+				DealOrders::<T>::remove(block, hash);
+				Self::release_collateral(&deal_order_id, &deal_order.borrower);
+				Self::unindex_deal_order(&deal_order_id, &deal_order);
+				Self::remove_deal_order_shares(&deal_order_id);
+			}
+
+			if swept_block {
+				cursor = ExpirySweep { block: block + One::one(), last_deal_order: None };
+			}
+		}
+
+		ExpiryCursor::<T>::put(cursor);
+
+		<T as Config>::WeightInfo::on_initialize(
+			asks,
+			bids,
+			offers,
+			deals,
+			funded_deals,
+			proposals,
+			repayments,
+			blocks,
+		)
+	}
+
+	/// Looks up a deal order that has not expired unfunded, even if the expiry sweep has not
+	/// removed it yet.
+	pub fn try_get_deal_order(
+		deal_order_id: &DealOrderId<T::BlockNumber, T::Hash>,
+	) -> Result<DealOrderFor<T>, Error<T>> {
+		let deal_order = Self::deal_orders(deal_order_id.expiration(), deal_order_id.hash())
+			.ok_or(Error::<T>::NonExistentDealOrder)?;
+		ensure!(!deal_order.has_expired(Self::block_number()), Error::<T>::DealOrderExpired);
+		Ok(deal_order)
+	}
+}
@@ -1,26 +1,27 @@
-use crate::{Blockchain, ExternalAddress};
-use base58::FromBase58;
+use crate::{types::AddressFormat, ExternalAddress};
+use alloc::string::String;
+use base58::{FromBase58, ToBase58};
+use bitcoin_bech32::WitnessProgram;
 use core::convert::TryFrom;
 use frame_support::BoundedVec;
+use ripemd::{Digest, Ripemd160};
 use sp_core::ecdsa::Public;
 use sp_io::hashing::keccak_256;
 use sp_io::hashing::sha2_256;
+use sp_std::prelude::*;
 
 pub fn generate_external_address(
-	blockchain: &Blockchain,
+	format: &AddressFormat,
 	// TODO: refactor: remove `reference` as a parameter for generate_external_address
 	// It's very suspicious to be giving the external address to the function that's meant to generate it.
 	reference: &ExternalAddress,
 	public_key: Public,
 ) -> Option<ExternalAddress> {
-	match blockchain {
-		Blockchain::Luniverse | Blockchain::Ethereum | Blockchain::Rinkeby
-			if EVMAddress::try_extract_address_type(reference).is_some() =>
-		{
+	match format {
+		AddressFormat::Evm if EVMAddress::try_extract_address_type(reference).is_some() => {
 			Some(EVMAddress::from_public(&public_key))
 		},
-		Blockchain::Bitcoin => None,
-		Blockchain::Other(_) => None,
+		AddressFormat::Bitcoin => BTCAddress::from_public_key(reference, public_key.as_ref()),
 		_ => None,
 	}
 }
@@ -53,13 +54,86 @@ impl PublicToAddress for EVMAddress {
 	}
 }
 
-pub fn address_is_well_formed(blockchain: &Blockchain, address: &ExternalAddress) -> bool {
-	match blockchain {
-		Blockchain::Bitcoin => btc_address_is_well_formed(address),
-		Blockchain::Ethereum | Blockchain::Luniverse | Blockchain::Rinkeby => {
-			eth_address_is_well_formed(address)
+pub struct BTCAddress;
+
+impl BTCAddress {
+	/// Derives the address of the same kind and network as `reference` that pays to the SEC1
+	/// encoded `public_key`, i.e. a P2PKH, P2SH-P2WPKH or P2WPKH address. The segwit kinds
+	/// require a compressed key.
+	pub fn from_public_key(reference: &[u8], public_key: &[u8]) -> Option<ExternalAddress> {
+		let reference = core::str::from_utf8(reference).ok()?;
+		let compressed = public_key.len() == 33;
+		let key_hash = hash160(public_key);
+
+		let address = if let Ok(witness_program) = WitnessProgram::from_address(reference) {
+			let is_p2wpkh = witness_program.version().to_u8() == 0
+				&& witness_program.program().len() == BTC_HASH_LENGTH;
+			if !is_p2wpkh || !compressed {
+				return None;
+			}
+			WitnessProgram::new(
+				witness_program.version(),
+				key_hash.to_vec(),
+				witness_program.network(),
+			)
+			.ok()?
+			.to_address()
+		} else {
+			let version = *reference.from_base58().ok()?.first()?;
+			let payload = match version {
+				BTC_P2PKH_MAINNET | BTC_P2PKH_TESTNET => key_hash,
+				// the script hash of a version 0 witness program paying to the key hash
+				BTC_P2SH_MAINNET | BTC_P2SH_TESTNET if compressed => {
+					let mut redeem_script = vec![0x00, BTC_HASH_LENGTH as u8];
+					redeem_script.extend_from_slice(&key_hash);
+					hash160(&redeem_script)
+				},
+				_ => return None,
+			};
+			base58_check(version, &payload)
+		};
+
+		BoundedVec::try_from(address.into_bytes()).ok()
+	}
+}
+
+pub fn address_is_well_formed(format: &AddressFormat, address: &ExternalAddress) -> bool {
+	match format {
+		AddressFormat::Bitcoin => btc_address_is_well_formed(address),
+		AddressFormat::Evm => eth_address_is_well_formed(address),
+	}
+}
+
+/// The canonical form of an external address, which is hashed into its `AddressId` and stored:
+/// the 20 raw bytes of an EVM address, which may also be given hex encoded in any letter case,
+/// and lowercase bech32 for bitcoin. Base58 is case sensitive, so a base58 bitcoin address is
+/// canonical as it is once its checksum is verified by `address_is_well_formed`. Returns `None`
+/// when `address` is already canonical or is not recognized.
+pub fn canonical_address(format: &AddressFormat, address: &[u8]) -> Option<Vec<u8>> {
+	match format {
+		AddressFormat::Evm => {
+			let hex = address
+				.strip_prefix(b"0x")
+				.or_else(|| address.strip_prefix(b"0X"))
+				.unwrap_or(address);
+			if hex.len() != 2 * ETH_ADDRESS_LENGTH {
+				return None;
+			}
+			hex::decode(hex).ok()
+		},
+		AddressFormat::Bitcoin => {
+			let address_str = core::str::from_utf8(address).ok()?;
+			let canonical = WitnessProgram::from_address(address_str).ok()?.to_address();
+			(canonical.as_bytes() != address).then(|| canonical.into_bytes())
 		},
-		Blockchain::Other(_) => false,
+	}
+}
+
+/// Replaces `address` with its canonical form, see `canonical_address`.
+pub fn normalize_address(format: &AddressFormat, address: ExternalAddress) -> ExternalAddress {
+	match canonical_address(format, &address).map(ExternalAddress::try_from) {
+		Some(Ok(canonical)) => canonical,
+		_ => address,
 	}
 }
 
@@ -100,6 +174,25 @@ fn btc_address_is_well_formed(address: &[u8]) -> bool {
 	true
 }
 
+const BTC_HASH_LENGTH: usize = 20;
+const BTC_P2PKH_MAINNET: u8 = 0x00;
+const BTC_P2SH_MAINNET: u8 = 0x05;
+const BTC_P2PKH_TESTNET: u8 = 0x6f;
+const BTC_P2SH_TESTNET: u8 = 0xc4;
+
+fn hash160(bytes: &[u8]) -> [u8; BTC_HASH_LENGTH] {
+	Ripemd160::digest(sha2_256(bytes)).into()
+}
+
+fn base58_check(version: u8, payload: &[u8]) -> String {
+	let mut bytes = Vec::with_capacity(payload.len() + 5);
+	bytes.push(version);
+	bytes.extend_from_slice(payload);
+	let checksum = sha2_256(&sha2_256(&bytes));
+	bytes.extend_from_slice(&checksum[0..4]);
+	bytes.to_base58()
+}
+
 // ether-like
 
 const ETH_ADDRESS_LENGTH: usize = 20;
@@ -109,8 +202,7 @@ fn eth_address_is_well_formed(address: &[u8]) -> bool {
 }
 #[cfg(test)]
 mod tests {
-	use core::convert::{TryFrom, TryInto};
-	use frame_support::BoundedVec;
+	use core::convert::TryInto;
 	use sp_core::Pair;
 
 	use super::*;
@@ -173,24 +265,118 @@ mod tests {
 
 	#[test]
 	fn address_is_well_formed_works() {
-		let ethereum = Blockchain::Ethereum;
-		let bitcoin = Blockchain::Bitcoin;
-		let rinkeby = Blockchain::Rinkeby;
-		let luniverse = Blockchain::Luniverse;
-		let other = Blockchain::Other(BoundedVec::try_from(b"other".to_vec()).unwrap());
-
 		let eth_addr = hex::decode("5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed")
 			.unwrap()
 			.try_into()
 			.unwrap();
 		let btc_addr = b"1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa".to_vec().try_into().unwrap();
 
-		assert!(address_is_well_formed(&ethereum, &eth_addr));
-		assert!(address_is_well_formed(&rinkeby, &eth_addr));
-		assert!(address_is_well_formed(&luniverse, &eth_addr));
-		assert!(address_is_well_formed(&bitcoin, &btc_addr));
-		assert!(!address_is_well_formed(&other, &eth_addr));
-		assert!(!address_is_well_formed(&other, &btc_addr));
+		assert!(address_is_well_formed(&AddressFormat::Evm, &eth_addr));
+		assert!(address_is_well_formed(&AddressFormat::Bitcoin, &btc_addr));
+		assert!(!address_is_well_formed(&AddressFormat::Evm, &btc_addr));
+		assert!(!address_is_well_formed(&AddressFormat::Bitcoin, &eth_addr));
+	}
+
+	#[test]
+	fn normalize_address_works() {
+		let normalize = |format: &AddressFormat, address: &[u8]| {
+			normalize_address(format, address.to_vec().try_into().unwrap()).into_inner()
+		};
+		let ethereum = AddressFormat::Evm;
+		let bitcoin = AddressFormat::Bitcoin;
+
+		let eth_addr = hex::decode("5aaeb6053f3e94c9b9a09f33669435e7ef1beaed").unwrap();
+		assert_eq!(normalize(&ethereum, &eth_addr), eth_addr);
+		assert_eq!(normalize(&ethereum, b"0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"), eth_addr);
+		assert_eq!(normalize(&ethereum, b"0X5AAEB6053F3E94C9B9A09F33669435E7EF1BEAED"), eth_addr);
+		assert_eq!(normalize(&ethereum, b"5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"), eth_addr);
+		// not hex
+		assert_eq!(
+			normalize(&ethereum, b"0xZaAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"),
+			b"0xZaAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"
+		);
+
+		let segwit = b"bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4";
+		assert_eq!(normalize(&bitcoin, segwit), segwit);
+		assert_eq!(normalize(&bitcoin, b"BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4"), segwit);
+		// mixed case is not valid bech32
+		assert_eq!(
+			normalize(&bitcoin, b"bc1qW508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"),
+			b"bc1qW508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+		);
+		assert_eq!(
+			normalize(&bitcoin, b"1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa"),
+			b"1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa"
+		);
+
+		// bitcoin addresses aren't hex
+		assert_eq!(
+			normalize(&bitcoin, b"0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"),
+			b"0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"
+		);
+	}
+
+	#[test]
+	#[allow(non_snake_case)]
+	fn BTCAddress_from_public_key_works() {
+		// the generator point, i.e. the key of the secret 1
+		let compressed =
+			hex::decode("0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798")
+				.unwrap();
+		let uncompressed = hex::decode("0479be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8").unwrap();
+
+		let derive = |reference: &[u8], public_key: &[u8]| {
+			BTCAddress::from_public_key(reference, public_key).map(|address| address.into_inner())
+		};
+		let expect = |address: &[u8]| Some(address.to_vec());
+
+		// p2pkh
+		assert_eq!(
+			derive(b"1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa", &compressed),
+			expect(b"1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH")
+		);
+		assert_eq!(
+			derive(b"1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa", &uncompressed),
+			expect(b"1EHNa6Q4Jz2uvNExL497mE43ikXhwF6kZm")
+		);
+
+		// p2sh-p2wpkh
+		assert_eq!(
+			derive(b"3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLy", &compressed),
+			expect(b"3JvL6Ymt8MVWiCNHC7oWU6nLeHNJKLZGLN")
+		);
+		assert_eq!(derive(b"3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLy", &uncompressed), None);
+
+		// p2wpkh
+		assert_eq!(
+			derive(b"bc1qnkyhslv83yyp0q0suxw0uj3lg9drgqq9c0auzc", &compressed),
+			expect(b"bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4")
+		);
+		assert_eq!(derive(b"bc1qnkyhslv83yyp0q0suxw0uj3lg9drgqq9c0auzc", &uncompressed), None);
+
+		// testnet
+		assert_eq!(
+			derive(b"mrCDrCybB6J1vRfbwM5hemdJz73FwDBC8r", &compressed),
+			expect(b"mrCDrCybB6J1vRfbwM5hemdJz73FwDBC8r")
+		);
+		assert_eq!(
+			derive(b"2NAUYAHhujozruyzpsFRP63mbrdaU5wnEpN", &compressed),
+			expect(b"2NAUYAHhujozruyzpsFRP63mbrdaU5wnEpN")
+		);
+		assert_eq!(
+			derive(b"tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx", &compressed),
+			expect(b"tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx")
+		);
+
+		// taproot and malformed addresses
+		assert_eq!(
+			derive(
+				b"bc1pw508d6qejxtdg4y5r3zarvary0c5xw7kw508d6qejxtdg4y5r3zarvary0c5xw7kt5nd6y",
+				&compressed
+			),
+			None
+		);
+		assert_eq!(derive(b"not an address", &compressed), None);
 	}
 
 	#[test]
@@ -0,0 +1,20 @@
+use crate::{pallet::*, types::ExternalChain, Blockchain, Error, TransferKind};
+use frame_support::ensure;
+
+impl<T: Config> Pallet<T> {
+	/// The registry entry of `blockchain`, which may be deprecated.
+	pub fn external_chain(blockchain: &Blockchain) -> Result<ExternalChain, Error<T>> {
+		ExternalChains::<T>::get(blockchain).ok_or(Error::<T>::UnsupportedChain)
+	}
+
+	/// The registry entry of `blockchain`, as long as it still takes new addresses and orders.
+	pub fn active_external_chain(blockchain: &Blockchain) -> Result<ExternalChain, Error<T>> {
+		let chain = Self::external_chain(blockchain)?;
+		ensure!(!chain.deprecated, Error::<T>::ChainDeprecated);
+		Ok(chain)
+	}
+
+	pub fn supports_transfer_kind(blockchain: &Blockchain, kind: &TransferKind) -> bool {
+		ExternalChains::<T>::get(blockchain).map_or(false, |chain| chain.supports(kind))
+	}
+}
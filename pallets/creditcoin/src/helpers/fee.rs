@@ -0,0 +1,37 @@
+use super::DealOrderFor;
+use crate::{pallet::*, DealOrderId, Error, FeePayer};
+use frame_support::traits::{tokens::currency::Currency, ExistenceRequirement};
+use sp_runtime::traits::Zero;
+
+impl<T: Config> Pallet<T> {
+	/// Charges the configured origination fee for a deal order that is being funded. The fee is
+	/// paid to the fee collector by the borrower or by the lender of the deal order.
+	pub fn charge_origination_fee(
+		deal_order_id: &DealOrderId<T::BlockNumber, T::Hash>,
+		deal_order: &DealOrderFor<T>,
+	) -> Result<(), Error<T>> {
+		let origination_fee = match Self::origination_fee() {
+			Some(origination_fee) => origination_fee,
+			None => return Ok(()),
+		};
+		let fee = origination_fee.rate.fee(deal_order.terms.amount);
+		if fee.is_zero() {
+			return Ok(());
+		}
+
+		let payer = match origination_fee.payer {
+			FeePayer::Borrower => deal_order.borrower.clone(),
+			FeePayer::Lender => Self::get_address(&deal_order.lender_address_id)?.owner,
+		};
+		<pallet_balances::Pallet<T> as Currency<T::AccountId>>::transfer(
+			&payer,
+			&origination_fee.collector,
+			fee,
+			ExistenceRequirement::KeepAlive,
+		)
+		.map_err(|_| Error::<T>::InsufficientBalanceForFee)?;
+
+		Self::deposit_event(Event::<T>::OriginationFeeCharged(deal_order_id.clone(), payer, fee));
+		Ok(())
+	}
+}
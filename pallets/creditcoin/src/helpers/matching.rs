@@ -0,0 +1,268 @@
+use crate::{
+	pallet::*, AskOrderId, BidOrderId, DoubleMapExt, Id, MatchableOrderId, Offer, OfferId,
+	MAX_MATCHES_PER_BLOCK, MAX_MATCH_CANDIDATES,
+};
+use core::cmp::Ordering;
+use frame_support::{weights::Weight, BoundedVec};
+use sp_std::prelude::*;
+
+type AskOrderFor<T> = crate::AskOrder<
+	<T as frame_system::Config>::AccountId,
+	<T as frame_system::Config>::BlockNumber,
+	<T as frame_system::Config>::Hash,
+>;
+
+type BidOrderFor<T> = crate::BidOrder<
+	<T as frame_system::Config>::AccountId,
+	<T as frame_system::Config>::BlockNumber,
+	<T as frame_system::Config>::Hash,
+>;
+
+impl<T: Config> Pallet<T> {
+	/// Adds a new ask order to the order book of its blockchain and currency and queues it for
+	/// matching. The order goes after every order with a lower or equal rate. If the book is full
+	/// the order with the highest rate drops out, or the new one if no order has a higher rate,
+	/// and an order left out of the book is not matched automatically.
+	pub fn enlist_ask_order(
+		ask_order_id: &AskOrderId<T::BlockNumber, T::Hash>,
+		ask_order: &AskOrderFor<T>,
+	) {
+		let head = Self::block_number();
+		let rate = &ask_order.terms.min_interest_rate;
+		let listed =
+			AskOrderBook::<T>::mutate(&ask_order.blockchain, &ask_order.terms.currency, |book| {
+				book.retain(|(_, id)| id.expiration() > head);
+				let position = book
+					.iter()
+					.position(|(book_rate, _)| rate.compare(book_rate) == Some(Ordering::Less))
+					.unwrap_or(book.len());
+				book.force_insert_keep_left(position, (rate.clone(), ask_order_id.clone()))
+					.is_ok()
+			});
+
+		if listed {
+			let _ = PendingMatches::<T>::try_append(MatchableOrderId::Ask(ask_order_id.clone()));
+		}
+	}
+
+	/// Adds a new bid order to the end of the order book of its blockchain and currency and
+	/// queues it for matching. If the book is full the order is left out of it and is not
+	/// matched automatically.
+	pub fn enlist_bid_order(
+		bid_order_id: &BidOrderId<T::BlockNumber, T::Hash>,
+		bid_order: &BidOrderFor<T>,
+	) {
+		let head = Self::block_number();
+		let listed =
+			BidOrderBook::<T>::mutate(&bid_order.blockchain, &bid_order.terms.currency, |book| {
+				book.retain(|id| id.expiration() > head);
+				book.try_push(bid_order_id.clone()).is_ok()
+			});
+
+		if listed {
+			let _ = PendingMatches::<T>::try_append(MatchableOrderId::Bid(bid_order_id.clone()));
+		}
+	}
+
+	/// Takes an ask order off the order book, e.g. once it was matched or cancelled.
+	pub fn delist_ask_order(
+		ask_order_id: &AskOrderId<T::BlockNumber, T::Hash>,
+		ask_order: &AskOrderFor<T>,
+	) {
+		AskOrderBook::<T>::mutate(&ask_order.blockchain, &ask_order.terms.currency, |book| {
+			book.retain(|(_, id)| id != ask_order_id)
+		});
+	}
+
+	/// Takes a bid order off the order book, e.g. once it was matched or cancelled.
+	pub fn delist_bid_order(
+		bid_order_id: &BidOrderId<T::BlockNumber, T::Hash>,
+		bid_order: &BidOrderFor<T>,
+	) {
+		BidOrderBook::<T>::mutate(&bid_order.blockchain, &bid_order.terms.currency, |book| {
+			book.retain(|id| id != bid_order_id)
+		});
+	}
+
+	/// Matches up to `MAX_MATCHES_PER_BLOCK` pending orders against the head of the other side
+	/// of the order book of the same blockchain and currency, considering at most
+	/// `MAX_MATCH_CANDIDATES` orders each. A bid order is matched with the first ask order that
+	/// fits it, i.e. the one offering the lowest rate, an ask order with the oldest bid order it
+	/// can fund. Every match creates an offer the borrower can accept with `add_deal_order`.
+	pub fn match_pending_orders() -> Weight {
+		if !Self::order_matching_enabled() {
+			return <T as Config>::WeightInfo::match_pending_orders(0, 0);
+		}
+
+		let mut pending = PendingMatches::<T>::get().into_inner();
+		let count = pending.len().min(MAX_MATCHES_PER_BLOCK as usize);
+		let mut candidates = 0u32;
+
+		for order_id in pending.drain(..count) {
+			candidates = candidates.saturating_add(match order_id {
+				MatchableOrderId::Ask(ask_order_id) => Self::match_ask_order(ask_order_id),
+				MatchableOrderId::Bid(bid_order_id) => Self::match_bid_order(bid_order_id),
+			});
+		}
+
+		if count > 0 {
+			PendingMatches::<T>::put(BoundedVec::truncate_from(pending));
+		}
+
+		<T as Config>::WeightInfo::match_pending_orders(count as u32, candidates)
+	}
+
+	/// Looks up a pending ask order, as long as it is still in the order book and does not
+	/// expire before an offer for it could be accepted.
+	fn matchable_ask_order(
+		ask_order_id: &AskOrderId<T::BlockNumber, T::Hash>,
+	) -> Option<AskOrderFor<T>> {
+		let head = Self::block_number();
+		let ask_order = AskOrders::<T>::try_get_id(ask_order_id).ok()?;
+		let listed = AskOrderBook::<T>::get(&ask_order.blockchain, &ask_order.terms.currency)
+			.iter()
+			.any(|(_, id)| id == ask_order_id);
+		(listed && ask_order.expiration_block > head).then_some(ask_order)
+	}
+
+	fn matchable_bid_order(
+		bid_order_id: &BidOrderId<T::BlockNumber, T::Hash>,
+	) -> Option<BidOrderFor<T>> {
+		let head = Self::block_number();
+		let bid_order = BidOrders::<T>::try_get_id(bid_order_id).ok()?;
+		let listed = BidOrderBook::<T>::get(&bid_order.blockchain, &bid_order.terms.currency)
+			.contains(bid_order_id);
+		(listed && bid_order.expiration_block > head).then_some(bid_order)
+	}
+
+	/// Returns the number of candidate orders read. Expired orders met on the way are dropped
+	/// from the book.
+	fn match_ask_order(ask_order_id: AskOrderId<T::BlockNumber, T::Hash>) -> u32 {
+		let ask_order = match Self::matchable_ask_order(&ask_order_id) {
+			Some(ask_order) => ask_order,
+			None => return 0,
+		};
+
+		let head = Self::block_number();
+		let book = BidOrderBook::<T>::get(&ask_order.blockchain, &ask_order.terms.currency);
+		let mut read = 0u32;
+		let mut stale = Vec::new();
+		let mut found = None;
+		for bid_order_id in book {
+			if read >= MAX_MATCH_CANDIDATES {
+				break;
+			}
+			if bid_order_id.expiration() <= head {
+				stale.push(bid_order_id);
+				continue;
+			}
+
+			read += 1;
+			let bid_order = match BidOrders::<T>::try_get_id(&bid_order_id) {
+				Ok(bid_order) => bid_order,
+				Err(()) => {
+					stale.push(bid_order_id);
+					continue;
+				},
+			};
+			if bid_order.borrower != ask_order.lender
+				&& ask_order.terms.match_with(&bid_order.terms)
+			{
+				found = Some((bid_order_id, bid_order));
+				break;
+			}
+		}
+
+		if !stale.is_empty() {
+			BidOrderBook::<T>::mutate(&ask_order.blockchain, &ask_order.terms.currency, |book| {
+				book.retain(|id| !stale.contains(id))
+			});
+		}
+		if let Some((bid_order_id, bid_order)) = found {
+			Self::add_matched_offer(ask_order_id, ask_order, bid_order_id, bid_order);
+		}
+
+		read
+	}
+
+	/// Returns the number of candidate orders read. Expired orders met on the way are dropped
+	/// from the book.
+	fn match_bid_order(bid_order_id: BidOrderId<T::BlockNumber, T::Hash>) -> u32 {
+		let bid_order = match Self::matchable_bid_order(&bid_order_id) {
+			Some(bid_order) => bid_order,
+			None => return 0,
+		};
+
+		let head = Self::block_number();
+		let book = AskOrderBook::<T>::get(&bid_order.blockchain, &bid_order.terms.currency);
+		let mut read = 0u32;
+		let mut stale = Vec::new();
+		let mut found = None;
+		for (_, ask_order_id) in book {
+			if read >= MAX_MATCH_CANDIDATES {
+				break;
+			}
+			if ask_order_id.expiration() <= head {
+				stale.push(ask_order_id);
+				continue;
+			}
+
+			read += 1;
+			let ask_order = match AskOrders::<T>::try_get_id(&ask_order_id) {
+				Ok(ask_order) => ask_order,
+				Err(()) => {
+					stale.push(ask_order_id);
+					continue;
+				},
+			};
+			if ask_order.lender != bid_order.borrower
+				&& ask_order.terms.match_with(&bid_order.terms)
+			{
+				found = Some((ask_order_id, ask_order));
+				break;
+			}
+		}
+
+		if !stale.is_empty() {
+			AskOrderBook::<T>::mutate(&bid_order.blockchain, &bid_order.terms.currency, |book| {
+				book.retain(|(_, id)| !stale.contains(id))
+			});
+		}
+		if let Some((ask_order_id, ask_order)) = found {
+			Self::add_matched_offer(ask_order_id, ask_order, bid_order_id, bid_order);
+		}
+
+		read
+	}
+
+	/// Takes both orders off the book and offers the loan to the borrower on behalf of the
+	/// lender. The offer expires together with the first of the two orders.
+	fn add_matched_offer(
+		ask_order_id: AskOrderId<T::BlockNumber, T::Hash>,
+		ask_order: AskOrderFor<T>,
+		bid_order_id: BidOrderId<T::BlockNumber, T::Hash>,
+		bid_order: BidOrderFor<T>,
+	) {
+		Self::delist_ask_order(&ask_order_id, &ask_order);
+		Self::delist_bid_order(&bid_order_id, &bid_order);
+
+		let expiration_block = ask_order.expiration_block.min(bid_order.expiration_block);
+		let offer_id = OfferId::new::<T>(expiration_block, &ask_order_id, &bid_order_id);
+		if Offers::<T>::contains_id(&offer_id) {
+			return;
+		}
+
+		let offer = Offer {
+			ask_id: ask_order_id,
+			bid_id: bid_order_id,
+			block: Self::block_number(),
+			blockchain: ask_order.blockchain,
+			expiration_block,
+			lender: ask_order.lender,
+		};
+
+		AccountOffers::<T>::insert(&offer.lender, &offer_id, ());
+		Self::deposit_event(Event::<T>::OfferAdded(offer_id.clone(), offer.clone()));
+		Offers::<T>::insert_id(offer_id, offer);
+	}
+}
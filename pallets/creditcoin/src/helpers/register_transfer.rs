@@ -90,7 +90,10 @@ impl<T: Config> Pallet<T> {
 
 		ensure!(from.blockchain == to.blockchain, Error::<T>::AddressBlockchainMismatch);
 
-		ensure!(from.blockchain.supports(&transfer_kind), Error::<T>::UnsupportedTransferKind);
+		ensure!(
+			Self::supports_transfer_kind(&from.blockchain, &transfer_kind),
+			Error::<T>::UnsupportedTransferKind
+		);
 
 		let transfer = Transfer {
 			blockchain: from.blockchain,
@@ -129,8 +132,9 @@ impl<T: Config> Pallet<T> {
 	) -> Result<(), crate::Error<T>> {
 		let deadline = pending_transfer.deadline;
 
+		// the transfer may have been archived, together with its deal order, since
 		ensure!(
-			!<UnverifiedTransfer<_, _, _, _> as TaskV2::<T>>::is_persisted(task_id),
+			!Self::is_transfer_registered(&TransferId::from(*task_id)),
 			Error::<T>::TransferAlreadyRegistered
 		);
 		ensure!(
@@ -266,4 +270,30 @@ mod tests {
 			assert_eq!(result, crate::Error::<Test>::TransferAlreadyRegistered);
 		})
 	}
+
+	#[test]
+	fn register_transfer_internal_should_error_when_transfer_was_archived() {
+		ExtBuilder::default().build_and_execute(|| {
+			let test_info = TestInfo::new_defaults();
+			let (deal_order_id, deal_order) = test_info.create_deal_order();
+			let tx: ExternalTxId = "0xabcabcabc".as_bytes().into_bounded();
+			crate::ArchivedTransfers::<Test>::insert(
+				TransferId::new::<Test>(&Blockchain::Rinkeby, &tx),
+				(),
+			);
+
+			let result = Creditcoin::register_transfer_internal(
+				test_info.lender.account_id,
+				deal_order.lender_address_id,
+				deal_order.borrower_address_id,
+				TransferKind::Native,
+				deal_order.terms.amount,
+				OrderId::Deal(deal_order_id),
+				tx,
+			)
+			.unwrap_err();
+
+			assert_eq!(result, crate::Error::<Test>::TransferAlreadyRegistered);
+		})
+	}
 }
@@ -0,0 +1,120 @@
+use super::DealOrderFor;
+use crate::{
+	pallet::*, AddressId, DealOrderId, DealOrderStatus, DoubleMapExt, Error, ExternalAmount,
+	LenderShare, OrderId, TransferId,
+};
+use frame_support::ensure;
+use sp_std::prelude::*;
+
+impl<T: Config> Pallet<T> {
+	/// Whether the loan amount of a deal order is split between several lenders. The lead
+	/// lender's share is added together with the first share of another lender.
+	pub fn is_syndicated(
+		deal_order_id: &DealOrderId<T::BlockNumber, T::Hash>,
+		deal_order: &DealOrderFor<T>,
+	) -> bool {
+		DealOrderShares::<T>::contains_key(deal_order_id, &deal_order.lender_address_id)
+	}
+
+	/// The lender address and share of `who` in a syndicated deal order.
+	pub fn lender_share_of(
+		deal_order_id: &DealOrderId<T::BlockNumber, T::Hash>,
+		who: &T::AccountId,
+	) -> Result<(AddressId<T::Hash>, LenderShare<T::Hash>), Error<T>> {
+		DealOrderShares::<T>::iter_prefix(deal_order_id)
+			.find(|(address_id, _)| {
+				Self::addresses(address_id).map_or(false, |address| address.owner == *who)
+			})
+			.ok_or(Error::<T>::NotLender)
+	}
+
+	/// Funds the share of a syndicated deal order that was sent by `transfer_id`. The deal order
+	/// itself becomes funded, and starts accruing interest, once every share has been funded.
+	pub fn fund_deal_order_share(
+		who: &T::AccountId,
+		deal_order_id: &DealOrderId<T::BlockNumber, T::Hash>,
+		transfer_id: &TransferId<T::Hash>,
+	) -> Result<(), Error<T>> {
+		let mut deal_order = Self::try_get_deal_order(deal_order_id)?;
+
+		let now = Self::timestamp();
+		ensure!(now >= deal_order.timestamp, Error::<T>::MalformedDealOrder);
+		ensure!(deal_order.funding_transfer_id.is_none(), Error::<T>::DealOrderAlreadyFunded);
+		let head = Self::block_number();
+		ensure!(deal_order.expiration_block >= head, Error::<T>::DealOrderExpired);
+
+		let mut transfer =
+			Transfers::<T>::try_get(transfer_id).map_err(|()| Error::<T>::NonExistentTransfer)?;
+		ensure!(
+			transfer.order_id == OrderId::Deal(deal_order_id.clone()),
+			Error::<T>::TransferDealOrderMismatch
+		);
+		ensure!(transfer.account_id == *who, Error::<T>::TransferAccountMismatch);
+		ensure!(!transfer.is_processed, Error::<T>::TransferAlreadyProcessed);
+
+		let lender_address_id = transfer.from.clone();
+		let mut share = DealOrderShares::<T>::get(deal_order_id, &lender_address_id)
+			.ok_or(Error::<T>::NotLender)?;
+		ensure!(share.funding_transfer_id.is_none(), Error::<T>::DealOrderAlreadyFunded);
+		ensure!(transfer.amount == share.amount, Error::<T>::TransferAmountMismatch);
+
+		share.funding_transfer_id = Some(transfer_id.clone());
+		DealOrderShares::<T>::insert(deal_order_id, &lender_address_id, share);
+		transfer.is_processed = true;
+		Transfers::<T>::insert(transfer_id, transfer);
+
+		let fully_funded = DealOrderShares::<T>::iter_prefix_values(deal_order_id)
+			.all(|share| share.funding_transfer_id.is_some());
+		if fully_funded {
+			Self::charge_origination_fee(deal_order_id, &deal_order)?;
+			deal_order.funding_transfer_id = Some(transfer_id.clone());
+			deal_order.timestamp = now;
+			deal_order.status = DealOrderStatus::Funded;
+			DealOrders::<T>::insert_id(deal_order_id.clone(), deal_order);
+			Self::deposit_event(Event::<T>::DealOrderFunded(deal_order_id.clone()));
+		} else {
+			Self::deposit_event(Event::<T>::DealOrderShareFunded(
+				deal_order_id.clone(),
+				lender_address_id,
+			));
+		}
+		Self::deposit_event(Event::<T>::TransferProcessed(transfer_id.clone()));
+
+		Ok(())
+	}
+
+	/// Credits a repayment of a syndicated deal order to the share of the lender it was sent to.
+	pub fn credit_share_repayment(
+		deal_order_id: &DealOrderId<T::BlockNumber, T::Hash>,
+		to: &AddressId<T::Hash>,
+		amount: ExternalAmount,
+	) -> Result<(), Error<T>> {
+		let mut share = DealOrderShares::<T>::get(deal_order_id, to)
+			.ok_or(Error::<T>::TransferRecipientMismatch)?;
+		share.repaid_amount = share.repaid_amount.saturating_add(amount);
+		DealOrderShares::<T>::insert(deal_order_id, to, share);
+		Ok(())
+	}
+
+	/// Whether every lender of a syndicated deal order has been repaid its pro rata part of the
+	/// amount owed.
+	pub fn shares_repaid(
+		deal_order_id: &DealOrderId<T::BlockNumber, T::Hash>,
+		deal_order: &DealOrderFor<T>,
+	) -> bool {
+		let owed = deal_order.amount_owed(Self::timestamp());
+		let loan_amount = deal_order.terms.amount;
+		DealOrderShares::<T>::iter_prefix_values(deal_order_id)
+			.all(|share| share.repaid_amount >= share.amount_owed(owed, loan_amount))
+	}
+
+	/// Drops the shares of a deal order that is being removed, because it expired before it was
+	/// funded or is being archived, along with the deal order index entries of its lenders.
+	pub fn remove_deal_order_shares(deal_order_id: &DealOrderId<T::BlockNumber, T::Hash>) {
+		for (address_id, _) in DealOrderShares::<T>::drain_prefix(deal_order_id) {
+			if let Some(address) = Self::addresses(&address_id) {
+				AccountDealOrders::<T>::remove(&address.owner, deal_order_id);
+			}
+		}
+	}
+}
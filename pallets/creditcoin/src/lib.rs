@@ -37,11 +37,16 @@ mod types;
 
 use ocw::tasks::collect_coins::GCreContract;
 pub use types::{
-	loan_terms, Address, AddressId, AskOrder, AskOrderId, AskTerms, BidOrder, BidOrderId, BidTerms,
-	Blockchain, CollectedCoinsId, CollectedCoinsStruct, DealOrder, DealOrderId, Duration,
-	ExternalAddress, ExternalAmount, ExternalTxId, Guid, InterestRate, InterestType, LegacySighash,
-	LoanTerms, Offer, OfferId, OrderId, RatePerPeriod, Task, TaskId, TaskOutput, Transfer,
-	TransferId, TransferKind, UnverifiedCollectedCoins, UnverifiedTransfer,
+	loan_terms, Address, AddressFormat, AddressId, ArchivedDealOrder, AskOrder, AskOrderId,
+	AskTerms, BidOrder, BidOrderId, BidTerms, Blockchain, CollateralAmount, CollectedCoinsId,
+	CollectedCoinsStruct, ContractWalletId, ContractWalletSignature, CreditRecord, DealOrder,
+	DealOrderId, DealOrderStatus, Duration, Exemption, ExemptionReason, ExpirySweep,
+	ExternalAddress, ExternalAmount, ExternalChain, ExternalTxId, FeePayer, Guid, InterestRate,
+	InterestType, LegacySighash, LenderShare, LoanBalance, LoanCurrency, LoanTerms,
+	MatchableOrderId, Offer, OfferId, OrderId, OriginationFee, OriginationFeeRate, RatePerPeriod,
+	RepaymentOrder, RepaymentOrderId, SupportedTransferKinds, Task, TaskId, TaskOutput,
+	TermsProposal, TermsProposalId, Transfer, TransferId, TransferKind, UnverifiedCollectedCoins,
+	UnverifiedContractWallet, UnverifiedTransfer,
 };
 
 pub(crate) use types::{DoubleMapExt, Id};
@@ -51,7 +56,45 @@ pub(crate) use types::test;
 
 pub type BalanceFor<T> = <T as pallet_balances::Config>::Balance;
 
-pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(7);
+pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(17);
+
+/// The maximum number of deal orders checked for defaults in a single block.
+pub const MAX_DEFAULT_CHECKS_PER_BLOCK: u32 = 256;
+
+/// The maximum number of orders waiting for the matching engine.
+pub const MAX_PENDING_MATCHES: u32 = 1_024;
+
+/// The maximum number of pending orders the matching engine processes in a single block.
+pub const MAX_MATCHES_PER_BLOCK: u32 = 16;
+
+/// The maximum number of orders from the other side of the book considered for a single match.
+pub const MAX_MATCH_CANDIDATES: u32 = 64;
+
+/// The maximum number of ask orders, and of bid orders, kept in the order book of a blockchain
+/// and currency.
+pub const MAX_ORDER_BOOK_DEPTH: u32 = 128;
+
+/// The maximum number of expired entries removed, or funded deal orders skipped, in a single
+/// block. Whatever is left is swept in the following blocks.
+pub const MAX_EXPIRY_SWEEP_ENTRIES: u32 = 256;
+
+/// The maximum number of expiration blocks the expiry sweep moves through in a single block.
+pub const MAX_EXPIRY_SWEEP_BLOCKS: u32 = 64;
+
+/// The maximum number of deal orders, plus their transfers, archived in a single block.
+/// Whatever is left is archived in the following blocks.
+pub const MAX_ARCHIVED_PER_BLOCK: u32 = 32;
+
+/// The maximum number of closing blocks the archival of deal orders moves through in a single
+/// block.
+pub const MAX_ARCHIVE_SWEEP_BLOCKS: u32 = 64;
+
+/// The maximum number of lenders sharing the loan amount of a syndicated deal order.
+pub const MAX_LENDERS_PER_DEAL: u32 = 16;
+
+/// The maximum number of ask, bid and deal orders of an account looked at to tell whether one
+/// of its addresses is still in use.
+pub const MAX_ADDRESS_ORDERS_SCANNED: u32 = 256;
 
 #[frame_support::pallet]
 pub mod pallet {
@@ -62,7 +105,10 @@ pub mod pallet {
 		dispatch::{DispatchResult, PostDispatchInfo},
 		fail,
 		pallet_prelude::*,
-		traits::tokens::{currency::Currency as CurrencyT, fungible::Mutate, ExistenceRequirement},
+		traits::{
+			tokens::{currency::Currency as CurrencyT, fungible::Mutate, ExistenceRequirement},
+			BalanceStatus, ReservableCurrency,
+		},
 		transactional,
 	};
 	use frame_system::{ensure_signed, offchain::CreateSignedTransaction, pallet_prelude::*};
@@ -121,7 +167,7 @@ pub mod pallet {
 	pub trait WeightInfo {
 		fn migration_v6(t: u32) -> Weight;
 		fn migration_v7(t: u32) -> Weight;
-		fn on_initialize(a: u32, b: u32, o: u32, d: u32, f: u32) -> Weight;
+		fn on_initialize(a: u32, b: u32, o: u32, d: u32, f: u32, p: u32, r: u32, s: u32) -> Weight;
 		fn register_address() -> Weight;
 		fn claim_legacy_wallet() -> Weight;
 		fn add_ask_order() -> Weight;
@@ -144,6 +190,30 @@ pub mod pallet {
 		fn remove_authority() -> Weight;
 		fn set_collect_coins_contract() -> Weight;
 		fn register_address_v2() -> Weight;
+		fn mark_defaulted_deal_orders(d: u32) -> Weight;
+		fn cancel_ask_order() -> Weight;
+		fn cancel_bid_order() -> Weight;
+		fn cancel_offer() -> Weight;
+		fn transfer_deal_order() -> Weight;
+		fn claim_collateral() -> Weight;
+		fn set_order_matching() -> Weight;
+		fn match_pending_orders(m: u32, c: u32) -> Weight;
+		fn propose_terms() -> Weight;
+		fn accept_terms() -> Weight;
+		fn add_deal_order_share() -> Weight;
+		fn register_share_repayment_transfer() -> Weight;
+		fn set_deal_order_retention() -> Weight;
+		fn archive_deal_orders(s: u32, d: u32, t: u32) -> Weight;
+		fn add_repayment_order() -> Weight;
+		fn cancel_repayment_order() -> Weight;
+		fn register_repayment_order_transfer() -> Weight;
+		fn set_origination_fee() -> Weight;
+		fn unregister_address(o: u32) -> Weight;
+		fn transfer_address(o: u32) -> Weight;
+		fn register_contract_wallet() -> Weight;
+		fn persist_contract_wallet() -> Weight;
+		fn fail_contract_wallet() -> Weight;
+		fn set_external_chain() -> Weight;
 	}
 
 	#[pallet::pallet]
@@ -167,6 +237,21 @@ pub mod pallet {
 		DealOrder<T::AccountId, T::BlockNumber, T::Hash, T::Moment>,
 	>;
 
+	/// The last deal order checked for a default, the next check resumes after it.
+	#[pallet::storage]
+	pub type DealOrderDefaultCursor<T: Config> =
+		StorageValue<_, DealOrderId<T::BlockNumber, T::Hash>>;
+
+	/// Where the sweep of expired orders, offers, deal orders and terms proposals continues.
+	#[pallet::storage]
+	pub type ExpiryCursor<T: Config> = StorageValue<_, ExpirySweep<T::BlockNumber, T::Hash>>;
+
+	/// Native CTC reserved from the borrower of a deal order as collateral for the loan.
+	#[pallet::storage]
+	#[pallet::getter(fn deal_order_collateral)]
+	pub type DealOrderCollateral<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, T::BlockNumber, Identity, T::Hash, T::Balance>;
+
 	#[pallet::storage]
 	#[pallet::getter(fn addresses)]
 	pub type Addresses<T: Config> =
@@ -198,6 +283,50 @@ pub mod pallet {
 		BidOrder<T::AccountId, T::BlockNumber, T::Hash>,
 	>;
 
+	/// Whether new ask and bid orders are matched automatically.
+	#[pallet::storage]
+	#[pallet::getter(fn order_matching_enabled)]
+	pub type OrderMatchingEnabled<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	/// Ask orders in the order book that have not been matched yet, per blockchain and currency,
+	/// with the rate each offers. The lowest rate comes first, older orders first among equal
+	/// rates.
+	#[pallet::storage]
+	pub type AskOrderBook<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		Blockchain,
+		Blake2_128Concat,
+		LoanCurrency,
+		BoundedVec<
+			(InterestRate, AskOrderId<T::BlockNumber, T::Hash>),
+			ConstU32<MAX_ORDER_BOOK_DEPTH>,
+		>,
+		ValueQuery,
+	>;
+
+	/// Bid orders in the order book that have not been matched yet, per blockchain and currency,
+	/// oldest first.
+	#[pallet::storage]
+	pub type BidOrderBook<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		Blockchain,
+		Blake2_128Concat,
+		LoanCurrency,
+		BoundedVec<BidOrderId<T::BlockNumber, T::Hash>, ConstU32<MAX_ORDER_BOOK_DEPTH>>,
+		ValueQuery,
+	>;
+
+	/// Newly added orders, oldest first, waiting for the matching engine to look for a
+	/// counterpart.
+	#[pallet::storage]
+	pub type PendingMatches<T: Config> = StorageValue<
+		_,
+		BoundedVec<MatchableOrderId<T::BlockNumber, T::Hash>, ConstU32<MAX_PENDING_MATCHES>>,
+		ValueQuery,
+	>;
+
 	#[pallet::storage]
 	#[pallet::getter(fn offers)]
 	pub type Offers<T: Config> = StorageDoubleMap<
@@ -209,6 +338,127 @@ pub mod pallet {
 		Offer<T::AccountId, T::BlockNumber, T::Hash>,
 	>;
 
+	/// Proposed changes to the terms of funded deal orders, removed once they expire.
+	#[pallet::storage]
+	#[pallet::getter(fn terms_proposals)]
+	pub type TermsProposals<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		Identity,
+		T::Hash,
+		TermsProposal<T::AccountId, T::BlockNumber, T::Hash>,
+	>;
+
+	/// The fee charged when a deal order is funded, if any.
+	#[pallet::storage]
+	#[pallet::getter(fn origination_fee)]
+	pub type OriginationFeeParameters<T: Config> =
+		StorageValue<_, OriginationFee<T::AccountId, T::Balance>>;
+
+	/// Repayments of deal orders by third parties, removed once they are settled or expire.
+	#[pallet::storage]
+	#[pallet::getter(fn repayment_orders)]
+	pub type RepaymentOrders<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		Identity,
+		T::Hash,
+		RepaymentOrder<T::AccountId, T::BlockNumber, T::Hash>,
+	>;
+
+	/// The repayment order paid by each registered repayment order transfer, kept until the
+	/// transfer is verified or fails so that it is credited even if the repayment order expires
+	/// or is cancelled in the meantime.
+	#[pallet::storage]
+	pub type RepaymentOrderTransfers<T: Config> = StorageMap<
+		_,
+		Identity,
+		TransferId<T::Hash>,
+		RepaymentOrder<T::AccountId, T::BlockNumber, T::Hash>,
+	>;
+
+	/// The shares of syndicated deal orders, keyed by the address of each lender. Includes the
+	/// share of the lead lender, i.e. the lender address of the deal order.
+	#[pallet::storage]
+	#[pallet::getter(fn deal_order_shares)]
+	pub type DealOrderShares<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		DealOrderId<T::BlockNumber, T::Hash>,
+		Blake2_128Concat,
+		AddressId<T::Hash>,
+		LenderShare<T::Hash>,
+	>;
+
+	/// Addresses registered by each account.
+	#[pallet::storage]
+	pub type AccountAddresses<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		AddressId<T::Hash>,
+		(),
+	>;
+
+	/// Ask orders placed by each lender.
+	#[pallet::storage]
+	pub type AccountAskOrders<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		AskOrderId<T::BlockNumber, T::Hash>,
+		(),
+	>;
+
+	/// Bid orders placed by each borrower.
+	#[pallet::storage]
+	pub type AccountBidOrders<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		BidOrderId<T::BlockNumber, T::Hash>,
+		(),
+	>;
+
+	/// Offers made by each lender.
+	#[pallet::storage]
+	pub type AccountOffers<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		OfferId<T::BlockNumber, T::Hash>,
+		(),
+	>;
+
+	/// Deal orders of each account, both as the borrower and as the owner of the lender address.
+	#[pallet::storage]
+	pub type AccountDealOrders<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		DealOrderId<T::BlockNumber, T::Hash>,
+		(),
+	>;
+
+	/// The credit record of each borrower on every blockchain it borrowed on.
+	#[pallet::storage]
+	pub type CreditHistory<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		Blockchain,
+		CreditRecord,
+		ValueQuery,
+	>;
+
 	#[pallet::storage]
 	#[pallet::getter(fn transfers)]
 	pub type Transfers<T: Config> = StorageMap<
@@ -218,6 +468,56 @@ pub mod pallet {
 		Transfer<T::AccountId, T::BlockNumber, T::Hash, T::Moment>,
 	>;
 
+	/// Transfers registered for each deal order, removed together with the deal order when it
+	/// is archived.
+	#[pallet::storage]
+	pub type DealOrderTransfers<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		DealOrderId<T::BlockNumber, T::Hash>,
+		Identity,
+		TransferId<T::Hash>,
+		(),
+	>;
+
+	/// Transfers that were removed when their deal order was archived, kept so that the same
+	/// external transaction can't be registered again.
+	#[pallet::storage]
+	pub type ArchivedTransfers<T: Config> = StorageMap<_, Identity, TransferId<T::Hash>, ()>;
+
+	/// The number of blocks a deal order is kept for after it was closed or exempted, before it
+	/// is archived. Deal orders are kept forever while it is not set.
+	#[pallet::storage]
+	#[pallet::getter(fn deal_order_retention)]
+	pub type DealOrderRetention<T: Config> = StorageValue<_, T::BlockNumber>;
+
+	/// Deal orders closed or exempted in each block, waiting to be archived.
+	#[pallet::storage]
+	pub type ClosedDealOrders<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		Blake2_128Concat,
+		DealOrderId<T::BlockNumber, T::Hash>,
+		(),
+	>;
+
+	/// The oldest block whose closed deal orders may not all have been archived yet.
+	#[pallet::storage]
+	pub type ArchiveCursor<T: Config> = StorageValue<_, T::BlockNumber>;
+
+	/// What is kept of archived deal orders, which also stops them from being added again.
+	#[pallet::storage]
+	#[pallet::getter(fn archived_deal_orders)]
+	pub type ArchivedDealOrders<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		Identity,
+		T::Hash,
+		ArchivedDealOrder<T::AccountId, T::BlockNumber, T::Hash>,
+	>;
+
 	#[pallet::storage]
 	#[pallet::getter(fn collected_coins)]
 	pub type CollectedCoins<T: Config> = StorageMap<
@@ -231,6 +531,32 @@ pub mod pallet {
 	#[pallet::getter(fn collect_coins_contract)]
 	pub type CollectCoinsContract<T: Config> = StorageValue<_, GCreContract, ValueQuery>;
 
+	/// The registry of supported external chains. Addresses, orders and transfers can only be
+	/// registered on the chains it holds.
+	#[pallet::storage]
+	pub type ExternalChains<T: Config> = StorageMap<_, Blake2_128Concat, Blockchain, ExternalChain>;
+
+	/// How many times each address has been moved to another account. The proof for the next
+	/// move signs it, so that a proof cannot be used twice.
+	#[pallet::storage]
+	#[pallet::getter(fn address_transfer_nonce)]
+	pub type AddressTransferNonces<T: Config> =
+		StorageMap<_, Identity, AddressId<T::Hash>, u64, ValueQuery>;
+
+	/// How many times the lender's side of each deal order has changed hands. The consent of the
+	/// next lender signs it, so that a consent cannot be used twice.
+	#[pallet::storage]
+	#[pallet::getter(fn deal_order_transfer_nonce)]
+	pub type DealOrderTransferNonces<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, T::BlockNumber, Identity, T::Hash, u64, ValueQuery>;
+
+	/// Addresses registered before address ids were computed from the canonical form of the
+	/// address, that share their canonical form with another registered address. Keyed by the
+	/// canonical id, then by the id the address is still registered under.
+	#[pallet::storage]
+	pub type LegacyAddressCollisions<T: Config> =
+		StorageDoubleMap<_, Identity, AddressId<T::Hash>, Identity, AddressId<T::Hash>, ()>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
@@ -326,6 +652,142 @@ pub mod pallet {
 		/// exchanging vested ERC-20 CC for native CC failed.
 		/// [collected_coins_id, cause]
 		CollectCoinsFailedVerification(CollectedCoinsId<T::Hash>, VerificationFailureCause),
+
+		/// A verified repayment transfer has been credited towards a deal order. This indicates
+		/// that the borrower has repaid an installment of the loan.
+		/// [deal_order_id, repayment_transfer_id, total_repaid_amount]
+		RepaymentRecorded(
+			DealOrderId<T::BlockNumber, T::Hash>,
+			TransferId<T::Hash>,
+			ExternalAmount,
+		),
+
+		/// A funded deal order reached the end of its term without being repaid.
+		/// [deal_order_id]
+		DealOrderDefaulted(DealOrderId<T::BlockNumber, T::Hash>),
+
+		/// An ask order has been cancelled by its lender before it expired.
+		/// [cancelled_ask_order_id]
+		AskOrderCancelled(AskOrderId<T::BlockNumber, T::Hash>),
+
+		/// A bid order has been cancelled by its borrower before it expired.
+		/// [cancelled_bid_order_id]
+		BidOrderCancelled(BidOrderId<T::BlockNumber, T::Hash>),
+
+		/// An offer has been cancelled by its lender before it expired.
+		/// [cancelled_offer_id]
+		OfferCancelled(OfferId<T::BlockNumber, T::Hash>),
+
+		/// The lender's position in a funded deal order has been transferred to another party,
+		/// who will receive the remaining repayments at their own address.
+		/// [deal_order_id, new_lender_address_id, new_lender]
+		DealOrderTransferred(
+			DealOrderId<T::BlockNumber, T::Hash>,
+			AddressId<T::Hash>,
+			T::AccountId,
+		),
+
+		/// The collateral of a deal order has been returned to the borrower because the loan
+		/// was repaid, exempted or never funded.
+		/// [deal_order_id, collateral_amount]
+		CollateralReleased(DealOrderId<T::BlockNumber, T::Hash>, T::Balance),
+
+		/// The collateral of an overdue or defaulted deal order has been claimed by the lender.
+		/// [deal_order_id, lender, collateral_amount]
+		CollateralClaimed(DealOrderId<T::BlockNumber, T::Hash>, T::AccountId, T::Balance),
+
+		/// New terms have been proposed for a funded deal order and wait for the other party
+		/// to accept them.
+		/// [terms_proposal_id, terms_proposal]
+		TermsProposed(
+			TermsProposalId<T::BlockNumber, T::Hash>,
+			TermsProposal<T::AccountId, T::BlockNumber, T::Hash>,
+		),
+
+		/// Both parties agreed on new terms for a deal order, which replaced its previous terms.
+		/// [deal_order_id, terms_proposal_id, new_terms]
+		DealOrderTermsUpdated(
+			DealOrderId<T::BlockNumber, T::Hash>,
+			TermsProposalId<T::BlockNumber, T::Hash>,
+			LoanTerms,
+		),
+
+		/// Another lender committed to fund part of the loan amount of a deal order, which
+		/// is taken from the share of the lead lender.
+		/// [deal_order_id, lender_address_id, amount]
+		DealOrderShareAdded(
+			DealOrderId<T::BlockNumber, T::Hash>,
+			AddressId<T::Hash>,
+			ExternalAmount,
+		),
+
+		/// A lender funded its share of a syndicated deal order. The deal order is funded once
+		/// all of its shares are.
+		/// [deal_order_id, lender_address_id]
+		DealOrderShareFunded(DealOrderId<T::BlockNumber, T::Hash>, AddressId<T::Hash>),
+
+		/// A closed or exempted deal order outlived the retention period and has been archived.
+		/// The deal order has been removed from storage, after each of its transfers was
+		/// removed with a `TransferArchived` event.
+		/// [deal_order_id, deal_order]
+		DealOrderArchived(
+			DealOrderId<T::BlockNumber, T::Hash>,
+			DealOrder<T::AccountId, T::BlockNumber, T::Hash, T::Moment>,
+		),
+
+		/// A lender forgave the borrower part of the outstanding debt on the loan. The deal order
+		/// stays open until the rest of it is repaid.
+		/// [deal_order_id, forgiven_amount]
+		LoanPartiallyExempted(DealOrderId<T::BlockNumber, T::Hash>, ExternalAmount),
+
+		/// A third party has offered to repay part of a deal order.
+		/// [repayment_order_id, repayment_order]
+		RepaymentOrderAdded(
+			RepaymentOrderId<T::BlockNumber, T::Hash>,
+			RepaymentOrder<T::AccountId, T::BlockNumber, T::Hash>,
+		),
+
+		/// A repayment order was cancelled by its payer.
+		/// [repayment_order_id]
+		RepaymentOrderCancelled(RepaymentOrderId<T::BlockNumber, T::Hash>),
+
+		/// The transfer of a repayment order was verified and credited towards its deal order.
+		/// [repayment_order_id, transfer_id]
+		RepaymentOrderSettled(RepaymentOrderId<T::BlockNumber, T::Hash>, TransferId<T::Hash>),
+
+		/// The origination fee of a deal order was paid to the fee collector when it was funded.
+		/// [deal_order_id, payer, fee]
+		OriginationFeeCharged(DealOrderId<T::BlockNumber, T::Hash>, T::AccountId, T::Balance),
+
+		/// An external address was unregistered by its owner.
+		/// [address_id, address]
+		AddressUnregistered(AddressId<T::Hash>, Address<T::AccountId>),
+
+		/// An external address was moved to another account.
+		/// [address_id, old_owner, new_owner]
+		AddressTransferred(AddressId<T::Hash>, T::AccountId, T::AccountId),
+
+		/// A smart contract wallet has been submitted for registration and will be verified.
+		/// [contract_wallet_id, unverified_contract_wallet]
+		ContractWalletRegistrationRequested(
+			ContractWalletId<T::Hash>,
+			UnverifiedContractWallet<T::AccountId>,
+		),
+
+		/// A smart contract wallet rejected the signature submitted to register it.
+		/// [contract_wallet_id, cause]
+		ContractWalletFailedVerification(ContractWalletId<T::Hash>, VerificationFailureCause),
+
+		/// An external chain has been added to or changed in the registry of supported chains.
+		/// [blockchain, external_chain]
+		ExternalChainSet(Blockchain, ExternalChain),
+
+		/// A transfer has been removed from storage while its deal order was being archived.
+		/// [transfer_id, transfer]
+		TransferArchived(
+			TransferId<T::Hash>,
+			Transfer<T::AccountId, T::BlockNumber, T::Hash, T::Moment>,
+		),
 	}
 
 	// Errors inform users that something went wrong.
@@ -507,18 +969,143 @@ pub mod pallet {
 
 		/// ECDSA public key recovery failed for an ownership proof using PersonalSign
 		PersonalSignPublicKeyRecoveryFailed,
+
+		/// The amount repaid does not cover the principal plus the interest accrued so far.
+		InsufficientRepayment,
+
+		/// The transfer was not sent to the lender address of the deal order.
+		TransferRecipientMismatch,
+
+		/// The borrower does not have enough free balance to post the collateral required by the
+		/// loan terms.
+		InsufficientCollateral,
+
+		/// The deal order has no collateral left, or the loan is neither overdue nor defaulted.
+		CollateralNotClaimable,
+
+		/// The ask or bid terms describe an empty range, e.g. a minimum amount above the maximum.
+		InvalidTermRange,
+
+		/// The specified terms proposal does not exist.
+		NonExistentTermsProposal,
+
+		/// The same terms have already been proposed for this deal order.
+		DuplicateTermsProposal,
+
+		/// The terms proposal has expired.
+		TermsProposalExpired,
+
+		/// The proposed terms may only extend the term length or change the interest rate of
+		/// the deal order, and must differ from its current terms.
+		InvalidTermsProposal,
+
+		/// The caller is neither the borrower nor the lender of the deal order.
+		NotDealOrderParty,
+
+		/// A terms proposal must be accepted by the other party of the deal order.
+		CannotAcceptOwnTermsProposal,
+
+		/// The deal order has already defaulted.
+		DealOrderAlreadyDefaulted,
+
+		/// The operation is not supported for deal orders funded by several lenders, or a
+		/// deal order with collateral was to be shared between lenders.
+		SyndicatedDealOrder,
+
+		/// The lender already has a share of the deal order.
+		DuplicateShare,
+
+		/// The deal order already has the maximum number of lenders.
+		TooManyLenders,
+
+		/// A share must be greater than zero and leave part of the loan amount to the lead
+		/// lender.
+		InvalidShareAmount,
+
+		/// The specified lender address has no share of the deal order.
+		NonExistentShare,
+
+		/// The amount to exempt must be greater than zero.
+		InvalidExemptionAmount,
+
+		/// The specified repayment order does not exist.
+		NonExistentRepaymentOrder,
+
+		/// The repayment order has expired.
+		RepaymentOrderExpired,
+
+		/// Only the payer of the repayment order can perform the action.
+		NotPayer,
+
+		/// The payer of the origination fee cannot afford it.
+		InsufficientBalanceForFee,
+
+		/// The transfer kind does not move the currency of the loan terms.
+		CurrencyMismatch,
+
+		/// The address is still referenced by an open ask, bid or deal order.
+		AddressInUse,
+
+		/// A valid bitcoin address could not be generated from the key recovered from a
+		/// BitcoinSign ownership proof
+		BitcoinSignExternalAddressGenerationFailed,
+
+		/// ECDSA public key recovery failed for an ownership proof using BitcoinSign
+		BitcoinSignPublicKeyRecoveryFailed,
+
+		/// A valid external address could not be generated for the specified blockchain and
+		/// recovered public key
+		TypedDataExternalAddressGenerationFailed,
+
+		/// ECDSA public key recovery failed for an ownership proof using TypedData
+		TypedDataPublicKeyRecoveryFailed,
+
+		/// The caller's registration of the smart contract wallet is already waiting to be verified.
+		ContractWalletRegistrationPending,
+
+		/// The external chain is not in the registry of supported chains.
+		UnsupportedChain,
+
+		/// The external chain is deprecated and takes no new addresses or orders.
+		ChainDeprecated,
+
+		/// The account has too many orders to tell whether the address is still in use.
+		TooManyOrdersToScan,
+
+		/// Only orders created before loans had a currency can be in the legacy currency.
+		LegacyCurrency,
+
+		/// The amount of a repayment order must be greater than zero.
+		ZeroRepaymentAmount,
+
+		/// The registry of external chains holds no EVM chain id for the blockchain of a TypedData
+		/// ownership proof, so there is no EIP-712 domain to sign for.
+		TypedDataChainIdUnknown,
+
+		/// The entry of the registry of external chains does not fit its address format, e.g. an
+		/// EVM chain without a chain id.
+		InvalidExternalChain,
+
+		/// The address format of a chain in the registry of external chains cannot change, the
+		/// ids of its addresses depend on it.
+		AddressFormatChanged,
 	}
 
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config> {
 		pub legacy_wallets: Vec<(LegacySighash, T::Balance)>,
 		pub legacy_balance_keeper: Option<T::AccountId>,
+		pub external_chains: Vec<(Blockchain, ExternalChain)>,
 	}
 
 	#[cfg(feature = "std")]
 	impl<T: Config> Default for GenesisConfig<T> {
 		fn default() -> Self {
-			Self { legacy_wallets: Vec::new(), legacy_balance_keeper: None }
+			Self {
+				legacy_wallets: Vec::new(),
+				legacy_balance_keeper: None,
+				external_chains: ExternalChain::builtin(),
+			}
 		}
 	}
 
@@ -531,6 +1118,9 @@ pub mod pallet {
 			if let Some(acct) = &self.legacy_balance_keeper {
 				LegacyBalanceKeeper::<T>::put(acct.clone());
 			}
+			for (blockchain, chain) in &self.external_chains {
+				ExternalChains::<T>::insert(blockchain, chain);
+			}
 		}
 	}
 
@@ -539,34 +1129,10 @@ pub mod pallet {
 		fn on_initialize(block_number: T::BlockNumber) -> Weight {
 			log::debug!("Cleaning up expired entries");
 
-			let ask_count = AskOrders::<T>::clear_prefix(block_number, u32::MAX, None).backend;
-			let bid_count = BidOrders::<T>::clear_prefix(block_number, u32::MAX, None).backend;
-			let offer_count = Offers::<T>::clear_prefix(block_number, u32::MAX, None).backend;
-
-			let mut deals_count = 0u32;
-			let deals_to_keep: Vec<_> = DealOrders::<T>::drain_prefix(block_number)
-				.filter_map(|(hash, deal)| {
-					deals_count = deals_count.saturating_add(1);
-					if deal.funding_transfer_id.is_some() {
-						Some((DealOrderId::with_expiration_hash::<T>(block_number, hash), deal))
-					} else {
-						None
-					}
-				})
-				.collect();
-			let funded_deals_count = deals_to_keep.len().unique_saturated_into();
-			let deals_count = deals_count.saturating_sub(funded_deals_count);
-			for (key, deal) in deals_to_keep {
-				DealOrders::<T>::insert_id(key, deal);
-			}
-
-			<T as Config>::WeightInfo::on_initialize(
-				ask_count,
-				bid_count,
-				offer_count,
-				deals_count,
-				funded_deals_count,
-			)
+			Self::sweep_expired_entries(block_number)
+				.saturating_add(Self::mark_defaulted_deal_orders())
+				.saturating_add(Self::match_pending_orders())
+				.saturating_add(Self::archive_closed_deal_orders(block_number))
 		}
 
 		fn on_runtime_upgrade() -> Weight {
@@ -620,6 +1186,8 @@ pub mod pallet {
 			// TODO: Why do we hash twice? I presume signed blake2 digest should be sufficient.
 			// The signing happens here: https://github.com/gluwa/creditcoin/blob/d1918252b27069afccbca290f35e4ecd8fce0640/creditcoin-js/src/utils.ts#L9
 			let who = ensure_signed(origin)?;
+			let format = Self::active_external_chain(&blockchain)?.address_format;
+			let address = helpers::normalize_address(&format, address);
 			let message = sp_io::hashing::sha2_256(who.encode().as_slice());
 			// Prep message for public key recovery/extraction
 			let message = &sp_io::hashing::blake2_256(message.as_ref());
@@ -631,7 +1199,7 @@ pub mod pallet {
 
 			// Build the external address from the public key
 			let recreated_address = helpers::generate_external_address(
-				&blockchain,
+				&format,
 				&address,
 				sp_core::ecdsa::Public::from_raw(raw_pubkey),
 			)
@@ -654,10 +1222,11 @@ pub mod pallet {
 			// note: this error condition is unreachable!
 			// AddressFormatNotSupported or OwnershipNotSatisfied will error out first
 			ensure!(
-				helpers::address_is_well_formed(&blockchain, &address),
+				helpers::address_is_well_formed(&format, &address),
 				Error::<T>::MalformedExternalAddress
 			);
 
+			AccountAddresses::<T>::insert(&who, &address_id, ());
 			let entry = Address { blockchain, value: address, owner: who };
 			Self::deposit_event(Event::<T>::AddressRegistered(address_id.clone(), entry.clone()));
 			<Addresses<T>>::insert(address_id, entry);
@@ -670,11 +1239,12 @@ pub mod pallet {
 		pub fn add_ask_order(
 			origin: OriginFor<T>,
 			address_id: AddressId<T::Hash>,
-			terms: LoanTerms,
+			terms: AskTerms,
 			expiration_block: BlockNumberFor<T>,
 			guid: Guid,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
+			terms.validate().map_err(Error::<T>::from)?;
 
 			let head = Self::block_number();
 			ensure!(expiration_block >= head, Error::<T>::AskOrderExpired);
@@ -684,18 +1254,24 @@ pub mod pallet {
 
 			let address = Self::get_address(&address_id)?;
 			ensure!(address.owner == who, Error::<T>::NotAddressOwner);
+			Self::active_external_chain(&address.blockchain)?;
 
 			Self::use_guid(&guid)?;
 
 			let ask_order = AskOrder {
 				blockchain: address.blockchain,
 				lender_address_id: address_id,
-				terms: terms.try_into().map_err(Error::<T>::from)?,
+				terms,
 				expiration_block,
 				block: <frame_system::Pallet<T>>::block_number(),
 				lender: who,
 			};
 
+			if Self::order_matching_enabled() {
+				Self::enlist_ask_order(&ask_order_id, &ask_order);
+			}
+
+			AccountAskOrders::<T>::insert(&ask_order.lender, &ask_order_id, ());
 			Self::deposit_event(Event::<T>::AskOrderAdded(ask_order_id.clone(), ask_order.clone()));
 			AskOrders::<T>::insert_id(ask_order_id, ask_order);
 			Ok(())
@@ -706,11 +1282,12 @@ pub mod pallet {
 		pub fn add_bid_order(
 			origin: OriginFor<T>,
 			address_id: AddressId<T::Hash>,
-			terms: LoanTerms,
+			terms: BidTerms,
 			expiration_block: BlockNumberFor<T>,
 			guid: Guid,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
+			terms.validate().map_err(Error::<T>::from)?;
 
 			let head = Self::block_number();
 			ensure!(expiration_block >= head, Error::<T>::BidOrderExpired);
@@ -720,18 +1297,24 @@ pub mod pallet {
 
 			let address = Self::get_address(&address_id)?;
 			ensure!(address.owner == who, Error::<T>::NotAddressOwner);
+			Self::active_external_chain(&address.blockchain)?;
 
 			Self::use_guid(&guid)?;
 
 			let bid_order = BidOrder {
 				blockchain: address.blockchain,
 				borrower_address_id: address_id,
-				terms: terms.try_into().map_err(Error::<T>::from)?,
+				terms,
 				expiration_block,
 				block: <frame_system::Pallet<T>>::block_number(),
 				borrower: who,
 			};
 
+			if Self::order_matching_enabled() {
+				Self::enlist_bid_order(&bid_order_id, &bid_order);
+			}
+
+			AccountBidOrders::<T>::insert(&bid_order.borrower, &bid_order_id, ());
 			Self::deposit_event(Event::<T>::BidOrderAdded(bid_order_id.clone(), bid_order.clone()));
 			BidOrders::<T>::insert_id(bid_order_id, bid_order);
 			Ok(())
@@ -772,6 +1355,9 @@ pub mod pallet {
 
 			ensure!(!Offers::<T>::contains_id(&offer_id), Error::<T>::DuplicateOffer);
 
+			Self::delist_ask_order(&ask_order_id, &ask_order);
+			Self::delist_bid_order(&bid_order_id, &bid_order);
+
 			let offer = Offer {
 				ask_id: ask_order_id,
 				bid_id: bid_order_id,
@@ -781,6 +1367,7 @@ pub mod pallet {
 				lender: who,
 			};
 
+			AccountOffers::<T>::insert(&offer.lender, &offer_id, ());
 			Self::deposit_event(Event::<T>::OfferAdded(offer_id.clone(), offer.clone()));
 			Offers::<T>::insert_id(offer_id, offer);
 
@@ -798,6 +1385,10 @@ pub mod pallet {
 
 			let deal_order_id = DealOrderId::new::<T>(expiration_block, &offer_id);
 			ensure!(!DealOrders::<T>::contains_id(&deal_order_id), Error::<T>::DuplicateDealOrder);
+			ensure!(
+				!ArchivedDealOrders::<T>::contains_id(&deal_order_id),
+				Error::<T>::DuplicateDealOrder
+			);
 
 			let offer = try_get_id!(Offers<T>, &offer_id, NonExistentOffer)?;
 
@@ -829,8 +1420,15 @@ pub mod pallet {
 				funding_transfer_id: None,
 				lock: None,
 				repayment_transfer_id: None,
+				repaid_amount: ExternalAmount::zero(),
+				status: DealOrderStatus::Pending,
+				exemption: None,
 			};
 
+			Self::reserve_collateral(&deal_order_id, &deal_order.borrower, &deal_order.terms)?;
+
+			AccountDealOrders::<T>::insert(&deal_order.borrower, &deal_order_id, ());
+			AccountDealOrders::<T>::insert(&ask_order.lender, &deal_order_id, ());
 			Self::deposit_event(Event::<T>::DealOrderAdded(
 				deal_order_id.clone(),
 				deal_order.clone(),
@@ -859,6 +1457,9 @@ pub mod pallet {
 					ensure!(deal_order.borrower == who, Error::<T>::NotBorrower);
 
 					deal_order.lock = Some(who);
+					if deal_order.status == DealOrderStatus::Funded {
+						deal_order.status = DealOrderStatus::Locked;
+					}
 					Self::deposit_event(Event::<T>::DealOrderLocked(deal_order_id.clone()));
 					Ok(())
 				},
@@ -876,6 +1477,12 @@ pub mod pallet {
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
+			let deal_order = Self::try_get_deal_order(&deal_order_id)?;
+			if Self::is_syndicated(&deal_order_id, &deal_order) {
+				Self::fund_deal_order_share(&who, &deal_order_id, &transfer_id)?;
+				return Ok(());
+			}
+
 			Self::try_mutate_deal_order_and_transfer(
 				&deal_order_id,
 				&transfer_id,
@@ -897,6 +1504,7 @@ pub mod pallet {
 
 					deal_order.funding_transfer_id = Some(transfer_id.clone());
 					deal_order.timestamp = now;
+					deal_order.status = DealOrderStatus::Funded;
 
 					Ok(Some(Event::<T>::DealOrderFunded(deal_order_id.clone())))
 				},
@@ -916,6 +1524,7 @@ pub mod pallet {
 					Ok(Some(Event::<T>::TransferProcessed(transfer_id.clone())))
 				},
 			)?;
+			Self::charge_origination_fee(&deal_order_id, &deal_order)?;
 
 			Ok(())
 		}
@@ -969,6 +1578,8 @@ pub mod pallet {
 			let deal_order_id = DealOrderId::new::<T>(expiration_block, &offer_id);
 			ensure!(!DealOrders::<T>::contains_id(&deal_order_id), Error::<T>::DuplicateDealOrder);
 
+			ensure!(terms.currency != LoanCurrency::Legacy, Error::<T>::LegacyCurrency);
+
 			let current_block = Self::block_number();
 
 			let ask_order = AskOrder {
@@ -1011,22 +1622,35 @@ pub mod pallet {
 				funding_transfer_id: None,
 				lock: None,
 				repayment_transfer_id: None,
+				repaid_amount: ExternalAmount::zero(),
+				status: DealOrderStatus::Pending,
+				exemption: None,
 			};
 
+			Self::reserve_collateral(&deal_order_id, &deal_order.borrower, &deal_order.terms)?;
+
+			AccountAskOrders::<T>::insert(&ask_order.lender, &ask_order_id, ());
 			AskOrders::<T>::insert_id(ask_order_id.clone(), ask_order.clone());
 			Self::deposit_event(Event::<T>::AskOrderAdded(ask_order_id, ask_order));
 
+			AccountBidOrders::<T>::insert(&bid_order.borrower, &bid_order_id, ());
 			BidOrders::<T>::insert_id(bid_order_id.clone(), bid_order.clone());
 			Self::deposit_event(Event::<T>::BidOrderAdded(bid_order_id, bid_order));
 
+			AccountOffers::<T>::insert(&offer.lender, &offer_id, ());
 			Offers::<T>::insert_id(offer_id.clone(), offer.clone());
 			Self::deposit_event(Event::<T>::OfferAdded(offer_id, offer));
 
+			AccountDealOrders::<T>::insert(&deal_order.borrower, &deal_order_id, ());
+			AccountDealOrders::<T>::insert(&lender.owner, &deal_order_id, ());
 			DealOrders::<T>::insert_id(deal_order_id.clone(), deal_order.clone());
 			Self::deposit_event(Event::<T>::DealOrderAdded(deal_order_id, deal_order));
 			Ok(())
 		}
 
+		/// Closes a deal order that has been repaid, naming one of the borrower's repayment
+		/// transfers towards it. The deal order must be locked and the repayments credited
+		/// towards it must cover what is due; see `close_repaid_deal_order`.
 		#[pallet::call_index(9)]
 		#[pallet::weight(<T as Config>::WeightInfo::close_deal_order())]
 		pub fn close_deal_order(
@@ -1036,47 +1660,14 @@ pub mod pallet {
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
-			Self::try_mutate_deal_order_and_transfer(
-				&deal_order_id,
-				&transfer_id,
-				|deal_order| {
-					let borrower = try_get!(
-						Addresses<T>,
-						&deal_order.borrower_address_id,
-						NonExistentAddress
-					)?;
-
-					ensure!(borrower.owner == who, Error::<T>::NotBorrower);
-
-					let now = Self::timestamp();
-					ensure!(now >= deal_order.timestamp, Error::<T>::MalformedDealOrder);
-
-					ensure!(
-						deal_order.repayment_transfer_id.is_none(),
-						Error::<T>::DealOrderAlreadyClosed
-					);
-
-					ensure!(deal_order.lock.is_some(), Error::<T>::DealOrderMustBeLocked);
-
-					deal_order.repayment_transfer_id = Some(transfer_id.clone());
-
-					Ok(Some(Event::<T>::DealOrderClosed(deal_order_id.clone())))
-				},
-				|transfer, _deal_order| {
-					ensure!(
-						transfer.order_id == OrderId::Deal(deal_order_id.clone()),
-						Error::<T>::TransferDealOrderMismatch
-					);
-
-					ensure!(transfer.block <= Self::block_number(), Error::<T>::MalformedTransfer);
-					ensure!(transfer.account_id == who, Error::<T>::TransferAccountMismatch);
-					ensure!(!transfer.is_processed, Error::<T>::TransferAlreadyProcessed);
-
-					transfer.is_processed = true;
-					Ok(Some(Event::<T>::TransferProcessed(transfer_id.clone())))
-				},
-			)?;
+			let transfer = try_get!(Transfers<T>, &transfer_id, NonExistentTransfer)?;
+			ensure!(
+				transfer.order_id == OrderId::Deal(deal_order_id.clone()),
+				Error::<T>::TransferDealOrderMismatch
+			);
+			ensure!(transfer.account_id == who, Error::<T>::TransferAccountMismatch);
 
+			Self::close_repaid(&who, deal_order_id)?;
 			Ok(())
 		}
 
@@ -1091,13 +1682,15 @@ pub mod pallet {
 			let who = ensure_signed(origin)?;
 
 			let contract = Self::collect_coins_contract();
+			let format = Self::external_chain(&contract.chain)?.address_format;
+			let evm_address = helpers::normalize_address(&format, evm_address);
 
 			let pending = types::UnverifiedCollectedCoins { to: evm_address, tx_id, contract };
 
 			let collect_coins_id = TaskV2::<T>::to_id(&pending);
 
 			ensure!(
-				!<UnverifiedCollectedCoins as TaskV2<T>>::is_persisted(&collect_coins_id),
+				!TaskV2::<T>::is_persisted(&pending, &collect_coins_id),
 				Error::<T>::CollectCoinsAlreadyRegistered
 			);
 
@@ -1133,14 +1726,22 @@ pub mod pallet {
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
-			let order = try_get_id!(DealOrders<T>, &deal_order_id, NonExistentDealOrder)?;
+			let order = Self::try_get_deal_order(&deal_order_id)?;
+			ensure!(order.terms.currency.accepts(&transfer_kind), Error::<T>::CurrencyMismatch);
+
+			let (lender_address_id, amount) = if Self::is_syndicated(&deal_order_id, &order) {
+				let (lender_address_id, share) = Self::lender_share_of(&deal_order_id, &who)?;
+				(lender_address_id, share.amount)
+			} else {
+				(order.lender_address_id, order.terms.amount)
+			};
 
 			let (transfer_id, transfer) = Self::register_transfer_internal(
 				who,
-				order.lender_address_id,
+				lender_address_id,
 				order.borrower_address_id,
 				transfer_kind,
-				order.terms.amount,
+				amount,
 				OrderId::Deal(deal_order_id),
 				blockchain_tx_id,
 			)?;
@@ -1161,7 +1762,8 @@ pub mod pallet {
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
-			let order = try_get_id!(DealOrders<T>, &deal_order_id, NonExistentDealOrder)?;
+			let order = Self::try_get_deal_order(&deal_order_id)?;
+			ensure!(order.terms.currency.accepts(&transfer_kind), Error::<T>::CurrencyMismatch);
 
 			let (transfer_id, transfer) = Self::register_transfer_internal(
 				who,
@@ -1177,53 +1779,80 @@ pub mod pallet {
 			Ok(())
 		}
 
+		/// Forgives the borrower `amount` of the outstanding debt on a deal order, or all of it
+		/// when no amount is given. The deal order is exempted once nothing is left to repay.
 		#[pallet::call_index(13)]
 		#[pallet::weight(<T as Config>::WeightInfo::exempt())]
 		pub fn exempt(
 			origin: OriginFor<T>,
 			deal_order_id: DealOrderId<T::BlockNumber, T::Hash>,
+			amount: Option<ExternalAmount>,
+			reason: Option<ExemptionReason>,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
-			DealOrders::<T>::try_mutate(
-				deal_order_id.expiration(),
+			ensure!(
+				amount.map_or(true, |amount| !amount.is_zero()),
+				Error::<T>::InvalidExemptionAmount
+			);
+
+			let (exempted, forgiven, borrower) = DealOrders::<T>::try_mutate(
+				deal_order_id.expiration(),
 				deal_order_id.hash(),
-				|value| -> DispatchResult {
+				|value| -> Result<_, DispatchError> {
 					let deal_order =
 						value.as_mut().ok_or(crate::Error::<T>::NonExistentDealOrder)?;
 					ensure!(
-						deal_order.repayment_transfer_id.is_none(),
-						Error::<T>::DealOrderAlreadyClosed
+						!deal_order.has_expired(Self::block_number()),
+						Error::<T>::DealOrderExpired
 					);
+					ensure!(!deal_order.is_closed(), Error::<T>::DealOrderAlreadyClosed);
 
 					let lender = Self::get_address(&deal_order.lender_address_id)?;
 					ensure!(who == lender.owner, Error::<T>::NotLender);
-
-					let fake_transfer = Transfer {
-						order_id: OrderId::Deal(deal_order_id.clone()),
-						block: Self::block_number(),
-						account_id: who,
-						amount: ExternalAmount::zero(),
-						is_processed: true,
-						kind: TransferKind::Native,
-						tx_id: ExternalTxId::try_from(b"0".to_vec()).expect(
-							"0 is a length of one which will always be < size bound of ExternalTxId",
+					ensure!(
+						!Self::is_syndicated(&deal_order_id, deal_order),
+						Error::<T>::SyndicatedDealOrder
+					);
+					// nothing has been lent before the deal order is funded
+					ensure!(
+						matches!(
+							deal_order.status,
+							DealOrderStatus::Funded | DealOrderStatus::Locked
 						),
-						blockchain: lender.blockchain,
-						from: deal_order.lender_address_id.clone(),
-						to: deal_order.lender_address_id.clone(),
-						timestamp: Some(Self::timestamp()),
-					};
-					let fake_transfer_id =
-						TransferId::new::<T>(&fake_transfer.blockchain, &fake_transfer.tx_id);
+						Error::<T>::DealNotFunded
+					);
 
-					deal_order.repayment_transfer_id = Some(fake_transfer_id);
+					let now = Self::timestamp();
+					let outstanding =
+						deal_order.amount_due(now).saturating_sub(deal_order.repaid_amount);
+					let forgiven = amount.map_or(outstanding, |amount| amount.min(outstanding));
+					let exempted = forgiven == outstanding;
+
+					deal_order.exemption = Some(Exemption {
+						lender: who,
+						timestamp: now,
+						amount: deal_order.forgiven_amount().saturating_add(forgiven),
+						reason,
+					});
+
+					if exempted {
+						let previous_status = deal_order.status;
+						deal_order.status = DealOrderStatus::Exempted;
+						Self::record_loan_outcome(deal_order, previous_status);
+					}
 
-					Ok(())
+					Ok((exempted, forgiven, deal_order.borrower.clone()))
 				},
 			)?;
 
-			Self::deposit_event(Event::<T>::LoanExempted(deal_order_id));
+			if exempted {
+				Self::release_collateral(&deal_order_id, &borrower);
+				Self::schedule_archival(&deal_order_id);
+				Self::deposit_event(Event::<T>::LoanExempted(deal_order_id));
+			} else {
+				Self::deposit_event(Event::<T>::LoanPartiallyExempted(deal_order_id, forgiven));
+			}
 			Ok(())
 		}
 
@@ -1232,6 +1861,7 @@ pub mod pallet {
 		#[pallet::weight(match &task_output {
 			crate::TaskOutput::CollectCoins(..) => <T as Config>::WeightInfo::persist_collect_coins(),
 			crate::TaskOutput::VerifyTransfer(..) => <T as Config>::WeightInfo::persist_transfer(),
+			crate::TaskOutput::VerifyContractWallet(..) => <T as Config>::WeightInfo::persist_contract_wallet(),
 		})]
 		pub fn persist_task_output(
 			origin: OriginFor<T>,
@@ -1242,18 +1872,21 @@ pub mod pallet {
 
 			ensure!(T::TaskScheduler::is_authority(&who), Error::<T>::InsufficientAuthority);
 
-			let (task_id, event) = match task_output {
+			let (task_id, event, verified_transfer) = match task_output {
 				TaskOutput::VerifyTransfer(id, transfer) => {
 					ensure!(
-						!Transfers::<T>::contains_key(&id),
+						!Self::is_transfer_registered(&id),
 						non_paying_error(Error::<T>::TransferAlreadyRegistered)
 					);
 
 					let mut transfer = transfer;
 					transfer.block = frame_system::Pallet::<T>::block_number();
 
+					if let Some(deal_order_id) = Self::deal_order_of_transfer(&id, &transfer) {
+						DealOrderTransfers::<T>::insert(deal_order_id, &id, ());
+					}
 					Transfers::<T>::insert(&id, transfer);
-					(id.clone().into_inner(), Event::<T>::TransferVerified(id))
+					(id.clone().into_inner(), Event::<T>::TransferVerified(id.clone()), Some(id))
 				},
 				TaskOutput::CollectCoins(id, collected_coins) => {
 					ensure!(
@@ -1270,13 +1903,32 @@ pub mod pallet {
 					)?;
 
 					CollectedCoins::<T>::insert(&id, collected_coins.clone());
-					(id.clone().into_inner(), Event::<T>::CollectedCoinsMinted(id, collected_coins))
+					(
+						id.clone().into_inner(),
+						Event::<T>::CollectedCoinsMinted(id, collected_coins),
+						None,
+					)
+				},
+				TaskOutput::VerifyContractWallet(id, address) => {
+					ensure!(
+						!Addresses::<T>::contains_key(&id),
+						non_paying_error(Error::<T>::AddressAlreadyRegistered)
+					);
+
+					let task_id = ContractWalletId::new::<T>(&id, &address.owner).into_inner();
+					AccountAddresses::<T>::insert(&address.owner, &id, ());
+					Addresses::<T>::insert(&id, address.clone());
+					(task_id, Event::<T>::AddressRegistered(id, address), None)
 				},
 			};
 			T::TaskScheduler::remove(&deadline, &task_id);
 
 			Self::deposit_event(event);
 
+			if let Some(transfer_id) = verified_transfer {
+				Self::record_repayment(&transfer_id);
+			}
+
 			Ok(PostDispatchInfo { actual_weight: None, pays_fee: Pays::No })
 		}
 
@@ -1284,6 +1936,7 @@ pub mod pallet {
 		#[pallet::weight(match &task_id {
 			crate::TaskId::VerifyTransfer(..) => <T as Config>::WeightInfo::fail_transfer(),
 			crate::TaskId::CollectCoins(..) => <T as Config>::WeightInfo::fail_collect_coins(),
+			crate::TaskId::VerifyContractWallet(..) => <T as Config>::WeightInfo::fail_contract_wallet(),
 		})]
 		pub fn fail_task(
 			origin: OriginFor<T>,
@@ -1298,9 +1951,10 @@ pub mod pallet {
 			let (task_id, event) = match task_id {
 				TaskId::VerifyTransfer(transfer_id) => {
 					ensure!(
-						!Transfers::<T>::contains_key(&transfer_id),
+						!Self::is_transfer_registered(&transfer_id),
 						Error::<T>::TransferAlreadyRegistered
 					);
+					RepaymentOrderTransfers::<T>::remove(&transfer_id);
 					(
 						transfer_id.clone().into_inner(),
 						Event::<T>::TransferFailedVerification(transfer_id, cause),
@@ -1316,6 +1970,10 @@ pub mod pallet {
 						Event::<T>::CollectCoinsFailedVerification(collected_coins_id, cause),
 					)
 				},
+				TaskId::VerifyContractWallet(contract_wallet_id) => (
+					contract_wallet_id.clone().into_inner(),
+					Event::<T>::ContractWalletFailedVerification(contract_wallet_id, cause),
+				),
 			};
 			T::TaskScheduler::remove(&deadline, &task_id);
 			Self::deposit_event(event);
@@ -1369,6 +2027,8 @@ pub mod pallet {
 		/// Registers an address on an external blockchain as the property of an onchain address.
 		/// To prove ownership, a signature is provided. To create the signature, the public key of the external address is used to sign a hash of the account_id of whoever is submitting this transaction.
 		/// The signature type allows the caller to specify if this address was signed using the older an insecure EthSign method or the new PersonalSign method. See here for details https://docs.metamask.io/wallet/how-to/sign-data/
+		/// EIP-712 typed data and the signed messages of bitcoin wallets are accepted as proofs too, see `OwnershipProof`.
+		/// The address is stored in its canonical form, e.g. the raw bytes of a hex encoded EVM address, see `helpers::canonical_address`.
 		#[pallet::call_index(22)]
 		#[pallet::weight(<T as Config>::WeightInfo::register_address_v2())]
 		pub fn register_address_v2(
@@ -1378,12 +2038,19 @@ pub mod pallet {
 			ownership_proof: OwnershipProof,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
+			let format = Self::active_external_chain(&blockchain)?.address_format;
+			let address = helpers::normalize_address(&format, address);
 
 			let encoded = who.encode();
 			let account = encoded.as_slice();
 
-			match helpers::try_extract_address::<T>(ownership_proof, account, &blockchain, &address)
-			{
+			match helpers::try_extract_address::<T>(
+				ownership_proof,
+				account,
+				&blockchain,
+				&format,
+				&address,
+			) {
 				Ok(recreated_address) => {
 					// Check if external address of keypair used to sign AccountID
 					// is the same one mentioned in this call to register_address
@@ -1402,10 +2069,11 @@ pub mod pallet {
 					// note: this error condition is unreachable!
 					// AddressFormatNotSupported or OwnershipNotSatisfied will error out first
 					ensure!(
-						helpers::address_is_well_formed(&blockchain, &address),
+						helpers::address_is_well_formed(&format, &address),
 						Error::<T>::MalformedExternalAddress
 					);
 
+					AccountAddresses::<T>::insert(&who, &address_id, ());
 					let entry = Address { blockchain, value: address, owner: who };
 					Self::deposit_event(Event::<T>::AddressRegistered(
 						address_id.clone(),
@@ -1419,5 +2087,728 @@ pub mod pallet {
 				},
 			}
 		}
+
+		/// Removes an ask order before it expires. Offers made against the ask order
+		/// can no longer be turned into deal orders.
+		#[pallet::call_index(23)]
+		#[pallet::weight(<T as Config>::WeightInfo::cancel_ask_order())]
+		pub fn cancel_ask_order(
+			origin: OriginFor<T>,
+			ask_order_id: AskOrderId<T::BlockNumber, T::Hash>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let ask_order = try_get_id!(AskOrders<T>, &ask_order_id, NonExistentAskOrder)?;
+			ensure!(ask_order.lender == who, Error::<T>::NotLender);
+
+			AskOrders::<T>::remove(ask_order_id.expiration(), ask_order_id.hash());
+			AccountAskOrders::<T>::remove(&who, &ask_order_id);
+			Self::delist_ask_order(&ask_order_id, &ask_order);
+			Self::deposit_event(Event::<T>::AskOrderCancelled(ask_order_id));
+			Ok(())
+		}
+
+		/// Removes a bid order before it expires. Offers made against the bid order
+		/// can no longer be turned into deal orders.
+		#[pallet::call_index(24)]
+		#[pallet::weight(<T as Config>::WeightInfo::cancel_bid_order())]
+		pub fn cancel_bid_order(
+			origin: OriginFor<T>,
+			bid_order_id: BidOrderId<T::BlockNumber, T::Hash>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let bid_order = try_get_id!(BidOrders<T>, &bid_order_id, NonExistentBidOrder)?;
+			ensure!(bid_order.borrower == who, Error::<T>::NotBorrower);
+
+			BidOrders::<T>::remove(bid_order_id.expiration(), bid_order_id.hash());
+			AccountBidOrders::<T>::remove(&who, &bid_order_id);
+			Self::delist_bid_order(&bid_order_id, &bid_order);
+			Self::deposit_event(Event::<T>::BidOrderCancelled(bid_order_id));
+			Ok(())
+		}
+
+		/// Removes an offer before it expires or is accepted by the borrower.
+		#[pallet::call_index(25)]
+		#[pallet::weight(<T as Config>::WeightInfo::cancel_offer())]
+		pub fn cancel_offer(
+			origin: OriginFor<T>,
+			offer_id: OfferId<T::BlockNumber, T::Hash>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let offer = try_get_id!(Offers<T>, &offer_id, NonExistentOffer)?;
+			ensure!(offer.lender == who, Error::<T>::NotLender);
+
+			Offers::<T>::remove(offer_id.expiration(), offer_id.hash());
+			AccountOffers::<T>::remove(&who, &offer_id);
+			Self::deposit_event(Event::<T>::OfferCancelled(offer_id));
+			Ok(())
+		}
+
+		/// Sells or hands over the lender's side of a funded deal order that has not been locked
+		/// by the borrower. The incoming lender consents by signing
+		/// `helpers::transfer_deal_order_message`, which covers the deal order, the current lender
+		/// address, the address that should receive the remaining repayments and the deal order's
+		/// transfer nonce.
+		#[pallet::call_index(26)]
+		#[pallet::weight(<T as Config>::WeightInfo::transfer_deal_order())]
+		pub fn transfer_deal_order(
+			origin: OriginFor<T>,
+			deal_order_id: DealOrderId<T::BlockNumber, T::Hash>,
+			new_lender_address_id: AddressId<T::Hash>,
+			new_lender_key: T::Signer,
+			new_lender_signature: T::SignerSignature,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let new_lender = new_lender_key.into_account();
+
+			DealOrders::<T>::try_mutate(
+				deal_order_id.expiration(),
+				deal_order_id.hash(),
+				|value| -> DispatchResult {
+					let deal_order =
+						value.as_mut().ok_or(crate::Error::<T>::NonExistentDealOrder)?;
+
+					let nonce = DealOrderTransferNonces::<T>::get(
+						deal_order_id.expiration(),
+						deal_order_id.hash(),
+					);
+					let message = helpers::transfer_deal_order_message::<T>(
+						&deal_order_id,
+						&deal_order.lender_address_id,
+						&new_lender_address_id,
+						nonce,
+					);
+					ensure!(
+						new_lender_signature.verify(message.as_slice(), &new_lender),
+						Error::<T>::InvalidSignature
+					);
+
+					let new_lender_address = Self::get_address(&new_lender_address_id)?;
+					ensure!(new_lender_address.owner == new_lender, Error::<T>::NotAddressOwner);
+
+					let lender = Self::get_address(&deal_order.lender_address_id)?;
+					ensure!(who == lender.owner, Error::<T>::NotLender);
+					ensure!(
+						!Self::is_syndicated(&deal_order_id, deal_order),
+						Error::<T>::SyndicatedDealOrder
+					);
+
+					ensure!(!deal_order.is_closed(), Error::<T>::DealOrderAlreadyClosed);
+					ensure!(deal_order.funding_transfer_id.is_some(), Error::<T>::DealNotFunded);
+					ensure!(deal_order.lock.is_none(), Error::<T>::DealOrderAlreadyLocked);
+
+					ensure!(
+						new_lender_address.blockchain == deal_order.blockchain,
+						Error::<T>::AddressBlockchainMismatch
+					);
+					ensure!(new_lender != deal_order.borrower, Error::<T>::SameOwner);
+
+					deal_order.lender_address_id = new_lender_address_id.clone();
+					DealOrderTransferNonces::<T>::insert(
+						deal_order_id.expiration(),
+						deal_order_id.hash(),
+						nonce.saturating_add(1),
+					);
+					Ok(())
+				},
+			)?;
+
+			AccountDealOrders::<T>::remove(&who, &deal_order_id);
+			AccountDealOrders::<T>::insert(&new_lender, &deal_order_id, ());
+
+			Self::deposit_event(Event::<T>::DealOrderTransferred(
+				deal_order_id,
+				new_lender_address_id,
+				new_lender,
+			));
+			Ok(())
+		}
+
+		/// Moves the collateral of a loan that went past its term without being repaid, or was
+		/// marked defaulted, from the borrower to the lender.
+		#[transactional]
+		#[pallet::call_index(27)]
+		#[pallet::weight(<T as Config>::WeightInfo::claim_collateral())]
+		pub fn claim_collateral(
+			origin: OriginFor<T>,
+			deal_order_id: DealOrderId<T::BlockNumber, T::Hash>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let mut deal_order = Self::try_get_deal_order(&deal_order_id)?;
+
+			let lender = Self::get_address(&deal_order.lender_address_id)?;
+			ensure!(who == lender.owner, Error::<T>::NotLender);
+
+			let overdue = deal_order.is_overdue(Self::timestamp());
+			ensure!(
+				overdue || deal_order.status == DealOrderStatus::Defaulted,
+				Error::<T>::CollateralNotClaimable
+			);
+
+			let collateral =
+				DealOrderCollateral::<T>::take(deal_order_id.expiration(), deal_order_id.hash())
+					.ok_or(Error::<T>::CollateralNotClaimable)?;
+
+			let shortfall = <pallet_balances::Pallet<T> as ReservableCurrency<T::AccountId>>::repatriate_reserved(
+				&deal_order.borrower,
+				&who,
+				collateral,
+				BalanceStatus::Free,
+			)?;
+			let claimed = collateral.saturating_sub(shortfall);
+
+			if overdue {
+				let previous_status = deal_order.status;
+				deal_order.status = DealOrderStatus::Defaulted;
+				Self::record_loan_outcome(&deal_order, previous_status);
+				DealOrders::<T>::insert_id(deal_order_id.clone(), deal_order);
+				Self::deposit_event(Event::<T>::DealOrderDefaulted(deal_order_id.clone()));
+			}
+
+			Self::deposit_event(Event::<T>::CollateralClaimed(deal_order_id, who, claimed));
+			Ok(())
+		}
+
+		/// Turns automatic matching of new ask and bid orders on or off. Orders already in the
+		/// order book stay there while matching is off.
+		#[pallet::call_index(28)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_order_matching())]
+		pub fn set_order_matching(origin: OriginFor<T>, enabled: bool) -> DispatchResult {
+			ensure_root(origin)?;
+			OrderMatchingEnabled::<T>::put(enabled);
+			Ok(())
+		}
+
+		/// Proposes new terms for a funded deal order that has not been closed yet. Only the
+		/// term length and the interest rate may change, and the term can only be extended.
+		/// The proposal takes effect once the other party accepts it with `accept_terms`.
+		#[pallet::call_index(29)]
+		#[pallet::weight(<T as Config>::WeightInfo::propose_terms())]
+		pub fn propose_terms(
+			origin: OriginFor<T>,
+			deal_order_id: DealOrderId<T::BlockNumber, T::Hash>,
+			terms: LoanTerms,
+			expiration_block: BlockNumberFor<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let head = Self::block_number();
+			ensure!(expiration_block >= head, Error::<T>::TermsProposalExpired);
+
+			let deal_order = Self::try_get_deal_order(&deal_order_id)?;
+			Self::ensure_deal_order_party(&deal_order, &who)?;
+			Self::ensure_terms_negotiable(&deal_order)?;
+			ensure!(
+				!Self::is_syndicated(&deal_order_id, &deal_order),
+				Error::<T>::SyndicatedDealOrder
+			);
+
+			let current = &deal_order.terms;
+			ensure!(
+				terms != *current
+					&& terms.amount == current.amount
+					&& terms.currency == current.currency
+					&& terms.collateral == current.collateral
+					&& terms.term_length >= current.term_length,
+				Error::<T>::InvalidTermsProposal
+			);
+
+			let terms_proposal_id =
+				TermsProposalId::new::<T>(expiration_block, &deal_order_id, &terms);
+			ensure!(
+				!TermsProposals::<T>::contains_id(&terms_proposal_id),
+				Error::<T>::DuplicateTermsProposal
+			);
+
+			let terms_proposal = TermsProposal {
+				deal_order_id,
+				terms,
+				expiration_block,
+				block: head,
+				proposer: who,
+			};
+
+			Self::deposit_event(Event::<T>::TermsProposed(
+				terms_proposal_id.clone(),
+				terms_proposal.clone(),
+			));
+			TermsProposals::<T>::insert_id(terms_proposal_id, terms_proposal);
+			Ok(())
+		}
+
+		/// Accepts terms proposed by the other party of a deal order, replacing its current
+		/// terms.
+		#[pallet::call_index(30)]
+		#[pallet::weight(<T as Config>::WeightInfo::accept_terms())]
+		pub fn accept_terms(
+			origin: OriginFor<T>,
+			terms_proposal_id: TermsProposalId<T::BlockNumber, T::Hash>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let terms_proposal =
+				try_get_id!(TermsProposals<T>, &terms_proposal_id, NonExistentTermsProposal)?;
+			ensure!(
+				terms_proposal.expiration_block >= Self::block_number(),
+				Error::<T>::TermsProposalExpired
+			);
+			ensure!(terms_proposal.proposer != who, Error::<T>::CannotAcceptOwnTermsProposal);
+
+			let deal_order_id = terms_proposal.deal_order_id;
+			DealOrders::<T>::try_mutate(
+				deal_order_id.expiration(),
+				deal_order_id.hash(),
+				|value| -> DispatchResult {
+					let deal_order =
+						value.as_mut().ok_or(crate::Error::<T>::NonExistentDealOrder)?;
+					// the lender may have changed since the terms were proposed
+					Self::ensure_deal_order_party(deal_order, &terms_proposal.proposer)?;
+					Self::ensure_deal_order_party(deal_order, &who)?;
+					Self::ensure_terms_negotiable(deal_order)?;
+
+					deal_order.terms = terms_proposal.terms.clone();
+					Ok(())
+				},
+			)?;
+
+			TermsProposals::<T>::remove(terms_proposal_id.expiration(), terms_proposal_id.hash());
+			Self::deposit_event(Event::<T>::DealOrderTermsUpdated(
+				deal_order_id,
+				terms_proposal_id,
+				terms_proposal.terms,
+			));
+			Ok(())
+		}
+
+		/// Shares the loan amount of a deal order that has not been funded yet with another
+		/// lender, who funds and gets repaid its share at its own address. The share is taken
+		/// from the lead lender's, i.e. the caller's, share. The incoming lender consents by
+		/// signing the deal order id followed by its address and the amount.
+		#[pallet::call_index(31)]
+		#[pallet::weight(<T as Config>::WeightInfo::add_deal_order_share())]
+		pub fn add_deal_order_share(
+			origin: OriginFor<T>,
+			deal_order_id: DealOrderId<T::BlockNumber, T::Hash>,
+			lender_address_id: AddressId<T::Hash>,
+			amount: ExternalAmount,
+			lender_key: T::Signer,
+			lender_signature: T::SignerSignature,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let lender = lender_key.into_account();
+
+			let message = deal_order_id
+				.encode()
+				.into_iter()
+				.chain(lender_address_id.encode())
+				.chain(amount.encode())
+				.collect::<Vec<u8>>();
+
+			ensure!(
+				lender_signature.verify(message.as_slice(), &lender),
+				Error::<T>::InvalidSignature
+			);
+
+			let lender_address = Self::get_address(&lender_address_id)?;
+			ensure!(lender_address.owner == lender, Error::<T>::NotAddressOwner);
+
+			let deal_order = Self::try_get_deal_order(&deal_order_id)?;
+
+			let lead_lender = Self::get_address(&deal_order.lender_address_id)?;
+			ensure!(who == lead_lender.owner, Error::<T>::NotLender);
+
+			ensure!(
+				lender_address.blockchain == deal_order.blockchain,
+				Error::<T>::AddressBlockchainMismatch
+			);
+			ensure!(lender != deal_order.borrower, Error::<T>::SameOwner);
+			ensure!(deal_order.funding_transfer_id.is_none(), Error::<T>::DealOrderAlreadyFunded);
+			let head = Self::block_number();
+			ensure!(deal_order.expiration_block >= head, Error::<T>::DealOrderExpired);
+			ensure!(deal_order.terms.collateral == 0, Error::<T>::SyndicatedDealOrder);
+
+			let mut lead_share =
+				Self::deal_order_shares(&deal_order_id, &deal_order.lender_address_id)
+					.unwrap_or_else(|| LenderShare::new(deal_order.terms.amount));
+			ensure!(lead_share.funding_transfer_id.is_none(), Error::<T>::DealOrderAlreadyFunded);
+
+			ensure!(lender != who, Error::<T>::DuplicateShare);
+			let mut lenders = 0u32;
+			for (address_id, _) in DealOrderShares::<T>::iter_prefix(&deal_order_id) {
+				lenders = lenders.saturating_add(1);
+				let owner = Self::addresses(&address_id).map(|address| address.owner);
+				ensure!(owner.as_ref() != Some(&lender), Error::<T>::DuplicateShare);
+			}
+			// the lead lender has no share entry until the first share is added
+			ensure!(lenders.max(1) < MAX_LENDERS_PER_DEAL, Error::<T>::TooManyLenders);
+
+			ensure!(
+				!amount.is_zero() && amount < lead_share.amount,
+				Error::<T>::InvalidShareAmount
+			);
+			lead_share.amount = lead_share.amount.saturating_sub(amount);
+
+			DealOrderShares::<T>::insert(&deal_order_id, &deal_order.lender_address_id, lead_share);
+			DealOrderShares::<T>::insert(
+				&deal_order_id,
+				&lender_address_id,
+				LenderShare::new(amount),
+			);
+			AccountDealOrders::<T>::insert(&lender, &deal_order_id, ());
+
+			Self::deposit_event(Event::<T>::DealOrderShareAdded(
+				deal_order_id,
+				lender_address_id,
+				amount,
+			));
+			Ok(())
+		}
+
+		/// Registers a repayment of a syndicated deal order sent to one of its lenders. Each
+		/// lender is owed the part of the repayment that matches its share of the loan amount.
+		#[transactional]
+		#[pallet::call_index(32)]
+		#[pallet::weight(<T as Config>::WeightInfo::register_share_repayment_transfer())]
+		pub fn register_share_repayment_transfer(
+			origin: OriginFor<T>,
+			transfer_kind: TransferKind,
+			repayment_amount: ExternalAmount,
+			deal_order_id: DealOrderId<T::BlockNumber, T::Hash>,
+			lender_address_id: AddressId<T::Hash>,
+			blockchain_tx_id: ExternalTxId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let order = Self::try_get_deal_order(&deal_order_id)?;
+			ensure!(
+				DealOrderShares::<T>::contains_key(&deal_order_id, &lender_address_id),
+				Error::<T>::NonExistentShare
+			);
+			ensure!(order.terms.currency.accepts(&transfer_kind), Error::<T>::CurrencyMismatch);
+
+			let (transfer_id, transfer) = Self::register_transfer_internal(
+				who,
+				order.borrower_address_id,
+				lender_address_id,
+				transfer_kind,
+				repayment_amount,
+				OrderId::Deal(deal_order_id),
+				blockchain_tx_id,
+			)?;
+			Self::deposit_event(Event::<T>::TransferRegistered(transfer_id, transfer));
+
+			Ok(())
+		}
+
+		/// Sets how many blocks closed and exempted deal orders are kept for before they are
+		/// archived, or keeps them forever if `retention` is `None`.
+		#[pallet::call_index(33)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_deal_order_retention())]
+		pub fn set_deal_order_retention(
+			origin: OriginFor<T>,
+			retention: Option<T::BlockNumber>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			DealOrderRetention::<T>::set(retention);
+			Ok(())
+		}
+
+		/// Offers to repay `amount` of a funded deal order from an address of the caller, who
+		/// need not be its borrower. The repayment is settled once the transfer registered with
+		/// `register_repayment_order_transfer` has been verified.
+		#[pallet::call_index(34)]
+		#[pallet::weight(<T as Config>::WeightInfo::add_repayment_order())]
+		pub fn add_repayment_order(
+			origin: OriginFor<T>,
+			deal_order_id: DealOrderId<T::BlockNumber, T::Hash>,
+			address_id: AddressId<T::Hash>,
+			amount: ExternalAmount,
+			expiration_block: BlockNumberFor<T>,
+			guid: Guid,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			ensure!(!amount.is_zero(), Error::<T>::ZeroRepaymentAmount);
+
+			let head = Self::block_number();
+			ensure!(expiration_block >= head, Error::<T>::RepaymentOrderExpired);
+
+			let repayment_order_id = RepaymentOrderId::new::<T>(expiration_block, &guid);
+			ensure!(
+				!RepaymentOrders::<T>::contains_id(&repayment_order_id),
+				Error::<T>::DuplicateId
+			);
+
+			let deal_order = Self::try_get_deal_order(&deal_order_id)?;
+			ensure!(deal_order.funding_transfer_id.is_some(), Error::<T>::DealNotFunded);
+			ensure!(!deal_order.is_closed(), Error::<T>::DealOrderAlreadyClosed);
+			ensure!(
+				!Self::is_syndicated(&deal_order_id, &deal_order),
+				Error::<T>::SyndicatedDealOrder
+			);
+
+			let address = Self::get_address(&address_id)?;
+			ensure!(address.owner == who, Error::<T>::NotAddressOwner);
+			ensure!(
+				address.blockchain == deal_order.blockchain,
+				Error::<T>::AddressBlockchainMismatch
+			);
+
+			Self::use_guid(&guid)?;
+
+			let repayment_order = RepaymentOrder {
+				deal_order_id,
+				payer_address_id: address_id,
+				amount,
+				expiration_block,
+				block: head,
+				payer: who,
+			};
+
+			Self::deposit_event(Event::<T>::RepaymentOrderAdded(
+				repayment_order_id.clone(),
+				repayment_order.clone(),
+			));
+			RepaymentOrders::<T>::insert_id(repayment_order_id, repayment_order);
+			Ok(())
+		}
+
+		/// Removes a repayment order before it expires or is settled.
+		#[pallet::call_index(35)]
+		#[pallet::weight(<T as Config>::WeightInfo::cancel_repayment_order())]
+		pub fn cancel_repayment_order(
+			origin: OriginFor<T>,
+			repayment_order_id: RepaymentOrderId<T::BlockNumber, T::Hash>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let repayment_order =
+				try_get_id!(RepaymentOrders<T>, &repayment_order_id, NonExistentRepaymentOrder)?;
+			ensure!(repayment_order.payer == who, Error::<T>::NotPayer);
+
+			RepaymentOrders::<T>::remove(
+				repayment_order_id.expiration(),
+				repayment_order_id.hash(),
+			);
+			Self::deposit_event(Event::<T>::RepaymentOrderCancelled(repayment_order_id));
+			Ok(())
+		}
+
+		/// Registers the transfer of a repayment order, sent from the payer's address to the
+		/// lender of the deal order.
+		#[transactional]
+		#[pallet::call_index(36)]
+		#[pallet::weight(<T as Config>::WeightInfo::register_repayment_order_transfer())]
+		pub fn register_repayment_order_transfer(
+			origin: OriginFor<T>,
+			transfer_kind: TransferKind,
+			repayment_order_id: RepaymentOrderId<T::BlockNumber, T::Hash>,
+			blockchain_tx_id: ExternalTxId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let repayment_order =
+				try_get_id!(RepaymentOrders<T>, &repayment_order_id, NonExistentRepaymentOrder)?;
+			ensure!(repayment_order.payer == who, Error::<T>::NotPayer);
+			ensure!(
+				repayment_order.expiration_block >= Self::block_number(),
+				Error::<T>::RepaymentOrderExpired
+			);
+			let order = Self::try_get_deal_order(&repayment_order.deal_order_id)?;
+			ensure!(order.terms.currency.accepts(&transfer_kind), Error::<T>::CurrencyMismatch);
+
+			let (transfer_id, transfer) = Self::register_transfer_internal(
+				who,
+				repayment_order.payer_address_id.clone(),
+				order.lender_address_id,
+				transfer_kind,
+				repayment_order.amount,
+				OrderId::Repayment(repayment_order_id),
+				blockchain_tx_id,
+			)?;
+			RepaymentOrderTransfers::<T>::insert(&transfer_id, repayment_order);
+			Self::deposit_event(Event::<T>::TransferRegistered(transfer_id, transfer));
+
+			Ok(())
+		}
+
+		/// Sets the fee charged when a deal order is funded, or stops charging one if `fee` is
+		/// `None`.
+		#[pallet::call_index(37)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_origination_fee())]
+		pub fn set_origination_fee(
+			origin: OriginFor<T>,
+			fee: Option<OriginationFee<T::AccountId, T::Balance>>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			OriginationFeeParameters::<T>::set(fee);
+			Ok(())
+		}
+
+		/// Removes an external address registered by the caller, e.g. because its key was
+		/// compromised. The address cannot be removed while an open ask, bid or deal order
+		/// references it. Repayment orders made from the address can no longer be used.
+		#[pallet::call_index(38)]
+		#[pallet::weight(<T as Config>::WeightInfo::unregister_address(MAX_ADDRESS_ORDERS_SCANNED))]
+		pub fn unregister_address(
+			origin: OriginFor<T>,
+			address_id: AddressId<T::Hash>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			let address = Self::get_address(&address_id)?;
+			ensure!(address.owner == who, Error::<T>::NotAddressOwner);
+
+			let (lent_deal_orders, scanned) = Self::ensure_address_unused(&who, &address_id)?;
+			for deal_order_id in lent_deal_orders {
+				AccountDealOrders::<T>::remove(&who, &deal_order_id);
+			}
+			AccountAddresses::<T>::remove(&who, &address_id);
+			Addresses::<T>::remove(&address_id);
+
+			Self::deposit_event(Event::<T>::AddressUnregistered(address_id, address));
+			Ok(Some(<T as Config>::WeightInfo::unregister_address(scanned)).into())
+		}
+
+		/// Moves an external address of the caller to `new_owner`. Along with the signature of
+		/// the current owner this takes a fresh `ownership_proof` of the address, made the same
+		/// way as for `register_address_v2` but over `helpers::transfer_address_message`, which
+		/// covers the address, both owners and the address' transfer nonce. The address cannot be
+		/// moved while an open ask, bid or deal order references it.
+		#[pallet::call_index(39)]
+		#[pallet::weight(<T as Config>::WeightInfo::transfer_address(MAX_ADDRESS_ORDERS_SCANNED))]
+		pub fn transfer_address(
+			origin: OriginFor<T>,
+			address_id: AddressId<T::Hash>,
+			new_owner: T::AccountId,
+			ownership_proof: OwnershipProof,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			let mut address = Self::get_address(&address_id)?;
+			ensure!(address.owner == who, Error::<T>::NotAddressOwner);
+			ensure!(new_owner != who, Error::<T>::SameOwner);
+
+			let format = Self::external_chain(&address.blockchain)?.address_format;
+			let nonce = Self::address_transfer_nonce(&address_id);
+			let message =
+				helpers::transfer_address_message::<T>(&address_id, &who, &new_owner, nonce);
+			let recreated_address = helpers::try_extract_address::<T>(
+				ownership_proof,
+				&message,
+				&address.blockchain,
+				&format,
+				&address.value,
+			)?;
+			ensure!(recreated_address == address.value, Error::<T>::OwnershipNotSatisfied);
+
+			let (lent_deal_orders, scanned) = Self::ensure_address_unused(&who, &address_id)?;
+			AddressTransferNonces::<T>::insert(&address_id, nonce.saturating_add(1));
+			for deal_order_id in lent_deal_orders {
+				AccountDealOrders::<T>::remove(&who, &deal_order_id);
+				AccountDealOrders::<T>::insert(&new_owner, &deal_order_id, ());
+			}
+			AccountAddresses::<T>::remove(&who, &address_id);
+			AccountAddresses::<T>::insert(&new_owner, &address_id, ());
+			address.owner = new_owner.clone();
+			Addresses::<T>::insert(&address_id, address);
+
+			Self::deposit_event(Event::<T>::AddressTransferred(address_id, who, new_owner));
+			Ok(Some(<T as Config>::WeightInfo::transfer_address(scanned)).into())
+		}
+
+		/// Registers a smart contract wallet, e.g. a Safe, on an EVM chain as the property of the
+		/// caller. Such wallets cannot sign the ownership proofs of `register_address_v2`, instead
+		/// an offchain worker asks the wallet through EIP-1271 whether `signature` is valid for
+		/// `helpers::contract_wallet_message_hash` of the caller's account id. The address is
+		/// registered once the wallet answers with the magic value. Other accounts may claim the
+		/// same wallet in the meantime, each claim is verified on its own.
+		#[transactional]
+		#[pallet::call_index(40)]
+		#[pallet::weight(<T as Config>::WeightInfo::register_contract_wallet())]
+		pub fn register_contract_wallet(
+			origin: OriginFor<T>,
+			blockchain: Blockchain,
+			address: ExternalAddress,
+			signature: ContractWalletSignature,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let format = Self::active_external_chain(&blockchain)?.address_format;
+			ensure!(format == AddressFormat::Evm, Error::<T>::AddressFormatNotSupported);
+			let address = helpers::normalize_address(&format, address);
+			ensure!(
+				helpers::address_is_well_formed(&format, &address),
+				Error::<T>::MalformedExternalAddress
+			);
+
+			let pending = UnverifiedContractWallet { blockchain, address, signature, owner: who };
+			let contract_wallet_id = TaskV2::<T>::to_id(&pending);
+
+			if let Ok(registered) = Addresses::<T>::try_get(pending.address_id::<T>()) {
+				if registered.owner == pending.owner {
+					fail!(Error::<T>::AddressAlreadyRegisteredByCaller);
+				}
+				fail!(Error::<T>::AddressAlreadyRegistered);
+			}
+
+			let deadline = T::TaskScheduler::deadline();
+
+			ensure!(
+				!T::TaskScheduler::is_scheduled(&deadline, &contract_wallet_id),
+				Error::<T>::ContractWalletRegistrationPending
+			);
+
+			T::TaskScheduler::insert(&deadline, &contract_wallet_id, Task::from(pending.clone()));
+
+			Self::deposit_event(Event::<T>::ContractWalletRegistrationRequested(
+				contract_wallet_id.into(),
+				pending,
+			));
+			Ok(())
+		}
+
+		/// Adds an external chain to the registry of supported chains, or changes it. Chains
+		/// are not removed, they are deprecated instead so the loans on them can still be
+		/// settled.
+		#[pallet::call_index(41)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_external_chain())]
+		pub fn set_external_chain(
+			origin: OriginFor<T>,
+			blockchain: Blockchain,
+			chain: ExternalChain,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(chain.is_valid(), Error::<T>::InvalidExternalChain);
+			// address ids hash addresses in the format of their chain, so it cannot change
+			let format = blockchain.builtin_address_format().or_else(|| {
+				ExternalChains::<T>::get(&blockchain).map(|chain| chain.address_format)
+			});
+			ensure!(
+				format.map_or(true, |format| format == chain.address_format),
+				Error::<T>::AddressFormatChanged
+			);
+			ExternalChains::<T>::insert(&blockchain, &chain);
+			Self::deposit_event(Event::<T>::ExternalChainSet(blockchain, chain));
+			Ok(())
+		}
+
+		/// Closes a locked deal order once the repayments credited towards it cover what is
+		/// due. Verified repayments are credited as they are persisted, and a locked deal order
+		/// is closed by the repayment that completes it, so this is only needed for a deal order
+		/// that was repaid in full before the borrower locked it.
+		#[pallet::call_index(42)]
+		#[pallet::weight(<T as Config>::WeightInfo::close_deal_order())]
+		pub fn close_repaid_deal_order(
+			origin: OriginFor<T>,
+			deal_order_id: DealOrderId<T::BlockNumber, T::Hash>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::close_repaid(&who, deal_order_id)?;
+			Ok(())
+		}
 	}
 }
@@ -12,12 +12,22 @@ pub(crate) trait Migrate {
 }
 
 mod v1;
+mod v10;
+mod v11;
+mod v12;
+mod v13;
+mod v14;
+mod v15;
+mod v16;
+mod v17;
 mod v2;
 mod v3;
 mod v4;
 mod v5;
 pub(crate) mod v6;
 pub mod v7;
+mod v8;
+mod v9;
 
 pub(crate) fn migrate<T: Config>() -> Weight {
 	let version = StorageVersion::get::<Pallet<T>>();
@@ -31,6 +41,16 @@ pub(crate) fn migrate<T: Config>() -> Weight {
 		&v5::Migration::<T>::new(),
 		&v6::Migration::<T>::new(),
 		&v7::Migration::<T>::new(),
+		&v8::Migration::<T>::new(),
+		&v9::Migration::<T>::new(),
+		&v10::Migration::<T>::new(),
+		&v11::Migration::<T>::new(),
+		&v12::Migration::<T>::new(),
+		&v13::Migration::<T>::new(),
+		&v14::Migration::<T>::new(),
+		&v15::Migration::<T>::new(),
+		&v16::Migration::<T>::new(),
+		&v17::Migration::<T>::new(),
 	];
 
 	for (idx, &calls) in callbacks.iter().enumerate() {
@@ -0,0 +1,412 @@
+// `collateral` added to `LoanTerms`
+
+use super::{AccountIdOf, BlockNumberOf, HashOf, Migrate, MomentOf};
+use crate::{
+	AddressId, Blockchain, Config, DealOrderStatus, Duration, ExternalAmount, InterestRate,
+	OfferId, TransferId,
+};
+use frame_support::{pallet_prelude::*, traits::Get, Identity, Twox64Concat};
+use sp_std::prelude::*;
+
+use super::v11::OldAskOrder as AskOrder;
+use super::v11::OldAskTerms as AskTerms;
+use super::v11::OldBidOrder as BidOrder;
+use super::v11::OldBidTerms as BidTerms;
+use super::v14::OldDealOrder as DealOrder;
+use super::v15::OldLoanTerms as LoanTerms;
+
+#[derive(Clone, Encode, Decode)]
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+pub struct OldLoanTerms {
+	pub amount: ExternalAmount,
+	pub interest_rate: InterestRate,
+	pub term_length: Duration,
+}
+
+#[cfg(test)]
+impl Default for OldLoanTerms {
+	fn default() -> Self {
+		Self {
+			amount: Default::default(),
+			interest_rate: InterestRate::default(),
+			term_length: Duration::from_millis(100_000),
+		}
+	}
+}
+
+#[derive(Encode, Decode)]
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+pub struct OldAskTerms(pub OldLoanTerms);
+
+#[derive(Encode, Decode)]
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+pub struct OldBidTerms(pub OldLoanTerms);
+
+#[derive(Encode, Decode)]
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+pub struct OldAskOrder<AccountId, BlockNum, Hash> {
+	pub blockchain: Blockchain,
+	pub lender_address_id: AddressId<Hash>,
+	pub terms: OldAskTerms,
+	pub expiration_block: BlockNum,
+	pub block: BlockNum,
+	pub lender: AccountId,
+}
+
+#[derive(Encode, Decode)]
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+pub struct OldBidOrder<AccountId, BlockNum, Hash> {
+	pub blockchain: Blockchain,
+	pub borrower_address_id: AddressId<Hash>,
+	pub terms: OldBidTerms,
+	pub expiration_block: BlockNum,
+	pub block: BlockNum,
+	pub borrower: AccountId,
+}
+
+#[derive(Encode, Decode)]
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+pub struct OldDealOrder<AccountId, BlockNum, Hash, Moment> {
+	pub blockchain: Blockchain,
+	pub offer_id: OfferId<BlockNum, Hash>,
+	pub lender_address_id: AddressId<Hash>,
+	pub borrower_address_id: AddressId<Hash>,
+	pub terms: OldLoanTerms,
+	pub expiration_block: BlockNum,
+	pub timestamp: Moment,
+	pub block: Option<BlockNum>,
+	pub funding_transfer_id: Option<TransferId<Hash>>,
+	pub repayment_transfer_id: Option<TransferId<Hash>>,
+	pub repaid_amount: ExternalAmount,
+	pub lock: Option<AccountId>,
+	pub borrower: AccountId,
+	pub status: DealOrderStatus,
+}
+
+// existing loans were made without collateral
+impl From<OldLoanTerms> for LoanTerms {
+	fn from(old: OldLoanTerms) -> Self {
+		Self {
+			amount: old.amount,
+			interest_rate: old.interest_rate,
+			term_length: old.term_length,
+			collateral: 0,
+		}
+	}
+}
+
+impl From<OldAskTerms> for AskTerms {
+	fn from(old: OldAskTerms) -> Self {
+		Self(LoanTerms::from(old.0))
+	}
+}
+
+impl From<OldBidTerms> for BidTerms {
+	fn from(old: OldBidTerms) -> Self {
+		Self(LoanTerms::from(old.0))
+	}
+}
+
+#[frame_support::storage_alias]
+type AskOrders<T: crate::Config> = StorageDoubleMap<
+	crate::Pallet<T>,
+	Twox64Concat,
+	BlockNumberOf<T>,
+	Identity,
+	HashOf<T>,
+	AskOrder<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>>,
+>;
+
+#[frame_support::storage_alias]
+type BidOrders<T: crate::Config> = StorageDoubleMap<
+	crate::Pallet<T>,
+	Twox64Concat,
+	BlockNumberOf<T>,
+	Identity,
+	HashOf<T>,
+	BidOrder<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>>,
+>;
+
+#[frame_support::storage_alias]
+type DealOrders<T: crate::Config> = StorageDoubleMap<
+	crate::Pallet<T>,
+	Twox64Concat,
+	BlockNumberOf<T>,
+	Identity,
+	HashOf<T>,
+	DealOrder<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>, MomentOf<T>>,
+>;
+
+pub(super) struct Migration<Runtime>(PhantomData<Runtime>);
+
+impl<Runtime> Migration<Runtime> {
+	pub(super) fn new() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<T: Config> Migrate for Migration<T> {
+	fn pre_upgrade(&self) -> Vec<u8> {
+		vec![]
+	}
+
+	fn migrate(&self) -> Weight {
+		let mut weight: Weight = Weight::zero();
+		let weight_each = T::DbWeight::get().reads_writes(1, 1);
+
+		DealOrders::<T>::translate::<
+			OldDealOrder<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>, MomentOf<T>>,
+			_,
+		>(|_exp, _hash, old_deal| {
+			weight = weight.saturating_add(weight_each);
+			Some(DealOrder {
+				blockchain: old_deal.blockchain,
+				offer_id: old_deal.offer_id,
+				lender_address_id: old_deal.lender_address_id,
+				borrower_address_id: old_deal.borrower_address_id,
+				terms: LoanTerms::from(old_deal.terms),
+				expiration_block: old_deal.expiration_block,
+				timestamp: old_deal.timestamp,
+				block: old_deal.block,
+				funding_transfer_id: old_deal.funding_transfer_id,
+				repayment_transfer_id: old_deal.repayment_transfer_id,
+				repaid_amount: old_deal.repaid_amount,
+				lock: old_deal.lock,
+				borrower: old_deal.borrower,
+				status: old_deal.status,
+			})
+		});
+
+		AskOrders::<T>::translate::<OldAskOrder<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>>, _>(
+			|_exp, _hash, old_ask| {
+				weight = weight.saturating_add(weight_each);
+				Some(AskOrder {
+					blockchain: old_ask.blockchain,
+					lender_address_id: old_ask.lender_address_id,
+					terms: AskTerms::from(old_ask.terms),
+					expiration_block: old_ask.expiration_block,
+					block: old_ask.block,
+					lender: old_ask.lender,
+				})
+			},
+		);
+
+		BidOrders::<T>::translate::<OldBidOrder<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>>, _>(
+			|_exp, _hash, old_bid| {
+				weight = weight.saturating_add(weight_each);
+				Some(BidOrder {
+					blockchain: old_bid.blockchain,
+					borrower_address_id: old_bid.borrower_address_id,
+					terms: BidTerms::from(old_bid.terms),
+					expiration_block: old_bid.expiration_block,
+					block: old_bid.block,
+					borrower: old_bid.borrower,
+				})
+			},
+		);
+
+		weight
+	}
+
+	fn post_upgrade(&self, _ctx: Vec<u8>) {
+		assert_eq!(
+			StorageVersion::get::<crate::Pallet<T>>(),
+			10,
+			"expected storage version to be 10 after migrations complete"
+		);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Migrate;
+
+	use crate::{
+		mock::{ExtBuilder, Test},
+		tests::TestInfo,
+		AskOrderId, BidOrderId, Blockchain, DealOrderId, DealOrderStatus, DoubleMapExt, Duration,
+		ExternalAmount, OfferId,
+	};
+
+	use super::{
+		AccountIdOf, AskOrder, AskTerms, BidOrder, BidTerms, BlockNumberOf, DealOrder, HashOf,
+		Identity, LoanTerms, MomentOf, OldAskOrder, OldAskTerms, OldBidOrder, OldBidTerms,
+		OldDealOrder, OldLoanTerms, Twox64Concat,
+	};
+
+	#[frame_support::storage_alias]
+	type DealOrders<T: crate::Config> = StorageDoubleMap<
+		crate::Pallet<T>,
+		Twox64Concat,
+		BlockNumberOf<T>,
+		Identity,
+		HashOf<T>,
+		OldDealOrder<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>, MomentOf<T>>,
+	>;
+
+	type OldDealOrders = DealOrders<Test>;
+
+	#[frame_support::storage_alias]
+	type AskOrders<T: crate::Config> = StorageDoubleMap<
+		crate::Pallet<T>,
+		Twox64Concat,
+		BlockNumberOf<T>,
+		Identity,
+		HashOf<T>,
+		OldAskOrder<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>>,
+	>;
+
+	type OldAskOrders = AskOrders<Test>;
+
+	#[frame_support::storage_alias]
+	type BidOrders<T: crate::Config> = StorageDoubleMap<
+		crate::Pallet<T>,
+		Twox64Concat,
+		BlockNumberOf<T>,
+		Identity,
+		HashOf<T>,
+		OldBidOrder<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>>,
+	>;
+
+	type OldBidOrders = BidOrders<Test>;
+
+	fn old_loan_terms() -> OldLoanTerms {
+		OldLoanTerms {
+			amount: 100u64.into(),
+			interest_rate: Default::default(),
+			term_length: Duration::from_millis(2000),
+		}
+	}
+
+	fn migrated_loan_terms() -> LoanTerms {
+		LoanTerms {
+			amount: 100u64.into(),
+			interest_rate: Default::default(),
+			term_length: Duration::from_millis(2000),
+			collateral: 0,
+		}
+	}
+
+	#[test]
+	fn ask_order_migrates() {
+		ExtBuilder::default().build_and_execute(|| {
+			let ask_order_id = AskOrderId::new::<Test>(100, "asdf".as_bytes());
+			let test_info = TestInfo::new_defaults();
+
+			let old_ask_order = OldAskOrder {
+				blockchain: Blockchain::Ethereum,
+				lender_address_id: test_info.lender.address_id,
+				terms: OldAskTerms(old_loan_terms()),
+				expiration_block: 100,
+				block: 1,
+				lender: test_info.lender.account_id,
+			};
+
+			OldAskOrders::insert_id(&ask_order_id, &old_ask_order);
+
+			super::Migration::<Test>::new().migrate();
+
+			let ask_order = super::AskOrders::<Test>::try_get_id(&ask_order_id).unwrap();
+
+			assert_eq!(
+				ask_order,
+				AskOrder {
+					blockchain: old_ask_order.blockchain,
+					lender_address_id: old_ask_order.lender_address_id,
+					terms: AskTerms(migrated_loan_terms()),
+					expiration_block: old_ask_order.expiration_block,
+					block: old_ask_order.block,
+					lender: old_ask_order.lender,
+				}
+			);
+		});
+	}
+
+	#[test]
+	fn bid_order_migrates() {
+		ExtBuilder::default().build_and_execute(|| {
+			let bid_order_id = BidOrderId::new::<Test>(100, "asdf".as_bytes());
+			let test_info = TestInfo::new_defaults();
+
+			let old_bid_order = OldBidOrder {
+				blockchain: Blockchain::Ethereum,
+				borrower_address_id: test_info.borrower.address_id,
+				terms: OldBidTerms(old_loan_terms()),
+				expiration_block: 100,
+				block: 1,
+				borrower: test_info.borrower.account_id,
+			};
+
+			OldBidOrders::insert_id(&bid_order_id, &old_bid_order);
+
+			super::Migration::<Test>::new().migrate();
+
+			let bid_order = super::BidOrders::<Test>::try_get_id(&bid_order_id).unwrap();
+
+			assert_eq!(
+				bid_order,
+				BidOrder {
+					blockchain: old_bid_order.blockchain,
+					borrower_address_id: old_bid_order.borrower_address_id,
+					terms: BidTerms(migrated_loan_terms()),
+					expiration_block: old_bid_order.expiration_block,
+					block: old_bid_order.block,
+					borrower: old_bid_order.borrower,
+				}
+			);
+		});
+	}
+
+	#[test]
+	fn deal_order_migrates() {
+		ExtBuilder::default().build_and_execute(|| {
+			let test_info = TestInfo::new_defaults();
+
+			let deal_id = DealOrderId::with_expiration_hash::<Test>(100, [0u8; 32].into());
+			let offer_id = OfferId::with_expiration_hash::<Test>(100, [1u8; 32].into());
+
+			let old_deal = OldDealOrder {
+				blockchain: Blockchain::Ethereum,
+				offer_id,
+				lender_address_id: test_info.lender.address_id,
+				borrower_address_id: test_info.borrower.address_id,
+				terms: old_loan_terms(),
+				expiration_block: 100,
+				timestamp: 0,
+				block: None,
+				funding_transfer_id: None,
+				repayment_transfer_id: None,
+				repaid_amount: ExternalAmount::zero(),
+				lock: None,
+				borrower: test_info.borrower.account_id,
+				status: DealOrderStatus::Pending,
+			};
+
+			OldDealOrders::insert_id(&deal_id, &old_deal);
+
+			super::Migration::<Test>::new().migrate();
+
+			let deal = super::DealOrders::<Test>::try_get_id(&deal_id).unwrap();
+
+			assert_eq!(
+				deal,
+				DealOrder {
+					blockchain: old_deal.blockchain,
+					offer_id: old_deal.offer_id,
+					lender_address_id: old_deal.lender_address_id,
+					borrower_address_id: old_deal.borrower_address_id,
+					terms: migrated_loan_terms(),
+					expiration_block: old_deal.expiration_block,
+					timestamp: old_deal.timestamp,
+					block: old_deal.block,
+					funding_transfer_id: old_deal.funding_transfer_id,
+					repayment_transfer_id: old_deal.repayment_transfer_id,
+					repaid_amount: old_deal.repaid_amount,
+					lock: old_deal.lock,
+					borrower: old_deal.borrower,
+					status: old_deal.status,
+				}
+			);
+		});
+	}
+}
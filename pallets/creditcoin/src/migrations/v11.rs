@@ -0,0 +1,287 @@
+// ask and bid terms become ranges
+
+use super::{AccountIdOf, BlockNumberOf, HashOf, Migrate};
+use crate::{AddressId, Blockchain, Config};
+use frame_support::{pallet_prelude::*, traits::Get, Identity, Twox64Concat};
+use sp_std::prelude::*;
+
+use super::v15::OldAskOrder as AskOrder;
+use super::v15::OldAskTerms as AskTerms;
+use super::v15::OldBidOrder as BidOrder;
+use super::v15::OldBidTerms as BidTerms;
+use super::v15::OldLoanTerms as LoanTerms;
+
+#[derive(Encode, Decode)]
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+pub struct OldAskTerms(pub LoanTerms);
+
+#[derive(Encode, Decode)]
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+pub struct OldBidTerms(pub LoanTerms);
+
+#[derive(Encode, Decode)]
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+pub struct OldAskOrder<AccountId, BlockNum, Hash> {
+	pub blockchain: Blockchain,
+	pub lender_address_id: AddressId<Hash>,
+	pub terms: OldAskTerms,
+	pub expiration_block: BlockNum,
+	pub block: BlockNum,
+	pub lender: AccountId,
+}
+
+#[derive(Encode, Decode)]
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+pub struct OldBidOrder<AccountId, BlockNum, Hash> {
+	pub blockchain: Blockchain,
+	pub borrower_address_id: AddressId<Hash>,
+	pub terms: OldBidTerms,
+	pub expiration_block: BlockNum,
+	pub block: BlockNum,
+	pub borrower: AccountId,
+}
+
+// existing orders only accept their exact terms
+impl From<OldAskTerms> for AskTerms {
+	fn from(old: OldAskTerms) -> Self {
+		let terms = old.0;
+		Self {
+			min_amount: terms.amount,
+			max_amount: terms.amount,
+			min_interest_rate: terms.interest_rate,
+			max_term_length: terms.term_length,
+			min_collateral: terms.collateral,
+		}
+	}
+}
+
+impl From<OldBidTerms> for BidTerms {
+	fn from(old: OldBidTerms) -> Self {
+		let terms = old.0;
+		Self {
+			amount: terms.amount,
+			max_interest_rate: terms.interest_rate,
+			min_term_length: terms.term_length,
+			max_term_length: terms.term_length,
+			max_collateral: terms.collateral,
+		}
+	}
+}
+
+#[frame_support::storage_alias]
+type AskOrders<T: crate::Config> = StorageDoubleMap<
+	crate::Pallet<T>,
+	Twox64Concat,
+	BlockNumberOf<T>,
+	Identity,
+	HashOf<T>,
+	AskOrder<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>>,
+>;
+
+#[frame_support::storage_alias]
+type BidOrders<T: crate::Config> = StorageDoubleMap<
+	crate::Pallet<T>,
+	Twox64Concat,
+	BlockNumberOf<T>,
+	Identity,
+	HashOf<T>,
+	BidOrder<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>>,
+>;
+
+pub(super) struct Migration<Runtime>(PhantomData<Runtime>);
+
+impl<Runtime> Migration<Runtime> {
+	pub(super) fn new() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<T: Config> Migrate for Migration<T> {
+	fn pre_upgrade(&self) -> Vec<u8> {
+		vec![]
+	}
+
+	fn migrate(&self) -> Weight {
+		let mut weight: Weight = Weight::zero();
+		let weight_each = T::DbWeight::get().reads_writes(1, 1);
+
+		AskOrders::<T>::translate::<OldAskOrder<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>>, _>(
+			|_exp, _hash, old_ask| {
+				weight = weight.saturating_add(weight_each);
+				Some(AskOrder {
+					blockchain: old_ask.blockchain,
+					lender_address_id: old_ask.lender_address_id,
+					terms: AskTerms::from(old_ask.terms),
+					expiration_block: old_ask.expiration_block,
+					block: old_ask.block,
+					lender: old_ask.lender,
+				})
+			},
+		);
+
+		BidOrders::<T>::translate::<OldBidOrder<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>>, _>(
+			|_exp, _hash, old_bid| {
+				weight = weight.saturating_add(weight_each);
+				Some(BidOrder {
+					blockchain: old_bid.blockchain,
+					borrower_address_id: old_bid.borrower_address_id,
+					terms: BidTerms::from(old_bid.terms),
+					expiration_block: old_bid.expiration_block,
+					block: old_bid.block,
+					borrower: old_bid.borrower,
+				})
+			},
+		);
+
+		weight
+	}
+
+	fn post_upgrade(&self, _ctx: Vec<u8>) {
+		assert_eq!(
+			StorageVersion::get::<crate::Pallet<T>>(),
+			11,
+			"expected storage version to be 11 after migrations complete"
+		);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Migrate;
+
+	use crate::{
+		mock::{ExtBuilder, Test},
+		tests::TestInfo,
+		AskOrderId, BidOrderId, Blockchain, DoubleMapExt, Duration,
+	};
+
+	use super::{
+		AccountIdOf, AskOrder, AskTerms, BidOrder, BidTerms, BlockNumberOf, HashOf, Identity,
+		LoanTerms, OldAskOrder, OldAskTerms, OldBidOrder, OldBidTerms, Twox64Concat,
+	};
+
+	#[frame_support::storage_alias]
+	type AskOrders<T: crate::Config> = StorageDoubleMap<
+		crate::Pallet<T>,
+		Twox64Concat,
+		BlockNumberOf<T>,
+		Identity,
+		HashOf<T>,
+		OldAskOrder<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>>,
+	>;
+
+	type OldAskOrders = AskOrders<Test>;
+
+	#[frame_support::storage_alias]
+	type BidOrders<T: crate::Config> = StorageDoubleMap<
+		crate::Pallet<T>,
+		Twox64Concat,
+		BlockNumberOf<T>,
+		Identity,
+		HashOf<T>,
+		OldBidOrder<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>>,
+	>;
+
+	type OldBidOrders = BidOrders<Test>;
+
+	fn loan_terms() -> LoanTerms {
+		LoanTerms {
+			amount: 100u64.into(),
+			interest_rate: Default::default(),
+			term_length: Duration::from_millis(2000),
+			collateral: 10,
+		}
+	}
+
+	#[test]
+	fn ask_order_migrates() {
+		ExtBuilder::default().build_and_execute(|| {
+			let ask_order_id = AskOrderId::new::<Test>(100, "asdf".as_bytes());
+			let test_info = TestInfo::new_defaults();
+
+			let old_ask_order = OldAskOrder {
+				blockchain: Blockchain::Ethereum,
+				lender_address_id: test_info.lender.address_id,
+				terms: OldAskTerms(loan_terms()),
+				expiration_block: 100,
+				block: 1,
+				lender: test_info.lender.account_id,
+			};
+
+			OldAskOrders::insert_id(&ask_order_id, &old_ask_order);
+
+			super::Migration::<Test>::new().migrate();
+
+			let ask_order = super::AskOrders::<Test>::try_get_id(&ask_order_id).unwrap();
+
+			assert_eq!(
+				ask_order,
+				AskOrder {
+					blockchain: old_ask_order.blockchain,
+					lender_address_id: old_ask_order.lender_address_id,
+					terms: AskTerms {
+						min_amount: 100u64.into(),
+						max_amount: 100u64.into(),
+						min_interest_rate: Default::default(),
+						max_term_length: Duration::from_millis(2000),
+						min_collateral: 10,
+					},
+					expiration_block: old_ask_order.expiration_block,
+					block: old_ask_order.block,
+					lender: old_ask_order.lender,
+				}
+			);
+		});
+	}
+
+	#[test]
+	fn bid_order_migrates() {
+		ExtBuilder::default().build_and_execute(|| {
+			let bid_order_id = BidOrderId::new::<Test>(100, "asdf".as_bytes());
+			let test_info = TestInfo::new_defaults();
+
+			let old_bid_order = OldBidOrder {
+				blockchain: Blockchain::Ethereum,
+				borrower_address_id: test_info.borrower.address_id,
+				terms: OldBidTerms(loan_terms()),
+				expiration_block: 100,
+				block: 1,
+				borrower: test_info.borrower.account_id,
+			};
+
+			OldBidOrders::insert_id(&bid_order_id, &old_bid_order);
+
+			super::Migration::<Test>::new().migrate();
+
+			let bid_order = super::BidOrders::<Test>::try_get_id(&bid_order_id).unwrap();
+
+			assert_eq!(
+				bid_order,
+				BidOrder {
+					blockchain: old_bid_order.blockchain,
+					borrower_address_id: old_bid_order.borrower_address_id,
+					terms: BidTerms {
+						amount: 100u64.into(),
+						max_interest_rate: Default::default(),
+						min_term_length: Duration::from_millis(2000),
+						max_term_length: Duration::from_millis(2000),
+						max_collateral: 10,
+					},
+					expiration_block: old_bid_order.expiration_block,
+					block: old_bid_order.block,
+					borrower: old_bid_order.borrower,
+				}
+			);
+		});
+	}
+
+	#[test]
+	fn migrated_orders_still_match() {
+		let ask_terms = crate::AskTerms::from(AskTerms::from(OldAskTerms(loan_terms())));
+		let bid_terms = crate::BidTerms::from(BidTerms::from(OldBidTerms(loan_terms())));
+		assert_eq!(
+			ask_terms.agreed_terms(bid_terms),
+			Some(loan_terms().with_currency(crate::LoanCurrency::Legacy))
+		);
+	}
+}
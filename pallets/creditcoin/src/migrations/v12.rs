@@ -0,0 +1,161 @@
+// per-account indexes of addresses, orders, offers and deal orders
+
+use super::{AccountIdOf, BlockNumberOf, HashOf, Migrate, MomentOf};
+use crate::{
+	AccountAddresses, AccountAskOrders, AccountBidOrders, AccountDealOrders, AccountOffers,
+	Address, AddressId, AskOrderId, BidOrderId, Config, DealOrderId, Offer, OfferId,
+};
+use frame_support::{pallet_prelude::*, traits::Get, Blake2_128Concat, Identity, Twox64Concat};
+use sp_std::prelude::*;
+
+use super::v14::OldDealOrder as DealOrder;
+use super::v15::OldAskOrder as AskOrder;
+use super::v15::OldBidOrder as BidOrder;
+
+#[frame_support::storage_alias]
+type Addresses<T: crate::Config> =
+	StorageMap<crate::Pallet<T>, Blake2_128Concat, AddressId<HashOf<T>>, Address<AccountIdOf<T>>>;
+
+#[frame_support::storage_alias]
+type AskOrders<T: crate::Config> = StorageDoubleMap<
+	crate::Pallet<T>,
+	Twox64Concat,
+	BlockNumberOf<T>,
+	Identity,
+	HashOf<T>,
+	AskOrder<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>>,
+>;
+
+#[frame_support::storage_alias]
+type BidOrders<T: crate::Config> = StorageDoubleMap<
+	crate::Pallet<T>,
+	Twox64Concat,
+	BlockNumberOf<T>,
+	Identity,
+	HashOf<T>,
+	BidOrder<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>>,
+>;
+
+#[frame_support::storage_alias]
+type Offers<T: crate::Config> = StorageDoubleMap<
+	crate::Pallet<T>,
+	Twox64Concat,
+	BlockNumberOf<T>,
+	Identity,
+	HashOf<T>,
+	Offer<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>>,
+>;
+
+#[frame_support::storage_alias]
+type DealOrders<T: crate::Config> = StorageDoubleMap<
+	crate::Pallet<T>,
+	Twox64Concat,
+	BlockNumberOf<T>,
+	Identity,
+	HashOf<T>,
+	DealOrder<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>, MomentOf<T>>,
+>;
+
+pub(super) struct Migration<Runtime>(PhantomData<Runtime>);
+
+impl<Runtime> Migration<Runtime> {
+	pub(super) fn new() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<T: Config> Migrate for Migration<T> {
+	fn pre_upgrade(&self) -> Vec<u8> {
+		vec![]
+	}
+
+	fn migrate(&self) -> Weight {
+		let mut weight: Weight = Weight::zero();
+		let weight_each = T::DbWeight::get().reads_writes(1, 1);
+
+		for (address_id, address) in Addresses::<T>::iter() {
+			weight = weight.saturating_add(weight_each);
+			AccountAddresses::<T>::insert(address.owner, address_id, ());
+		}
+
+		for (expiration, hash, ask_order) in AskOrders::<T>::iter() {
+			weight = weight.saturating_add(weight_each);
+			let ask_order_id = AskOrderId::with_expiration_hash::<T>(expiration, hash);
+			AccountAskOrders::<T>::insert(ask_order.lender, ask_order_id, ());
+		}
+
+		for (expiration, hash, bid_order) in BidOrders::<T>::iter() {
+			weight = weight.saturating_add(weight_each);
+			let bid_order_id = BidOrderId::with_expiration_hash::<T>(expiration, hash);
+			AccountBidOrders::<T>::insert(bid_order.borrower, bid_order_id, ());
+		}
+
+		for (expiration, hash, offer) in Offers::<T>::iter() {
+			weight = weight.saturating_add(weight_each);
+			let offer_id = OfferId::with_expiration_hash::<T>(expiration, hash);
+			AccountOffers::<T>::insert(offer.lender, offer_id, ());
+		}
+
+		let weight_each = T::DbWeight::get().reads_writes(2, 2);
+		for (expiration, hash, deal_order) in DealOrders::<T>::iter() {
+			weight = weight.saturating_add(weight_each);
+			let deal_order_id = DealOrderId::with_expiration_hash::<T>(expiration, hash);
+			if let Some(lender) = Addresses::<T>::get(&deal_order.lender_address_id) {
+				AccountDealOrders::<T>::insert(lender.owner, &deal_order_id, ());
+			}
+			AccountDealOrders::<T>::insert(deal_order.borrower, deal_order_id, ());
+		}
+
+		weight
+	}
+
+	fn post_upgrade(&self, _ctx: Vec<u8>) {
+		assert_eq!(
+			StorageVersion::get::<crate::Pallet<T>>(),
+			12,
+			"expected storage version to be 12 after migrations complete"
+		);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Migrate;
+	use crate::{
+		mock::{ExtBuilder, Test},
+		tests::TestInfo,
+		AccountAddresses, AccountAskOrders, AccountBidOrders, AccountDealOrders, AccountOffers,
+		DoubleMapExt,
+	};
+
+	#[test]
+	fn indexes_are_built_for_existing_entries() {
+		ExtBuilder::default().build_and_execute(|| {
+			let test_info = TestInfo::new_defaults();
+			let (deal_order_id, deal_order) = test_info.create_deal_order();
+			let offer = crate::Offers::<Test>::try_get_id(&deal_order.offer_id).unwrap();
+			super::super::v15::tests::store_orders_in_old_layout();
+
+			let lender = test_info.lender.account_id.clone();
+			let borrower = test_info.borrower.account_id.clone();
+			let _ = AccountAddresses::<Test>::clear(u32::MAX, None);
+			let _ = AccountAskOrders::<Test>::clear(u32::MAX, None);
+			let _ = AccountBidOrders::<Test>::clear(u32::MAX, None);
+			let _ = AccountOffers::<Test>::clear(u32::MAX, None);
+			let _ = AccountDealOrders::<Test>::clear(u32::MAX, None);
+
+			super::Migration::<Test>::new().migrate();
+
+			assert!(AccountAddresses::<Test>::contains_key(&lender, &test_info.lender.address_id));
+			assert!(AccountAddresses::<Test>::contains_key(
+				&borrower,
+				&test_info.borrower.address_id
+			));
+			assert!(AccountAskOrders::<Test>::contains_key(&lender, &offer.ask_id));
+			assert!(AccountBidOrders::<Test>::contains_key(&borrower, &offer.bid_id));
+			assert!(AccountOffers::<Test>::contains_key(&lender, &deal_order.offer_id));
+			assert!(AccountDealOrders::<Test>::contains_key(&lender, &deal_order_id));
+			assert!(AccountDealOrders::<Test>::contains_key(&borrower, &deal_order_id));
+		});
+	}
+}
@@ -0,0 +1,118 @@
+// deal orders closed before the upgrade are queued for archival, transfers are indexed by deal order
+
+use super::{AccountIdOf, BlockNumberOf, HashOf, Migrate, MomentOf};
+use crate::{
+	ArchiveCursor, ClosedDealOrders, Config, DealOrderId, DealOrderStatus, DealOrderTransfers,
+	OrderId, Transfer, TransferId,
+};
+use frame_support::{pallet_prelude::*, traits::Get, Identity, Twox64Concat};
+use sp_std::prelude::*;
+
+use super::v14::OldDealOrder as DealOrder;
+
+#[frame_support::storage_alias]
+type DealOrders<T: crate::Config> = StorageDoubleMap<
+	crate::Pallet<T>,
+	Twox64Concat,
+	BlockNumberOf<T>,
+	Identity,
+	HashOf<T>,
+	DealOrder<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>, MomentOf<T>>,
+>;
+
+#[frame_support::storage_alias]
+type Transfers<T: crate::Config> = StorageMap<
+	crate::Pallet<T>,
+	Identity,
+	TransferId<HashOf<T>>,
+	Transfer<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>, MomentOf<T>>,
+>;
+
+pub(super) struct Migration<Runtime>(PhantomData<Runtime>);
+
+impl<Runtime> Migration<Runtime> {
+	pub(super) fn new() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<T: Config> Migrate for Migration<T> {
+	fn pre_upgrade(&self) -> Vec<u8> {
+		vec![]
+	}
+
+	fn migrate(&self) -> Weight {
+		let mut weight: Weight = Weight::zero();
+		let weight_each = T::DbWeight::get().reads_writes(1, 1);
+
+		for (transfer_id, transfer) in Transfers::<T>::iter() {
+			weight = weight.saturating_add(weight_each);
+			if let OrderId::Deal(deal_order_id) = transfer.order_id {
+				DealOrderTransfers::<T>::insert(deal_order_id, transfer_id, ());
+			}
+		}
+
+		// the retention period of deal orders closed before the upgrade starts now
+		let now = frame_system::Pallet::<T>::block_number();
+		let mut queued = false;
+		for (expiration, hash, deal_order) in DealOrders::<T>::iter() {
+			weight = weight.saturating_add(weight_each);
+			if matches!(deal_order.status, DealOrderStatus::Closed | DealOrderStatus::Exempted) {
+				let deal_order_id = DealOrderId::with_expiration_hash::<T>(expiration, hash);
+				ClosedDealOrders::<T>::insert(now, deal_order_id, ());
+				queued = true;
+			}
+		}
+
+		if queued && !ArchiveCursor::<T>::exists() {
+			weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
+			ArchiveCursor::<T>::put(now);
+		}
+
+		weight
+	}
+
+	fn post_upgrade(&self, _ctx: Vec<u8>) {
+		assert_eq!(
+			StorageVersion::get::<crate::Pallet<T>>(),
+			13,
+			"expected storage version to be 13 after migrations complete"
+		);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Migrate;
+	use crate::{
+		mock::{ExtBuilder, Test},
+		tests::{fund_and_close_deal_order, TestInfo},
+		ArchiveCursor, ClosedDealOrders, DealOrderTransfers,
+	};
+
+	#[test]
+	fn closed_deal_orders_are_queued_and_transfers_indexed() {
+		ExtBuilder::default().build_and_execute(|| {
+			frame_system::Pallet::<Test>::set_block_number(1);
+			let test_info = TestInfo::new_defaults();
+			let (deal_order_id, funding_transfer_id, repayment_transfer_id) =
+				fund_and_close_deal_order(&test_info);
+			super::super::v15::tests::store_orders_in_old_layout();
+
+			let _ = DealOrderTransfers::<Test>::clear(u32::MAX, None);
+			let _ = ClosedDealOrders::<Test>::clear(u32::MAX, None);
+			ArchiveCursor::<Test>::kill();
+			frame_system::Pallet::<Test>::set_block_number(5);
+
+			super::Migration::<Test>::new().migrate();
+
+			assert!(DealOrderTransfers::<Test>::contains_key(&deal_order_id, &funding_transfer_id));
+			assert!(DealOrderTransfers::<Test>::contains_key(
+				&deal_order_id,
+				&repayment_transfer_id
+			));
+			assert!(ClosedDealOrders::<Test>::contains_key(5, &deal_order_id));
+			assert_eq!(ArchiveCursor::<Test>::get(), Some(5));
+		});
+	}
+}
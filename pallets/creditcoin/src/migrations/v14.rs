@@ -0,0 +1,191 @@
+// exemptions are recorded on the deal order instead of as a fake repayment transfer
+
+use super::{AccountIdOf, BlockNumberOf, HashOf, Migrate, MomentOf};
+use crate::{
+	AddressId, Blockchain, Config, DealOrderStatus, Exemption, ExternalAmount, OfferId, TransferId,
+};
+use frame_support::{pallet_prelude::*, traits::Get, Blake2_128Concat, Identity, Twox64Concat};
+use sp_runtime::traits::Zero;
+use sp_std::prelude::*;
+
+use super::v15::OldDealOrder as DealOrder;
+use super::v15::OldLoanTerms as LoanTerms;
+
+#[derive(Encode, Decode)]
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+pub struct OldDealOrder<AccountId, BlockNum, Hash, Moment> {
+	pub blockchain: Blockchain,
+	pub offer_id: OfferId<BlockNum, Hash>,
+	pub lender_address_id: AddressId<Hash>,
+	pub borrower_address_id: AddressId<Hash>,
+	pub terms: LoanTerms,
+	pub expiration_block: BlockNum,
+	pub timestamp: Moment,
+	pub block: Option<BlockNum>,
+	pub funding_transfer_id: Option<TransferId<Hash>>,
+	pub repayment_transfer_id: Option<TransferId<Hash>>,
+	pub repaid_amount: ExternalAmount,
+	pub lock: Option<AccountId>,
+	pub borrower: AccountId,
+	pub status: DealOrderStatus,
+}
+
+#[frame_support::storage_alias]
+type Addresses<T: crate::Config> = StorageMap<
+	crate::Pallet<T>,
+	Blake2_128Concat,
+	AddressId<HashOf<T>>,
+	crate::Address<AccountIdOf<T>>,
+>;
+
+#[frame_support::storage_alias]
+type DealOrders<T: crate::Config> = StorageDoubleMap<
+	crate::Pallet<T>,
+	Twox64Concat,
+	BlockNumberOf<T>,
+	Identity,
+	HashOf<T>,
+	DealOrder<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>, MomentOf<T>>,
+>;
+
+pub(super) struct Migration<Runtime>(PhantomData<Runtime>);
+
+impl<Runtime> Migration<Runtime> {
+	pub(super) fn new() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<T: Config> Migrate for Migration<T> {
+	fn pre_upgrade(&self) -> Vec<u8> {
+		vec![]
+	}
+
+	fn migrate(&self) -> Weight {
+		let mut weight: Weight = Weight::zero();
+		let weight_each = T::DbWeight::get().reads_writes(1, 1);
+		let now = pallet_timestamp::Pallet::<T>::get();
+
+		DealOrders::<T>::translate::<
+			OldDealOrder<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>, MomentOf<T>>,
+			_,
+		>(|_exp, _hash, old_deal| {
+			weight = weight.saturating_add(weight_each);
+			let exempted = old_deal.status == DealOrderStatus::Exempted;
+			let mut deal = DealOrder {
+				blockchain: old_deal.blockchain,
+				offer_id: old_deal.offer_id,
+				lender_address_id: old_deal.lender_address_id,
+				borrower_address_id: old_deal.borrower_address_id,
+				terms: old_deal.terms,
+				expiration_block: old_deal.expiration_block,
+				timestamp: old_deal.timestamp,
+				block: old_deal.block,
+				funding_transfer_id: old_deal.funding_transfer_id,
+				// the repayment transfer of an exempted deal order was never stored
+				repayment_transfer_id: if exempted { None } else { old_deal.repayment_transfer_id },
+				repaid_amount: old_deal.repaid_amount,
+				lock: old_deal.lock,
+				borrower: old_deal.borrower,
+				status: old_deal.status,
+				exemption: None,
+			};
+
+			if exempted {
+				weight = weight.saturating_add(T::DbWeight::get().reads(1));
+				if let Some(lender) = Addresses::<T>::get(&deal.lender_address_id) {
+					let amount = if deal.funding_transfer_id.is_some() {
+						deal.amount_owed(now).saturating_sub(deal.repaid_amount)
+					} else {
+						ExternalAmount::zero()
+					};
+					// the time of the exemption was not recorded, the upgrade is the best we know
+					deal.exemption = Some(Exemption {
+						lender: lender.owner,
+						timestamp: now,
+						amount,
+						reason: None,
+					});
+				}
+			}
+
+			Some(deal)
+		});
+
+		weight
+	}
+
+	fn post_upgrade(&self, _ctx: Vec<u8>) {
+		assert_eq!(
+			StorageVersion::get::<crate::Pallet<T>>(),
+			14,
+			"expected storage version to be 14 after migrations complete"
+		);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{AccountIdOf, BlockNumberOf, HashOf, Migrate, MomentOf, OldDealOrder};
+	use crate::{
+		mock::{ExtBuilder, Test},
+		tests::{fund_deal_order_for_repayment, TestInfo},
+		DealOrderStatus, DoubleMapExt, Exemption, TransferId,
+	};
+	use frame_support::{Identity, Twox64Concat};
+
+	#[frame_support::storage_alias]
+	type DealOrders<T: crate::Config> = StorageDoubleMap<
+		crate::Pallet<T>,
+		Twox64Concat,
+		BlockNumberOf<T>,
+		Identity,
+		HashOf<T>,
+		OldDealOrder<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>, MomentOf<T>>,
+	>;
+
+	type OldDealOrders = DealOrders<Test>;
+
+	#[test]
+	fn exempted_deal_order_gets_an_exemption_record() {
+		ExtBuilder::default().build_and_execute(|| {
+			frame_system::Pallet::<Test>::set_block_number(1);
+			let test_info = TestInfo::new_defaults();
+			let (deal_order_id, deal_order) = fund_deal_order_for_repayment(&test_info);
+
+			let old_deal = OldDealOrder {
+				blockchain: deal_order.blockchain,
+				offer_id: deal_order.offer_id,
+				lender_address_id: deal_order.lender_address_id,
+				borrower_address_id: deal_order.borrower_address_id,
+				terms: deal_order.terms.into(),
+				expiration_block: deal_order.expiration_block,
+				timestamp: deal_order.timestamp,
+				block: deal_order.block,
+				funding_transfer_id: deal_order.funding_transfer_id,
+				repayment_transfer_id: Some(TransferId::new::<Test>(&test_info.blockchain, b"0")),
+				repaid_amount: deal_order.repaid_amount,
+				lock: deal_order.lock,
+				borrower: deal_order.borrower,
+				status: DealOrderStatus::Exempted,
+			};
+			OldDealOrders::insert_id(&deal_order_id, &old_deal);
+
+			super::Migration::<Test>::new().migrate();
+
+			let deal = super::DealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
+			let now = pallet_timestamp::Pallet::<Test>::get();
+			assert_eq!(deal.repayment_transfer_id, None);
+			assert_eq!(deal.status, DealOrderStatus::Exempted);
+			assert_eq!(
+				deal.exemption,
+				Some(Exemption {
+					lender: test_info.lender.account_id.clone(),
+					timestamp: now,
+					amount: deal.amount_owed(now).saturating_sub(deal.repaid_amount),
+					reason: None,
+				})
+			);
+		});
+	}
+}
@@ -0,0 +1,558 @@
+// `currency` added to `LoanTerms`, `AskTerms` and `BidTerms`
+
+use super::{AccountIdOf, BlockNumberOf, HashOf, Migrate, MomentOf};
+use crate::{
+	AddressId, AskOrder, AskTerms, BidOrder, BidTerms, Blockchain, CollateralAmount, Config,
+	DealOrder, DealOrderId, DealOrderStatus, DoubleMapExt, Duration, Exemption, ExternalAmount,
+	InterestRate, LoanCurrency, LoanTerms, OfferId, TermsProposal, TransferId, TransferKind,
+	Transfers,
+};
+use frame_support::{pallet_prelude::*, traits::Get, Identity, Twox64Concat};
+use sp_runtime::traits::UniqueSaturatedInto;
+use sp_std::prelude::*;
+
+#[derive(Clone, Encode, Decode)]
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+pub struct OldLoanTerms {
+	pub amount: ExternalAmount,
+	pub interest_rate: InterestRate,
+	pub term_length: Duration,
+	pub collateral: CollateralAmount,
+}
+
+#[derive(Encode, Decode)]
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+pub struct OldAskTerms {
+	pub min_amount: ExternalAmount,
+	pub max_amount: ExternalAmount,
+	pub min_interest_rate: InterestRate,
+	pub max_term_length: Duration,
+	pub min_collateral: CollateralAmount,
+}
+
+#[derive(Encode, Decode)]
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+pub struct OldBidTerms {
+	pub amount: ExternalAmount,
+	pub max_interest_rate: InterestRate,
+	pub min_term_length: Duration,
+	pub max_term_length: Duration,
+	pub max_collateral: CollateralAmount,
+}
+
+#[derive(Encode, Decode)]
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+pub struct OldAskOrder<AccountId, BlockNum, Hash> {
+	pub blockchain: Blockchain,
+	pub lender_address_id: AddressId<Hash>,
+	pub terms: OldAskTerms,
+	pub expiration_block: BlockNum,
+	pub block: BlockNum,
+	pub lender: AccountId,
+}
+
+#[derive(Encode, Decode)]
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+pub struct OldBidOrder<AccountId, BlockNum, Hash> {
+	pub blockchain: Blockchain,
+	pub borrower_address_id: AddressId<Hash>,
+	pub terms: OldBidTerms,
+	pub expiration_block: BlockNum,
+	pub block: BlockNum,
+	pub borrower: AccountId,
+}
+
+#[derive(Encode, Decode)]
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+pub struct OldDealOrder<AccountId, BlockNum, Hash, Moment> {
+	pub blockchain: Blockchain,
+	pub offer_id: OfferId<BlockNum, Hash>,
+	pub lender_address_id: AddressId<Hash>,
+	pub borrower_address_id: AddressId<Hash>,
+	pub terms: OldLoanTerms,
+	pub expiration_block: BlockNum,
+	pub timestamp: Moment,
+	pub block: Option<BlockNum>,
+	pub funding_transfer_id: Option<TransferId<Hash>>,
+	pub repayment_transfer_id: Option<TransferId<Hash>>,
+	pub repaid_amount: ExternalAmount,
+	pub lock: Option<AccountId>,
+	pub borrower: AccountId,
+	pub status: DealOrderStatus,
+	pub exemption: Option<Exemption<AccountId, Moment>>,
+}
+
+#[derive(Encode, Decode)]
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+pub struct OldTermsProposal<AccountId, BlockNum, Hash> {
+	pub deal_order_id: DealOrderId<BlockNum, Hash>,
+	pub terms: OldLoanTerms,
+	pub expiration_block: BlockNum,
+	pub block: BlockNum,
+	pub proposer: AccountId,
+}
+
+impl OldLoanTerms {
+	pub fn with_currency(self, currency: LoanCurrency) -> LoanTerms {
+		LoanTerms {
+			amount: self.amount,
+			currency,
+			interest_rate: self.interest_rate,
+			term_length: self.term_length,
+			collateral: self.collateral,
+		}
+	}
+}
+
+impl<AccountId, BlockNum, Hash, Moment> OldDealOrder<AccountId, BlockNum, Hash, Moment>
+where
+	Moment: UniqueSaturatedInto<u64> + Copy,
+{
+	/// Same as `DealOrder::amount_owed`, interest doesn't depend on the currency.
+	pub fn amount_owed(&self, now: Moment) -> ExternalAmount {
+		let funded_at: u64 = self.timestamp.unique_saturated_into();
+		let now: u64 = now.unique_saturated_into();
+		self.terms
+			.clone()
+			.with_currency(LoanCurrency::Legacy)
+			.amount_owed(&Duration::from_millis(now.saturating_sub(funded_at)))
+	}
+}
+
+// the currency of existing asks and bids can't be told
+impl From<OldAskTerms> for AskTerms {
+	fn from(old: OldAskTerms) -> Self {
+		Self {
+			min_amount: old.min_amount,
+			max_amount: old.max_amount,
+			currency: LoanCurrency::Legacy,
+			min_interest_rate: old.min_interest_rate,
+			max_term_length: old.max_term_length,
+			min_collateral: old.min_collateral,
+		}
+	}
+}
+
+impl From<OldBidTerms> for BidTerms {
+	fn from(old: OldBidTerms) -> Self {
+		Self {
+			amount: old.amount,
+			currency: LoanCurrency::Legacy,
+			max_interest_rate: old.max_interest_rate,
+			min_term_length: old.min_term_length,
+			max_term_length: old.max_term_length,
+			max_collateral: old.max_collateral,
+		}
+	}
+}
+
+/// The currency moved by a transfer of `kind`, if it can be told.
+fn currency_of(kind: &TransferKind) -> LoanCurrency {
+	match kind {
+		TransferKind::Erc20(contract) | TransferKind::Ethless(contract) => {
+			LoanCurrency::Token { contract: contract.clone() }
+		},
+		TransferKind::Native => LoanCurrency::Native,
+		TransferKind::Other(_) => LoanCurrency::Legacy,
+	}
+}
+
+#[frame_support::storage_alias]
+type AskOrders<T: crate::Config> = StorageDoubleMap<
+	crate::Pallet<T>,
+	Twox64Concat,
+	BlockNumberOf<T>,
+	Identity,
+	HashOf<T>,
+	AskOrder<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>>,
+>;
+
+#[frame_support::storage_alias]
+type BidOrders<T: crate::Config> = StorageDoubleMap<
+	crate::Pallet<T>,
+	Twox64Concat,
+	BlockNumberOf<T>,
+	Identity,
+	HashOf<T>,
+	BidOrder<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>>,
+>;
+
+#[frame_support::storage_alias]
+type DealOrders<T: crate::Config> = StorageDoubleMap<
+	crate::Pallet<T>,
+	Twox64Concat,
+	BlockNumberOf<T>,
+	Identity,
+	HashOf<T>,
+	DealOrder<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>, MomentOf<T>>,
+>;
+
+#[frame_support::storage_alias]
+type TermsProposals<T: crate::Config> = StorageDoubleMap<
+	crate::Pallet<T>,
+	Twox64Concat,
+	BlockNumberOf<T>,
+	Identity,
+	HashOf<T>,
+	TermsProposal<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>>,
+>;
+
+pub(super) struct Migration<Runtime>(PhantomData<Runtime>);
+
+impl<Runtime> Migration<Runtime> {
+	pub(super) fn new() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<T: Config> Migrate for Migration<T> {
+	fn pre_upgrade(&self) -> Vec<u8> {
+		vec![]
+	}
+
+	fn migrate(&self) -> Weight {
+		let mut weight: Weight = Weight::zero();
+		let weight_each = T::DbWeight::get().reads_writes(1, 1);
+
+		// funded deal orders are in the currency they were funded with, the currency of the rest
+		// can't be told and any transfer is accepted for them as before
+		DealOrders::<T>::translate::<
+			OldDealOrder<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>, MomentOf<T>>,
+			_,
+		>(|_exp, _hash, old_deal| {
+			weight = weight.saturating_add(weight_each);
+			let currency = match &old_deal.funding_transfer_id {
+				Some(transfer_id) => {
+					weight = weight.saturating_add(T::DbWeight::get().reads(1));
+					Transfers::<T>::get(transfer_id)
+						.map_or(LoanCurrency::Legacy, |transfer| currency_of(&transfer.kind))
+				},
+				None => LoanCurrency::Legacy,
+			};
+
+			Some(DealOrder {
+				blockchain: old_deal.blockchain,
+				offer_id: old_deal.offer_id,
+				lender_address_id: old_deal.lender_address_id,
+				borrower_address_id: old_deal.borrower_address_id,
+				terms: old_deal.terms.with_currency(currency),
+				expiration_block: old_deal.expiration_block,
+				timestamp: old_deal.timestamp,
+				block: old_deal.block,
+				funding_transfer_id: old_deal.funding_transfer_id,
+				repayment_transfer_id: old_deal.repayment_transfer_id,
+				repaid_amount: old_deal.repaid_amount,
+				lock: old_deal.lock,
+				borrower: old_deal.borrower,
+				status: old_deal.status,
+				exemption: old_deal.exemption,
+			})
+		});
+
+		// proposed terms can't change the currency of their deal order
+		TermsProposals::<T>::translate::<
+			OldTermsProposal<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>>,
+			_,
+		>(|_exp, _hash, old_proposal| {
+			weight = weight.saturating_add(T::DbWeight::get().reads_writes(2, 1));
+			let currency = DealOrders::<T>::try_get_id(&old_proposal.deal_order_id)
+				.map_or(LoanCurrency::Legacy, |deal| deal.terms.currency);
+
+			Some(TermsProposal {
+				deal_order_id: old_proposal.deal_order_id,
+				terms: old_proposal.terms.with_currency(currency),
+				expiration_block: old_proposal.expiration_block,
+				block: old_proposal.block,
+				proposer: old_proposal.proposer,
+			})
+		});
+
+		AskOrders::<T>::translate::<OldAskOrder<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>>, _>(
+			|_exp, _hash, old_ask| {
+				weight = weight.saturating_add(weight_each);
+				Some(AskOrder {
+					blockchain: old_ask.blockchain,
+					lender_address_id: old_ask.lender_address_id,
+					terms: AskTerms::from(old_ask.terms),
+					expiration_block: old_ask.expiration_block,
+					block: old_ask.block,
+					lender: old_ask.lender,
+				})
+			},
+		);
+
+		BidOrders::<T>::translate::<OldBidOrder<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>>, _>(
+			|_exp, _hash, old_bid| {
+				weight = weight.saturating_add(weight_each);
+				Some(BidOrder {
+					blockchain: old_bid.blockchain,
+					borrower_address_id: old_bid.borrower_address_id,
+					terms: BidTerms::from(old_bid.terms),
+					expiration_block: old_bid.expiration_block,
+					block: old_bid.block,
+					borrower: old_bid.borrower,
+				})
+			},
+		);
+
+		weight
+	}
+
+	fn post_upgrade(&self, _ctx: Vec<u8>) {
+		assert_eq!(
+			StorageVersion::get::<crate::Pallet<T>>(),
+			15,
+			"expected storage version to be 15 after migrations complete"
+		);
+	}
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+	use super::{
+		AccountIdOf, BlockNumberOf, HashOf, Migrate, MomentOf, OldAskOrder, OldAskTerms,
+		OldBidOrder, OldBidTerms, OldDealOrder, OldLoanTerms, OldTermsProposal,
+	};
+	use crate::{
+		helpers::extensions::HexToAddress,
+		mock::{ExtBuilder, Test},
+		tests::{fund_deal_order_for_repayment, TestInfo},
+		AskOrder, AskTerms, BidOrder, BidTerms, DealOrder, DoubleMapExt, LoanCurrency, LoanTerms,
+		TermsProposalId, TransferKind,
+	};
+	use frame_support::{Identity, Twox64Concat};
+
+	impl From<LoanTerms> for OldLoanTerms {
+		fn from(terms: LoanTerms) -> Self {
+			Self {
+				amount: terms.amount,
+				interest_rate: terms.interest_rate,
+				term_length: terms.term_length,
+				collateral: terms.collateral,
+			}
+		}
+	}
+
+	impl<AccountId, BlockNum, Hash> From<AskOrder<AccountId, BlockNum, Hash>>
+		for OldAskOrder<AccountId, BlockNum, Hash>
+	{
+		fn from(ask: AskOrder<AccountId, BlockNum, Hash>) -> Self {
+			let AskTerms {
+				min_amount,
+				max_amount,
+				min_interest_rate,
+				max_term_length,
+				min_collateral,
+				..
+			} = ask.terms;
+			Self {
+				blockchain: ask.blockchain,
+				lender_address_id: ask.lender_address_id,
+				terms: OldAskTerms {
+					min_amount,
+					max_amount,
+					min_interest_rate,
+					max_term_length,
+					min_collateral,
+				},
+				expiration_block: ask.expiration_block,
+				block: ask.block,
+				lender: ask.lender,
+			}
+		}
+	}
+
+	impl<AccountId, BlockNum, Hash> From<BidOrder<AccountId, BlockNum, Hash>>
+		for OldBidOrder<AccountId, BlockNum, Hash>
+	{
+		fn from(bid: BidOrder<AccountId, BlockNum, Hash>) -> Self {
+			let BidTerms {
+				amount,
+				max_interest_rate,
+				min_term_length,
+				max_term_length,
+				max_collateral,
+				..
+			} = bid.terms;
+			Self {
+				blockchain: bid.blockchain,
+				borrower_address_id: bid.borrower_address_id,
+				terms: OldBidTerms {
+					amount,
+					max_interest_rate,
+					min_term_length,
+					max_term_length,
+					max_collateral,
+				},
+				expiration_block: bid.expiration_block,
+				block: bid.block,
+				borrower: bid.borrower,
+			}
+		}
+	}
+
+	impl<AccountId, BlockNum, Hash, Moment> From<DealOrder<AccountId, BlockNum, Hash, Moment>>
+		for OldDealOrder<AccountId, BlockNum, Hash, Moment>
+	{
+		fn from(deal: DealOrder<AccountId, BlockNum, Hash, Moment>) -> Self {
+			Self {
+				blockchain: deal.blockchain,
+				offer_id: deal.offer_id,
+				lender_address_id: deal.lender_address_id,
+				borrower_address_id: deal.borrower_address_id,
+				terms: deal.terms.into(),
+				expiration_block: deal.expiration_block,
+				timestamp: deal.timestamp,
+				block: deal.block,
+				funding_transfer_id: deal.funding_transfer_id,
+				repayment_transfer_id: deal.repayment_transfer_id,
+				repaid_amount: deal.repaid_amount,
+				lock: deal.lock,
+				borrower: deal.borrower,
+				status: deal.status,
+				exemption: deal.exemption,
+			}
+		}
+	}
+
+	#[frame_support::storage_alias]
+	type AskOrders<T: crate::Config> = StorageDoubleMap<
+		crate::Pallet<T>,
+		Twox64Concat,
+		BlockNumberOf<T>,
+		Identity,
+		HashOf<T>,
+		OldAskOrder<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>>,
+	>;
+
+	#[frame_support::storage_alias]
+	type BidOrders<T: crate::Config> = StorageDoubleMap<
+		crate::Pallet<T>,
+		Twox64Concat,
+		BlockNumberOf<T>,
+		Identity,
+		HashOf<T>,
+		OldBidOrder<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>>,
+	>;
+
+	#[frame_support::storage_alias]
+	type DealOrders<T: crate::Config> = StorageDoubleMap<
+		crate::Pallet<T>,
+		Twox64Concat,
+		BlockNumberOf<T>,
+		Identity,
+		HashOf<T>,
+		OldDealOrder<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>, MomentOf<T>>,
+	>;
+
+	#[frame_support::storage_alias]
+	type TermsProposals<T: crate::Config> = StorageDoubleMap<
+		crate::Pallet<T>,
+		Twox64Concat,
+		BlockNumberOf<T>,
+		Identity,
+		HashOf<T>,
+		OldTermsProposal<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>>,
+	>;
+
+	/// Rewrites the orders created through the pallet in their layout before this migration, so
+	/// the migrations that ran on that layout can be tested with them.
+	pub(in crate::migrations) fn store_orders_in_old_layout() {
+		let asks: Vec<_> = crate::AskOrders::<Test>::iter().collect();
+		for (expiration, hash, ask) in asks {
+			AskOrders::<Test>::insert(expiration, hash, OldAskOrder::from(ask));
+		}
+		let bids: Vec<_> = crate::BidOrders::<Test>::iter().collect();
+		for (expiration, hash, bid) in bids {
+			BidOrders::<Test>::insert(expiration, hash, OldBidOrder::from(bid));
+		}
+		let deals: Vec<_> = crate::DealOrders::<Test>::iter().collect();
+		for (expiration, hash, deal) in deals {
+			DealOrders::<Test>::insert(expiration, hash, OldDealOrder::from(deal));
+		}
+	}
+
+	#[test]
+	fn funded_deal_order_takes_the_currency_of_its_funding_transfer() {
+		ExtBuilder::default().build_and_execute(|| {
+			frame_system::Pallet::<Test>::set_block_number(1);
+			let test_info = TestInfo::new_defaults();
+			let (deal_order_id, deal_order) = fund_deal_order_for_repayment(&test_info);
+
+			let contract = "0x0ad1439a0e0bfdcd49939f9722866651a4aa9b3c".hex_to_address();
+			let funding_transfer_id = deal_order.funding_transfer_id.clone().unwrap();
+			crate::Transfers::<Test>::mutate(&funding_transfer_id, |transfer| {
+				transfer.as_mut().unwrap().kind = TransferKind::Ethless(contract.clone());
+			});
+
+			let terms_proposal_id =
+				TermsProposalId::new::<Test>(1_000, &deal_order_id, &deal_order.terms);
+			TermsProposals::<Test>::insert_id(
+				&terms_proposal_id,
+				OldTermsProposal {
+					deal_order_id: deal_order_id.clone(),
+					terms: deal_order.terms.clone().into(),
+					expiration_block: 1_000,
+					block: 1,
+					proposer: test_info.borrower.account_id.clone(),
+				},
+			);
+			store_orders_in_old_layout();
+
+			super::Migration::<Test>::new().migrate();
+
+			let currency = LoanCurrency::Token { contract };
+			let deal = super::DealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
+			assert_eq!(
+				deal,
+				DealOrder {
+					terms: LoanTerms { currency: currency.clone(), ..deal_order.terms.clone() },
+					..deal_order
+				}
+			);
+			let proposal = super::TermsProposals::<Test>::try_get_id(&terms_proposal_id).unwrap();
+			assert_eq!(proposal.terms.currency, currency);
+		});
+	}
+
+	#[test]
+	fn orders_are_in_the_legacy_currency() {
+		ExtBuilder::default().build_and_execute(|| {
+			let test_info = TestInfo::new_defaults();
+			let (deal_order_id, deal_order) = test_info.create_deal_order();
+			let offer = crate::Offers::<Test>::try_get_id(&deal_order.offer_id).unwrap();
+			let ask_order = crate::AskOrders::<Test>::try_get_id(&offer.ask_id).unwrap();
+			let bid_order = crate::BidOrders::<Test>::try_get_id(&offer.bid_id).unwrap();
+			store_orders_in_old_layout();
+
+			super::Migration::<Test>::new().migrate();
+
+			let ask = super::AskOrders::<Test>::try_get_id(&offer.ask_id).unwrap();
+			assert_eq!(
+				ask,
+				AskOrder {
+					terms: AskTerms { currency: LoanCurrency::Legacy, ..ask_order.terms.clone() },
+					..ask_order
+				}
+			);
+			let bid = super::BidOrders::<Test>::try_get_id(&offer.bid_id).unwrap();
+			assert_eq!(
+				bid,
+				BidOrder {
+					terms: BidTerms { currency: LoanCurrency::Legacy, ..bid_order.terms.clone() },
+					..bid_order
+				}
+			);
+			let deal = super::DealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
+			assert_eq!(
+				deal,
+				DealOrder {
+					terms: LoanTerms { currency: LoanCurrency::Legacy, ..deal_order.terms.clone() },
+					..deal_order
+				}
+			);
+			assert!(deal.terms.currency.accepts(&TransferKind::Native));
+			assert!(ask.terms.match_with(&bid.terms));
+		});
+	}
+}
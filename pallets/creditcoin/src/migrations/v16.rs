@@ -0,0 +1,401 @@
+// address ids are computed from the canonical form of external addresses, addresses registered
+// before in another form are moved to their canonical id and rewritten in that form, together
+// with the indexes, orders, transfers and pending tasks that refer to them. Addresses that collide
+// with each other are left as they are and recorded in `LegacyAddressCollisions`
+
+use super::{AccountIdOf, BlockNumberOf, HashOf, Migrate, MomentOf};
+use crate::{
+	helpers::normalize_address, AccountAddresses, AddressId, Addresses, ArchivedDealOrders,
+	AskOrders, BidOrders, Blockchain, CollectedCoins, Config, ContractWalletId, DealOrderShares,
+	DealOrders, ExternalAddress, LegacyAddressCollisions, RepaymentOrders, Task, Transfers,
+};
+use frame_support::{pallet_prelude::*, storage_alias, traits::Get};
+use sp_std::{collections::btree_map::BTreeMap, prelude::*};
+
+/// The tasks of the offchain task scheduler, which the runtime names `TaskScheduler`.
+#[storage_alias]
+type PendingTasks<T: Config> = StorageDoubleMap<
+	TaskScheduler,
+	Identity,
+	BlockNumberOf<T>,
+	Identity,
+	HashOf<T>,
+	Task<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>, MomentOf<T>>,
+>;
+
+pub(super) struct Migration<Runtime>(PhantomData<Runtime>);
+
+impl<Runtime> Migration<Runtime> {
+	pub(super) fn new() -> Self {
+		Self(PhantomData)
+	}
+}
+
+/// The canonical id of each address registered under another id, by the id it is registered
+/// under.
+type Moves<T> = BTreeMap<HashOf<T>, AddressId<HashOf<T>>>;
+
+/// Groups the registered addresses by the id of their canonical form.
+fn by_canonical_id<T: Config>() -> BTreeMap<HashOf<T>, Vec<AddressId<HashOf<T>>>> {
+	let mut by_canonical_id: BTreeMap<HashOf<T>, Vec<AddressId<HashOf<T>>>> = BTreeMap::new();
+	for (address_id, address) in Addresses::<T>::iter() {
+		let canonical_id = AddressId::new::<T>(&address.blockchain, &address.value).into_inner();
+		by_canonical_id.entry(canonical_id).or_default().push(address_id);
+	}
+	by_canonical_id
+}
+
+/// Rewrites `address` in the canonical form of the addresses of `blockchain`.
+fn canonical_value(blockchain: &Blockchain, address: ExternalAddress) -> ExternalAddress {
+	match blockchain.builtin_address_format() {
+		Some(format) => normalize_address(&format, address),
+		None => address,
+	}
+}
+
+/// Points `address_id` to the canonical id if the address moves, returns whether it did.
+fn rekey<T: Config>(moves: &Moves<T>, address_id: &mut AddressId<HashOf<T>>) -> bool {
+	match moves.get(&address_id.clone().into_inner()) {
+		Some(canonical_id) => {
+			*address_id = canonical_id.clone();
+			true
+		},
+		None => false,
+	}
+}
+
+impl<T: Config> Migrate for Migration<T> {
+	fn pre_upgrade(&self) -> Vec<u8> {
+		vec![]
+	}
+
+	fn migrate(&self) -> Weight {
+		let mut reads = 0u64;
+		let mut writes = 0u64;
+		let mut moves = Moves::<T>::new();
+
+		for (canonical_id, address_ids) in by_canonical_id::<T>() {
+			reads = reads.saturating_add(address_ids.len() as u64);
+			let canonical_id = AddressId::from(canonical_id);
+			match address_ids.as_slice() {
+				[address_id] if *address_id != canonical_id => {
+					moves.insert(address_id.clone().into_inner(), canonical_id);
+				},
+				[_] => {},
+				address_ids => {
+					log::warn!(
+						target: "runtime::Creditcoin",
+						"addresses {:?} have the same canonical form, with id {:?}",
+						address_ids,
+						canonical_id,
+					);
+					for address_id in address_ids {
+						LegacyAddressCollisions::<T>::insert(&canonical_id, address_id, ());
+						writes = writes.saturating_add(1);
+					}
+				},
+			}
+		}
+
+		// pending transfers are kept by the id of their transfer, pending contract wallets by an
+		// id that hashes the id of their address
+		let mut tasks = Vec::new();
+		for (deadline, task_id, task) in PendingTasks::<T>::iter() {
+			reads = reads.saturating_add(1);
+			match task {
+				Task::VerifyTransfer(mut pending) => {
+					let from = rekey::<T>(&moves, &mut pending.transfer.from);
+					let to = rekey::<T>(&moves, &mut pending.transfer.to);
+					if from || to {
+						let blockchain = &pending.transfer.blockchain;
+						pending.from_external = canonical_value(blockchain, pending.from_external);
+						pending.to_external = canonical_value(blockchain, pending.to_external);
+						tasks.push((deadline, task_id, task_id, Task::VerifyTransfer(pending)));
+					}
+				},
+				Task::VerifyContractWallet(mut pending) => {
+					pending.address = canonical_value(&pending.blockchain, pending.address);
+					let id = ContractWalletId::new::<T>(&pending.address_id::<T>(), &pending.owner)
+						.into_inner();
+					if id != task_id {
+						tasks.push((deadline, task_id, id, Task::VerifyContractWallet(pending)));
+					}
+				},
+				// addresses are looked up by their external form once the coins are collected
+				Task::CollectCoins(_) => {},
+			}
+		}
+		for (deadline, previous_id, task_id, task) in tasks {
+			PendingTasks::<T>::remove(deadline, previous_id);
+			// a wallet that is also pending in its canonical form keeps that task
+			if !PendingTasks::<T>::contains_key(deadline, task_id) {
+				PendingTasks::<T>::insert(deadline, task_id, task);
+			}
+			reads = reads.saturating_add(1);
+			writes = writes.saturating_add(2);
+		}
+
+		if moves.is_empty() {
+			return T::DbWeight::get().reads_writes(reads, writes);
+		}
+
+		for (address_id, canonical_id) in &moves {
+			let address_id = AddressId::from(*address_id);
+			if let Some(mut address) = Addresses::<T>::take(&address_id) {
+				address.value = canonical_value(&address.blockchain, address.value);
+				AccountAddresses::<T>::remove(&address.owner, &address_id);
+				AccountAddresses::<T>::insert(&address.owner, canonical_id, ());
+				Addresses::<T>::insert(canonical_id, address);
+				reads = reads.saturating_add(1);
+				writes = writes.saturating_add(4);
+			}
+		}
+
+		for (expiration_block, hash, mut ask_order) in AskOrders::<T>::iter() {
+			reads = reads.saturating_add(1);
+			if rekey::<T>(&moves, &mut ask_order.lender_address_id) {
+				AskOrders::<T>::insert(expiration_block, hash, ask_order);
+				writes = writes.saturating_add(1);
+			}
+		}
+
+		for (expiration_block, hash, mut bid_order) in BidOrders::<T>::iter() {
+			reads = reads.saturating_add(1);
+			if rekey::<T>(&moves, &mut bid_order.borrower_address_id) {
+				BidOrders::<T>::insert(expiration_block, hash, bid_order);
+				writes = writes.saturating_add(1);
+			}
+		}
+
+		for (expiration_block, hash, mut deal_order) in DealOrders::<T>::iter() {
+			reads = reads.saturating_add(1);
+			let lender = rekey::<T>(&moves, &mut deal_order.lender_address_id);
+			let borrower = rekey::<T>(&moves, &mut deal_order.borrower_address_id);
+			if lender || borrower {
+				DealOrders::<T>::insert(expiration_block, hash, deal_order);
+				writes = writes.saturating_add(1);
+			}
+		}
+
+		let mut shares = Vec::new();
+		for (deal_order_id, mut address_id, share) in DealOrderShares::<T>::iter() {
+			reads = reads.saturating_add(1);
+			let previous_id = address_id.clone();
+			if rekey::<T>(&moves, &mut address_id) {
+				shares.push((deal_order_id, previous_id, address_id, share));
+			}
+		}
+		for (deal_order_id, previous_id, address_id, share) in shares {
+			DealOrderShares::<T>::remove(&deal_order_id, &previous_id);
+			DealOrderShares::<T>::insert(&deal_order_id, &address_id, share);
+			writes = writes.saturating_add(2);
+		}
+
+		for (expiration_block, hash, mut repayment_order) in RepaymentOrders::<T>::iter() {
+			reads = reads.saturating_add(1);
+			if rekey::<T>(&moves, &mut repayment_order.payer_address_id) {
+				RepaymentOrders::<T>::insert(expiration_block, hash, repayment_order);
+				writes = writes.saturating_add(1);
+			}
+		}
+
+		for (expiration_block, hash, mut archived) in ArchivedDealOrders::<T>::iter() {
+			reads = reads.saturating_add(1);
+			if rekey::<T>(&moves, &mut archived.lender_address_id) {
+				ArchivedDealOrders::<T>::insert(expiration_block, hash, archived);
+				writes = writes.saturating_add(1);
+			}
+		}
+
+		for (transfer_id, mut transfer) in Transfers::<T>::iter() {
+			reads = reads.saturating_add(1);
+			let from = rekey::<T>(&moves, &mut transfer.from);
+			let to = rekey::<T>(&moves, &mut transfer.to);
+			if from || to {
+				Transfers::<T>::insert(transfer_id, transfer);
+				writes = writes.saturating_add(1);
+			}
+		}
+
+		for (collected_coins_id, mut collected_coins) in CollectedCoins::<T>::iter() {
+			reads = reads.saturating_add(1);
+			if rekey::<T>(&moves, &mut collected_coins.to) {
+				CollectedCoins::<T>::insert(collected_coins_id, collected_coins);
+				writes = writes.saturating_add(1);
+			}
+		}
+
+		T::DbWeight::get().reads_writes(reads, writes)
+	}
+
+	fn post_upgrade(&self, _ctx: Vec<u8>) {
+		assert_eq!(
+			StorageVersion::get::<crate::Pallet<T>>(),
+			16,
+			"expected storage version to be 16 after migrations complete"
+		);
+
+		for (address_id, address) in Addresses::<T>::iter() {
+			let canonical_id = AddressId::new::<T>(&address.blockchain, &address.value);
+			assert!(
+				address_id == canonical_id
+					|| LegacyAddressCollisions::<T>::contains_key(&canonical_id, &address_id),
+				"address {address_id:?} is neither canonical nor a recorded collision"
+			);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{Migrate, PendingTasks};
+	use crate::{
+		mock::{AccountId, ExtBuilder, Test},
+		test::create_unverified_transfer,
+		tests::TestInfo,
+		types::UnverifiedContractWallet,
+		AccountAddresses, Address, AddressId, Addresses, AskOrder, AskOrders, Blockchain,
+		ContractWalletId, DealOrder, DealOrders, DoubleMapExt, ExternalAddress,
+		LegacyAddressCollisions, Offers, Task,
+	};
+	use frame_system::Config as SystemConfig;
+	use pallet_offchain_task_scheduler::tasks::TaskV2;
+	use sp_runtime::traits::Hash as _;
+
+	type Hash = <Test as SystemConfig>::Hash;
+
+	const RAW: &str = "5aaeb6053f3e94c9b9a09f33669435e7ef1beaed";
+	const HEX_ENCODED: &[u8] = b"0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+
+	/// The id of `value` as it was computed before address ids were canonical, i.e. the hash of
+	/// the bytes as they were registered.
+	fn legacy_id(blockchain: &Blockchain, value: &[u8]) -> AddressId<<Test as SystemConfig>::Hash> {
+		let key = [blockchain.as_bytes(), value].concat();
+		AddressId::from(<Test as SystemConfig>::Hashing::hash(&key))
+	}
+
+	fn address(value: &[u8], owner: u8) -> Address<AccountId> {
+		Address {
+			blockchain: Blockchain::Ethereum,
+			value: value.to_vec().try_into().unwrap(),
+			owner: AccountId::new([owner; 32]),
+		}
+	}
+
+	#[test]
+	fn legacy_address_is_moved_to_its_canonical_id() {
+		ExtBuilder::default().build_and_execute(|| {
+			let (deal_order_id, deal_order) = TestInfo::new_defaults().create_deal_order();
+			let ask_order_id = Offers::<Test>::try_get_id(&deal_order.offer_id).unwrap().ask_id;
+			let ask_order = AskOrders::<Test>::try_get_id(&ask_order_id).unwrap();
+
+			let legacy_id = legacy_id(&Blockchain::Ethereum, HEX_ENCODED);
+			let canonical_id =
+				AddressId::new::<Test>(&Blockchain::Ethereum, &hex::decode(RAW).unwrap());
+			assert_ne!(legacy_id, canonical_id);
+
+			let legacy = address(HEX_ENCODED, 1);
+			Addresses::<Test>::insert(&legacy_id, legacy.clone());
+			AccountAddresses::<Test>::insert(&legacy.owner, &legacy_id, ());
+			AskOrders::<Test>::insert_id(
+				ask_order_id.clone(),
+				AskOrder { lender_address_id: legacy_id.clone(), ..ask_order },
+			);
+			DealOrders::<Test>::insert_id(
+				deal_order_id.clone(),
+				DealOrder { lender_address_id: legacy_id.clone(), ..deal_order.clone() },
+			);
+
+			super::Migration::<Test>::new().migrate();
+
+			assert!(!Addresses::<Test>::contains_key(&legacy_id));
+			let raw = hex::decode(RAW).unwrap().try_into().unwrap();
+			assert_eq!(
+				Addresses::<Test>::get(&canonical_id),
+				Some(Address { value: raw, ..legacy.clone() })
+			);
+			assert!(!AccountAddresses::<Test>::contains_key(&legacy.owner, &legacy_id));
+			assert!(AccountAddresses::<Test>::contains_key(&legacy.owner, &canonical_id));
+			assert_eq!(
+				AskOrders::<Test>::try_get_id(&ask_order_id).unwrap().lender_address_id,
+				canonical_id
+			);
+			let migrated = DealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
+			assert_eq!(migrated.lender_address_id, canonical_id);
+			assert_eq!(migrated.borrower_address_id, deal_order.borrower_address_id);
+			assert_eq!(LegacyAddressCollisions::<Test>::iter().count(), 0);
+		});
+	}
+
+	#[test]
+	fn pending_tasks_refer_to_the_canonical_id() {
+		ExtBuilder::default().build_and_execute(|| {
+			let legacy_id = legacy_id(&Blockchain::Ethereum, HEX_ENCODED);
+			let canonical_id =
+				AddressId::new::<Test>(&Blockchain::Ethereum, &hex::decode(RAW).unwrap());
+			let legacy = address(HEX_ENCODED, 1);
+			Addresses::<Test>::insert(&legacy_id, legacy.clone());
+
+			let mut pending_transfer = create_unverified_transfer();
+			pending_transfer.transfer.blockchain = Blockchain::Ethereum;
+			pending_transfer.transfer.from = legacy_id.clone();
+			pending_transfer.from_external = HEX_ENCODED.to_vec().try_into().unwrap();
+			let transfer_id: Hash = TaskV2::<Test>::to_id(&pending_transfer);
+			PendingTasks::<Test>::insert(1, transfer_id, Task::from(pending_transfer.clone()));
+
+			let pending_wallet = UnverifiedContractWallet {
+				blockchain: Blockchain::Ethereum,
+				address: HEX_ENCODED.to_vec().try_into().unwrap(),
+				signature: vec![1u8; 65].try_into().unwrap(),
+				owner: legacy.owner.clone(),
+			};
+			let legacy_wallet_id: Hash =
+				ContractWalletId::new::<Test>(&legacy_id, &legacy.owner).into_inner();
+			PendingTasks::<Test>::insert(1, legacy_wallet_id, Task::from(pending_wallet.clone()));
+
+			super::Migration::<Test>::new().migrate();
+
+			let raw: ExternalAddress = hex::decode(RAW).unwrap().try_into().unwrap();
+			let migrated = match PendingTasks::<Test>::get(1, transfer_id) {
+				Some(Task::VerifyTransfer(pending)) => pending,
+				task => panic!("expected the pending transfer, found {task:?}"),
+			};
+			assert_eq!(migrated.transfer.from, canonical_id);
+			assert_eq!(migrated.transfer.to, pending_transfer.transfer.to);
+			assert_eq!(migrated.from_external, raw);
+
+			let wallet_id: Hash =
+				ContractWalletId::new::<Test>(&canonical_id, &legacy.owner).into_inner();
+			assert!(!PendingTasks::<Test>::contains_key(1, legacy_wallet_id));
+			assert_eq!(
+				PendingTasks::<Test>::get(1, wallet_id),
+				Some(Task::from(UnverifiedContractWallet { address: raw, ..pending_wallet }))
+			);
+		});
+	}
+
+	#[test]
+	fn colliding_addresses_are_recorded() {
+		ExtBuilder::default().build_and_execute(|| {
+			let raw = hex::decode(RAW).unwrap();
+			let other = hex::decode("09231da7b19a016f9e576d23b16277062f4d46a8").unwrap();
+
+			let canonical_id = AddressId::new::<Test>(&Blockchain::Ethereum, &raw);
+			// registered before normalization, under the hash of its hex encoded form
+			let legacy_id = legacy_id(&Blockchain::Ethereum, HEX_ENCODED);
+			let other_id = AddressId::new::<Test>(&Blockchain::Ethereum, &other);
+
+			Addresses::<Test>::insert(&canonical_id, address(&raw, 1));
+			Addresses::<Test>::insert(&legacy_id, address(HEX_ENCODED, 2));
+			Addresses::<Test>::insert(&other_id, address(&other, 3));
+
+			super::Migration::<Test>::new().migrate();
+
+			assert_eq!(Addresses::<Test>::iter_keys().count(), 3);
+			assert!(Addresses::<Test>::contains_key(&legacy_id));
+			assert_eq!(LegacyAddressCollisions::<Test>::iter().count(), 2);
+			assert!(LegacyAddressCollisions::<Test>::contains_key(&canonical_id, &canonical_id));
+			assert!(LegacyAddressCollisions::<Test>::contains_key(&canonical_id, &legacy_id));
+		});
+	}
+}
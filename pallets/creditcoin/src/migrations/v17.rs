@@ -0,0 +1,89 @@
+// the chains supported before the registry of external chains are added to it, with Rinkeby
+// deprecated since it has been shut down
+
+use super::Migrate;
+use crate::{Config, ExternalChain, ExternalChains};
+use frame_support::{pallet_prelude::*, traits::Get};
+use sp_std::prelude::*;
+
+pub(super) struct Migration<Runtime>(PhantomData<Runtime>);
+
+impl<Runtime> Migration<Runtime> {
+	pub(super) fn new() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<T: Config> Migrate for Migration<T> {
+	fn pre_upgrade(&self) -> Vec<u8> {
+		vec![]
+	}
+
+	fn migrate(&self) -> Weight {
+		let mut weight = Weight::zero();
+
+		for (blockchain, chain) in ExternalChain::builtin() {
+			weight.saturating_accrue(T::DbWeight::get().reads(1));
+			if !ExternalChains::<T>::contains_key(&blockchain) {
+				ExternalChains::<T>::insert(blockchain, chain);
+				weight.saturating_accrue(T::DbWeight::get().writes(1));
+			}
+		}
+
+		weight
+	}
+
+	fn post_upgrade(&self, _ctx: Vec<u8>) {
+		assert_eq!(
+			StorageVersion::get::<crate::Pallet<T>>(),
+			17,
+			"expected storage version to be 17 after migrations complete"
+		);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Migrate;
+	use crate::{
+		mock::{ExtBuilder, Test},
+		AddressFormat, Blockchain, ExternalChain, ExternalChains,
+	};
+
+	#[test]
+	fn migrate_seeds_builtin_chains() {
+		ExtBuilder::default().build_and_execute(|| {
+			let _ = ExternalChains::<Test>::clear(u32::MAX, None);
+
+			super::Migration::<Test>::new().migrate();
+
+			let ethereum = ExternalChains::<Test>::get(Blockchain::Ethereum).unwrap();
+			assert_eq!(ethereum.address_format, AddressFormat::Evm);
+			assert_eq!(ethereum.evm_chain_id, Some(1));
+			assert!(!ethereum.deprecated);
+
+			let rinkeby = ExternalChains::<Test>::get(Blockchain::Rinkeby).unwrap();
+			assert!(rinkeby.deprecated);
+
+			let bitcoin = ExternalChains::<Test>::get(Blockchain::Bitcoin).unwrap();
+			assert_eq!(bitcoin.address_format, AddressFormat::Bitcoin);
+			assert!(bitcoin.supports(&crate::TransferKind::Native));
+			assert!(!bitcoin.supports(&crate::TransferKind::Erc20(Default::default())));
+
+			assert_eq!(ExternalChains::<Test>::iter().count(), ExternalChain::builtin().len());
+		});
+	}
+
+	#[test]
+	fn migrate_keeps_registered_chains() {
+		ExtBuilder::default().build_and_execute(|| {
+			let mut luniverse = ExternalChains::<Test>::get(Blockchain::Luniverse).unwrap();
+			luniverse.evm_chain_id = Some(42);
+			ExternalChains::<Test>::insert(Blockchain::Luniverse, &luniverse);
+
+			super::Migration::<Test>::new().migrate();
+
+			assert_eq!(ExternalChains::<Test>::get(Blockchain::Luniverse), Some(luniverse));
+		});
+	}
+}
@@ -1,7 +1,6 @@
 // `interest_type` added to `LoanTerms`
 
-use super::{v1, v2, AccountIdOf, BlockNumberOf, HashOf, Migrate, MomentOf};
-use core::convert::TryFrom;
+use super::{v1, v10, v2, v8, AccountIdOf, BlockNumberOf, HashOf, Migrate, MomentOf};
 use frame_support::dispatch::Weight;
 use frame_support::{pallet_prelude::*, traits::Get, Identity, Twox64Concat};
 use sp_std::prelude::*;
@@ -16,13 +15,13 @@ use v1::InterestRate as OldInterestRate;
 use v1::LoanTerms as OldLoanTerms;
 use v2::DealOrder as OldDealOrder;
 
-use crate::AskOrder;
-use crate::AskTerms;
-use crate::BidOrder;
-use crate::BidTerms;
-use crate::DealOrder;
 use crate::InterestRate;
-use crate::LoanTerms;
+use v10::OldAskOrder as AskOrder;
+use v10::OldAskTerms as AskTerms;
+use v10::OldBidOrder as BidOrder;
+use v10::OldBidTerms as BidTerms;
+use v10::OldLoanTerms as LoanTerms;
+use v8::OldDealOrder as DealOrder;
 
 impl From<OldInterestRate> for InterestRate {
 	fn from(old: OldInterestRate) -> Self {
@@ -47,13 +46,13 @@ impl From<OldLoanTerms> for LoanTerms {
 
 impl From<OldAskTerms> for AskTerms {
 	fn from(old: OldAskTerms) -> Self {
-		Self::try_from(LoanTerms::from(old.0)).expect("existing ask terms must be valid")
+		Self(LoanTerms::from(old.0))
 	}
 }
 
 impl From<OldBidTerms> for BidTerms {
 	fn from(old: OldBidTerms) -> Self {
-		Self::try_from(LoanTerms::from(old.0)).expect("existing bid terms must be valid")
+		Self(LoanTerms::from(old.0))
 	}
 }
 
@@ -168,7 +167,6 @@ impl<T: Config> Migrate for Migration<T> {
 #[cfg(test)]
 mod tests {
 	use super::Migrate;
-	use core::convert::TryFrom;
 
 	use crate::{
 		mock::{ExtBuilder, Test},
@@ -252,7 +250,7 @@ mod tests {
 				AskOrder {
 					blockchain: old_ask_order.blockchain,
 					lender_address_id: old_ask_order.lender_address_id,
-					terms: AskTerms::try_from(LoanTerms {
+					terms: AskTerms(LoanTerms {
 						amount: old_ask_order.terms.0.amount,
 						interest_rate: InterestRate {
 							rate_per_period: old_ask_order.terms.0.interest_rate.rate_per_period,
@@ -261,8 +259,7 @@ mod tests {
 							interest_type: crate::InterestType::Simple,
 						},
 						term_length: old_ask_order.terms.0.term_length,
-					})
-					.unwrap(),
+					}),
 					expiration_block: old_ask_order.expiration_block,
 					block: old_ask_order.block,
 					lender: old_ask_order.lender,
@@ -305,7 +302,7 @@ mod tests {
 				BidOrder {
 					blockchain: old_bid_order.blockchain,
 					borrower_address_id: old_bid_order.borrower_address_id,
-					terms: BidTerms::try_from(LoanTerms {
+					terms: BidTerms(LoanTerms {
 						amount: old_bid_order.terms.0.amount,
 						interest_rate: InterestRate {
 							rate_per_period: old_bid_order.terms.0.interest_rate.rate_per_period,
@@ -314,8 +311,7 @@ mod tests {
 							interest_type: crate::InterestType::Simple,
 						},
 						term_length: old_bid_order.terms.0.term_length,
-					})
-					.unwrap(),
+					}),
 					expiration_block: old_bid_order.expiration_block,
 					block: old_bid_order.block,
 					borrower: old_bid_order.borrower,
@@ -0,0 +1,210 @@
+// `repaid_amount` added to `DealOrder`
+
+use super::{AccountIdOf, BlockNumberOf, HashOf, Migrate, MomentOf};
+use crate::{AddressId, Blockchain, Config, ExternalAmount, OfferId, TransferId, Transfers};
+use frame_support::{pallet_prelude::*, traits::Get, Identity, Twox64Concat};
+use sp_std::prelude::*;
+
+use super::v10::OldLoanTerms as LoanTerms;
+use super::v9::OldDealOrder as DealOrder;
+
+#[derive(Encode, Decode)]
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+pub struct OldDealOrder<AccountId, BlockNum, Hash, Moment> {
+	pub blockchain: Blockchain,
+	pub offer_id: OfferId<BlockNum, Hash>,
+	pub lender_address_id: AddressId<Hash>,
+	pub borrower_address_id: AddressId<Hash>,
+	pub terms: LoanTerms,
+	pub expiration_block: BlockNum,
+	pub timestamp: Moment,
+	pub block: Option<BlockNum>,
+	pub funding_transfer_id: Option<TransferId<Hash>>,
+	pub repayment_transfer_id: Option<TransferId<Hash>>,
+	pub lock: Option<AccountId>,
+	pub borrower: AccountId,
+}
+
+#[frame_support::storage_alias]
+type DealOrders<T: crate::Config> = StorageDoubleMap<
+	crate::Pallet<T>,
+	Twox64Concat,
+	BlockNumberOf<T>,
+	Identity,
+	HashOf<T>,
+	DealOrder<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>, MomentOf<T>>,
+>;
+
+pub(super) struct Migration<Runtime>(PhantomData<Runtime>);
+
+impl<Runtime> Migration<Runtime> {
+	pub(super) fn new() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<T: Config> Migrate for Migration<T> {
+	fn pre_upgrade(&self) -> Vec<u8> {
+		vec![]
+	}
+
+	fn migrate(&self) -> Weight {
+		let mut weight: Weight = Weight::zero();
+		let weight_each = T::DbWeight::get().reads_writes(2, 1);
+
+		DealOrders::<T>::translate::<
+			OldDealOrder<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>, MomentOf<T>>,
+			_,
+		>(|_exp, _hash, old_deal| {
+			weight = weight.saturating_add(weight_each);
+
+			// a closed deal order was repaid in full by its single repayment transfer
+			let repaid_amount = old_deal
+				.repayment_transfer_id
+				.as_ref()
+				.and_then(Transfers::<T>::get)
+				.map(|transfer| transfer.amount)
+				.unwrap_or_else(ExternalAmount::zero);
+
+			Some(DealOrder {
+				blockchain: old_deal.blockchain,
+				offer_id: old_deal.offer_id,
+				lender_address_id: old_deal.lender_address_id,
+				borrower_address_id: old_deal.borrower_address_id,
+				terms: old_deal.terms,
+				expiration_block: old_deal.expiration_block,
+				timestamp: old_deal.timestamp,
+				block: old_deal.block,
+				funding_transfer_id: old_deal.funding_transfer_id,
+				repayment_transfer_id: old_deal.repayment_transfer_id,
+				repaid_amount,
+				lock: old_deal.lock,
+				borrower: old_deal.borrower,
+			})
+		});
+
+		weight
+	}
+
+	fn post_upgrade(&self, _ctx: Vec<u8>) {
+		assert_eq!(
+			StorageVersion::get::<crate::Pallet<T>>(),
+			8,
+			"expected storage version to be 8 after migrations complete"
+		);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Migrate;
+
+	use crate::{
+		mock::{ExtBuilder, Test},
+		tests::TestInfo,
+		Blockchain, DealOrderId, DoubleMapExt, ExternalAmount, OfferId, Transfers,
+	};
+
+	use super::{
+		AccountIdOf, BlockNumberOf, DealOrder, HashOf, Identity, LoanTerms, MomentOf, OldDealOrder,
+		Twox64Concat,
+	};
+
+	#[frame_support::storage_alias]
+	type DealOrders<T: crate::Config> = StorageDoubleMap<
+		crate::Pallet<T>,
+		Twox64Concat,
+		BlockNumberOf<T>,
+		Identity,
+		HashOf<T>,
+		OldDealOrder<AccountIdOf<T>, BlockNumberOf<T>, HashOf<T>, MomentOf<T>>,
+	>;
+
+	type OldDealOrders = DealOrders<Test>;
+
+	fn old_deal_order(
+		test_info: &TestInfo,
+	) -> OldDealOrder<AccountIdOf<Test>, BlockNumberOf<Test>, HashOf<Test>, MomentOf<Test>> {
+		OldDealOrder {
+			blockchain: Blockchain::Rinkeby,
+			offer_id: OfferId::with_expiration_hash::<Test>(100, [1u8; 32].into()),
+			lender_address_id: test_info.lender.address_id.clone(),
+			borrower_address_id: test_info.borrower.address_id.clone(),
+			terms: LoanTerms::default(),
+			expiration_block: 100,
+			timestamp: 0,
+			block: None,
+			funding_transfer_id: None,
+			repayment_transfer_id: None,
+			lock: None,
+			borrower: test_info.borrower.account_id.clone(),
+		}
+	}
+
+	#[test]
+	fn open_deal_order_migrates_with_nothing_repaid() {
+		ExtBuilder::default().build_and_execute(|| {
+			let test_info = TestInfo::new_defaults();
+
+			let deal_id = DealOrderId::with_expiration_hash::<Test>(100, [0u8; 32].into());
+			let old_deal = old_deal_order(&test_info);
+
+			OldDealOrders::insert_id(&deal_id, &old_deal);
+
+			super::Migration::<Test>::new().migrate();
+
+			let deal = super::DealOrders::<Test>::try_get_id(&deal_id).unwrap();
+
+			assert_eq!(
+				deal,
+				DealOrder {
+					blockchain: old_deal.blockchain,
+					offer_id: old_deal.offer_id,
+					lender_address_id: old_deal.lender_address_id,
+					borrower_address_id: old_deal.borrower_address_id,
+					terms: old_deal.terms,
+					expiration_block: old_deal.expiration_block,
+					timestamp: old_deal.timestamp,
+					block: old_deal.block,
+					funding_transfer_id: old_deal.funding_transfer_id,
+					repayment_transfer_id: old_deal.repayment_transfer_id,
+					repaid_amount: ExternalAmount::zero(),
+					lock: old_deal.lock,
+					borrower: old_deal.borrower,
+				}
+			);
+		});
+	}
+
+	#[test]
+	fn closed_deal_order_migrates_with_repayment_amount() {
+		ExtBuilder::default().build_and_execute(|| {
+			let test_info = TestInfo::new_defaults();
+
+			let deal_id = DealOrderId::with_expiration_hash::<Test>(100, [0u8; 32].into());
+			let (transfer_id, transfer) = test_info.make_transfer(
+				&test_info.borrower,
+				&test_info.lender,
+				1234u64,
+				&deal_id,
+				"0xafafaf",
+				None,
+			);
+			Transfers::<Test>::insert(&transfer_id, &transfer);
+
+			let old_deal = OldDealOrder {
+				repayment_transfer_id: Some(transfer_id),
+				..old_deal_order(&test_info)
+			};
+
+			OldDealOrders::insert_id(&deal_id, &old_deal);
+
+			super::Migration::<Test>::new().migrate();
+
+			let deal = super::DealOrders::<Test>::try_get_id(&deal_id).unwrap();
+
+			assert_eq!(deal.repaid_amount, transfer.amount);
+			assert_eq!(deal.repayment_transfer_id, old_deal.repayment_transfer_id);
+		});
+	}
+}
@@ -1,7 +1,8 @@
 use crate::{
 	self as pallet_creditcoin,
+	helpers::extensions::HexToAddress,
 	ocw::rpc::{JsonRpcRequest, JsonRpcResponse},
-	Blockchain, LegacySighash,
+	Blockchain, ExternalChain, LegacySighash, LoanCurrency,
 };
 use ethereum_types::U256;
 use frame_support::{
@@ -258,6 +259,7 @@ impl ExtBuilder {
 		let _ = crate::GenesisConfig::<Test> {
 			legacy_wallets: self.legacy_wallets,
 			legacy_balance_keeper: self.legacy_keeper,
+			external_chains: mock_external_chains(),
 		}
 		.assimilate_storage(&mut storage);
 
@@ -409,6 +411,11 @@ pub(crate) fn get_mock_contract() -> String {
 		.to_string()
 }
 
+/// The currency of the token transferred by the mocked ethless transaction.
+pub(crate) fn get_mock_currency() -> LoanCurrency {
+	LoanCurrency::Token { contract: get_mock_contract().hex_to_address() }
+}
+
 pub(crate) fn get_mock_tx_block_num() -> String {
 	let responses = &*ETHLESS_RESPONSES;
 
@@ -556,6 +563,17 @@ impl MockedRpcRequests {
 	}
 }
 
+/// The built-in chains, with Rinkeby still live for the tests that use it. Their chain ids are
+/// unknown, so the mocked RPC nodes aren't asked for them.
+pub(crate) fn mock_external_chains() -> Vec<(Blockchain, ExternalChain)> {
+	ExternalChain::builtin()
+		.into_iter()
+		.map(|(blockchain, chain)| {
+			(blockchain, ExternalChain { evm_chain_id: None, deprecated: false, ..chain })
+		})
+		.collect()
+}
+
 #[test]
 fn default_works() {
 	ExtBuilder::default().build_offchain_and_execute(|| {
@@ -563,5 +581,6 @@ fn default_works() {
 
 		assert_eq!(defaults.legacy_wallets.len(), 0);
 		assert_eq!(defaults.legacy_balance_keeper, None);
+		assert_eq!(defaults.external_chains, ExternalChain::builtin());
 	});
 }
@@ -4,7 +4,7 @@ pub(crate) mod tasks;
 
 use self::{errors::RpcUrlError, rpc::errors::RpcError};
 use super::ExternalAddress;
-use crate::{Blockchain, ExternalTxId, TransferKind};
+use crate::{Blockchain, Config, ExternalChains, ExternalTxId};
 use alloc::string::String;
 pub(crate) use errors::{OffchainError, VerificationFailureCause, VerificationResult};
 use sp_runtime::offchain::storage::StorageValueRef;
@@ -24,15 +24,47 @@ impl Blockchain {
 			Err(RpcUrlError::NoValue)
 		}
 	}
-	pub fn supports(&self, kind: &TransferKind) -> bool {
-		match (self, kind) {
-			(
-				Blockchain::Ethereum | Blockchain::Luniverse | Blockchain::Rinkeby,
-				TransferKind::Erc20(_) | TransferKind::Ethless(_) | TransferKind::Native,
-			) => true,
-			(Blockchain::Bitcoin, TransferKind::Native) => true,
-			(_, _) => false, // TODO: refine this later
+}
+
+impl<T: Config> crate::Pallet<T> {
+	/// The RPC url of an EVM chain, once the node behind it answers with the chain id the
+	/// registry of external chains holds for `blockchain`. Nodes of chains without a known id
+	/// are trusted as they are.
+	pub fn evm_rpc_url(blockchain: &Blockchain) -> OffchainResult<String> {
+		let rpc_url = blockchain.rpc_url()?;
+		let expected = ExternalChains::<T>::get(blockchain).and_then(|chain| chain.evm_chain_id);
+		if let Some(expected) = expected {
+			let chain_id = Self::eth_chain_id(blockchain, &rpc_url)?;
+			if chain_id != expected {
+				log::warn!(
+					"RPC node of {:?} serves chain {}, expected {}",
+					blockchain,
+					chain_id,
+					expected
+				);
+				return Err(OffchainError::IncorrectChainId);
+			}
+		}
+		Ok(rpc_url)
+	}
+
+	/// The chain id the node at `rpc_url` answers with. It is asked once per block, the answer
+	/// is kept in offchain local storage for the tasks that follow in the same block.
+	fn eth_chain_id(blockchain: &Blockchain, rpc_url: &str) -> OffchainResult<u64> {
+		let key = [blockchain.as_bytes(), b"-chain-id"].concat();
+		let chain_id_storage = StorageValueRef::persistent(&key);
+		let block_number = frame_system::Pallet::<T>::block_number();
+		if let Ok(Some((asked_at, url, chain_id))) =
+			chain_id_storage.get::<(T::BlockNumber, Vec<u8>, u64)>()
+		{
+			if asked_at == block_number && url == rpc_url.as_bytes() {
+				return Ok(chain_id);
+			}
 		}
+
+		let chain_id = rpc::eth_chain_id(rpc_url)?.as_u64();
+		chain_id_storage.set(&(block_number, rpc_url.as_bytes(), chain_id));
+		Ok(chain_id)
 	}
 }
 
@@ -12,6 +12,8 @@ pub enum OffchainError {
 	InvalidTask(VerificationFailureCause),
 	NoRpcUrl(RpcUrlError),
 	RpcError(RpcError),
+	/// The RPC node serves another chain than the registry of external chains expects.
+	IncorrectChainId,
 }
 
 #[derive(Debug)]
@@ -44,6 +46,7 @@ pub enum VerificationFailureCause {
 	InvalidAddress,
 	UnsupportedMethod,
 	TransactionNotFound,
+	InvalidSignature,
 }
 
 impl VerificationFailureCause {
@@ -53,7 +56,7 @@ impl VerificationFailureCause {
 			TaskFailed | IncorrectContract | MissingSender | MissingReceiver | AbiMismatch
 			| IncorrectInputLength | IncorrectInputType | IncorrectAmount | IncorrectNonce
 			| InvalidAddress | UnsupportedMethod | TaskInFuture | IncorrectSender | EmptyInput
-			| IncorrectReceiver | TaskNonexistent | TransactionNotFound => true,
+			| IncorrectReceiver | TaskNonexistent | TransactionNotFound | InvalidSignature => true,
 			TaskPending | TaskUnconfirmed => false,
 		}
 	}
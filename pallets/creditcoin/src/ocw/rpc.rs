@@ -352,6 +352,11 @@ pub fn eth_get_block_number(rpc_url: &str) -> OffchainResult<U64, RpcError> {
 	rpc_req.send(rpc_url)
 }
 
+pub fn eth_chain_id(rpc_url: &str) -> OffchainResult<U64, RpcError> {
+	let rpc_req = JsonRpcRequest::new("eth_chainId", None);
+	rpc_req.send(rpc_url)
+}
+
 pub fn eth_get_block_by_number(
 	block_number: U64,
 	rpc_url: &str,
@@ -363,6 +368,17 @@ pub fn eth_get_block_by_number(
 	rpc_req.send(rpc_url)
 }
 
+pub fn eth_call(to: &Address, data: &[u8], rpc_url: &str) -> OffchainResult<Bytes, RpcError> {
+	let mut call = serde_json::Map::new();
+	call.insert("to".into(), serde_json::Value::String(to_json_hex(to.as_bytes())));
+	call.insert("data".into(), serde_json::Value::String(to_json_hex(data)));
+	let rpc_req = JsonRpcRequest::new(
+		"eth_call",
+		[serde_json::Value::Object(call), serde_json::Value::String("latest".into())],
+	);
+	rpc_req.send(rpc_url)
+}
+
 #[cfg(test)]
 mod tests {
 	#[test]
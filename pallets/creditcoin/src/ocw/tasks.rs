@@ -1,4 +1,5 @@
 pub mod collect_coins;
+pub mod verify_contract_wallet;
 pub mod verify_transfer;
 
 use crate::ocw::errors::VerificationResult;
@@ -38,6 +39,9 @@ where
 			CollectCoins(unverified) => {
 				unverified.forward_task(deadline).map(|c: crate::pallet::Call<T>| c.into())
 			},
+			VerifyContractWallet(unverified) => {
+				unverified.forward_task(deadline).map(|c: crate::pallet::Call<T>| c.into())
+			},
 		}
 	}
 }
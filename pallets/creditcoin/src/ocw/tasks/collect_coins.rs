@@ -124,7 +124,7 @@ impl<T: CreditcoinConfig> Pallet<T> {
 		log::debug!("verifying OCW Collect Coins");
 		let UnverifiedCollectedCoins { to, tx_id, contract: GCreContract { address, chain } } =
 			u_cc;
-		let rpc_url = &chain.rpc_url()?;
+		let rpc_url = &Self::evm_rpc_url(chain)?;
 		let tx = ocw::eth_get_transaction(tx_id, rpc_url)?;
 		let tx_receipt = rpc::eth_get_transaction_receipt(tx_id, rpc_url)?;
 		let eth_tip = rpc::eth_get_block_number(rpc_url)?;
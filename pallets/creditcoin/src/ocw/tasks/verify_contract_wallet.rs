@@ -0,0 +1,567 @@
+use crate::helpers::contract_wallet_message_hash;
+use crate::ocw::{
+	self,
+	errors::{OffchainError, VerificationFailureCause, VerificationResult},
+	rpc::{self, errors::RpcError, JsonRpcError},
+	OffchainResult,
+};
+use crate::pallet::{Config as CreditcoinConfig, Pallet};
+use crate::types::UnverifiedContractWallet;
+use ethabi::{Function, Param, ParamType, StateMutability, Token};
+use frame_support::ensure;
+use parity_scale_codec::Encode;
+#[cfg_attr(feature = "std", allow(unused_imports))]
+use sp_std::prelude::*;
+
+/// What `isValidSignature` returns when the wallet accepts a signature, see EIP-1271.
+pub const EIP1271_MAGIC_VALUE: [u8; 4] = [0x16, 0x26, 0xba, 0x7e];
+
+/// The JSON-RPC error code of an `eth_call` that reverted.
+const EXECUTION_REVERTED: i32 = 3;
+
+pub(crate) fn is_valid_signature_function_abi() -> Function {
+	#[allow(deprecated)]
+	Function {
+		name: "isValidSignature".into(),
+		inputs: vec![
+			Param { name: "_hash".into(), kind: ParamType::FixedBytes(32), internal_type: None },
+			Param { name: "_signature".into(), kind: ParamType::Bytes, internal_type: None },
+		],
+		outputs: vec![Param {
+			name: "magicValue".into(),
+			kind: ParamType::FixedBytes(4),
+			internal_type: None,
+		}],
+		constant: Some(true),
+		state_mutability: StateMutability::View,
+	}
+}
+
+pub(crate) fn is_valid_signature_input(hash: [u8; 32], signature: &[u8]) -> Vec<u8> {
+	is_valid_signature_function_abi()
+		.encode_input(&[Token::FixedBytes(hash.to_vec()), Token::Bytes(signature.to_vec())])
+		.expect("the tokens match the function inputs; qed")
+}
+
+pub fn validate_is_valid_signature(output: &[u8]) -> OffchainResult<()> {
+	let outputs = is_valid_signature_function_abi().decode_output(output).map_err(|e| {
+		log::error!("failed to decode isValidSignature output: {:?}", e);
+		VerificationFailureCause::InvalidSignature
+	})?;
+
+	match outputs.get(0) {
+		Some(Token::FixedBytes(value)) => {
+			ensure!(value[..] == EIP1271_MAGIC_VALUE, VerificationFailureCause::InvalidSignature);
+			Ok(())
+		},
+		_ => Err(VerificationFailureCause::InvalidSignature.into()),
+	}
+}
+
+fn is_revert(error: &JsonRpcError) -> bool {
+	error.code == EXECUTION_REVERTED || error.message.contains("revert")
+}
+
+impl<T: CreditcoinConfig> Pallet<T> {
+	pub fn verify_contract_wallet_ocw(
+		wallet: &UnverifiedContractWallet<T::AccountId>,
+	) -> VerificationResult<()> {
+		log::debug!("verifying OCW contract wallet");
+		let UnverifiedContractWallet { blockchain, address, signature, owner } = wallet;
+		let rpc_url = &Self::evm_rpc_url(blockchain)?;
+		let wallet_address = ocw::parse_eth_address(address)?;
+
+		let hash = contract_wallet_message_hash(owner.encode().as_slice());
+		let input = is_valid_signature_input(hash, signature);
+
+		// wallets revert instead of returning another value when they reject a signature
+		let output = rpc::eth_call(&wallet_address, &input, rpc_url).map_err(|e| match e {
+			RpcError::FailureResponse(error) if is_revert(&error) => {
+				OffchainError::InvalidTask(VerificationFailureCause::InvalidSignature)
+			},
+			e => e.into(),
+		})?;
+
+		validate_is_valid_signature(&output.0)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::helpers::non_paying_error;
+	use crate::mock::{
+		pending_rpc_request, roll_by_with_ocw, set_rpc_uri, AccountId, ExtBuilder, OffchainState,
+		RuntimeOrigin, RwLock, TaskScheduler, Test,
+	};
+	use crate::ocw::errors::SchedulerError;
+	use crate::ocw::rpc::JsonRpcResponse;
+	use crate::ocw::VerificationFailureCause as Cause;
+	use crate::types::{Address, AddressId, Blockchain, ContractWalletId, ExternalAddress};
+	use crate::Pallet as Creditcoin;
+	use alloc::sync::Arc;
+	use assert_matches::assert_matches;
+	use frame_support::{assert_noop, assert_ok};
+	use frame_system::Pallet as System;
+	use pallet_offchain_task_scheduler::tasks::{
+		error::TaskError, TaskScheduler as TaskSchedulerT, TaskV2,
+	};
+	use parity_scale_codec::Decode;
+	use sp_runtime::traits::IdentifyAccount;
+	use std::collections::HashMap;
+
+	const CHAIN: Blockchain = Blockchain::Ethereum;
+	const RPC_URI: &str = "dummy";
+	const WALLET: &str = "41675c099f32341bf84bfc5382af534df5c7461a";
+
+	fn wallet_address() -> ExternalAddress {
+		hex::decode(WALLET).unwrap().try_into().unwrap()
+	}
+
+	fn owner() -> AccountId {
+		AccountId::new([7; 32])
+	}
+
+	fn signature() -> crate::types::ContractWalletSignature {
+		vec![0xab; 65].try_into().unwrap()
+	}
+
+	fn magic_output() -> String {
+		let mut output = [0u8; 32];
+		output[..4].copy_from_slice(&EIP1271_MAGIC_VALUE);
+		format!("0x{}", hex::encode(output))
+	}
+
+	/// call from externalities context
+	fn mock_eth_call(
+		state: &Arc<RwLock<OffchainState>>,
+		response: JsonRpcResponse<serde_json::Value>,
+	) {
+		set_rpc_uri(&CHAIN, RPC_URI);
+
+		let hash = contract_wallet_message_hash(owner().encode().as_slice());
+		let input = is_valid_signature_input(hash, &signature());
+		let params = vec![
+			serde_json::json!({
+				"to": format!("0x{WALLET}"),
+				"data": format!("0x{}", hex::encode(input)),
+			}),
+			"latest".into(),
+		];
+		let responses = HashMap::from([("eth_call".to_string(), response)]);
+		let request = pending_rpc_request("eth_call", params, RPC_URI, &responses);
+		state.write().expect_request(request);
+	}
+
+	/// call from externalities context
+	fn mock_eth_chain_id(state: &Arc<RwLock<OffchainState>>, chain_id: &str) {
+		set_rpc_uri(&CHAIN, RPC_URI);
+
+		let response = result_response(chain_id.into());
+		let responses = HashMap::from([("eth_chainId".to_string(), response)]);
+		let request = pending_rpc_request("eth_chainId", None, RPC_URI, &responses);
+		state.write().expect_request(request);
+	}
+
+	fn set_chain_id(chain_id: u64) {
+		crate::ExternalChains::<Test>::mutate(&CHAIN, |chain| {
+			chain.as_mut().unwrap().evm_chain_id = Some(chain_id);
+		});
+	}
+
+	fn unverified_wallet() -> UnverifiedContractWallet<AccountId> {
+		UnverifiedContractWallet {
+			blockchain: CHAIN,
+			address: wallet_address(),
+			signature: signature(),
+			owner: owner(),
+		}
+	}
+
+	fn result_response(result: String) -> JsonRpcResponse<serde_json::Value> {
+		JsonRpcResponse { jsonrpc: "2.0".into(), id: 1, error: None, result: Some(result.into()) }
+	}
+
+	fn error_response(code: i32, message: &str) -> JsonRpcResponse<serde_json::Value> {
+		JsonRpcResponse {
+			jsonrpc: "2.0".into(),
+			id: 1,
+			error: Some(JsonRpcError { code, message: message.into() }),
+			result: None,
+		}
+	}
+
+	fn register_wallet() {
+		assert_ok!(Creditcoin::<Test>::register_contract_wallet(
+			RuntimeOrigin::signed(owner()),
+			CHAIN,
+			wallet_address(),
+			signature(),
+		));
+	}
+
+	fn contract_wallet_id(owner: &AccountId) -> ContractWalletId<crate::mock::Hash> {
+		let address_id = AddressId::new::<Test>(&CHAIN, &wallet_address());
+		ContractWalletId::new::<Test>(&address_id, owner)
+	}
+
+	fn assert_invalid(res: OffchainResult<()>, cause: VerificationFailureCause) {
+		assert_matches!(res, Err(OffchainError::InvalidTask(c)) =>{ assert_eq!(c,cause); });
+	}
+
+	#[test]
+	fn selector_is_the_magic_value() {
+		assert_eq!(is_valid_signature_function_abi().short_signature(), EIP1271_MAGIC_VALUE);
+	}
+
+	#[test]
+	fn validate_accepts_magic_value() {
+		let output = hex::decode(magic_output().trim_start_matches("0x")).unwrap();
+		assert_ok!(validate_is_valid_signature(&output));
+	}
+
+	#[test]
+	fn validate_rejects_other_values() {
+		let mut output = hex::decode(magic_output().trim_start_matches("0x")).unwrap();
+		output[3] = 0x7f;
+		assert_invalid(validate_is_valid_signature(&output), Cause::InvalidSignature);
+	}
+
+	#[test]
+	fn validate_rejects_empty_output() {
+		// e.g. the address has no code
+		assert_invalid(validate_is_valid_signature(&[]), Cause::InvalidSignature);
+	}
+
+	#[test]
+	fn persist_is_submitted() {
+		let mut ext = ExtBuilder::default();
+		ext.generate_authority();
+		ext.build_offchain_and_execute_with_state(|state, pool| {
+			mock_eth_call(&state, result_response(magic_output()));
+
+			register_wallet();
+			let deadline = Test::unverified_transfer_deadline();
+
+			roll_by_with_ocw(1);
+
+			let address_id = AddressId::new::<Test>(&CHAIN, &wallet_address());
+			let address = Address { blockchain: CHAIN, value: wallet_address(), owner: owner() };
+			let call = crate::Call::<Test>::persist_task_output {
+				deadline,
+				task_output: (address_id, address).into(),
+			};
+
+			assert_matches!(pool.write().transactions.pop(), Some(tx) => {
+				let tx = crate::mock::Extrinsic::decode(&mut &*tx).unwrap();
+				assert_eq!(tx.call, crate::mock::RuntimeCall::Creditcoin(call));
+			});
+		});
+	}
+
+	#[test]
+	fn fail_is_submitted_when_the_wallet_reverts() {
+		let mut ext = ExtBuilder::default();
+		ext.generate_authority();
+		ext.build_offchain_and_execute_with_state(|state, pool| {
+			mock_eth_call(&state, error_response(EXECUTION_REVERTED, "execution reverted"));
+
+			register_wallet();
+			let deadline = Test::unverified_transfer_deadline();
+
+			roll_by_with_ocw(1);
+
+			let call = crate::Call::<Test>::fail_task {
+				deadline,
+				task_id: contract_wallet_id(&owner()).into(),
+				cause: Cause::InvalidSignature,
+			};
+
+			assert_matches!(pool.write().transactions.pop(), Some(tx) => {
+				let tx = crate::mock::Extrinsic::decode(&mut &*tx).unwrap();
+				assert_eq!(tx.call, crate::mock::RuntimeCall::Creditcoin(call));
+			});
+		});
+	}
+
+	#[test]
+	fn fail_is_submitted_when_the_wallet_returns_another_value() {
+		let mut ext = ExtBuilder::default();
+		ext.generate_authority();
+		ext.build_offchain_and_execute_with_state(|state, pool| {
+			mock_eth_call(&state, result_response(format!("0x{}", hex::encode([0u8; 32]))));
+
+			register_wallet();
+			let deadline = Test::unverified_transfer_deadline();
+
+			roll_by_with_ocw(1);
+
+			let call = crate::Call::<Test>::fail_task {
+				deadline,
+				task_id: contract_wallet_id(&owner()).into(),
+				cause: Cause::InvalidSignature,
+			};
+
+			assert_matches!(pool.write().transactions.pop(), Some(tx) => {
+				let tx = crate::mock::Extrinsic::decode(&mut &*tx).unwrap();
+				assert_eq!(tx.call, crate::mock::RuntimeCall::Creditcoin(call));
+			});
+		});
+	}
+
+	#[test]
+	fn persist_registers_address() {
+		let mut ext = ExtBuilder::default();
+		let acct_pubkey = ext.generate_authority();
+		let auth = AccountId::from(acct_pubkey.into_account().0);
+		ext.build_offchain_and_execute_with_state(|_, _| {
+			System::<Test>::set_block_number(1);
+
+			register_wallet();
+			let deadline = Test::unverified_transfer_deadline();
+			let address_id = AddressId::new::<Test>(&CHAIN, &wallet_address());
+			let address = Address { blockchain: CHAIN, value: wallet_address(), owner: owner() };
+
+			assert_ok!(Creditcoin::<Test>::persist_task_output(
+				RuntimeOrigin::signed(auth),
+				deadline,
+				(address_id.clone(), address.clone()).into(),
+			));
+
+			assert_eq!(Creditcoin::<Test>::addresses(&address_id), Some(address.clone()));
+			assert!(crate::AccountAddresses::<Test>::contains_key(owner(), &address_id));
+			let task_id = contract_wallet_id(&owner()).into_inner();
+			assert!(!TaskScheduler::is_scheduled(&deadline, &task_id));
+
+			let event = System::<Test>::events().pop().expect("an event").event;
+			assert_eq!(
+				event,
+				crate::mock::RuntimeEvent::Creditcoin(crate::Event::<Test>::AddressRegistered(
+					address_id, address
+				))
+			);
+		});
+	}
+
+	#[test]
+	fn persist_not_reentrant() {
+		let mut ext = ExtBuilder::default();
+		let acct_pubkey = ext.generate_authority();
+		let auth = AccountId::from(acct_pubkey.into_account().0);
+		ext.build_offchain_and_execute_with_state(|_, _| {
+			let address_id = AddressId::new::<Test>(&CHAIN, &wallet_address());
+			let address = Address { blockchain: CHAIN, value: wallet_address(), owner: owner() };
+
+			assert_ok!(Creditcoin::<Test>::persist_task_output(
+				RuntimeOrigin::signed(auth.clone()),
+				Test::unverified_transfer_deadline(),
+				(address_id.clone(), address.clone()).into(),
+			));
+
+			assert_noop!(
+				Creditcoin::<Test>::persist_task_output(
+					RuntimeOrigin::signed(auth),
+					Test::unverified_transfer_deadline(),
+					(address_id, address).into(),
+				),
+				non_paying_error(crate::Error::<Test>::AddressAlreadyRegistered)
+			);
+		});
+	}
+
+	#[test]
+	fn fail_contract_wallet_emits_events() {
+		let mut ext = ExtBuilder::default();
+		let acct_pubkey = ext.generate_authority();
+		let auth = AccountId::from(acct_pubkey.into_account().0);
+		ext.build_offchain_and_execute_with_state(|_, _| {
+			System::<Test>::set_block_number(1);
+
+			let expected_id = contract_wallet_id(&owner());
+
+			assert_ok!(Creditcoin::<Test>::fail_task(
+				RuntimeOrigin::signed(auth),
+				Test::unverified_transfer_deadline(),
+				expected_id.clone().into(),
+				Cause::InvalidSignature,
+			));
+
+			let event = System::<Test>::events().pop().expect("an event").event;
+			assert_matches!(
+				event,
+				crate::mock::RuntimeEvent::Creditcoin(crate::Event::<Test>::ContractWalletFailedVerification(id, cause)) => {
+					assert_eq!(id, expected_id);
+					assert_eq!(cause, Cause::InvalidSignature);
+				}
+			);
+		});
+	}
+
+	#[test]
+	fn request_pending_not_reentrant() {
+		ExtBuilder::default().build_offchain_and_execute_with_state(|_, _| {
+			register_wallet();
+
+			assert_noop!(
+				Creditcoin::<Test>::register_contract_wallet(
+					RuntimeOrigin::signed(owner()),
+					CHAIN,
+					wallet_address(),
+					signature(),
+				),
+				crate::Error::<Test>::ContractWalletRegistrationPending
+			);
+		});
+	}
+
+	#[test]
+	fn request_pending_for_another_account_does_not_block_the_owner() {
+		ExtBuilder::default().build_offchain_and_execute_with_state(|_, _| {
+			let squatter = AccountId::new([8; 32]);
+			assert_ok!(Creditcoin::<Test>::register_contract_wallet(
+				RuntimeOrigin::signed(squatter.clone()),
+				CHAIN,
+				wallet_address(),
+				signature(),
+			));
+
+			register_wallet();
+
+			let deadline = Test::unverified_transfer_deadline();
+			for account in [squatter, owner()] {
+				let task_id = contract_wallet_id(&account).into_inner();
+				assert!(TaskScheduler::is_scheduled(&deadline, &task_id));
+			}
+		});
+	}
+
+	#[test]
+	fn is_persisted_once_the_wallet_is_registered() {
+		ExtBuilder::default().build_and_execute(|| {
+			let wallet = unverified_wallet();
+			let id = TaskV2::<Test>::to_id(&wallet);
+			assert!(!TaskV2::<Test>::is_persisted(&wallet, &id));
+
+			let address_id = wallet.address_id::<Test>();
+			crate::Addresses::<Test>::insert(&address_id, wallet.clone().into_output());
+
+			assert!(TaskV2::<Test>::is_persisted(&wallet, &id));
+		});
+	}
+
+	#[test]
+	fn forward_task_is_finished_once_another_account_registered_the_wallet() {
+		let mut ext = ExtBuilder::default();
+		let acct_pubkey = ext.generate_authority();
+		let auth = AccountId::from(acct_pubkey.into_account().0);
+		ext.build_offchain_and_execute_with_state(|_, _| {
+			let squatter = AccountId::new([8; 32]);
+			let address_id = AddressId::new::<Test>(&CHAIN, &wallet_address());
+			let address = Address { blockchain: CHAIN, value: wallet_address(), owner: squatter };
+			assert_ok!(Creditcoin::<Test>::persist_task_output(
+				RuntimeOrigin::signed(auth),
+				Test::unverified_transfer_deadline(),
+				(address_id, address).into(),
+			));
+
+			let deadline = Test::unverified_transfer_deadline();
+			assert_matches!(
+				TaskV2::<Test>::forward_task(&unverified_wallet(), deadline),
+				Err(TaskError::FinishedTask)
+			);
+		});
+	}
+
+	#[test]
+	fn request_registered_address() {
+		let mut ext = ExtBuilder::default();
+		let acct_pubkey = ext.generate_authority();
+		let auth = AccountId::from(acct_pubkey.into_account().0);
+		ext.build_offchain_and_execute_with_state(|_, _| {
+			let address_id = AddressId::new::<Test>(&CHAIN, &wallet_address());
+			let address = Address { blockchain: CHAIN, value: wallet_address(), owner: owner() };
+			assert_ok!(Creditcoin::<Test>::persist_task_output(
+				RuntimeOrigin::signed(auth),
+				Test::unverified_transfer_deadline(),
+				(address_id, address).into(),
+			));
+
+			assert_noop!(
+				Creditcoin::<Test>::register_contract_wallet(
+					RuntimeOrigin::signed(owner()),
+					CHAIN,
+					wallet_address(),
+					signature(),
+				),
+				crate::Error::<Test>::AddressAlreadyRegisteredByCaller
+			);
+			assert_noop!(
+				Creditcoin::<Test>::register_contract_wallet(
+					RuntimeOrigin::signed(AccountId::new([8; 32])),
+					CHAIN,
+					wallet_address(),
+					signature(),
+				),
+				crate::Error::<Test>::AddressAlreadyRegistered
+			);
+		});
+	}
+
+	#[test]
+	fn request_unsupported_chain() {
+		ExtBuilder::default().build_offchain_and_execute_with_state(|_, _| {
+			assert_noop!(
+				Creditcoin::<Test>::register_contract_wallet(
+					RuntimeOrigin::signed(owner()),
+					Blockchain::Bitcoin,
+					wallet_address(),
+					signature(),
+				),
+				crate::Error::<Test>::AddressFormatNotSupported
+			);
+		});
+	}
+
+	#[test]
+	fn verify_checks_the_chain_id_of_the_rpc_node() {
+		ExtBuilder::default().build_offchain_and_execute_with_state(|state, _| {
+			set_chain_id(1);
+			mock_eth_chain_id(&state, "0x1");
+			mock_eth_call(&state, result_response(magic_output()));
+
+			assert_ok!(Creditcoin::<Test>::verify_contract_wallet_ocw(&unverified_wallet()));
+		});
+	}
+
+	#[test]
+	fn rpc_node_is_asked_for_its_chain_id_once_per_block() {
+		ExtBuilder::default().build_offchain_and_execute_with_state(|state, _| {
+			set_chain_id(1);
+			mock_eth_chain_id(&state, "0x1");
+
+			assert_eq!(Creditcoin::<Test>::evm_rpc_url(&CHAIN).unwrap(), RPC_URI);
+			assert_eq!(Creditcoin::<Test>::evm_rpc_url(&CHAIN).unwrap(), RPC_URI);
+
+			System::<Test>::set_block_number(System::<Test>::block_number() + 1);
+			mock_eth_chain_id(&state, "0x1");
+
+			assert_eq!(Creditcoin::<Test>::evm_rpc_url(&CHAIN).unwrap(), RPC_URI);
+		});
+	}
+
+	#[test]
+	fn verify_rejects_an_rpc_node_of_another_chain() {
+		ExtBuilder::default().build_offchain_and_execute_with_state(|state, _| {
+			set_chain_id(1);
+			mock_eth_chain_id(&state, "0x4");
+
+			let wallet = unverified_wallet();
+			let id = TaskV2::<Test>::to_id(&wallet);
+			let deadline = Test::unverified_transfer_deadline();
+			assert_matches!(
+				TaskV2::<Test>::persistence_call(&wallet, deadline, &id),
+				Err(TaskError::Scheduler(SchedulerError::IncorrectChainId))
+			);
+		});
+	}
+}
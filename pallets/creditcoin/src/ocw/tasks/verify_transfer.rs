@@ -132,7 +132,7 @@ impl<T: Config> crate::Pallet<T> {
 		amount: &ExternalAmount,
 		tx_id: &ExternalTxId,
 	) -> VerificationResult<Option<T::Moment>> {
-		let rpc_url = blockchain.rpc_url()?;
+		let rpc_url = Self::evm_rpc_url(blockchain)?;
 		let tx = ocw::eth_get_transaction(tx_id, &rpc_url)?;
 		let tx_receipt = rpc::eth_get_transaction_receipt(tx_id, &rpc_url)?;
 		let eth_tip = rpc::eth_get_block_number(&rpc_url)?;
@@ -178,10 +178,10 @@ mod tests {
 	use crate::mock::RuntimeCall;
 	use crate::mock::RuntimeOrigin as Origin;
 	use crate::mock::{
-		get_mock_amount, get_mock_contract, get_mock_nonce, get_mock_tx_block_num,
-		get_mock_tx_hash, roll_to_with_ocw, set_rpc_uri, with_failing_create_transaction,
-		Creditcoin, ExtBuilder, MockedRpcRequests, PendingRequestExt, TaskScheduler,
-		TaskSchedulerT, Test, ETHLESS_RESPONSES,
+		get_mock_amount, get_mock_contract, get_mock_currency, get_mock_nonce,
+		get_mock_tx_block_num, get_mock_tx_hash, roll_to_with_ocw, set_rpc_uri,
+		with_failing_create_transaction, Creditcoin, ExtBuilder, MockedRpcRequests,
+		PendingRequestExt, TaskScheduler, TaskSchedulerT, Test, ETHLESS_RESPONSES,
 	};
 	use crate::ocw::tasks::Task;
 	use crate::ocw::tasks::TaskV2;
@@ -212,7 +212,11 @@ mod tests {
 			set_rpc_uri(&Blockchain::Rinkeby, dummy_url);
 
 			let loan_amount = get_mock_amount();
-			let terms = LoanTerms { amount: loan_amount, ..Default::default() };
+			let terms = LoanTerms {
+				amount: loan_amount,
+				currency: get_mock_currency(),
+				..Default::default()
+			};
 
 			let test_info = TestInfo { blockchain, loan_terms: terms, ..Default::default() };
 
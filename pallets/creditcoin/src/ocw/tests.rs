@@ -15,11 +15,11 @@ use crate::Pallet as Creditcoin;
 use crate::{
 	helpers::extensions::HexToAddress,
 	mock::{
-		get_mock_amount, get_mock_contract, get_mock_from_address, get_mock_input_data,
-		get_mock_nonce, get_mock_timestamp, get_mock_to_address, get_mock_tx_block_num,
-		get_mock_tx_hash, roll_to, roll_to_with_ocw, set_rpc_uri, ExtBuilder, Extrinsic,
-		MockedRpcRequests, PendingRequestExt, RuntimeCall as Call, RuntimeOrigin as Origin, RwLock,
-		TaskScheduler, Test, ETHLESS_RESPONSES,
+		get_mock_amount, get_mock_contract, get_mock_currency, get_mock_from_address,
+		get_mock_input_data, get_mock_nonce, get_mock_timestamp, get_mock_to_address,
+		get_mock_tx_block_num, get_mock_tx_hash, roll_to, roll_to_with_ocw, set_rpc_uri,
+		ExtBuilder, Extrinsic, MockedRpcRequests, PendingRequestExt, RuntimeCall as Call,
+		RuntimeOrigin as Origin, RwLock, TaskScheduler, Test, ETHLESS_RESPONSES,
 	},
 	ocw::rpc::{errors::RpcError, JsonRpcResponse},
 	tests::TestInfo,
@@ -340,36 +340,73 @@ fn blockchain_rpc_url_invalid_scale() {
 
 #[test]
 fn blockchain_supports_etherlike() {
-	assert!(Blockchain::Ethereum.supports(&crate::TransferKind::Native));
-	assert!(Blockchain::Rinkeby.supports(&crate::TransferKind::Native));
-	assert!(Blockchain::Luniverse.supports(&crate::TransferKind::Native));
-	assert!(Blockchain::Ethereum.supports(&crate::TransferKind::Erc20(default())));
-	assert!(Blockchain::Rinkeby.supports(&crate::TransferKind::Erc20(default())));
-	assert!(Blockchain::Luniverse.supports(&crate::TransferKind::Erc20(default())));
-	assert!(Blockchain::Ethereum.supports(&crate::TransferKind::Ethless(default())));
-	assert!(Blockchain::Rinkeby.supports(&crate::TransferKind::Ethless(default())));
-	assert!(Blockchain::Luniverse.supports(&crate::TransferKind::Ethless(default())));
+	ExtBuilder::default().build_and_execute(|| {
+		let supports = Creditcoin::<Test>::supports_transfer_kind;
+		assert!(supports(&Blockchain::Ethereum, &crate::TransferKind::Native));
+		assert!(supports(&Blockchain::Rinkeby, &crate::TransferKind::Native));
+		assert!(supports(&Blockchain::Luniverse, &crate::TransferKind::Native));
+		assert!(supports(&Blockchain::Ethereum, &crate::TransferKind::Erc20(default())));
+		assert!(supports(&Blockchain::Rinkeby, &crate::TransferKind::Erc20(default())));
+		assert!(supports(&Blockchain::Luniverse, &crate::TransferKind::Erc20(default())));
+		assert!(supports(&Blockchain::Ethereum, &crate::TransferKind::Ethless(default())));
+		assert!(supports(&Blockchain::Rinkeby, &crate::TransferKind::Ethless(default())));
+		assert!(supports(&Blockchain::Luniverse, &crate::TransferKind::Ethless(default())));
+	});
 }
 
 #[test]
 fn blockchain_unsupported() {
-	assert!(!Blockchain::Other(default()).supports(&crate::TransferKind::Native));
-	assert!(!Blockchain::Other(default()).supports(&crate::TransferKind::Erc20(default())));
-	assert!(!Blockchain::Other(default()).supports(&crate::TransferKind::Ethless(default())));
-	assert!(!Blockchain::Other(default()).supports(&crate::TransferKind::Other(default())));
+	ExtBuilder::default().build_and_execute(|| {
+		let supports = Creditcoin::<Test>::supports_transfer_kind;
+		let other = Blockchain::Other(default());
+		assert!(!supports(&other, &crate::TransferKind::Native));
+		assert!(!supports(&other, &crate::TransferKind::Erc20(default())));
+		assert!(!supports(&other, &crate::TransferKind::Ethless(default())));
+		assert!(!supports(&other, &crate::TransferKind::Other(default())));
+
+		assert!(!supports(&Blockchain::Ethereum, &crate::TransferKind::Other(default())));
+		assert!(!supports(&Blockchain::Rinkeby, &crate::TransferKind::Other(default())));
+		assert!(!supports(&Blockchain::Luniverse, &crate::TransferKind::Other(default())));
+		assert!(!supports(&Blockchain::Bitcoin, &crate::TransferKind::Other(default())));
+
+		assert!(!supports(&Blockchain::Bitcoin, &crate::TransferKind::Erc20(default())));
+		assert!(!supports(&Blockchain::Bitcoin, &crate::TransferKind::Ethless(default())));
+	});
+}
 
-	assert!(!Blockchain::Ethereum.supports(&crate::TransferKind::Other(default())));
-	assert!(!Blockchain::Rinkeby.supports(&crate::TransferKind::Other(default())));
-	assert!(!Blockchain::Luniverse.supports(&crate::TransferKind::Other(default())));
-	assert!(!Blockchain::Bitcoin.supports(&crate::TransferKind::Other(default())));
+#[test]
+fn blockchain_supports_what_the_registry_allows() {
+	ExtBuilder::default().build_and_execute(|| {
+		let supports = Creditcoin::<Test>::supports_transfer_kind;
+		let other = Blockchain::Other(BoundedVec::try_from(b"other".to_vec()).unwrap());
+		crate::ExternalChains::<Test>::insert(
+			&other,
+			crate::ExternalChain {
+				address_format: crate::AddressFormat::Evm,
+				evm_chain_id: None,
+				transfer_kinds: crate::SupportedTransferKinds {
+					erc20: true,
+					ethless: false,
+					native: false,
+				},
+				deprecated: false,
+			},
+		);
 
-	assert!(!Blockchain::Bitcoin.supports(&crate::TransferKind::Erc20(default())));
-	assert!(!Blockchain::Bitcoin.supports(&crate::TransferKind::Ethless(default())));
+		assert!(supports(&other, &crate::TransferKind::Erc20(default())));
+		assert!(!supports(&other, &crate::TransferKind::Ethless(default())));
+		assert!(!supports(&other, &crate::TransferKind::Native));
+	});
 }
 
 #[test]
 fn blockchain_supports_bitcoin_native_transfer() {
-	assert!(Blockchain::Bitcoin.supports(&crate::TransferKind::Native));
+	ExtBuilder::default().build_and_execute(|| {
+		assert!(Creditcoin::<Test>::supports_transfer_kind(
+			&Blockchain::Bitcoin,
+			&crate::TransferKind::Native
+		));
+	});
 }
 
 type MockTransfer = crate::Transfer<
@@ -467,7 +504,11 @@ pub(crate) fn set_up_verify_transfer_env(
 	set_rpc_uri(&Blockchain::Rinkeby, rpc_uri);
 
 	let test_info = TestInfo {
-		loan_terms: LoanTerms { amount: get_mock_amount(), ..Default::default() },
+		loan_terms: LoanTerms {
+			amount: get_mock_amount(),
+			currency: get_mock_currency(),
+			..Default::default()
+		},
 		..TestInfo::new_defaults()
 	};
 	let (deal_order_id, deal_order) = test_info.create_deal_order();
@@ -676,7 +717,8 @@ fn unconfirmed_verify_transfer_retries() {
 		set_rpc_uri(&Blockchain::Rinkeby, dummy_url);
 
 		let loan_amount = get_mock_amount();
-		let terms = LoanTerms { amount: loan_amount, ..Default::default() };
+		let terms =
+			LoanTerms { amount: loan_amount, currency: get_mock_currency(), ..Default::default() };
 
 		let test_info =
 			TestInfo { blockchain: blockchain.clone(), loan_terms: terms, ..Default::default() };
@@ -740,7 +782,8 @@ fn luniverse_succeeds_with_fake_nonce() {
 		set_rpc_uri(&Blockchain::Rinkeby, dummy_url);
 
 		let loan_amount = get_mock_amount();
-		let terms = LoanTerms { amount: loan_amount, ..Default::default() };
+		let terms =
+			LoanTerms { amount: loan_amount, currency: get_mock_currency(), ..Default::default() };
 
 		let test_info =
 			TestInfo { blockchain: blockchain.clone(), loan_terms: terms, ..Default::default() };
@@ -5,14 +5,21 @@ use crate::{
 	},
 	mock::{RuntimeOrigin as Origin, *},
 	types::{DoubleMapExt, OwnershipProof},
-	AddressId, AskOrder, AskOrderId, BidOrder, BidOrderId, Blockchain, DealOrder, DealOrderId,
-	DealOrders, Duration, ExternalAddress, ExternalAmount, Guid, Id, LegacySighash, LoanTerms,
-	Offer, OfferId, OrderId, Transfer, TransferId, TransferKind, Transfers, WeightInfo,
+	AddressId, AskOrder, AskOrderId, AskTerms, BidOrder, BidOrderId, BidTerms, Blockchain,
+	CreditRecord, DealOrder, DealOrderId, DealOrderStatus, DealOrders, Duration, Exemption,
+	ExemptionReason, ExternalAddress, ExternalAmount, FeePayer, Guid, Id, InterestRate,
+	InterestType, LegacySighash, LenderShare, LoanBalance, LoanCurrency, LoanTerms, Offer, OfferId,
+	OrderId, OriginationFee, OriginationFeeRate, RepaymentOrderId, RepaymentOrders, Transfer,
+	TransferId, TransferKind, Transfers, WeightInfo,
 };
 use assert_matches::assert_matches;
 use bstr::B;
 use ethereum_types::{BigEndianHash, H256, U256};
-use frame_support::{assert_noop, assert_ok, BoundedVec};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{Currency, Hooks, ReservableCurrency},
+	BoundedVec,
+};
 use frame_system::RawOrigin;
 use pallet_offchain_task_scheduler::authority::AuthorityController;
 use parity_scale_codec::Encode;
@@ -20,7 +27,7 @@ use sp_core::Pair;
 use sp_runtime::{
 	offchain::storage::StorageValueRef,
 	traits::{BadOrigin, IdentifyAccount},
-	AccountId32, MultiSigner,
+	AccountId32, DispatchResult, MultiSigner,
 };
 use std::convert::{TryFrom, TryInto};
 
@@ -114,6 +121,19 @@ pub(crate) fn build_proof_of_ownership(
 	external_keypair.sign(message.as_slice())
 }
 
+/// Generates the proof that moves an address from `owner` to `new_owner`
+pub(crate) fn build_transfer_address_proof(
+	address_id: &AddressId<Hash>,
+	owner: &AccountId,
+	new_owner: &AccountId,
+	nonce: u64,
+	external_keypair: sp_core::ecdsa::Pair,
+) -> sp_core::ecdsa::Signature {
+	let message =
+		crate::helpers::transfer_address_message::<Test>(address_id, owner, new_owner, nonce);
+	external_keypair.sign(sp_io::hashing::sha2_256(&message).as_slice())
+}
+
 /// Generates an account, an external address, and proof of account ownership
 /// using the **same keypair** for the external address, and cc account.
 pub(crate) fn generate_address_with_proof(
@@ -173,7 +193,7 @@ impl TestInfo {
 		assert_ok!(Creditcoin::add_ask_order(
 			Origin::signed(account_id.clone()),
 			address_id.clone(),
-			loan_terms.clone(),
+			loan_terms.clone().try_into().unwrap(),
 			*expiration_block,
 			ask_guid.clone()
 		));
@@ -193,7 +213,7 @@ impl TestInfo {
 		assert_ok!(Creditcoin::add_bid_order(
 			Origin::signed(account_id.clone()),
 			address_id.clone(),
-			loan_terms.clone(),
+			loan_terms.clone().try_into().unwrap(),
 			*expiration_block,
 			bid_guid.clone()
 		));
@@ -316,6 +336,7 @@ impl TestInfo {
 		let (id, transfer) =
 			self.make_transfer(from, to, amount, deal_order_id, blockchain_tx_id, None);
 		Transfers::<Test>::insert(&id, &transfer);
+		crate::DealOrderTransfers::<Test>::insert(deal_order_id, &id, ());
 		(id, transfer)
 	}
 
@@ -568,7 +589,7 @@ fn add_ask_order_expired() {
 			Creditcoin::add_ask_order(
 				Origin::signed(account_id),
 				address_id,
-				loan_terms,
+				loan_terms.try_into().unwrap(),
 				expiration_block,
 				ask_guid
 			),
@@ -592,7 +613,7 @@ fn add_ask_order_used_guid() {
 			Creditcoin::add_ask_order(
 				Origin::signed(account_id),
 				address_id,
-				loan_terms,
+				loan_terms.try_into().unwrap(),
 				expiration_block,
 				ask_guid
 			),
@@ -620,7 +641,7 @@ fn add_ask_order_pre_existing() {
 			Creditcoin::add_ask_order(
 				Origin::signed(account_id),
 				address_id,
-				loan_terms,
+				loan_terms.try_into().unwrap(),
 				expiration_block,
 				ask_guid
 			),
@@ -636,8 +657,10 @@ fn add_add_ask_order_rejects_zero_term_length_ms() {
 		let test_info = TestInfo {
 			loan_terms: LoanTerms {
 				amount: 0u64.into(),
+				currency: LoanCurrency::Native,
 				interest_rate: Default::default(),
 				term_length: Duration::from_millis(0),
+				collateral: 0,
 			},
 			..TestInfo::new_defaults()
 		};
@@ -685,7 +708,7 @@ fn add_bid_order_expired() {
 			Creditcoin::add_bid_order(
 				Origin::signed(account_id),
 				address_id,
-				loan_terms,
+				loan_terms.try_into().unwrap(),
 				expiration_block,
 				bid_guid
 			),
@@ -709,7 +732,7 @@ fn add_bid_order_used_guid() {
 			Creditcoin::add_ask_order(
 				Origin::signed(account_id),
 				address_id,
-				loan_terms,
+				loan_terms.try_into().unwrap(),
 				expiration_block,
 				bid_guid
 			),
@@ -737,7 +760,7 @@ fn add_bid_order_pre_existing() {
 			Creditcoin::add_bid_order(
 				Origin::signed(account_id),
 				address_id,
-				loan_terms,
+				loan_terms.try_into().unwrap(),
 				expiration_block,
 				bid_guid
 			),
@@ -758,6 +781,204 @@ fn add_bid_ask_order_rejects_zero_term_length_ms() {
 	});
 }
 
+#[test]
+fn add_ask_order_should_error_when_amount_range_is_empty() {
+	ExtBuilder::default().build_and_execute(|| {
+		let TestInfo { lender, loan_terms, expiration_block, ask_guid, .. } =
+			TestInfo::new_defaults();
+		let RegisteredAddress { address_id, account_id } = lender;
+		let terms = AskTerms {
+			min_amount: loan_terms.amount + 1u64,
+			..AskTerms::try_from(loan_terms).unwrap()
+		};
+
+		assert_noop!(
+			Creditcoin::add_ask_order(
+				Origin::signed(account_id),
+				address_id,
+				terms,
+				expiration_block,
+				ask_guid
+			),
+			crate::Error::<Test>::InvalidTermRange
+		);
+	});
+}
+
+#[test]
+fn add_bid_order_should_error_when_term_range_is_empty() {
+	ExtBuilder::default().build_and_execute(|| {
+		let TestInfo { borrower, loan_terms, expiration_block, bid_guid, .. } =
+			TestInfo::new_defaults();
+		let RegisteredAddress { address_id, account_id } = borrower;
+		let terms = BidTerms {
+			min_term_length: Duration::from_millis(2_000),
+			max_term_length: Duration::from_millis(1_000),
+			..BidTerms::try_from(loan_terms).unwrap()
+		};
+
+		assert_noop!(
+			Creditcoin::add_bid_order(
+				Origin::signed(account_id),
+				address_id,
+				terms,
+				expiration_block,
+				bid_guid
+			),
+			crate::Error::<Test>::InvalidTermRange
+		);
+	});
+}
+
+#[test]
+fn add_ask_order_should_error_when_currency_is_legacy() {
+	ExtBuilder::default().build_and_execute(|| {
+		let TestInfo { lender, loan_terms, expiration_block, ask_guid, .. } =
+			TestInfo::new_defaults();
+		let RegisteredAddress { address_id, account_id } = lender;
+		let terms =
+			AskTerms { currency: LoanCurrency::Legacy, ..AskTerms::try_from(loan_terms).unwrap() };
+
+		assert_noop!(
+			Creditcoin::add_ask_order(
+				Origin::signed(account_id),
+				address_id,
+				terms,
+				expiration_block,
+				ask_guid
+			),
+			crate::Error::<Test>::LegacyCurrency
+		);
+	});
+}
+
+#[test]
+fn add_offer_should_accept_overlapping_ranges_and_deal_order_uses_agreed_terms() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let TestInfo { lender, borrower, loan_terms, expiration_block, ask_guid, bid_guid, .. } =
+			test_info.clone();
+
+		let ask_terms = AskTerms {
+			min_amount: loan_terms.amount / 2u64,
+			max_amount: loan_terms.amount * 2u64,
+			max_term_length: Duration::from_millis(1_000_000),
+			..AskTerms::try_from(loan_terms.clone()).unwrap()
+		};
+		let bid_terms = BidTerms {
+			max_interest_rate: InterestRate {
+				rate_per_period: 5,
+				..loan_terms.interest_rate.clone()
+			},
+			min_term_length: Duration::from_millis(1_000),
+			max_term_length: Duration::from_millis(2_000_000),
+			..BidTerms::try_from(loan_terms.clone()).unwrap()
+		};
+
+		assert_ok!(Creditcoin::add_ask_order(
+			Origin::signed(lender.account_id.clone()),
+			lender.address_id,
+			ask_terms,
+			expiration_block,
+			ask_guid.clone()
+		));
+		assert_ok!(Creditcoin::add_bid_order(
+			Origin::signed(borrower.account_id.clone()),
+			borrower.address_id,
+			bid_terms,
+			expiration_block,
+			bid_guid.clone()
+		));
+
+		let ask_order_id = AskOrderId::new::<Test>(expiration_block, &ask_guid);
+		let bid_order_id = BidOrderId::new::<Test>(expiration_block, &bid_guid);
+		assert_ok!(Creditcoin::add_offer(
+			Origin::signed(lender.account_id),
+			ask_order_id.clone(),
+			bid_order_id.clone(),
+			expiration_block,
+		));
+
+		let offer_id = OfferId::new::<Test>(expiration_block, &ask_order_id, &bid_order_id);
+		assert_ok!(Creditcoin::add_deal_order(
+			Origin::signed(borrower.account_id),
+			offer_id.clone(),
+			expiration_block,
+		));
+
+		let deal_order_id = DealOrderId::new::<Test>(expiration_block, &offer_id);
+		let deal_order = DealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
+		assert_eq!(
+			deal_order.terms,
+			LoanTerms { term_length: Duration::from_millis(1_000_000), ..loan_terms }
+		);
+	});
+}
+
+#[test]
+fn add_offer_should_error_when_ranges_do_not_overlap() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let TestInfo { borrower, loan_terms, expiration_block, bid_guid, .. } = test_info.clone();
+		let (ask_order_id, _) = test_info.create_ask_order();
+
+		let terms = BidTerms {
+			amount: loan_terms.amount + 1u64,
+			..BidTerms::try_from(loan_terms).unwrap()
+		};
+		assert_ok!(Creditcoin::add_bid_order(
+			Origin::signed(borrower.account_id),
+			borrower.address_id,
+			terms,
+			expiration_block,
+			bid_guid.clone()
+		));
+		let bid_order_id = BidOrderId::new::<Test>(expiration_block, &bid_guid);
+
+		assert_noop!(
+			Creditcoin::add_offer(
+				Origin::signed(test_info.lender.account_id),
+				ask_order_id,
+				bid_order_id,
+				expiration_block,
+			),
+			crate::Error::<Test>::AskBidMismatch
+		);
+	});
+}
+
+#[test]
+fn add_offer_should_error_when_currencies_differ() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let TestInfo { borrower, loan_terms, expiration_block, bid_guid, .. } = test_info.clone();
+		let (ask_order_id, _) = test_info.create_ask_order();
+
+		let terms = BidTerms {
+			currency: LoanCurrency::Token { contract: "usdc".as_bytes().into_bounded() },
+			..BidTerms::try_from(loan_terms).unwrap()
+		};
+		assert_ok!(Creditcoin::add_bid_order(
+			Origin::signed(borrower.account_id),
+			borrower.address_id,
+			terms,
+			expiration_block,
+			bid_guid.clone()
+		));
+		let bid_order_id = BidOrderId::new::<Test>(expiration_block, &bid_guid);
+
+		assert_noop!(
+			Creditcoin::add_offer(
+				Origin::signed(test_info.lender.account_id),
+				ask_order_id,
+				bid_order_id,
+				expiration_block,
+			),
+			crate::Error::<Test>::AskBidMismatch
+		);
+	});
+}
+
 #[test]
 fn add_offer_basic() {
 	ExtBuilder::default().build_and_execute(|| {
@@ -839,7 +1060,10 @@ fn add_deal_order_basic() {
 			funding_transfer_id: None,
 			lock: None,
 			repayment_transfer_id: None,
+			repaid_amount: ExternalAmount::zero(),
+			status: DealOrderStatus::Pending,
 			block: Some(Creditcoin::block_number()),
+			exemption: None,
 		};
 
 		assert_eq!(new_deal_order, deal_order);
@@ -861,6 +1085,187 @@ fn add_deal_order_existing() {
 	});
 }
 
+#[test]
+fn cancel_ask_order_should_remove_ask_order_and_emit_event() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+		let test_info = TestInfo::new_defaults();
+
+		let (ask_order_id, _) = test_info.create_ask_order();
+
+		assert_ok!(Creditcoin::cancel_ask_order(
+			Origin::signed(test_info.lender.account_id.clone()),
+			ask_order_id.clone()
+		));
+
+		assert!(!crate::AskOrders::<Test>::contains_id(&ask_order_id));
+		let event = <frame_system::Pallet<Test>>::events().pop().expect("expected an event").event;
+		assert_eq!(
+			event,
+			crate::mock::RuntimeEvent::Creditcoin(crate::Event::AskOrderCancelled(ask_order_id))
+		);
+	});
+}
+
+#[test]
+fn cancel_ask_order_should_error_when_not_lender() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+
+		let (ask_order_id, _) = test_info.create_ask_order();
+
+		assert_noop!(
+			Creditcoin::cancel_ask_order(
+				Origin::signed(test_info.borrower.account_id.clone()),
+				ask_order_id
+			),
+			crate::Error::<Test>::NotLender
+		);
+	});
+}
+
+#[test]
+fn cancel_ask_order_should_error_for_non_existent_ask_order() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+
+		let ask_order_id = AskOrderId::new::<Test>(test_info.expiration_block, &test_info.ask_guid);
+
+		assert_noop!(
+			Creditcoin::cancel_ask_order(Origin::signed(test_info.lender.account_id), ask_order_id),
+			crate::Error::<Test>::NonExistentAskOrder
+		);
+	});
+}
+
+#[test]
+fn cancel_bid_order_should_remove_bid_order_and_emit_event() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+		let test_info = TestInfo::new_defaults();
+
+		let (bid_order_id, _) = test_info.create_bid_order();
+
+		assert_ok!(Creditcoin::cancel_bid_order(
+			Origin::signed(test_info.borrower.account_id.clone()),
+			bid_order_id.clone()
+		));
+
+		assert!(!crate::BidOrders::<Test>::contains_id(&bid_order_id));
+		let event = <frame_system::Pallet<Test>>::events().pop().expect("expected an event").event;
+		assert_eq!(
+			event,
+			crate::mock::RuntimeEvent::Creditcoin(crate::Event::BidOrderCancelled(bid_order_id))
+		);
+	});
+}
+
+#[test]
+fn cancel_bid_order_should_error_when_not_borrower() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+
+		let (bid_order_id, _) = test_info.create_bid_order();
+
+		assert_noop!(
+			Creditcoin::cancel_bid_order(
+				Origin::signed(test_info.lender.account_id.clone()),
+				bid_order_id
+			),
+			crate::Error::<Test>::NotBorrower
+		);
+	});
+}
+
+#[test]
+fn cancel_offer_should_remove_offer_and_emit_event() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+		let test_info = TestInfo::new_defaults();
+
+		let (offer_id, _) = test_info.create_offer();
+
+		assert_ok!(Creditcoin::cancel_offer(
+			Origin::signed(test_info.lender.account_id.clone()),
+			offer_id.clone()
+		));
+
+		assert!(!crate::Offers::<Test>::contains_id(&offer_id));
+		let event = <frame_system::Pallet<Test>>::events().pop().expect("expected an event").event;
+		assert_eq!(
+			event,
+			crate::mock::RuntimeEvent::Creditcoin(crate::Event::OfferCancelled(offer_id.clone()))
+		);
+
+		assert_noop!(
+			Creditcoin::add_deal_order(
+				Origin::signed(test_info.borrower.account_id),
+				offer_id,
+				1_000
+			),
+			crate::Error::<Test>::NonExistentOffer
+		);
+	});
+}
+
+#[test]
+fn cancel_offer_should_error_when_not_lender() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+
+		let (offer_id, _) = test_info.create_offer();
+
+		assert_noop!(
+			Creditcoin::cancel_offer(Origin::signed(test_info.borrower.account_id), offer_id),
+			crate::Error::<Test>::NotLender
+		);
+	});
+}
+
+#[test]
+fn add_deal_order_should_error_when_ask_order_was_cancelled() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+
+		let (offer_id, offer) = test_info.create_offer();
+		assert_ok!(Creditcoin::cancel_ask_order(
+			Origin::signed(test_info.lender.account_id.clone()),
+			offer.ask_id
+		));
+
+		assert_noop!(
+			Creditcoin::add_deal_order(
+				Origin::signed(test_info.borrower.account_id),
+				offer_id,
+				1_000
+			),
+			crate::Error::<Test>::NonExistentAskOrder
+		);
+	});
+}
+
+#[test]
+fn add_deal_order_should_error_when_bid_order_was_cancelled() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+
+		let (offer_id, offer) = test_info.create_offer();
+		assert_ok!(Creditcoin::cancel_bid_order(
+			Origin::signed(test_info.borrower.account_id.clone()),
+			offer.bid_id
+		));
+
+		assert_noop!(
+			Creditcoin::add_deal_order(
+				Origin::signed(test_info.borrower.account_id),
+				offer_id,
+				1_000
+			),
+			crate::Error::<Test>::NonExistentBidOrder
+		);
+	});
+}
+
 #[test]
 fn lock_deal_order_should_emit_deal_order_locked_event() {
 	ExtBuilder::default().build_and_execute(|| {
@@ -1179,8 +1584,10 @@ fn fund_deal_order_should_error_when_transfer_order_id_doesnt_match_deal_order_i
 			blockchain: Blockchain::Rinkeby,
 			loan_terms: LoanTerms {
 				amount: 2_000_000u64.into(),
+				currency: LoanCurrency::Native,
 				interest_rate: Default::default(),
 				term_length: Duration::from_millis(1_000_000),
+				collateral: 0,
 			},
 			ask_guid: "second-ask-guid".as_bytes().into_bounded(),
 			bid_guid: "second-bid-guid".as_bytes().into_bounded(),
@@ -1191,11 +1598,10 @@ fn fund_deal_order_should_error_when_transfer_order_id_doesnt_match_deal_order_i
 
 		//  insert as exemption to bypass transfer verification
 		let tx_hash = "0".as_bytes().into_bounded();
-		let contract = "0x0ad1439a0e0bfdcd49939f9722866651a4aa9b3c".as_bytes().into_bounded();
 
 		assert_ok!(Creditcoin::register_funding_transfer(
 			Origin::signed(second_test_info.lender.account_id.clone()),
-			TransferKind::Ethless(contract),
+			TransferKind::Native,
 			bogus_deal_order_id.clone(),
 			tx_hash
 		));
@@ -1222,11 +1628,10 @@ fn fund_deal_order_should_error_when_transfer_amount_doesnt_match() {
 
 		//  insert as exemption to bypass transfer verification
 		let tx_hash = "0".as_bytes().into_bounded();
-		let contract = "0x0ad1439a0e0bfdcd49939f9722866651a4aa9b3c".as_bytes().into_bounded();
 
 		assert_ok!(Creditcoin::register_funding_transfer(
 			Origin::signed(test_info.lender.account_id.clone()),
-			TransferKind::Ethless(contract),
+			TransferKind::Native,
 			deal_order_id.clone(),
 			tx_hash
 		));
@@ -1262,11 +1667,10 @@ fn fund_deal_order_should_error_when_transfer_sighash_doesnt_match_lender() {
 
 		//  insert as exemption to bypass transfer verification
 		let tx_hash = "0".as_bytes().into_bounded();
-		let contract = "0x0ad1439a0e0bfdcd49939f9722866651a4aa9b3c".as_bytes().into_bounded();
 
 		assert_ok!(Creditcoin::register_funding_transfer(
 			Origin::signed(test_info.lender.account_id.clone()),
-			TransferKind::Ethless(contract),
+			TransferKind::Native,
 			deal_order_id.clone(),
 			tx_hash
 		));
@@ -1329,11 +1733,10 @@ fn fund_deal_order_works() {
 
 		//  insert as exemption to bypass transfer verification
 		let tx_hash = "0".as_bytes().into_bounded();
-		let contract = "0x0ad1439a0e0bfdcd49939f9722866651a4aa9b3c".as_bytes().into_bounded();
 
 		assert_ok!(Creditcoin::register_funding_transfer(
 			Origin::signed(test_info.lender.account_id.clone()),
-			TransferKind::Ethless(contract),
+			TransferKind::Native,
 			deal_order_id.clone(),
 			tx_hash
 		));
@@ -1731,6 +2134,9 @@ fn register_deal_order_should_error_when_deal_order_id_exists() {
 			funding_transfer_id: None,
 			lock: None,
 			repayment_transfer_id: None,
+			repaid_amount: ExternalAmount::zero(),
+			status: DealOrderStatus::Pending,
+			exemption: None,
 		};
 
 		// insert this DealOrder into storage which will use-up the ID
@@ -1897,1211 +2303,4719 @@ fn register_deal_order_accepts_ed25519() {
 }
 
 #[test]
-fn close_deal_order_should_error_when_not_signed() {
+fn close_deal_order_should_error_when_transfer_does_not_exist() {
 	ExtBuilder::default().build_and_execute(|| {
 		let test_info = TestInfo::new_defaults();
-		let (deal_order_id, deal_order) = test_info.create_deal_order();
-		let transfer_id = TransferId::new::<Test>(&deal_order.blockchain, b"12345678");
+		let (deal_order_id, _) = test_info.create_deal_order();
+		let transfer_id = TransferId::new::<Test>(&Blockchain::Rinkeby, b"0xafafaf");
 
 		assert_noop!(
-			Creditcoin::close_deal_order(Origin::none(), deal_order_id, transfer_id,),
-			BadOrigin
+			Creditcoin::close_deal_order(
+				Origin::signed(test_info.borrower.account_id.clone()),
+				deal_order_id,
+				transfer_id,
+			),
+			crate::Error::<Test>::NonExistentTransfer
 		);
 	});
 }
 
 #[test]
-fn close_deal_order_should_error_when_borrower_address_is_not_registered() {
+fn close_deal_order_should_error_when_transfer_order_id_doesnt_match_deal_order_id() {
 	ExtBuilder::default().build_and_execute(|| {
 		let test_info = TestInfo::new_defaults();
 		let (deal_order_id, deal_order) = test_info.create_deal_order();
-		let transfer_id = TransferId::new::<Test>(&deal_order.blockchain, b"12345678");
-
-		// simulate deal with an address that isn't registered
-		crate::DealOrders::<Test>::mutate(
-			deal_order_id.expiration(),
-			deal_order_id.hash(),
-			|deal_order_storage| {
-				let blockchain = Blockchain::Rinkeby;
-
-				deal_order_storage.as_mut().unwrap().borrower_address_id =
-					AddressId::new::<Test>(&blockchain, &[1, 2, 3, 4, 5, 6, 7, 8, 9, 0]);
-			},
+		let (transfer_id, _) = test_info.mock_transfer(
+			&test_info.borrower,
+			&test_info.lender,
+			deal_order.terms.amount,
+			&deal_order_id,
+			"0xafafaf",
 		);
+		let other_deal_order_id = DealOrderId::with_expiration_hash::<Test>(100, H256::zero());
 
 		assert_noop!(
 			Creditcoin::close_deal_order(
-				Origin::signed(test_info.borrower.account_id),
-				deal_order_id,
+				Origin::signed(test_info.borrower.account_id.clone()),
+				other_deal_order_id,
 				transfer_id,
 			),
-			crate::Error::<Test>::NonExistentAddress
+			crate::Error::<Test>::TransferDealOrderMismatch
 		);
 	});
 }
 
 #[test]
-fn close_deal_order_should_error_when_not_signed_by_borrower() {
+fn close_deal_order_should_error_when_transfer_was_registered_by_someone_else() {
 	ExtBuilder::default().build_and_execute(|| {
 		let test_info = TestInfo::new_defaults();
 		let (deal_order_id, deal_order) = test_info.create_deal_order();
-		let transfer_id = TransferId::new::<Test>(&deal_order.blockchain, b"12345678");
+		let (transfer_id, _) = test_info.mock_transfer(
+			&test_info.borrower,
+			&test_info.lender,
+			deal_order.terms.amount,
+			&deal_order_id,
+			"0xafafaf",
+		);
 
 		assert_noop!(
 			Creditcoin::close_deal_order(
-				// bogus signature --------v
-				Origin::signed(test_info.lender.account_id),
+				Origin::signed(test_info.lender.account_id.clone()),
 				deal_order_id,
 				transfer_id,
 			),
-			crate::Error::<Test>::NotBorrower
+			crate::Error::<Test>::TransferAccountMismatch
 		);
 	});
 }
 
 #[test]
-fn close_deal_order_should_error_when_deal_timestamp_is_in_the_future() {
+fn close_repaid_deal_order_should_error_when_not_signed() {
 	ExtBuilder::default().build_and_execute(|| {
 		let test_info = TestInfo::new_defaults();
-		let (deal_order_id, deal_order) = test_info.create_deal_order();
-		let transfer_id = TransferId::new::<Test>(&deal_order.blockchain, b"12345678");
-
-		// simulate deal with a timestamp in the future
-		crate::DealOrders::<Test>::mutate(
-			deal_order_id.expiration(),
-			deal_order_id.hash(),
-			|deal_order_storage| {
-				deal_order_storage.as_mut().unwrap().timestamp = Creditcoin::timestamp() + 99999;
-			},
-		);
+		let (deal_order_id, _) = test_info.create_deal_order();
 
-		assert_noop!(
-			Creditcoin::close_deal_order(
-				Origin::signed(test_info.borrower.account_id),
-				deal_order_id,
-				transfer_id,
-			),
-			crate::Error::<Test>::MalformedDealOrder
-		);
+		assert_noop!(Creditcoin::close_repaid_deal_order(Origin::none(), deal_order_id), BadOrigin);
 	});
 }
 
 #[test]
-fn close_deal_order_should_error_when_deal_order_has_already_been_repaid() {
+fn close_repaid_deal_order_should_error_when_borrower_address_is_not_registered() {
 	ExtBuilder::default().build_and_execute(|| {
 		let test_info = TestInfo::new_defaults();
-		let (deal_order_id, deal_order) = test_info.create_deal_order();
-		let transfer_id = TransferId::new::<Test>(&deal_order.blockchain, b"12345678");
+		let (deal_order_id, _) = test_info.create_deal_order();
 
-		// simulate DealOrder which has been repaid
+		// simulate deal with an address that isn't registered
 		crate::DealOrders::<Test>::mutate(
 			deal_order_id.expiration(),
 			deal_order_id.hash(),
 			|deal_order_storage| {
-				deal_order_storage.as_mut().unwrap().repayment_transfer_id =
-					Some(TransferId::new::<Test>(&deal_order.blockchain, b"4444"));
+				let blockchain = Blockchain::Rinkeby;
+
+				deal_order_storage.as_mut().unwrap().borrower_address_id =
+					AddressId::new::<Test>(&blockchain, &[1, 2, 3, 4, 5, 6, 7, 8, 9, 0]);
 			},
 		);
 
 		assert_noop!(
-			Creditcoin::close_deal_order(
+			Creditcoin::close_repaid_deal_order(
 				Origin::signed(test_info.borrower.account_id),
 				deal_order_id,
-				transfer_id,
 			),
-			crate::Error::<Test>::DealOrderAlreadyClosed
+			crate::Error::<Test>::NonExistentAddress
 		);
 	});
 }
 
 #[test]
-fn close_deal_order_should_error_when_deal_isnt_locked() {
+fn close_repaid_deal_order_should_error_when_not_signed_by_borrower() {
 	ExtBuilder::default().build_and_execute(|| {
 		let test_info = TestInfo::new_defaults();
-		let (deal_order_id, deal_order) = test_info.create_deal_order();
-		let transfer_id = TransferId::new::<Test>(&deal_order.blockchain, b"12345678");
-
-		// simulate deal which is not locked
-		crate::DealOrders::<Test>::mutate(
-			deal_order_id.expiration(),
-			deal_order_id.hash(),
-			|deal_order_storage| {
-				deal_order_storage.as_mut().unwrap().lock = None;
-			},
-		);
+		let (deal_order_id, _) = test_info.create_deal_order();
 
 		assert_noop!(
-			Creditcoin::close_deal_order(
-				Origin::signed(test_info.borrower.account_id),
+			Creditcoin::close_repaid_deal_order(
+				// bogus signature --------v
+				Origin::signed(test_info.lender.account_id),
 				deal_order_id,
-				transfer_id,
 			),
-			crate::Error::<Test>::DealOrderMustBeLocked
+			crate::Error::<Test>::NotBorrower
 		);
 	});
 }
 
 #[test]
-fn close_deal_order_should_error_when_transfer_order_id_doesnt_match_deal_order_id() {
+fn close_repaid_deal_order_should_error_when_deal_timestamp_is_in_the_future() {
 	ExtBuilder::default().build_and_execute(|| {
 		let test_info = TestInfo::new_defaults();
 		let (deal_order_id, _) = test_info.create_deal_order();
 
-		// lock DealOrder
+		// simulate deal with a timestamp in the future
 		crate::DealOrders::<Test>::mutate(
 			deal_order_id.expiration(),
 			deal_order_id.hash(),
 			|deal_order_storage| {
-				deal_order_storage.as_mut().unwrap().lock =
-					Some(test_info.borrower.account_id.clone());
+				deal_order_storage.as_mut().unwrap().timestamp = Creditcoin::timestamp() + 99999;
 			},
 		);
-		// this is a deal_order from another person
-		let second_test_info = TestInfo {
-			lender: RegisteredAddress::new("lender2", Blockchain::Rinkeby),
-			borrower: RegisteredAddress::new("borrower2", Blockchain::Rinkeby),
-			blockchain: Blockchain::Rinkeby,
-			loan_terms: LoanTerms {
-				amount: 2_000_000u64.into(),
-				interest_rate: Default::default(),
-				term_length: Duration::from_millis(1_000_000),
-			},
-			ask_guid: "second-ask-guid".as_bytes().into_bounded(),
-			bid_guid: "second-bid-guid".as_bytes().into_bounded(),
-			expiration_block: 3_333,
-		};
-
-		let (bogus_deal_order_id, _) = second_test_info.create_deal_order();
 
-		let (transfer_id, _) =
-			second_test_info.create_repayment_transfer(&bogus_deal_order_id, 33u64);
-
-		// Person1 tries closing the deal by using the transfer made by Person2
 		assert_noop!(
-			Creditcoin::close_deal_order(
+			Creditcoin::close_repaid_deal_order(
 				Origin::signed(test_info.borrower.account_id),
 				deal_order_id,
-				transfer_id,
 			),
-			crate::Error::<Test>::TransferDealOrderMismatch
+			crate::Error::<Test>::MalformedDealOrder
 		);
 	});
 }
 
 #[test]
-fn close_deal_order_should_error_when_transfer_block_is_greater_than_current_block() {
+fn close_repaid_deal_order_should_error_when_deal_order_has_already_been_repaid() {
 	ExtBuilder::default().build_and_execute(|| {
 		let test_info = TestInfo::new_defaults();
-		let (deal_order_id, deal_order) = test_info.create_deal_order();
+		let (deal_order_id, _) = test_info.create_deal_order();
 
-		// lock DealOrder
+		// simulate DealOrder which has been repaid
 		crate::DealOrders::<Test>::mutate(
 			deal_order_id.expiration(),
 			deal_order_id.hash(),
 			|deal_order_storage| {
-				deal_order_storage.as_mut().unwrap().lock =
-					Some(test_info.borrower.account_id.clone());
+				deal_order_storage.as_mut().unwrap().status = DealOrderStatus::Closed;
 			},
 		);
 
-		let (transfer_id, _) =
-			test_info.create_repayment_transfer(&deal_order_id, deal_order.terms.amount);
-
-		// modify transfer in order to cause transfer mismatch
-		crate::Transfers::<Test>::mutate(&transfer_id, |transfer_storage| {
-			let mut ts = transfer_storage.as_mut().unwrap();
-			// b/c amount above is 0
-			ts.amount = deal_order.terms.amount;
-			ts.block = Creditcoin::block_number() + 1;
-		});
-
 		assert_noop!(
-			Creditcoin::close_deal_order(
+			Creditcoin::close_repaid_deal_order(
 				Origin::signed(test_info.borrower.account_id),
 				deal_order_id,
-				transfer_id,
 			),
-			crate::Error::<Test>::MalformedTransfer
+			crate::Error::<Test>::DealOrderAlreadyClosed
 		);
 	});
 }
 
 #[test]
-fn close_deal_order_should_error_when_transfer_sighash_doesnt_match_borrower() {
+fn close_repaid_deal_order_should_error_when_deal_isnt_locked() {
 	ExtBuilder::default().build_and_execute(|| {
 		let test_info = TestInfo::new_defaults();
-		let (deal_order_id, deal_order) = test_info.create_deal_order();
+		let (deal_order_id, _) = test_info.create_deal_order();
 
-		// lock DealOrder
+		// simulate deal which is not locked
 		crate::DealOrders::<Test>::mutate(
 			deal_order_id.expiration(),
 			deal_order_id.hash(),
 			|deal_order_storage| {
-				deal_order_storage.as_mut().unwrap().lock =
-					Some(test_info.borrower.account_id.clone());
+				deal_order_storage.as_mut().unwrap().lock = None;
 			},
 		);
 
-		let (transfer_id, _) =
-			test_info.create_repayment_transfer(&deal_order_id, deal_order.terms.amount);
-
-		// modify transfer in order to cause transfer mismatch
-		crate::Transfers::<Test>::mutate(&transfer_id, |transfer_storage| {
-			let mut ts = transfer_storage.as_mut().unwrap();
-			ts.account_id = AccountId::new([44; 32]);
-		});
-
 		assert_noop!(
-			Creditcoin::close_deal_order(
+			Creditcoin::close_repaid_deal_order(
 				Origin::signed(test_info.borrower.account_id),
 				deal_order_id,
-				transfer_id,
 			),
-			crate::Error::<Test>::TransferAccountMismatch
+			crate::Error::<Test>::DealOrderMustBeLocked
 		);
 	});
 }
 
 #[test]
-fn close_deal_order_should_error_when_transfer_has_already_been_processed() {
+fn close_repaid_deal_order_should_error_when_repayment_does_not_cover_interest() {
 	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
 		let test_info = TestInfo::new_defaults();
-		let (deal_order_id, deal_order) = test_info.create_deal_order();
+		let (deal_order_id, deal_order) = fund_deal_order_for_repayment(&test_info);
+		lock_for_repayment(&test_info, &deal_order_id);
 
-		// lock DealOrder
+		// charge 10% simple interest every second
 		crate::DealOrders::<Test>::mutate(
 			deal_order_id.expiration(),
 			deal_order_id.hash(),
 			|deal_order_storage| {
-				deal_order_storage.as_mut().unwrap().lock =
-					Some(test_info.borrower.account_id.clone());
+				deal_order_storage.as_mut().unwrap().terms.interest_rate = InterestRate {
+					rate_per_period: 10,
+					decimals: 2,
+					period: Duration::from_millis(1_000),
+					interest_type: InterestType::Simple,
+				};
 			},
 		);
 
-		let (transfer_id, _) =
-			test_info.create_repayment_transfer(&deal_order_id, deal_order.terms.amount);
+		// three periods have started, so 30% interest is owed
+		Timestamp::set_timestamp(deal_order.timestamp + 2_500);
 
-		// modify transfer in order to cause transfer mismatch
-		crate::Transfers::<Test>::mutate(&transfer_id, |transfer_storage| {
-			let mut ts = transfer_storage.as_mut().unwrap();
-			// b/c amount above is 0
-			ts.is_processed = true;
-		});
+		let repayment_amount = deal_order.terms.amount * 12u64 / 10u64;
+		persist_repayment_transfer(&test_info, &deal_order_id, repayment_amount, "0xafafaf");
 
 		assert_noop!(
-			Creditcoin::close_deal_order(
+			Creditcoin::close_repaid_deal_order(
 				Origin::signed(test_info.borrower.account_id),
-				deal_order_id,
-				transfer_id,
+				deal_order_id
 			),
-			crate::Error::<Test>::TransferAlreadyProcessed
+			crate::Error::<Test>::InsufficientRepayment
 		);
 	});
 }
 
 #[test]
-fn close_deal_order_should_succeed() {
+fn close_deal_order_should_close_deal_order_repaid_by_verified_transfer() {
 	ExtBuilder::default().build_and_execute(|| {
 		System::set_block_number(1);
 
 		let test_info = TestInfo::new_defaults();
-		let (deal_order_id, deal_order) = test_info.create_deal_order();
-
-		// lock DealOrder
-		crate::DealOrders::<Test>::mutate(
-			deal_order_id.expiration(),
-			deal_order_id.hash(),
-			|deal_order_storage| {
-				deal_order_storage.as_mut().unwrap().lock =
-					Some(test_info.borrower.account_id.clone());
-			},
-		);
-
-		//  insert as exemption to bypass transfer verification
-		let tx_hash = "0".as_bytes().into_bounded();
-		let contract = "0x0ad1439a0e0bfdcd49939f9722866651a4aa9b3c".as_bytes().into_bounded();
+		let (deal_order_id, deal_order) = fund_deal_order_for_repayment(&test_info);
+		let tx = "12345678";
 
-		assert_ok!(Creditcoin::register_transfer_internal(
-			test_info.borrower.account_id.clone(),
-			test_info.borrower.address_id.clone(),
-			test_info.lender.address_id.clone(),
-			TransferKind::Ethless(contract),
-			33u64.into(),
-			OrderId::Deal(deal_order_id.clone()),
-			tx_hash
+		assert_ok!(Creditcoin::register_repayment_transfer(
+			Origin::signed(test_info.borrower.account_id.clone()),
+			TransferKind::Native,
+			deal_order.terms.amount,
+			deal_order_id.clone(),
+			tx.as_bytes().into_bounded()
 		));
+		let transfer_id =
+			persist_repayment_transfer(&test_info, &deal_order_id, deal_order.terms.amount, tx);
 
-		let (transfer_id, _) =
-			test_info.create_repayment_transfer(&deal_order_id, deal_order.terms.amount + 1u64);
-
-		// modify transfer to make sure we have transfered enough funds
-		crate::Transfers::<Test>::mutate(&transfer_id, |transfer_storage| {
-			let mut ts = transfer_storage.as_mut().unwrap();
-
-			ts.amount = deal_order.terms.amount + 1u64;
-		});
+		// the deal order isn't locked, so the repayment is credited without closing it
+		let saved_deal_order = DealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
+		assert_eq!(saved_deal_order.repaid_amount, deal_order.terms.amount);
+		assert_eq!(saved_deal_order.status, DealOrderStatus::Funded);
+		assert!(Creditcoin::transfers(&transfer_id).unwrap().is_processed);
 
+		lock_for_repayment(&test_info, &deal_order_id);
 		assert_ok!(Creditcoin::close_deal_order(
-			Origin::signed(test_info.borrower.account_id),
+			Origin::signed(test_info.borrower.account_id.clone()),
 			deal_order_id.clone(),
 			transfer_id.clone(),
 		));
 
-		// assert field values were updated in storage
 		let saved_deal_order = DealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
-		assert_eq!(saved_deal_order.repayment_transfer_id, Some(transfer_id.clone()));
-
-		let saved_transfer = crate::Pallet::<Test>::transfers(&transfer_id).unwrap();
-		assert!(saved_transfer.is_processed);
-
-		// assert events in reversed order
-		let mut all_events = <frame_system::Pallet<Test>>::events();
-		let event2 = all_events.pop().expect("Second EventRecord").event;
-		assert_matches!(
-			event2,
-			crate::mock::RuntimeEvent::Creditcoin(crate::Event::TransferProcessed(id)) =>{
-				assert_eq!(id,transfer_id);
-			}
-		);
+		assert_eq!(saved_deal_order.status, DealOrderStatus::Closed);
+		assert_eq!(saved_deal_order.repaid_amount, deal_order.terms.amount);
+		assert_eq!(saved_deal_order.repayment_transfer_id, Some(transfer_id));
+		assert!(crate::ClosedDealOrders::<Test>::contains_key(1, &deal_order_id));
 
-		let event1 = all_events.pop().expect("First EventRecord").event;
-		assert_matches!(
-			event1,
-			crate::mock::RuntimeEvent::Creditcoin(crate::Event::DealOrderClosed(id)) =>{
-				assert_eq!(id,deal_order_id);
-			}
+		let event = System::events().pop().expect("expected event").event;
+		assert_eq!(
+			event,
+			crate::mock::RuntimeEvent::Creditcoin(crate::Event::DealOrderClosed(deal_order_id))
 		);
 	});
 }
 
 #[test]
-fn exempt_should_error_when_not_signed() {
+fn close_repaid_deal_order_should_close_deal_order_repaid_before_it_was_locked() {
 	ExtBuilder::default().build_and_execute(|| {
-		let test_info = TestInfo::new_defaults();
-		let (deal_order_id, _) = test_info.create_deal_order();
-
-		assert_noop!(Creditcoin::exempt(Origin::none(), deal_order_id), BadOrigin);
-	});
-}
+		System::set_block_number(1);
 
-#[test]
-fn exempt_should_error_when_deal_order_has_already_been_repaid() {
-	ExtBuilder::default().build_and_execute(|| {
 		let test_info = TestInfo::new_defaults();
-		let (deal_order_id, deal_order) = test_info.create_deal_order();
-
-		// simulate DealOrder which has been repaid
-		crate::DealOrders::<Test>::mutate(
-			deal_order_id.expiration(),
-			deal_order_id.hash(),
-			|deal_order_storage| {
-				deal_order_storage.as_mut().unwrap().repayment_transfer_id =
-					Some(TransferId::new::<Test>(&deal_order.blockchain, b"4444"));
-			},
+		let (deal_order_id, deal_order) = fund_deal_order_for_repayment(&test_info);
+		persist_repayment_transfer(&test_info, &deal_order_id, deal_order.terms.amount, "0xafafaf");
+		assert_eq!(
+			DealOrders::<Test>::try_get_id(&deal_order_id).unwrap().status,
+			DealOrderStatus::Funded
 		);
 
-		assert_noop!(
-			Creditcoin::exempt(Origin::signed(test_info.lender.account_id), deal_order_id),
-			crate::Error::<Test>::DealOrderAlreadyClosed
+		lock_for_repayment(&test_info, &deal_order_id);
+		assert_ok!(Creditcoin::close_repaid_deal_order(
+			Origin::signed(test_info.borrower.account_id.clone()),
+			deal_order_id.clone(),
+		));
+
+		let saved_deal_order = DealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
+		assert_eq!(saved_deal_order.status, DealOrderStatus::Closed);
+
+		let event = System::events().pop().expect("expected event").event;
+		assert_eq!(
+			event,
+			crate::mock::RuntimeEvent::Creditcoin(crate::Event::DealOrderClosed(deal_order_id))
 		);
 	});
 }
 
-#[test]
-fn exempt_should_error_for_non_lender() {
-	ExtBuilder::default().build_and_execute(|| {
-		let test_info = TestInfo::new_defaults();
-		let (deal_order_id, _) = test_info.create_deal_order();
+pub(crate) fn fund_deal_order_for_repayment(test_info: &TestInfo) -> TestDealOrder {
+	// authorize lender so it can persist verified transfers
+	assert_ok!(Creditcoin::add_authority(
+		crate::mock::RuntimeOrigin::from(RawOrigin::Root),
+		test_info.lender.account_id.clone(),
+	));
+
+	let (deal_order_id, _) = test_info.create_deal_order();
+	let (funding_transfer_id, _) = test_info.create_funding_transfer(&deal_order_id);
+	crate::DealOrders::<Test>::mutate(
+		deal_order_id.expiration(),
+		deal_order_id.hash(),
+		|deal_order_storage| {
+			let deal_order = deal_order_storage.as_mut().unwrap();
+			deal_order.funding_transfer_id = Some(funding_transfer_id);
+			deal_order.status = DealOrderStatus::Funded;
+		},
+	);
+
+	let deal_order = DealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
+	(deal_order_id, deal_order)
+}
 
-		assert_noop!(
-			Creditcoin::exempt(Origin::signed(test_info.borrower.account_id), deal_order_id),
-			crate::Error::<Test>::NotLender
-		);
-	});
+/// Locks a funded deal order the way its borrower does before repaying the rest of it.
+fn lock_for_repayment(test_info: &TestInfo, deal_order_id: &TestDealOrderId) {
+	assert_ok!(Creditcoin::lock_deal_order(
+		Origin::signed(test_info.borrower.account_id.clone()),
+		deal_order_id.clone()
+	));
+}
+
+fn persist_repayment_transfer(
+	test_info: &TestInfo,
+	deal_order_id: &TestDealOrderId,
+	amount: ExternalAmount,
+	tx: &str,
+) -> TransferId<Hash> {
+	let (transfer_id, transfer) = test_info.make_transfer(
+		&test_info.borrower,
+		&test_info.lender,
+		amount,
+		deal_order_id,
+		tx,
+		None,
+	);
+
+	assert_ok!(Creditcoin::persist_task_output(
+		Origin::signed(test_info.lender.account_id.clone()),
+		Test::unverified_transfer_deadline(),
+		(transfer_id.clone(), transfer).into(),
+	));
+
+	transfer_id
+}
+
+/// Funds a deal order, locks it and repays it in full, which closes it.
+pub(crate) fn fund_and_close_deal_order(
+	test_info: &TestInfo,
+) -> (TestDealOrderId, TransferId<Hash>, TransferId<Hash>) {
+	let (deal_order_id, deal_order) = fund_deal_order_for_repayment(test_info);
+	lock_for_repayment(test_info, &deal_order_id);
+	let repayment_transfer_id =
+		persist_repayment_transfer(test_info, &deal_order_id, deal_order.terms.amount, "0xafafaf");
+	let funding_transfer_id = deal_order.funding_transfer_id.unwrap();
+
+	let deal_order = DealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
+	assert_eq!(deal_order.status, DealOrderStatus::Closed);
+	(deal_order_id, funding_transfer_id, repayment_transfer_id)
 }
 
 #[test]
-fn exempt_should_succeed() {
+fn persist_repayment_transfer_should_record_installment() {
 	ExtBuilder::default().build_and_execute(|| {
 		System::set_block_number(1);
 
 		let test_info = TestInfo::new_defaults();
-		let (deal_order_id, deal_order) = test_info.create_deal_order();
-
-		assert_ok!(Creditcoin::exempt(
-			Origin::signed(test_info.lender.account_id),
-			deal_order_id.clone()
-		));
+		let (deal_order_id, deal_order) = fund_deal_order_for_repayment(&test_info);
+		let installment = deal_order.terms.amount / 4u64;
 
-		let transfer_id = TransferId::new::<Test>(&deal_order.blockchain, b"0");
+		let transfer_id =
+			persist_repayment_transfer(&test_info, &deal_order_id, installment, "0xafafaf");
 
-		// assert field values were updated in storage
 		let saved_deal_order = DealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
-		assert_eq!(saved_deal_order.repayment_transfer_id, Some(transfer_id));
+		assert_eq!(saved_deal_order.repaid_amount, installment);
+		assert_eq!(saved_deal_order.repayment_transfer_id, Some(transfer_id.clone()));
+		assert!(Creditcoin::transfers(&transfer_id).unwrap().is_processed);
 
-		// assert events in reversed order
-		let mut all_events = <frame_system::Pallet<Test>>::events();
-		let event = all_events.pop().expect("Expected at least one EventRecord to be found").event;
-		assert_eq!(
+		let event = <frame_system::Pallet<Test>>::events().pop().expect("expected event").event;
+		assert_matches!(
 			event,
-			crate::mock::RuntimeEvent::Creditcoin(crate::Event::LoanExempted(deal_order_id))
+			crate::mock::RuntimeEvent::Creditcoin(crate::Event::RepaymentRecorded(id, transfer, total)) => {
+				assert_eq!(id, deal_order_id);
+				assert_eq!(transfer, transfer_id);
+				assert_eq!(total, installment);
+			}
 		);
-	});
-}
 
-#[test]
-fn verify_transfer_should_error_when_not_signed() {
-	ExtBuilder::default().build_and_execute(|| {
-		let test_info = TestInfo::new_defaults();
-		let (deal_order_id, _) = test_info.create_deal_order();
-		let (transfer_id, transfer) = test_info.create_funding_transfer(&deal_order_id);
-		let deadline = Test::unverified_transfer_deadline();
-		assert_noop!(
-			Creditcoin::persist_task_output(
-				Origin::none(),
-				deadline,
-				(transfer_id, transfer).into()
-			),
-			BadOrigin
+		let transfer_id =
+			persist_repayment_transfer(&test_info, &deal_order_id, installment, "0xbfbfbf");
+
+		let saved_deal_order = DealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
+		assert_eq!(saved_deal_order.repaid_amount, installment * 2u64);
+		assert_eq!(saved_deal_order.repayment_transfer_id, Some(transfer_id.clone()));
+
+		let event = <frame_system::Pallet<Test>>::events().pop().expect("expected event").event;
+		assert_matches!(
+			event,
+			crate::mock::RuntimeEvent::Creditcoin(crate::Event::RepaymentRecorded(_, transfer, total)) => {
+				assert_eq!(transfer, transfer_id);
+				assert_eq!(total, installment * 2u64);
+			}
 		);
 	});
 }
 
 #[test]
-fn verify_transfer_should_error_when_signer_not_authorized() {
+fn persist_repayment_transfer_should_close_deal_order_when_fully_repaid() {
 	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
 		let test_info = TestInfo::new_defaults();
-		let (deal_order_id, _) = test_info.create_deal_order();
-		let (transfer_id, transfer) = test_info.create_funding_transfer(&deal_order_id);
-		let deadline = Test::unverified_transfer_deadline();
-		assert_noop!(
-			Creditcoin::persist_task_output(
-				Origin::signed(test_info.lender.account_id),
-				deadline,
-				(transfer_id, transfer).into(),
-			),
-			crate::Error::<Test>::InsufficientAuthority,
+		let (deal_order_id, deal_order) = fund_deal_order_for_repayment(&test_info);
+		lock_for_repayment(&test_info, &deal_order_id);
+		let half = deal_order.terms.amount / 2u64;
+
+		persist_repayment_transfer(&test_info, &deal_order_id, half, "0xafafaf");
+		let transfer_id = persist_repayment_transfer(
+			&test_info,
+			&deal_order_id,
+			deal_order.terms.amount - half,
+			"0xbfbfbf",
+		);
+
+		let saved_deal_order = DealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
+		assert_eq!(saved_deal_order.repaid_amount, deal_order.terms.amount);
+		assert_eq!(saved_deal_order.repayment_transfer_id, Some(transfer_id.clone()));
+
+		let mut all_events = <frame_system::Pallet<Test>>::events();
+		let event = all_events.pop().expect("expected event").event;
+		assert_eq!(
+			event,
+			crate::mock::RuntimeEvent::Creditcoin(crate::Event::DealOrderClosed(deal_order_id))
+		);
+
+		let event = all_events.pop().expect("expected event").event;
+		assert_matches!(
+			event,
+			crate::mock::RuntimeEvent::Creditcoin(crate::Event::RepaymentRecorded(_, transfer, total)) => {
+				assert_eq!(transfer, transfer_id);
+				assert_eq!(total, deal_order.terms.amount);
+			}
 		);
 	});
 }
 
 #[test]
-fn verify_transfer_should_error_when_transfer_has_already_been_registered() {
+fn persist_repayment_transfer_should_credit_repayment_sent_to_the_previous_lender() {
 	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
 		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, deal_order) = fund_deal_order_for_repayment(&test_info);
+		let installment = deal_order.terms.amount / 4u64;
+
+		// the repayment was registered to the lender, then the deal order was transferred
+		let (transfer_id, transfer) = test_info.make_transfer(
+			&test_info.borrower,
+			&test_info.lender,
+			installment,
+			&deal_order_id,
+			"0xafafaf",
+			None,
+		);
+		crate::DealOrders::<Test>::mutate(
+			deal_order_id.expiration(),
+			deal_order_id.hash(),
+			|deal_order_storage| {
+				deal_order_storage.as_mut().unwrap().lender_address_id =
+					AddressId::new::<Test>(&Blockchain::Rinkeby, &[1, 2, 3, 4, 5, 6, 7, 8, 9, 0]);
+			},
+		);
 
-		// authorize lender
-		let root = RawOrigin::Root;
-		assert_ok!(Creditcoin::add_authority(
-			crate::mock::RuntimeOrigin::from(root),
-			test_info.lender.account_id.clone(),
+		assert_ok!(Creditcoin::persist_task_output(
+			Origin::signed(test_info.lender.account_id.clone()),
+			Test::unverified_transfer_deadline(),
+			(transfer_id.clone(), transfer).into(),
 		));
 
-		let (deal_order_id, _) = test_info.create_deal_order();
-		let (transfer_id, transfer) = test_info.create_funding_transfer(&deal_order_id);
-		let deadline = Test::unverified_transfer_deadline();
-
-		assert_noop!(
-			Creditcoin::persist_task_output(
-				Origin::signed(test_info.lender.account_id),
-				deadline,
-				(transfer_id, transfer).into(),
-			),
-			non_paying_error(crate::Error::<Test>::TransferAlreadyRegistered),
-		);
+		let saved_deal_order = DealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
+		assert_eq!(saved_deal_order.repaid_amount, installment);
+		assert_eq!(saved_deal_order.repayment_transfer_id, Some(transfer_id.clone()));
+		assert!(Creditcoin::transfers(&transfer_id).unwrap().is_processed);
 	});
 }
 
 #[test]
-fn verify_transfer_should_work() {
+fn persist_repayment_transfer_should_not_record_installment_for_unfunded_deal_order() {
 	ExtBuilder::default().build_and_execute(|| {
 		System::set_block_number(1);
 
 		let test_info = TestInfo::new_defaults();
-
-		// authorize lender
-		let root = RawOrigin::Root;
 		assert_ok!(Creditcoin::add_authority(
-			crate::mock::RuntimeOrigin::from(root),
+			crate::mock::RuntimeOrigin::from(RawOrigin::Root),
 			test_info.lender.account_id.clone(),
 		));
-
 		let (deal_order_id, deal_order) = test_info.create_deal_order();
 
-		// create a transfer but don't add it into storage
-		let tx = "0xafafaf".as_bytes().into_bounded();
-		let transfer_id = TransferId::new::<Test>(&Blockchain::Rinkeby, &tx);
-		let transfer = Transfer {
-			blockchain: test_info.blockchain.clone(),
-			kind: TransferKind::Native,
-			from: test_info.lender.address_id.clone(),
-			to: test_info.borrower.address_id.clone(),
-			order_id: OrderId::Deal(deal_order_id),
-			amount: deal_order.terms.amount,
-			tx_id: tx,
-			block: System::block_number(),
-			is_processed: false,
-			account_id: test_info.lender.account_id.clone(),
-			timestamp: None,
-		};
-		let deadline = Test::unverified_transfer_deadline();
-
-		assert_ok!(Creditcoin::persist_task_output(
-			Origin::signed(test_info.lender.account_id),
-			deadline,
-			(transfer_id.clone(), transfer.clone()).into(),
-		));
-
-		let mut all_events = <frame_system::Pallet<Test>>::events();
-
-		// assert events in reversed order
-		let last_event = all_events.pop().expect("At least one EventRecord").event;
-		assert_matches!(
-			last_event,
-			crate::mock::RuntimeEvent::Creditcoin(crate::Event::TransferVerified(id)) => {
-				assert_eq!(transfer_id, id)
-			}
+		let transfer_id = persist_repayment_transfer(
+			&test_info,
+			&deal_order_id,
+			deal_order.terms.amount,
+			"0xafafaf",
 		);
 
-		assert_eq!(Transfers::<Test>::get(&transfer_id), Some(transfer));
+		let saved_deal_order = DealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
+		assert_eq!(saved_deal_order.repaid_amount, ExternalAmount::zero());
+		assert_eq!(saved_deal_order.repayment_transfer_id, None);
+		assert!(!Creditcoin::transfers(&transfer_id).unwrap().is_processed);
 	});
 }
 
 #[test]
-fn fail_transfer_should_work() {
+fn loan_balance_should_be_none_for_nonexistent_deal_order() {
+	ExtBuilder::default().build_and_execute(|| {
+		let deal_order_id = DealOrderId::with_expiration_hash::<Test>(100, H256::zero());
+		assert_eq!(Creditcoin::loan_balance(&deal_order_id), None);
+	});
+}
+
+#[test]
+fn loan_balance_should_report_accrued_interest_and_repayments() {
 	ExtBuilder::default().build_and_execute(|| {
 		System::set_block_number(1);
 
 		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, deal_order) = fund_deal_order_for_repayment(&test_info);
 
-		let root = RawOrigin::Root;
-		assert_ok!(Creditcoin::add_authority(
-			crate::mock::RuntimeOrigin::from(root),
-			test_info.lender.account_id.clone(),
-		));
+		// charge 10% simple interest every second
+		crate::DealOrders::<Test>::mutate(
+			deal_order_id.expiration(),
+			deal_order_id.hash(),
+			|deal_order_storage| {
+				deal_order_storage.as_mut().unwrap().terms.interest_rate = InterestRate {
+					rate_per_period: 10,
+					decimals: 2,
+					period: Duration::from_millis(1_000),
+					interest_type: InterestType::Simple,
+				};
+			},
+		);
 
-		let _ = test_info.create_deal_order();
+		let installment = deal_order.terms.amount / 2u64;
+		persist_repayment_transfer(&test_info, &deal_order_id, installment, "0xafafaf");
+		Timestamp::set_timestamp(deal_order.timestamp + 1_500);
 
-		let tx = "0xafafaf".hex_to_address();
-		let transfer_id = TransferId::new::<Test>(&Blockchain::Rinkeby, &tx);
+		assert_eq!(
+			Creditcoin::loan_balance(&deal_order_id),
+			Some(LoanBalance {
+				principal: deal_order.terms.amount,
+				interest: deal_order.terms.amount / 5u64,
+				repaid: installment,
+				due: deal_order.timestamp + 100_000,
+			})
+		);
+	});
+}
 
-		let failure_cause = crate::ocw::errors::VerificationFailureCause::TaskFailed;
-		let deadline = Test::unverified_transfer_deadline();
+const COLLATERAL: u128 = 10_000;
 
-		assert_ok!(Creditcoin::fail_task(
-			Origin::signed(test_info.lender.account_id),
-			deadline,
-			transfer_id.clone().into(),
-			failure_cause
-		));
+fn collateralized_test_info() -> TestInfo {
+	let test_info = TestInfo::new_defaults();
+	Balances::make_free_balance_be(&test_info.borrower.account_id, 1_000_000);
+	TestInfo {
+		loan_terms: LoanTerms { collateral: COLLATERAL, ..test_info.loan_terms.clone() },
+		..test_info
+	}
+}
 
-		let mut all_events = System::events();
+#[test]
+fn add_deal_order_should_reserve_collateral() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = collateralized_test_info();
+		let (deal_order_id, _) = test_info.create_deal_order();
 
-		assert_matches!(
-			all_events.pop().unwrap().event,
-			crate::mock::RuntimeEvent::Creditcoin(crate::Event::<Test>::TransferFailedVerification(id, cause)) => {
-				assert_eq!(id, transfer_id);
-				assert_eq!(cause, failure_cause);
-			}
+		assert_eq!(Balances::reserved_balance(&test_info.borrower.account_id), COLLATERAL);
+		assert_eq!(
+			Creditcoin::deal_order_collateral(deal_order_id.expiration(), deal_order_id.hash()),
+			Some(COLLATERAL)
 		);
-	})
+	});
 }
 
 #[test]
-fn fail_transfer_should_error_when_not_signed() {
+fn add_deal_order_should_error_when_borrower_cannot_cover_collateral() {
 	ExtBuilder::default().build_and_execute(|| {
-		System::set_block_number(1);
-
-		let test_info = TestInfo::new_defaults();
-
-		let _ = test_info.create_deal_order();
-
-		let tx = "0xafafaf".hex_to_address();
-		let transfer_id = TransferId::new::<Test>(&Blockchain::Rinkeby, &tx);
-
-		let failure_cause = crate::ocw::errors::VerificationFailureCause::TaskFailed;
-		let deadline = Test::unverified_transfer_deadline();
+		let test_info = collateralized_test_info();
+		Balances::make_free_balance_be(&test_info.borrower.account_id, COLLATERAL - 1);
+		let (offer_id, _) = test_info.create_offer();
 
 		assert_noop!(
-			Creditcoin::fail_task(Origin::none(), deadline, transfer_id.into(), failure_cause),
-			BadOrigin
+			Creditcoin::add_deal_order(
+				Origin::signed(test_info.borrower.account_id),
+				offer_id,
+				test_info.expiration_block
+			),
+			crate::Error::<Test>::InsufficientCollateral
 		);
-	})
+	});
 }
 
 #[test]
-fn fail_transfer_should_error_when_not_authority() {
+fn add_offer_should_error_when_collateral_differs_between_ask_and_bid() {
 	ExtBuilder::default().build_and_execute(|| {
-		System::set_block_number(1);
-
-		let test_info = TestInfo::new_defaults();
-
-		let _ = test_info.create_deal_order();
-
-		let tx = "0xafafaf".hex_to_address();
-		let transfer_id = TransferId::new::<Test>(&Blockchain::Rinkeby, &tx);
-
-		let failure_cause = crate::ocw::errors::VerificationFailureCause::TaskFailed;
-		let deadline = Test::unverified_transfer_deadline();
+		let test_info = collateralized_test_info();
+		let (ask_order_id, _) = test_info.create_ask_order();
+		let (bid_order_id, _) = TestInfo {
+			loan_terms: LoanTerms { collateral: 0, ..test_info.loan_terms.clone() },
+			..test_info.clone()
+		}
+		.create_bid_order();
 
 		assert_noop!(
-			Creditcoin::fail_task(
+			Creditcoin::add_offer(
 				Origin::signed(test_info.lender.account_id),
-				deadline,
-				transfer_id.into(),
-				failure_cause
+				ask_order_id,
+				bid_order_id,
+				test_info.expiration_block
 			),
-			crate::Error::<Test>::InsufficientAuthority
+			crate::Error::<Test>::AskBidMismatch
 		);
-	})
+	});
 }
 
 #[test]
-fn fail_transfer_should_error_when_transfer_registered() {
+fn repaying_deal_order_should_release_collateral() {
 	ExtBuilder::default().build_and_execute(|| {
 		System::set_block_number(1);
 
-		let test_info = TestInfo::new_defaults();
+		let test_info = collateralized_test_info();
+		let (deal_order_id, deal_order) = fund_deal_order_for_repayment(&test_info);
+		assert_eq!(Balances::reserved_balance(&test_info.borrower.account_id), COLLATERAL);
 
-		let (deal_order_id, _) = test_info.create_deal_order();
+		lock_for_repayment(&test_info, &deal_order_id);
+		persist_repayment_transfer(&test_info, &deal_order_id, deal_order.terms.amount, "0xafafaf");
 
-		let (transfer_id, _) = test_info.create_funding_transfer(&deal_order_id);
+		assert_eq!(Balances::reserved_balance(&test_info.borrower.account_id), 0);
+		assert_eq!(Balances::free_balance(&test_info.borrower.account_id), 1_000_000);
+		assert!(<frame_system::Pallet<Test>>::events().iter().any(|record| record.event
+			== crate::mock::RuntimeEvent::Creditcoin(crate::Event::CollateralReleased(
+				deal_order_id.clone(),
+				COLLATERAL
+			))));
+	});
+}
 
-		let root = RawOrigin::Root;
-		assert_ok!(Creditcoin::add_authority(
-			crate::mock::RuntimeOrigin::from(root),
-			test_info.lender.account_id.clone(),
-		));
+#[test]
+fn exempt_should_release_collateral() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = collateralized_test_info();
+		let (deal_order_id, _) = fund_deal_order_for_repayment(&test_info);
 
-		let failure_cause = crate::ocw::errors::VerificationFailureCause::TaskFailed;
-		let deadline = Test::unverified_transfer_deadline();
+		assert_ok!(Creditcoin::exempt(
+			Origin::signed(test_info.lender.account_id.clone()),
+			deal_order_id.clone(),
+			None,
+			None
+		));
 
-		assert_noop!(
-			Creditcoin::fail_task(
-				Origin::signed(test_info.lender.account_id),
-				deadline,
-				transfer_id.into(),
-				failure_cause
-			),
-			crate::Error::<Test>::TransferAlreadyRegistered
+		assert_eq!(Balances::reserved_balance(&test_info.borrower.account_id), 0);
+		assert_eq!(
+			Creditcoin::deal_order_collateral(deal_order_id.expiration(), deal_order_id.hash()),
+			None
 		);
-	})
+	});
 }
 
 #[test]
-fn on_initialize_removes_expired_deals_without_transfers() {
-	ExtBuilder::default().build_offchain_and_execute(|| {
-		System::set_block_number(1);
-		let mut expected_deal_orders = Vec::new();
+fn on_initialize_should_release_collateral_of_expired_unfunded_deal_order() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = collateralized_test_info();
+		let (deal_order_id, _) = test_info.create_deal_order();
 
-		let now = System::block_number();
-		for expiration_block in now..=20 {
-			let seed1 = format!("{:02}0", expiration_block.clone());
-			let seed2 = format!("{:02}1", expiration_block.clone());
+		Creditcoin::on_initialize(deal_order_id.expiration());
 
-			let test_info = TestInfo {
-				lender: RegisteredAddress::new(&seed1, Blockchain::Rinkeby),
-				borrower: RegisteredAddress::new(&seed2, Blockchain::Rinkeby),
-				blockchain: Blockchain::Rinkeby,
-				loan_terms: LoanTerms {
-					amount: 2_000_000u64.into(),
-					interest_rate: Default::default(),
-					term_length: Duration::from_millis(1_000_000),
-				},
-				ask_guid: format!("{:?}-ask-guid", expiration_block.clone())
-					.as_bytes()
-					.into_bounded(),
-				bid_guid: format!("{:?}-bid-guid", expiration_block.clone())
-					.as_bytes()
-					.into_bounded(),
-				expiration_block,
-			};
+		assert_eq!(Balances::reserved_balance(&test_info.borrower.account_id), 0);
+		assert_eq!(Balances::free_balance(&test_info.borrower.account_id), 1_000_000);
+	});
+}
 
-			let (offer_id, _) = test_info.create_offer();
-			assert_ok!(Creditcoin::add_deal_order(
-				Origin::signed(test_info.borrower.account_id.clone()),
+#[test]
+fn claim_collateral_should_pay_lender_after_loan_term() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
+		let test_info = collateralized_test_info();
+		let (deal_order_id, deal_order) = fund_deal_order_for_repayment(&test_info);
+		let lender_balance = Balances::free_balance(&test_info.lender.account_id);
+
+		Timestamp::set_timestamp(deal_order.timestamp + 100_001);
+		assert_ok!(Creditcoin::claim_collateral(
+			Origin::signed(test_info.lender.account_id.clone()),
+			deal_order_id.clone()
+		));
+
+		assert_eq!(Balances::reserved_balance(&test_info.borrower.account_id), 0);
+		assert_eq!(
+			Balances::free_balance(&test_info.lender.account_id),
+			lender_balance + COLLATERAL
+		);
+
+		let saved_deal_order = DealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
+		assert_eq!(saved_deal_order.status, DealOrderStatus::Defaulted);
+
+		let event = <frame_system::Pallet<Test>>::events().pop().expect("expected event").event;
+		assert_eq!(
+			event,
+			crate::mock::RuntimeEvent::Creditcoin(crate::Event::CollateralClaimed(
+				deal_order_id.clone(),
+				test_info.lender.account_id.clone(),
+				COLLATERAL
+			))
+		);
+
+		assert_noop!(
+			Creditcoin::claim_collateral(
+				Origin::signed(test_info.lender.account_id),
+				deal_order_id
+			),
+			crate::Error::<Test>::CollateralNotClaimable
+		);
+	});
+}
+
+#[test]
+fn claim_collateral_should_error_before_loan_term_ends() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = collateralized_test_info();
+		let (deal_order_id, deal_order) = fund_deal_order_for_repayment(&test_info);
+
+		Timestamp::set_timestamp(deal_order.timestamp + 100_000);
+		assert_noop!(
+			Creditcoin::claim_collateral(
+				Origin::signed(test_info.lender.account_id),
+				deal_order_id
+			),
+			crate::Error::<Test>::CollateralNotClaimable
+		);
+	});
+}
+
+#[test]
+fn claim_collateral_should_error_when_not_lender() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = collateralized_test_info();
+		let (deal_order_id, deal_order) = fund_deal_order_for_repayment(&test_info);
+
+		Timestamp::set_timestamp(deal_order.timestamp + 100_001);
+		assert_noop!(
+			Creditcoin::claim_collateral(
+				Origin::signed(test_info.borrower.account_id),
+				deal_order_id
+			),
+			crate::Error::<Test>::NotLender
+		);
+	});
+}
+
+fn matching_test_info() -> TestInfo {
+	let mut test_info = TestInfo::new_defaults();
+	test_info.loan_terms.interest_rate.rate_per_period = 10;
+	test_info
+}
+
+fn add_matchable_ask_order(
+	test_info: &TestInfo,
+	guid: &str,
+	interest_rate: InterestRate,
+) -> AskOrderId<BlockNumber, Hash> {
+	let terms = AskTerms {
+		min_interest_rate: interest_rate,
+		..AskTerms::try_from(test_info.loan_terms.clone()).unwrap()
+	};
+	let guid: Guid = guid.as_bytes().into_bounded();
+	assert_ok!(Creditcoin::add_ask_order(
+		Origin::signed(test_info.lender.account_id.clone()),
+		test_info.lender.address_id.clone(),
+		terms,
+		test_info.expiration_block,
+		guid.clone()
+	));
+	AskOrderId::new::<Test>(test_info.expiration_block, &guid)
+}
+
+#[test]
+fn set_order_matching_should_error_when_not_root() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		assert_noop!(
+			Creditcoin::set_order_matching(Origin::signed(test_info.lender.account_id), true),
+			BadOrigin
+		);
+	});
+}
+
+#[test]
+fn order_matching_should_be_disabled_by_default() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+		let test_info = matching_test_info();
+		let (ask_order_id, _) = test_info.create_ask_order();
+		let (bid_order_id, _) = test_info.create_bid_order();
+
+		System::set_block_number(2);
+		Creditcoin::on_initialize(2);
+
+		let offer_id = OfferId::new::<Test>(1_000, &ask_order_id, &bid_order_id);
+		assert!(!crate::Offers::<Test>::contains_id(&offer_id));
+		assert!(crate::PendingMatches::<Test>::get().is_empty());
+	});
+}
+
+#[test]
+fn order_matching_should_offer_overlapping_orders() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+		assert_ok!(Creditcoin::set_order_matching(RawOrigin::Root.into(), true));
+		let test_info = matching_test_info();
+		let (ask_order_id, _) = test_info.create_ask_order();
+		let (bid_order_id, _) = test_info.create_bid_order();
+
+		System::set_block_number(2);
+		Creditcoin::on_initialize(2);
+
+		let offer_id = OfferId::new::<Test>(1_000, &ask_order_id, &bid_order_id);
+		let offer = crate::Offers::<Test>::try_get_id(&offer_id).unwrap();
+		assert_eq!(offer.lender, test_info.lender.account_id);
+		assert_eq!(offer.ask_id, ask_order_id);
+		assert_eq!(offer.bid_id, bid_order_id);
+		assert!(System::events().iter().any(|record| record.event
+			== crate::mock::RuntimeEvent::Creditcoin(crate::Event::OfferAdded(
 				offer_id.clone(),
-				expiration_block,
-			));
-			let deal_order_id = DealOrderId::new::<Test>(expiration_block, &offer_id);
-			let deal_order =
-				Creditcoin::deal_orders(deal_order_id.expiration(), deal_order_id.hash()).unwrap();
+				offer.clone()
+			))));
+		assert!(crate::PendingMatches::<Test>::get().is_empty());
 
-			// fund only deal orders which expire at even blocks
-			if expiration_block % 2 == 0 {
-				let tx = format!("0xfafafa{:02}", expiration_block.clone());
-				assert_ok!(Creditcoin::register_funding_transfer(
-					Origin::signed(test_info.lender.account_id.clone()),
-					TransferKind::Native,
-					deal_order_id.clone(),
-					tx.as_bytes().into_bounded()
-				));
-				let (transfer_id, _) = test_info.mock_transfer(
-					&test_info.lender,
-					&test_info.borrower,
-					deal_order.terms.amount,
-					&deal_order_id,
-					tx,
-				);
+		assert_ok!(Creditcoin::add_deal_order(
+			Origin::signed(test_info.borrower.account_id),
+			offer_id,
+			1_000
+		));
+	});
+}
 
-				// attach transfer to deal order
-				assert_ok!(Creditcoin::fund_deal_order(
-					Origin::signed(test_info.lender.account_id.clone()),
-					deal_order_id.clone(),
-					transfer_id.clone()
-				));
-				// it's funded so it should be kept
-				expected_deal_orders.push(deal_order_id.clone());
-			} else if expiration_block > 15 {
-				// still hasn't expired so should be kept regardless
-				expected_deal_orders.push(deal_order_id.clone());
-			}
+#[test]
+fn order_matching_should_prefer_the_lowest_rate() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+		assert_ok!(Creditcoin::set_order_matching(RawOrigin::Root.into(), true));
+		let test_info = matching_test_info();
+		let rate = test_info.loan_terms.interest_rate.clone();
+
+		let _ = add_matchable_ask_order(
+			&test_info,
+			"ask-8",
+			InterestRate { rate_per_period: 8, ..rate.clone() },
+		);
+		let cheapest = add_matchable_ask_order(
+			&test_info,
+			"ask-5",
+			InterestRate { rate_per_period: 5, ..rate.clone() },
+		);
+		let _ = add_matchable_ask_order(
+			&test_info,
+			"ask-12",
+			InterestRate { rate_per_period: 12, ..rate },
+		);
+		Creditcoin::match_pending_orders();
+
+		System::set_block_number(2);
+		let (bid_order_id, _) = test_info.create_bid_order();
+		Creditcoin::match_pending_orders();
+
+		let offer_id = OfferId::new::<Test>(1_000, &cheapest, &bid_order_id);
+		assert!(crate::Offers::<Test>::contains_id(&offer_id));
+		assert_eq!(crate::Offers::<Test>::iter().count(), 1);
+		let ask_book =
+			crate::AskOrderBook::<Test>::get(&test_info.blockchain, &test_info.loan_terms.currency);
+		assert!(!ask_book.iter().any(|(_, ask_order_id)| ask_order_id == &cheapest));
+		assert_eq!(ask_book.len(), 2);
+	});
+}
+
+#[test]
+fn order_matching_should_keep_the_ask_order_book_sorted_by_rate() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+		assert_ok!(Creditcoin::set_order_matching(RawOrigin::Root.into(), true));
+		let test_info = matching_test_info();
+		let rate = test_info.loan_terms.interest_rate.clone();
+
+		let ask_8 = add_matchable_ask_order(
+			&test_info,
+			"ask-8",
+			InterestRate { rate_per_period: 8, ..rate.clone() },
+		);
+		let ask_5 = add_matchable_ask_order(
+			&test_info,
+			"ask-5",
+			InterestRate { rate_per_period: 5, ..rate.clone() },
+		);
+		let ask_12 = add_matchable_ask_order(
+			&test_info,
+			"ask-12",
+			InterestRate { rate_per_period: 12, ..rate.clone() },
+		);
+		let other_ask_8 = add_matchable_ask_order(
+			&test_info,
+			"other-ask-8",
+			InterestRate { rate_per_period: 8, ..rate },
+		);
+
+		let ask_book =
+			crate::AskOrderBook::<Test>::get(&test_info.blockchain, &test_info.loan_terms.currency);
+		let ask_order_ids: Vec<_> = ask_book.into_iter().map(|(_, id)| id).collect();
+		assert_eq!(ask_order_ids, vec![ask_5, ask_8, other_ask_8, ask_12]);
+	});
+}
+
+#[test]
+fn order_matching_should_drop_the_highest_rate_from_a_full_ask_order_book() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+		assert_ok!(Creditcoin::set_order_matching(RawOrigin::Root.into(), true));
+		let test_info = matching_test_info();
+		let rate = test_info.loan_terms.interest_rate.clone();
+
+		for i in 0..crate::MAX_ORDER_BOOK_DEPTH {
+			let _ = add_matchable_ask_order(
+				&test_info,
+				&format!("ask-10-{i}"),
+				InterestRate { rate_per_period: 10, ..rate.clone() },
+			);
 		}
+		let highest = add_matchable_ask_order(
+			&test_info,
+			"ask-20",
+			InterestRate { rate_per_period: 20, ..rate.clone() },
+		);
+		let lowest = add_matchable_ask_order(
+			&test_info,
+			"ask-5",
+			InterestRate { rate_per_period: 5, ..rate },
+		);
 
-		// advance blocks, will perform housekeeping
-		roll_to(15);
+		let ask_book =
+			crate::AskOrderBook::<Test>::get(&test_info.blockchain, &test_info.loan_terms.currency);
+		assert_eq!(ask_book.len() as u32, crate::MAX_ORDER_BOOK_DEPTH);
+		assert_eq!(ask_book[0].1, lowest);
+		assert!(!ask_book.iter().any(|(_, ask_order_id)| ask_order_id == &highest));
+		assert!(
+			!crate::PendingMatches::<Test>::get().contains(&crate::MatchableOrderId::Ask(highest))
+		);
+	});
+}
 
-		for expected_order_id in expected_deal_orders.iter() {
-			let _order = DealOrders::<Test>::try_get_id(&expected_order_id).unwrap();
+#[test]
+fn order_matching_should_prefer_the_older_order_on_equal_rates() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+		assert_ok!(Creditcoin::set_order_matching(RawOrigin::Root.into(), true));
+		let test_info = matching_test_info();
+		let rate = test_info.loan_terms.interest_rate.clone();
+
+		let older = add_matchable_ask_order(
+			&test_info,
+			"older-ask",
+			InterestRate { rate_per_period: 5, ..rate.clone() },
+		);
+		System::set_block_number(2);
+		let _ = add_matchable_ask_order(
+			&test_info,
+			"newer-ask",
+			InterestRate { rate_per_period: 50, decimals: rate.decimals + 1, ..rate },
+		);
+		Creditcoin::match_pending_orders();
+
+		System::set_block_number(3);
+		let (bid_order_id, _) = test_info.create_bid_order();
+		Creditcoin::match_pending_orders();
+
+		let offer_id = OfferId::new::<Test>(1_000, &older, &bid_order_id);
+		assert!(crate::Offers::<Test>::contains_id(&offer_id));
+		assert_eq!(crate::Offers::<Test>::iter().count(), 1);
+	});
+}
+
+#[test]
+fn order_matching_should_skip_cancelled_orders() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+		assert_ok!(Creditcoin::set_order_matching(RawOrigin::Root.into(), true));
+		let test_info = matching_test_info();
+		let (ask_order_id, _) = test_info.create_ask_order();
+		assert_ok!(Creditcoin::cancel_ask_order(
+			Origin::signed(test_info.lender.account_id.clone()),
+			ask_order_id
+		));
+		let _ = test_info.create_bid_order();
+
+		Creditcoin::match_pending_orders();
+
+		assert_eq!(crate::Offers::<Test>::iter().count(), 0);
+	});
+}
+
+#[test]
+fn order_matching_should_process_a_bounded_number_of_orders_per_block() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+		assert_ok!(Creditcoin::set_order_matching(RawOrigin::Root.into(), true));
+		let test_info = matching_test_info();
+
+		for i in 0..=crate::MAX_MATCHES_PER_BLOCK {
+			let test_info = TestInfo {
+				bid_guid: format!("bid-guid-{i}").as_bytes().into_bounded(),
+				..test_info.clone()
+			};
+			let _ = test_info.create_bid_order();
 		}
+		assert_eq!(
+			crate::PendingMatches::<Test>::get().len() as u32,
+			crate::MAX_MATCHES_PER_BLOCK + 1
+		);
+
+		Creditcoin::match_pending_orders();
+
+		assert_eq!(crate::PendingMatches::<Test>::get().len(), 1);
+		assert_eq!(
+			crate::BidOrderBook::<Test>::get(&test_info.blockchain, &test_info.loan_terms.currency)
+				.len() as u32,
+			crate::MAX_MATCHES_PER_BLOCK + 1
+		);
+	});
+}
+
+fn new_lender_consent(
+	seed: &str,
+	deal_order_id: &TestDealOrderId,
+) -> (RegisteredAddress, sp_core::ecdsa::Public, sp_core::ecdsa::Signature) {
+	let new_lender = RegisteredAddress::new(seed, Blockchain::Rinkeby);
+	let key_pair = generate_keypair_from_seed(seed);
+	let signature = sign_deal_order_transfer(&key_pair, deal_order_id, &new_lender.address_id);
+
+	(new_lender, key_pair.public(), signature)
+}
+
+fn sign_deal_order_transfer(
+	key_pair: &sp_core::ecdsa::Pair,
+	deal_order_id: &TestDealOrderId,
+	new_lender_address_id: &AddressId<Hash>,
+) -> sp_core::ecdsa::Signature {
+	let deal_order = DealOrders::<Test>::try_get_id(deal_order_id).unwrap();
+	let nonce =
+		Creditcoin::deal_order_transfer_nonce(deal_order_id.expiration(), deal_order_id.hash());
+	let message = crate::helpers::transfer_deal_order_message::<Test>(
+		deal_order_id,
+		&deal_order.lender_address_id,
+		new_lender_address_id,
+		nonce,
+	);
+	key_pair.sign(&message)
+}
+
+#[test]
+fn transfer_deal_order_should_move_lender_position_to_new_lender() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, _) = fund_deal_order_for_repayment(&test_info);
+		let (new_lender, key, signature) = new_lender_consent("lender2", &deal_order_id);
+
+		assert_ok!(Creditcoin::transfer_deal_order(
+			Origin::signed(test_info.lender.account_id.clone()),
+			deal_order_id.clone(),
+			new_lender.address_id.clone(),
+			key.into(),
+			signature.into(),
+		));
+
+		let saved_deal_order = DealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
+		assert_eq!(saved_deal_order.lender_address_id, new_lender.address_id);
+
+		let event = <frame_system::Pallet<Test>>::events().pop().expect("expected event").event;
+		assert_eq!(
+			event,
+			crate::mock::RuntimeEvent::Creditcoin(crate::Event::DealOrderTransferred(
+				deal_order_id.clone(),
+				new_lender.address_id.clone(),
+				new_lender.account_id.clone(),
+			))
+		);
+
+		// repayments are now expected at the new lender's address
+		let (_, transfer) = test_info.create_repayment_transfer(&deal_order_id, 1u64);
+		let event = <frame_system::Pallet<Test>>::events().pop().expect("expected event").event;
+		assert_matches!(
+			event,
+			crate::mock::RuntimeEvent::Creditcoin(crate::Event::TransferRegistered(_, registered)) => {
+				assert_eq!(registered.to, new_lender.address_id);
+				assert_eq!(registered.from, transfer.from);
+			}
+		);
+	});
+}
+
+#[test]
+fn transfer_deal_order_should_error_when_a_consent_is_reused() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, _) = fund_deal_order_for_repayment(&test_info);
+		let (new_lender, key, signature) = new_lender_consent("lender2", &deal_order_id);
+
+		assert_ok!(Creditcoin::transfer_deal_order(
+			Origin::signed(test_info.lender.account_id.clone()),
+			deal_order_id.clone(),
+			new_lender.address_id.clone(),
+			key.into(),
+			signature.clone().into(),
+		));
+		assert_eq!(
+			Creditcoin::deal_order_transfer_nonce(deal_order_id.expiration(), deal_order_id.hash()),
+			1
+		);
+
+		// the original lender buys the position back
+		let lender_key = generate_keypair_from_seed("lender");
+		let consent =
+			sign_deal_order_transfer(&lender_key, &deal_order_id, &test_info.lender.address_id);
+		assert_ok!(Creditcoin::transfer_deal_order(
+			Origin::signed(new_lender.account_id.clone()),
+			deal_order_id.clone(),
+			test_info.lender.address_id.clone(),
+			lender_key.public().into(),
+			consent.into(),
+		));
+
+		assert_noop!(
+			Creditcoin::transfer_deal_order(
+				Origin::signed(test_info.lender.account_id),
+				deal_order_id,
+				new_lender.address_id,
+				key.into(),
+				signature.into(),
+			),
+			crate::Error::<Test>::InvalidSignature
+		);
+	});
+}
+
+#[test]
+fn transfer_deal_order_should_error_when_not_lender() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, _) = fund_deal_order_for_repayment(&test_info);
+		let (new_lender, key, signature) = new_lender_consent("lender2", &deal_order_id);
+
+		assert_noop!(
+			Creditcoin::transfer_deal_order(
+				Origin::signed(new_lender.account_id),
+				deal_order_id,
+				new_lender.address_id,
+				key.into(),
+				signature.into(),
+			),
+			crate::Error::<Test>::NotLender
+		);
+	});
+}
+
+#[test]
+fn transfer_deal_order_should_error_when_signature_is_invalid() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, _) = fund_deal_order_for_repayment(&test_info);
+		let (new_lender, _, signature) = new_lender_consent("lender2", &deal_order_id);
+		let (wrong_key, _) = sp_core::ecdsa::Pair::generate();
+
+		assert_noop!(
+			Creditcoin::transfer_deal_order(
+				Origin::signed(test_info.lender.account_id),
+				deal_order_id,
+				new_lender.address_id,
+				wrong_key.public().into(),
+				signature.into(),
+			),
+			crate::Error::<Test>::InvalidSignature
+		);
+	});
+}
+
+#[test]
+fn transfer_deal_order_should_error_when_not_funded() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, _) = test_info.create_deal_order();
+		let (new_lender, key, signature) = new_lender_consent("lender2", &deal_order_id);
+
+		assert_noop!(
+			Creditcoin::transfer_deal_order(
+				Origin::signed(test_info.lender.account_id),
+				deal_order_id,
+				new_lender.address_id,
+				key.into(),
+				signature.into(),
+			),
+			crate::Error::<Test>::DealNotFunded
+		);
+	});
+}
+
+#[test]
+fn transfer_deal_order_should_error_when_locked() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, _) = fund_deal_order_for_repayment(&test_info);
+		assert_ok!(Creditcoin::lock_deal_order(
+			Origin::signed(test_info.borrower.account_id.clone()),
+			deal_order_id.clone()
+		));
+		let (new_lender, key, signature) = new_lender_consent("lender2", &deal_order_id);
+
+		assert_noop!(
+			Creditcoin::transfer_deal_order(
+				Origin::signed(test_info.lender.account_id),
+				deal_order_id,
+				new_lender.address_id,
+				key.into(),
+				signature.into(),
+			),
+			crate::Error::<Test>::DealOrderAlreadyLocked
+		);
+	});
+}
+
+#[test]
+fn account_indexes_should_track_orders_offers_and_deal_orders() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, deal_order) = test_info.create_deal_order();
+		let offer = crate::Offers::<Test>::try_get_id(&deal_order.offer_id).unwrap();
+		let lender = &test_info.lender.account_id;
+		let borrower = &test_info.borrower.account_id;
+
+		assert!(crate::AccountAddresses::<Test>::contains_key(
+			lender,
+			&test_info.lender.address_id
+		));
+		assert!(crate::AccountAddresses::<Test>::contains_key(
+			borrower,
+			&test_info.borrower.address_id
+		));
+		assert!(crate::AccountAskOrders::<Test>::contains_key(lender, &offer.ask_id));
+		assert!(crate::AccountBidOrders::<Test>::contains_key(borrower, &offer.bid_id));
+		assert!(crate::AccountOffers::<Test>::contains_key(lender, &deal_order.offer_id));
+		assert!(crate::AccountDealOrders::<Test>::contains_key(lender, &deal_order_id));
+		assert!(crate::AccountDealOrders::<Test>::contains_key(borrower, &deal_order_id));
+
+		assert!(!crate::AccountAskOrders::<Test>::contains_key(borrower, &offer.ask_id));
+		assert!(!crate::AccountBidOrders::<Test>::contains_key(lender, &offer.bid_id));
+	});
+}
+
+#[test]
+fn account_indexes_should_drop_cancelled_orders_and_offers() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (offer_id, offer) = test_info.create_offer();
+		let lender = &test_info.lender.account_id;
+		let borrower = &test_info.borrower.account_id;
+
+		assert_ok!(Creditcoin::cancel_offer(Origin::signed(lender.clone()), offer_id.clone()));
+		assert_ok!(Creditcoin::cancel_ask_order(
+			Origin::signed(lender.clone()),
+			offer.ask_id.clone()
+		));
+		assert_ok!(Creditcoin::cancel_bid_order(
+			Origin::signed(borrower.clone()),
+			offer.bid_id.clone()
+		));
+
+		assert!(!crate::AccountOffers::<Test>::contains_key(lender, &offer_id));
+		assert!(!crate::AccountAskOrders::<Test>::contains_key(lender, &offer.ask_id));
+		assert!(!crate::AccountBidOrders::<Test>::contains_key(borrower, &offer.bid_id));
+	});
+}
+
+#[test]
+fn account_indexes_should_drop_expired_entries() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, deal_order) = test_info.create_deal_order();
+		let offer = crate::Offers::<Test>::try_get_id(&deal_order.offer_id).unwrap();
+		let lender = &test_info.lender.account_id;
+		let borrower = &test_info.borrower.account_id;
+
+		Creditcoin::on_initialize(test_info.expiration_block);
+
+		assert!(!crate::AccountAskOrders::<Test>::contains_key(lender, &offer.ask_id));
+		assert!(!crate::AccountBidOrders::<Test>::contains_key(borrower, &offer.bid_id));
+		assert!(!crate::AccountOffers::<Test>::contains_key(lender, &deal_order.offer_id));
+		assert!(!crate::AccountDealOrders::<Test>::contains_key(lender, &deal_order_id));
+		assert!(!crate::AccountDealOrders::<Test>::contains_key(borrower, &deal_order_id));
+
+		// addresses never expire
+		assert!(crate::AccountAddresses::<Test>::contains_key(
+			lender,
+			&test_info.lender.address_id
+		));
+	});
+}
+
+#[test]
+fn account_indexes_should_follow_transferred_deal_order() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, _) = fund_deal_order_for_repayment(&test_info);
+		let (new_lender, key, signature) = new_lender_consent("lender2", &deal_order_id);
+
+		assert_ok!(Creditcoin::transfer_deal_order(
+			Origin::signed(test_info.lender.account_id.clone()),
+			deal_order_id.clone(),
+			new_lender.address_id.clone(),
+			key.into(),
+			signature.into(),
+		));
+
+		assert!(!crate::AccountDealOrders::<Test>::contains_key(
+			&test_info.lender.account_id,
+			&deal_order_id
+		));
+		assert!(crate::AccountDealOrders::<Test>::contains_key(
+			&new_lender.account_id,
+			&deal_order_id
+		));
+		assert!(crate::AccountDealOrders::<Test>::contains_key(
+			&test_info.borrower.account_id,
+			&deal_order_id
+		));
+	});
+}
+
+#[test]
+fn credit_history_should_record_on_time_repayment() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, deal_order) = fund_deal_order_for_repayment(&test_info);
+		lock_for_repayment(&test_info, &deal_order_id);
+
+		persist_repayment_transfer(&test_info, &deal_order_id, deal_order.terms.amount, "0xafafaf");
+
+		let record = CreditRecord {
+			loans: 1,
+			on_time_repayments: 1,
+			volume: deal_order.terms.amount,
+			..Default::default()
+		};
+		assert_eq!(
+			Creditcoin::credit_history(&test_info.borrower.account_id),
+			vec![(Blockchain::Rinkeby, record)]
+		);
+		assert!(Creditcoin::credit_history(&test_info.lender.account_id).is_empty());
+	});
+}
+
+#[test]
+fn credit_history_should_record_late_repayment() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, deal_order) = fund_deal_order_for_repayment(&test_info);
+		lock_for_repayment(&test_info, &deal_order_id);
+
+		Timestamp::set_timestamp(deal_order.timestamp + 100_001);
+		persist_repayment_transfer(&test_info, &deal_order_id, deal_order.terms.amount, "0xafafaf");
+
+		let record =
+			crate::CreditHistory::<Test>::get(&test_info.borrower.account_id, &Blockchain::Rinkeby);
+		assert_eq!(
+			record,
+			CreditRecord {
+				loans: 1,
+				late_repayments: 1,
+				volume: deal_order.terms.amount,
+				..Default::default()
+			}
+		);
+	});
+}
+
+#[test]
+fn credit_history_should_count_defaulted_loan_once_when_repaid_later() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, deal_order) = fund_deal_order_for_repayment(&test_info);
+		lock_for_repayment(&test_info, &deal_order_id);
+
+		Timestamp::set_timestamp(deal_order.timestamp + 100_001);
+		Creditcoin::mark_defaulted_deal_orders();
+
+		let record =
+			crate::CreditHistory::<Test>::get(&test_info.borrower.account_id, &Blockchain::Rinkeby);
+		assert_eq!(
+			record,
+			CreditRecord {
+				loans: 1,
+				defaults: 1,
+				volume: deal_order.terms.amount,
+				..Default::default()
+			}
+		);
+
+		persist_repayment_transfer(&test_info, &deal_order_id, deal_order.terms.amount, "0xafafaf");
+
+		let record =
+			crate::CreditHistory::<Test>::get(&test_info.borrower.account_id, &Blockchain::Rinkeby);
+		assert_eq!(
+			record,
+			CreditRecord {
+				loans: 1,
+				late_repayments: 1,
+				defaults: 1,
+				volume: deal_order.terms.amount,
+				..Default::default()
+			}
+		);
+	});
+}
+
+#[test]
+fn credit_history_should_count_exempted_loan() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, deal_order) = fund_deal_order_for_repayment(&test_info);
+
+		assert_ok!(Creditcoin::exempt(
+			Origin::signed(test_info.lender.account_id.clone()),
+			deal_order_id,
+			None,
+			None
+		));
+
+		let record =
+			crate::CreditHistory::<Test>::get(&test_info.borrower.account_id, &Blockchain::Rinkeby);
+		assert_eq!(
+			record,
+			CreditRecord { loans: 1, volume: deal_order.terms.amount, ..Default::default() }
+		);
+	});
+}
+
+#[test]
+fn credit_history_should_ignore_unfunded_deal_order() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, _) = test_info.create_deal_order();
+
+		assert_noop!(
+			Creditcoin::exempt(
+				Origin::signed(test_info.lender.account_id.clone()),
+				deal_order_id,
+				None,
+				None
+			),
+			crate::Error::<Test>::DealNotFunded
+		);
+
+		assert!(Creditcoin::credit_history(&test_info.borrower.account_id).is_empty());
+	});
+}
+
+fn propose_longer_term(
+	test_info: &TestInfo,
+	deal_order_id: &TestDealOrderId,
+	proposer: &AccountId,
+) -> (crate::TermsProposalId<BlockNumber, Hash>, LoanTerms) {
+	let terms =
+		LoanTerms { term_length: Duration::from_millis(200_000), ..test_info.loan_terms.clone() };
+	let expiration_block = 1_000;
+
+	assert_ok!(Creditcoin::propose_terms(
+		Origin::signed(proposer.clone()),
+		deal_order_id.clone(),
+		terms.clone(),
+		expiration_block,
+	));
+
+	let terms_proposal_id =
+		crate::TermsProposalId::new::<Test>(expiration_block, deal_order_id, &terms);
+	(terms_proposal_id, terms)
+}
+
+#[test]
+fn propose_terms_should_add_proposal_and_emit_event() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, _) = fund_deal_order_for_repayment(&test_info);
+		let (terms_proposal_id, terms) =
+			propose_longer_term(&test_info, &deal_order_id, &test_info.borrower.account_id);
+
+		let terms_proposal = crate::TermsProposals::<Test>::try_get_id(&terms_proposal_id).unwrap();
+		assert_eq!(
+			terms_proposal,
+			crate::TermsProposal {
+				deal_order_id: deal_order_id.clone(),
+				terms,
+				expiration_block: 1_000,
+				block: 1,
+				proposer: test_info.borrower.account_id.clone(),
+			}
+		);
+
+		let event = <frame_system::Pallet<Test>>::events().pop().expect("expected event").event;
+		assert_eq!(
+			event,
+			crate::mock::RuntimeEvent::Creditcoin(crate::Event::TermsProposed(
+				terms_proposal_id,
+				terms_proposal
+			))
+		);
+
+		// the deal order keeps its terms until the lender accepts
+		let saved_deal_order = DealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
+		assert_eq!(saved_deal_order.terms, test_info.loan_terms);
+	});
+}
+
+#[test]
+fn propose_terms_should_error_when_changing_amount_or_shortening_term() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, _) = fund_deal_order_for_repayment(&test_info);
+
+		let invalid_terms = [
+			LoanTerms { amount: 1u64.into(), ..test_info.loan_terms.clone() },
+			LoanTerms { collateral: 1, ..test_info.loan_terms.clone() },
+			LoanTerms {
+				term_length: Duration::from_millis(99_999),
+				..test_info.loan_terms.clone()
+			},
+			LoanTerms {
+				currency: LoanCurrency::Token { contract: "usdc".as_bytes().into_bounded() },
+				..test_info.loan_terms.clone()
+			},
+			test_info.loan_terms.clone(),
+		];
+
+		for terms in invalid_terms {
+			assert_noop!(
+				Creditcoin::propose_terms(
+					Origin::signed(test_info.lender.account_id.clone()),
+					deal_order_id.clone(),
+					terms,
+					1_000,
+				),
+				crate::Error::<Test>::InvalidTermsProposal
+			);
+		}
+	});
+}
+
+#[test]
+fn propose_terms_should_error_when_not_deal_order_party() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, _) = fund_deal_order_for_repayment(&test_info);
+		let stranger = RegisteredAddress::new("stranger", Blockchain::Rinkeby);
+
+		assert_noop!(
+			Creditcoin::propose_terms(
+				Origin::signed(stranger.account_id),
+				deal_order_id,
+				test_info.loan_terms.clone(),
+				1_000,
+			),
+			crate::Error::<Test>::NotDealOrderParty
+		);
+	});
+}
+
+#[test]
+fn propose_terms_should_error_when_deal_order_is_not_funded() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, _) = test_info.create_deal_order();
+		let terms = LoanTerms {
+			term_length: Duration::from_millis(200_000),
+			..test_info.loan_terms.clone()
+		};
+
+		assert_noop!(
+			Creditcoin::propose_terms(
+				Origin::signed(test_info.borrower.account_id.clone()),
+				deal_order_id,
+				terms,
+				1_000,
+			),
+			crate::Error::<Test>::DealNotFunded
+		);
+	});
+}
+
+#[test]
+fn accept_terms_should_replace_terms_and_emit_event() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, deal_order) = fund_deal_order_for_repayment(&test_info);
+		let (terms_proposal_id, terms) =
+			propose_longer_term(&test_info, &deal_order_id, &test_info.borrower.account_id);
+
+		assert_ok!(Creditcoin::accept_terms(
+			Origin::signed(test_info.lender.account_id.clone()),
+			terms_proposal_id.clone(),
+		));
+
+		let saved_deal_order = DealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
+		assert_eq!(saved_deal_order.terms, terms);
+		assert!(!crate::TermsProposals::<Test>::contains_id(&terms_proposal_id));
+
+		let event = <frame_system::Pallet<Test>>::events().pop().expect("expected event").event;
+		assert_eq!(
+			event,
+			crate::mock::RuntimeEvent::Creditcoin(crate::Event::DealOrderTermsUpdated(
+				deal_order_id.clone(),
+				terms_proposal_id,
+				terms
+			))
+		);
+
+		// the extended loan is not overdue at its original due date
+		Timestamp::set_timestamp(deal_order.timestamp + 100_001);
+		Creditcoin::mark_defaulted_deal_orders();
+		let saved_deal_order = DealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
+		assert_eq!(saved_deal_order.status, DealOrderStatus::Funded);
+	});
+}
+
+#[test]
+fn accept_terms_should_error_when_accepting_own_proposal() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, _) = fund_deal_order_for_repayment(&test_info);
+		let (terms_proposal_id, _) =
+			propose_longer_term(&test_info, &deal_order_id, &test_info.lender.account_id);
+
+		assert_noop!(
+			Creditcoin::accept_terms(
+				Origin::signed(test_info.lender.account_id.clone()),
+				terms_proposal_id,
+			),
+			crate::Error::<Test>::CannotAcceptOwnTermsProposal
+		);
+	});
+}
+
+#[test]
+fn accept_terms_should_error_when_proposal_expired() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, _) = fund_deal_order_for_repayment(&test_info);
+		let (terms_proposal_id, _) =
+			propose_longer_term(&test_info, &deal_order_id, &test_info.borrower.account_id);
+
+		System::set_block_number(terms_proposal_id.expiration() + 1);
+		assert_noop!(
+			Creditcoin::accept_terms(
+				Origin::signed(test_info.lender.account_id.clone()),
+				terms_proposal_id.clone(),
+			),
+			crate::Error::<Test>::TermsProposalExpired
+		);
+
+		Creditcoin::on_initialize(terms_proposal_id.expiration());
+		assert!(!crate::TermsProposals::<Test>::contains_id(&terms_proposal_id));
+	});
+}
+
+fn lender_share_consent(
+	seed: &str,
+	deal_order_id: &TestDealOrderId,
+	lender: &RegisteredAddress,
+	amount: ExternalAmount,
+) -> (sp_core::ecdsa::Public, sp_core::ecdsa::Signature) {
+	let key_pair = generate_keypair_from_seed(seed);
+	let message = deal_order_id
+		.encode()
+		.into_iter()
+		.chain(lender.address_id.encode())
+		.chain(amount.encode())
+		.collect::<Vec<u8>>();
+	let signature = key_pair.sign(&message);
+
+	(key_pair.public(), signature)
+}
+
+fn add_lender_share(
+	test_info: &TestInfo,
+	deal_order_id: &TestDealOrderId,
+	seed: &str,
+	amount: ExternalAmount,
+) -> RegisteredAddress {
+	let lender = RegisteredAddress::new(seed, Blockchain::Rinkeby);
+	let (key, signature) = lender_share_consent(seed, deal_order_id, &lender, amount);
+
+	assert_ok!(Creditcoin::add_deal_order_share(
+		Origin::signed(test_info.lender.account_id.clone()),
+		deal_order_id.clone(),
+		lender.address_id.clone(),
+		amount,
+		key.into(),
+		signature.into(),
+	));
+
+	lender
+}
+
+/// A deal order whose loan amount is split 60/40 between the lead lender and a second lender,
+/// funded by both of them.
+fn fund_syndicated_deal_order(test_info: &TestInfo) -> (TestDealOrderId, RegisteredAddress) {
+	let (deal_order_id, deal_order) = test_info.create_deal_order();
+	let lead_amount = deal_order.terms.amount / 10u64 * 6u64;
+	let co_amount = deal_order.terms.amount - lead_amount;
+	let co_lender = add_lender_share(test_info, &deal_order_id, "lender2", co_amount);
+
+	let (lead_transfer_id, _) = test_info.mock_transfer(
+		&test_info.lender,
+		&test_info.borrower,
+		lead_amount,
+		&deal_order_id,
+		"0xfafafa",
+	);
+	assert_ok!(Creditcoin::fund_deal_order(
+		Origin::signed(test_info.lender.account_id.clone()),
+		deal_order_id.clone(),
+		lead_transfer_id,
+	));
+
+	let (co_transfer_id, _) = test_info.mock_transfer(
+		&co_lender,
+		&test_info.borrower,
+		co_amount,
+		&deal_order_id,
+		"0xfbfbfb",
+	);
+	assert_ok!(Creditcoin::fund_deal_order(
+		Origin::signed(co_lender.account_id.clone()),
+		deal_order_id.clone(),
+		co_transfer_id,
+	));
+
+	(deal_order_id, co_lender)
+}
+
+#[test]
+fn add_deal_order_share_should_split_loan_amount_and_emit_event() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, deal_order) = test_info.create_deal_order();
+		let amount = ExternalAmount::from(4_000_000u64);
+		let co_lender = add_lender_share(&test_info, &deal_order_id, "lender2", amount);
+
+		let lead_share =
+			Creditcoin::deal_order_shares(&deal_order_id, &test_info.lender.address_id).unwrap();
+		assert_eq!(lead_share, LenderShare::new(deal_order.terms.amount - amount));
+		let co_share =
+			Creditcoin::deal_order_shares(&deal_order_id, &co_lender.address_id).unwrap();
+		assert_eq!(co_share, LenderShare::new(amount));
+		assert!(crate::AccountDealOrders::<Test>::contains_key(
+			&co_lender.account_id,
+			&deal_order_id
+		));
+
+		let event = <frame_system::Pallet<Test>>::events().pop().expect("expected event").event;
+		assert_eq!(
+			event,
+			crate::mock::RuntimeEvent::Creditcoin(crate::Event::DealOrderShareAdded(
+				deal_order_id,
+				co_lender.address_id,
+				amount,
+			))
+		);
+	});
+}
+
+#[test]
+fn add_deal_order_share_should_error_for_invalid_amount_or_duplicate_lender() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, deal_order) = test_info.create_deal_order();
+		let lender = RegisteredAddress::new("lender2", Blockchain::Rinkeby);
+
+		for amount in [ExternalAmount::zero(), deal_order.terms.amount] {
+			let (key, signature) = lender_share_consent("lender2", &deal_order_id, &lender, amount);
+			assert_noop!(
+				Creditcoin::add_deal_order_share(
+					Origin::signed(test_info.lender.account_id.clone()),
+					deal_order_id.clone(),
+					lender.address_id.clone(),
+					amount,
+					key.into(),
+					signature.into(),
+				),
+				crate::Error::<Test>::InvalidShareAmount
+			);
+		}
+
+		let amount = ExternalAmount::from(1_000u64);
+		let (key, signature) = lender_share_consent("lender2", &deal_order_id, &lender, amount);
+		assert_ok!(Creditcoin::add_deal_order_share(
+			Origin::signed(test_info.lender.account_id.clone()),
+			deal_order_id.clone(),
+			lender.address_id.clone(),
+			amount,
+			key.clone().into(),
+			signature.clone().into(),
+		));
+		assert_noop!(
+			Creditcoin::add_deal_order_share(
+				Origin::signed(test_info.lender.account_id.clone()),
+				deal_order_id,
+				lender.address_id,
+				amount,
+				key.into(),
+				signature.into(),
+			),
+			crate::Error::<Test>::DuplicateShare
+		);
+	});
+}
+
+#[test]
+fn add_deal_order_share_should_error_when_deal_order_is_funded() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, _) = fund_deal_order_for_repayment(&test_info);
+		let lender = RegisteredAddress::new("lender2", Blockchain::Rinkeby);
+		let amount = ExternalAmount::from(1_000u64);
+		let (key, signature) = lender_share_consent("lender2", &deal_order_id, &lender, amount);
+
+		assert_noop!(
+			Creditcoin::add_deal_order_share(
+				Origin::signed(test_info.lender.account_id.clone()),
+				deal_order_id,
+				lender.address_id,
+				amount,
+				key.into(),
+				signature.into(),
+			),
+			crate::Error::<Test>::DealOrderAlreadyFunded
+		);
+	});
+}
+
+#[test]
+fn fund_deal_order_should_fund_syndicated_deal_order_once_every_share_is_funded() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, deal_order) = test_info.create_deal_order();
+		let co_amount = ExternalAmount::from(4_000_000u64);
+		let lead_amount = deal_order.terms.amount - co_amount;
+		let co_lender = add_lender_share(&test_info, &deal_order_id, "lender2", co_amount);
+
+		// the lead lender can only fund its own share
+		let (transfer_id, _) = test_info.mock_transfer(
+			&test_info.lender,
+			&test_info.borrower,
+			deal_order.terms.amount,
+			&deal_order_id,
+			"0xfcfcfc",
+		);
+		assert_noop!(
+			Creditcoin::fund_deal_order(
+				Origin::signed(test_info.lender.account_id.clone()),
+				deal_order_id.clone(),
+				transfer_id,
+			),
+			crate::Error::<Test>::TransferAmountMismatch
+		);
+
+		let (transfer_id, _) = test_info.mock_transfer(
+			&test_info.lender,
+			&test_info.borrower,
+			lead_amount,
+			&deal_order_id,
+			"0xfafafa",
+		);
+		assert_ok!(Creditcoin::fund_deal_order(
+			Origin::signed(test_info.lender.account_id.clone()),
+			deal_order_id.clone(),
+			transfer_id.clone(),
+		));
+
+		let saved_deal_order = DealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
+		assert_eq!(saved_deal_order.funding_transfer_id, None);
+		let lead_share =
+			Creditcoin::deal_order_shares(&deal_order_id, &test_info.lender.address_id).unwrap();
+		assert_eq!(lead_share.funding_transfer_id, Some(transfer_id));
+		assert!(<frame_system::Pallet<Test>>::events().iter().any(|record| record.event
+			== crate::mock::RuntimeEvent::Creditcoin(crate::Event::DealOrderShareFunded(
+				deal_order_id.clone(),
+				test_info.lender.address_id.clone(),
+			))));
+
+		let (transfer_id, _) = test_info.mock_transfer(
+			&co_lender,
+			&test_info.borrower,
+			co_amount,
+			&deal_order_id,
+			"0xfbfbfb",
+		);
+		assert_ok!(Creditcoin::fund_deal_order(
+			Origin::signed(co_lender.account_id.clone()),
+			deal_order_id.clone(),
+			transfer_id.clone(),
+		));
+
+		let saved_deal_order = DealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
+		assert_eq!(saved_deal_order.funding_transfer_id, Some(transfer_id));
+		assert_eq!(saved_deal_order.status, DealOrderStatus::Funded);
+		assert!(<frame_system::Pallet<Test>>::events().iter().any(|record| record.event
+			== crate::mock::RuntimeEvent::Creditcoin(crate::Event::DealOrderFunded(
+				deal_order_id.clone()
+			))));
+	});
+}
+
+#[test]
+fn register_funding_transfer_should_use_lender_share_of_syndicated_deal_order() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, _) = test_info.create_deal_order();
+		let amount = ExternalAmount::from(4_000_000u64);
+		let co_lender = add_lender_share(&test_info, &deal_order_id, "lender2", amount);
+
+		assert_ok!(Creditcoin::register_funding_transfer(
+			Origin::signed(co_lender.account_id.clone()),
+			TransferKind::Native,
+			deal_order_id,
+			"0xfafafa".as_bytes().into_bounded()
+		));
+
+		let event = <frame_system::Pallet<Test>>::events().pop().expect("expected event").event;
+		assert_matches!(
+			event,
+			crate::mock::RuntimeEvent::Creditcoin(crate::Event::TransferRegistered(_, transfer)) => {
+				assert_eq!(transfer.from, co_lender.address_id);
+				assert_eq!(transfer.to, test_info.borrower.address_id);
+				assert_eq!(transfer.amount, amount);
+			}
+		);
+	});
+}
+
+#[test]
+fn persist_repayment_transfer_should_close_syndicated_deal_order_once_every_lender_is_repaid() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
+		let test_info = TestInfo::new_defaults();
+		assert_ok!(Creditcoin::add_authority(
+			crate::mock::RuntimeOrigin::from(RawOrigin::Root),
+			test_info.lender.account_id.clone(),
+		));
+		let (deal_order_id, co_lender) = fund_syndicated_deal_order(&test_info);
+		lock_for_repayment(&test_info, &deal_order_id);
+		let deal_order = DealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
+		let lead_amount = deal_order.terms.amount / 10u64 * 6u64;
+		let co_amount = deal_order.terms.amount - lead_amount;
+
+		// overpaying one lender does not make up for the other
+		let (transfer_id, transfer) = test_info.make_transfer(
+			&test_info.borrower,
+			&co_lender,
+			deal_order.terms.amount,
+			&deal_order_id,
+			"0xafafaf",
+			None,
+		);
+		assert_ok!(Creditcoin::persist_task_output(
+			Origin::signed(test_info.lender.account_id.clone()),
+			Test::unverified_transfer_deadline(),
+			(transfer_id.clone(), transfer).into(),
+		));
+
+		let saved_deal_order = DealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
+		assert_eq!(saved_deal_order.status, DealOrderStatus::Locked);
+		let co_share =
+			Creditcoin::deal_order_shares(&deal_order_id, &co_lender.address_id).unwrap();
+		assert_eq!(co_share.repaid_amount, deal_order.terms.amount);
+		assert!(co_share.repaid_amount > co_amount);
+
+		let transfer_id =
+			persist_repayment_transfer(&test_info, &deal_order_id, lead_amount, "0xbfbfbf");
+
+		let saved_deal_order = DealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
+		assert_eq!(saved_deal_order.repayment_transfer_id, Some(transfer_id));
+		assert_eq!(saved_deal_order.status, DealOrderStatus::Closed);
+		let event = <frame_system::Pallet<Test>>::events().pop().expect("expected event").event;
+		assert_eq!(
+			event,
+			crate::mock::RuntimeEvent::Creditcoin(crate::Event::DealOrderClosed(deal_order_id))
+		);
+	});
+}
+
+#[test]
+fn register_share_repayment_transfer_should_error_for_address_without_share() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, _) = fund_syndicated_deal_order(&test_info);
+		let stranger = RegisteredAddress::new("stranger", Blockchain::Rinkeby);
+
+		assert_noop!(
+			Creditcoin::register_share_repayment_transfer(
+				Origin::signed(test_info.borrower.account_id.clone()),
+				TransferKind::Native,
+				1u64.into(),
+				deal_order_id,
+				stranger.address_id,
+				"0xafafaf".as_bytes().into_bounded()
+			),
+			crate::Error::<Test>::NonExistentShare
+		);
+	});
+}
+
+#[test]
+fn register_share_repayment_transfer_should_error_when_transfer_kind_is_not_loan_currency() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, co_lender) = fund_syndicated_deal_order(&test_info);
+
+		assert_noop!(
+			Creditcoin::register_share_repayment_transfer(
+				Origin::signed(test_info.borrower.account_id.clone()),
+				TransferKind::Ethless("usdc".as_bytes().into_bounded()),
+				1u64.into(),
+				deal_order_id,
+				co_lender.address_id,
+				"0xafafaf".as_bytes().into_bounded()
+			),
+			crate::Error::<Test>::CurrencyMismatch
+		);
+	});
+}
+
+#[test]
+fn transfer_deal_order_and_exempt_should_error_for_syndicated_deal_order() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, _) = fund_syndicated_deal_order(&test_info);
+		let (new_lender, key, signature) = new_lender_consent("lender3", &deal_order_id);
+
+		assert_noop!(
+			Creditcoin::transfer_deal_order(
+				Origin::signed(test_info.lender.account_id.clone()),
+				deal_order_id.clone(),
+				new_lender.address_id,
+				key.into(),
+				signature.into(),
+			),
+			crate::Error::<Test>::SyndicatedDealOrder
+		);
+		assert_noop!(
+			Creditcoin::exempt(
+				Origin::signed(test_info.lender.account_id.clone()),
+				deal_order_id,
+				None,
+				None
+			),
+			crate::Error::<Test>::SyndicatedDealOrder
+		);
+	});
+}
+
+#[test]
+fn on_initialize_should_remove_shares_of_expired_unfunded_deal_order() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, _) = test_info.create_deal_order();
+		let co_lender = add_lender_share(&test_info, &deal_order_id, "lender2", 1_000u64.into());
+
+		Creditcoin::on_initialize(deal_order_id.expiration());
+
+		assert_eq!(crate::DealOrderShares::<Test>::iter_prefix(&deal_order_id).count(), 0);
+		assert!(!crate::AccountDealOrders::<Test>::contains_key(
+			&co_lender.account_id,
+			&deal_order_id
+		));
+	});
+}
+
+#[test]
+fn on_initialize_should_sweep_expired_entries_over_several_blocks_when_over_budget() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
+		let test_info = TestInfo::new_defaults();
+		let (_, ask_order) = test_info.create_ask_order();
+		let expiration_block = ask_order.expiration_block;
+		for i in 0..crate::MAX_EXPIRY_SWEEP_ENTRIES + 10 {
+			let ask_order_id = AskOrderId::new::<Test>(expiration_block, &i.to_be_bytes());
+			crate::AskOrders::<Test>::insert_id(ask_order_id, ask_order.clone());
+		}
+		let remaining = || crate::AskOrders::<Test>::iter_prefix(expiration_block).count() as u32;
+		assert_eq!(remaining(), crate::MAX_EXPIRY_SWEEP_ENTRIES + 11);
+
+		Creditcoin::on_initialize(expiration_block);
+		assert_eq!(remaining(), 11);
+		assert_eq!(
+			crate::ExpiryCursor::<Test>::get(),
+			Some(crate::ExpirySweep { block: expiration_block, last_deal_order: None })
+		);
+
+		Creditcoin::on_initialize(expiration_block + 1);
+		assert_eq!(remaining(), 0);
+		assert_eq!(
+			crate::ExpiryCursor::<Test>::get(),
+			Some(crate::ExpirySweep { block: expiration_block + 2, last_deal_order: None })
+		);
+	});
+}
+
+#[test]
+fn on_initialize_should_catch_up_on_skipped_expiration_blocks() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
+		let test_info = TestInfo::new_defaults();
+		let (ask_order_id, _) = test_info.create_ask_order();
+		let (deal_order_id, _) = test_info.create_deal_order();
+
+		Creditcoin::on_initialize(ask_order_id.expiration() - 2);
+		Creditcoin::on_initialize(ask_order_id.expiration() + 1);
+
+		assert!(!crate::AskOrders::<Test>::contains_id(&ask_order_id));
+		assert!(!DealOrders::<Test>::contains_id(&deal_order_id));
+	});
+}
+
+#[test]
+fn on_initialize_should_keep_funded_deal_orders_and_move_on() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
+		let test_info = TestInfo::new_defaults();
+		let (funded_deal_order_id, _) = fund_deal_order_for_repayment(&test_info);
+		let other = TestInfo {
+			lender: RegisteredAddress::new("lender2", Blockchain::Rinkeby),
+			borrower: RegisteredAddress::new("borrower2", Blockchain::Rinkeby),
+			ask_guid: "ask_guid2".as_bytes().into_bounded(),
+			bid_guid: "bid_guid2".as_bytes().into_bounded(),
+			..TestInfo::new_defaults()
+		};
+		let (unfunded_deal_order_id, _) = other.create_deal_order();
+		let expiration_block = funded_deal_order_id.expiration();
+
+		Creditcoin::on_initialize(expiration_block);
+
+		assert!(DealOrders::<Test>::contains_id(&funded_deal_order_id));
+		assert!(!DealOrders::<Test>::contains_id(&unfunded_deal_order_id));
+		assert_eq!(
+			crate::ExpiryCursor::<Test>::get(),
+			Some(crate::ExpirySweep { block: expiration_block + 1, last_deal_order: None })
+		);
+	});
+}
+
+#[test]
+fn expired_unfunded_deal_order_should_be_unusable_before_it_is_swept() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, _) = test_info.create_deal_order();
+		System::set_block_number(deal_order_id.expiration() + 1);
+
+		assert!(DealOrders::<Test>::contains_id(&deal_order_id));
+		assert_noop!(
+			Creditcoin::register_funding_transfer(
+				Origin::signed(test_info.lender.account_id.clone()),
+				TransferKind::Native,
+				deal_order_id.clone(),
+				"0xfafafa".as_bytes().into_bounded()
+			),
+			crate::Error::<Test>::DealOrderExpired
+		);
+		assert_noop!(
+			Creditcoin::exempt(
+				Origin::signed(test_info.lender.account_id.clone()),
+				deal_order_id.clone(),
+				None,
+				None
+			),
+			crate::Error::<Test>::DealOrderExpired
+		);
+		assert_eq!(Creditcoin::loan_balance(&deal_order_id), None);
+	});
+}
+
+#[test]
+fn on_initialize_should_archive_closed_deal_order_after_retention() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+		assert_ok!(Creditcoin::set_deal_order_retention(RawOrigin::Root.into(), Some(10)));
+
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, funding_transfer_id, repayment_transfer_id) =
+			fund_and_close_deal_order(&test_info);
+		let deal_order = DealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
+
+		Creditcoin::on_initialize(10);
+		assert!(DealOrders::<Test>::contains_id(&deal_order_id));
+
+		Creditcoin::on_initialize(11);
+		assert!(!DealOrders::<Test>::contains_id(&deal_order_id));
+		assert!(!Transfers::<Test>::contains_key(&funding_transfer_id));
+		assert!(!Transfers::<Test>::contains_key(&repayment_transfer_id));
+		assert!(Creditcoin::is_transfer_registered(&funding_transfer_id));
+		assert!(Creditcoin::is_transfer_registered(&repayment_transfer_id));
+		assert!(!crate::AccountDealOrders::<Test>::contains_key(
+			&test_info.borrower.account_id,
+			&deal_order_id
+		));
+
+		let archived = crate::ArchivedDealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
+		assert_eq!(archived.status, DealOrderStatus::Closed);
+		assert_eq!(archived.principal, deal_order.terms.amount);
+		assert_eq!(archived.repaid_amount, deal_order.repaid_amount);
+		assert_eq!(archived.archived_at, 11);
+
+		let mut all_events = <frame_system::Pallet<Test>>::events();
+		let event = all_events.pop().expect("expected event").event;
+		assert_eq!(
+			event,
+			crate::mock::RuntimeEvent::Creditcoin(crate::Event::DealOrderArchived(
+				deal_order_id,
+				deal_order
+			))
+		);
+		let archived_transfers = all_events
+			.iter()
+			.filter(|record| {
+				matches!(
+					record.event,
+					crate::mock::RuntimeEvent::Creditcoin(crate::Event::TransferArchived(..))
+				)
+			})
+			.count();
+		assert_eq!(archived_transfers, 2);
+	});
+}
+
+#[test]
+fn on_initialize_should_archive_transfers_of_a_deal_order_across_blocks() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+		assert_ok!(Creditcoin::set_deal_order_retention(RawOrigin::Root.into(), Some(10)));
+
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, ..) = fund_and_close_deal_order(&test_info);
+		for i in 0..crate::MAX_ARCHIVED_PER_BLOCK {
+			let (transfer_id, transfer) = test_info.make_transfer(
+				&test_info.borrower,
+				&test_info.lender,
+				1u64,
+				&deal_order_id,
+				format!("overpayment-{i}"),
+				None,
+			);
+			crate::DealOrderTransfers::<Test>::insert(&deal_order_id, &transfer_id, ());
+			Transfers::<Test>::insert(&transfer_id, transfer);
+		}
+		let archived_transfers = || {
+			<frame_system::Pallet<Test>>::events()
+				.iter()
+				.filter(|record| {
+					matches!(
+						record.event,
+						crate::mock::RuntimeEvent::Creditcoin(crate::Event::TransferArchived(..))
+					)
+				})
+				.count() as u32
+		};
+
+		// the budget of a block runs out before every transfer has been removed
+		Creditcoin::on_initialize(11);
+		assert!(DealOrders::<Test>::contains_id(&deal_order_id));
+		assert_eq!(archived_transfers(), crate::MAX_ARCHIVED_PER_BLOCK - 1);
+		assert_eq!(crate::ArchiveCursor::<Test>::get(), Some(1));
+
+		Creditcoin::on_initialize(12);
+		assert!(!DealOrders::<Test>::contains_id(&deal_order_id));
+		assert!(crate::ArchivedDealOrders::<Test>::contains_id(&deal_order_id));
+		assert_eq!(archived_transfers(), crate::MAX_ARCHIVED_PER_BLOCK + 2);
+		assert_eq!(crate::DealOrderTransfers::<Test>::iter_prefix(&deal_order_id).count(), 0);
+	});
+}
+
+#[test]
+fn on_initialize_should_keep_closed_deal_orders_without_retention() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, ..) = fund_and_close_deal_order(&test_info);
+
+		Creditcoin::on_initialize(100);
+
+		assert!(DealOrders::<Test>::contains_id(&deal_order_id));
+		assert!(!crate::ArchivedDealOrders::<Test>::contains_id(&deal_order_id));
+	});
+}
+
+#[test]
+fn persist_task_output_should_error_when_transfer_was_archived() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+		assert_ok!(Creditcoin::set_deal_order_retention(RawOrigin::Root.into(), Some(10)));
+
+		let test_info = TestInfo::new_defaults();
+		let (_, _, repayment_transfer_id) = fund_and_close_deal_order(&test_info);
+		let transfer = Creditcoin::transfers(&repayment_transfer_id).unwrap();
+
+		Creditcoin::on_initialize(11);
+
+		assert_noop!(
+			Creditcoin::persist_task_output(
+				Origin::signed(test_info.lender.account_id),
+				Test::unverified_transfer_deadline(),
+				(repayment_transfer_id, transfer).into(),
+			),
+			non_paying_error(crate::Error::<Test>::TransferAlreadyRegistered),
+		);
+	});
+}
+
+#[test]
+fn add_deal_order_should_error_when_deal_order_was_archived() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+		assert_ok!(Creditcoin::set_deal_order_retention(RawOrigin::Root.into(), Some(10)));
+
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, ..) = fund_and_close_deal_order(&test_info);
+		let deal_order = DealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
+
+		Creditcoin::on_initialize(11);
+		System::set_block_number(11);
+
+		assert_noop!(
+			Creditcoin::add_deal_order(
+				Origin::signed(test_info.borrower.account_id),
+				deal_order.offer_id,
+				deal_order.expiration_block,
+			),
+			crate::Error::<Test>::DuplicateDealOrder
+		);
+	});
+}
+
+#[test]
+fn set_deal_order_retention_should_error_when_not_root() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+
+		assert_noop!(
+			Creditcoin::set_deal_order_retention(
+				Origin::signed(test_info.lender.account_id),
+				Some(10)
+			),
+			BadOrigin
+		);
+	});
+}
+
+fn add_repayment_order(
+	deal_order_id: &TestDealOrderId,
+	payer: &RegisteredAddress,
+	amount: ExternalAmount,
+	expiration_block: BlockNumber,
+) -> RepaymentOrderId<BlockNumber, Hash> {
+	let guid: Guid = "repayment_guid".as_bytes().into_bounded();
+	assert_ok!(Creditcoin::add_repayment_order(
+		Origin::signed(payer.account_id.clone()),
+		deal_order_id.clone(),
+		payer.address_id.clone(),
+		amount,
+		expiration_block,
+		guid.clone(),
+	));
+	RepaymentOrderId::new::<Test>(expiration_block, &guid)
+}
+
+#[test]
+fn add_repayment_order_should_store_the_order() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, deal_order) = fund_deal_order_for_repayment(&test_info);
+		let payer = RegisteredAddress::new("payer", test_info.blockchain.clone());
+
+		let repayment_order_id =
+			add_repayment_order(&deal_order_id, &payer, deal_order.terms.amount, 1_000);
+
+		let repayment_order = RepaymentOrders::<Test>::try_get_id(&repayment_order_id).unwrap();
+		assert_eq!(repayment_order.deal_order_id, deal_order_id);
+		assert_eq!(repayment_order.payer_address_id, payer.address_id);
+		assert_eq!(repayment_order.amount, deal_order.terms.amount);
+		assert_eq!(repayment_order.payer, payer.account_id);
+
+		let event = System::events().pop().expect("expected an event").event;
+		assert_eq!(
+			event,
+			crate::mock::RuntimeEvent::Creditcoin(crate::Event::RepaymentOrderAdded(
+				repayment_order_id,
+				repayment_order
+			))
+		);
+	});
+}
+
+#[test]
+fn add_repayment_order_should_error_when_deal_order_is_not_funded() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, deal_order) = test_info.create_deal_order();
+		let payer = RegisteredAddress::new("payer", test_info.blockchain.clone());
+
+		assert_noop!(
+			Creditcoin::add_repayment_order(
+				Origin::signed(payer.account_id),
+				deal_order_id,
+				payer.address_id,
+				deal_order.terms.amount,
+				1_000,
+				"repayment_guid".as_bytes().into_bounded(),
+			),
+			crate::Error::<Test>::DealNotFunded
+		);
+	});
+}
+
+#[test]
+fn add_repayment_order_should_error_when_amount_is_zero() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, _) = fund_deal_order_for_repayment(&test_info);
+		let payer = RegisteredAddress::new("payer", test_info.blockchain.clone());
+
+		assert_noop!(
+			Creditcoin::add_repayment_order(
+				Origin::signed(payer.account_id),
+				deal_order_id,
+				payer.address_id,
+				ExternalAmount::zero(),
+				1_000,
+				"repayment_guid".as_bytes().into_bounded(),
+			),
+			crate::Error::<Test>::ZeroRepaymentAmount
+		);
+	});
+}
+
+#[test]
+fn add_repayment_order_should_error_when_caller_does_not_own_the_address() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, deal_order) = fund_deal_order_for_repayment(&test_info);
+		let payer = RegisteredAddress::new("payer", test_info.blockchain.clone());
+
+		assert_noop!(
+			Creditcoin::add_repayment_order(
+				Origin::signed(payer.account_id),
+				deal_order_id,
+				test_info.borrower.address_id,
+				deal_order.terms.amount,
+				1_000,
+				"repayment_guid".as_bytes().into_bounded(),
+			),
+			crate::Error::<Test>::NotAddressOwner
+		);
+	});
+}
+
+#[test]
+fn cancel_repayment_order_should_only_be_allowed_for_the_payer() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, deal_order) = fund_deal_order_for_repayment(&test_info);
+		let payer = RegisteredAddress::new("payer", test_info.blockchain.clone());
+		let repayment_order_id =
+			add_repayment_order(&deal_order_id, &payer, deal_order.terms.amount, 1_000);
+
+		assert_noop!(
+			Creditcoin::cancel_repayment_order(
+				Origin::signed(test_info.borrower.account_id),
+				repayment_order_id.clone()
+			),
+			crate::Error::<Test>::NotPayer
+		);
+
+		assert_ok!(Creditcoin::cancel_repayment_order(
+			Origin::signed(payer.account_id),
+			repayment_order_id.clone()
+		));
+		assert!(!RepaymentOrders::<Test>::contains_id(&repayment_order_id));
+		let event = System::events().pop().expect("expected an event").event;
+		assert_eq!(
+			event,
+			crate::mock::RuntimeEvent::Creditcoin(crate::Event::RepaymentOrderCancelled(
+				repayment_order_id
+			))
+		);
+	});
+}
+
+#[test]
+fn register_repayment_order_transfer_should_register_transfer_from_payer_to_lender() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, deal_order) = fund_deal_order_for_repayment(&test_info);
+		let payer = RegisteredAddress::new("payer", test_info.blockchain.clone());
+		let repayment_order_id =
+			add_repayment_order(&deal_order_id, &payer, deal_order.terms.amount, 1_000);
+
+		assert_ok!(Creditcoin::register_repayment_order_transfer(
+			Origin::signed(payer.account_id.clone()),
+			TransferKind::Native,
+			repayment_order_id.clone(),
+			"0xabcabcabc".as_bytes().into_bounded(),
+		));
+
+		let event = System::events().pop().expect("expected an event").event;
+		assert_matches!(
+			event,
+			crate::mock::RuntimeEvent::Creditcoin(crate::Event::TransferRegistered(_, transfer)) => {
+				assert_eq!(transfer.from, payer.address_id);
+				assert_eq!(transfer.to, test_info.lender.address_id);
+				assert_eq!(transfer.amount, deal_order.terms.amount);
+				assert_eq!(transfer.order_id, OrderId::Repayment(repayment_order_id));
+				assert_eq!(transfer.account_id, payer.account_id);
+			}
+		);
+	});
+}
+
+#[test]
+fn register_repayment_order_transfer_should_error_when_caller_is_not_the_payer() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, deal_order) = fund_deal_order_for_repayment(&test_info);
+		let payer = RegisteredAddress::new("payer", test_info.blockchain.clone());
+		let repayment_order_id =
+			add_repayment_order(&deal_order_id, &payer, deal_order.terms.amount, 1_000);
+
+		assert_noop!(
+			Creditcoin::register_repayment_order_transfer(
+				Origin::signed(test_info.borrower.account_id.clone()),
+				TransferKind::Native,
+				repayment_order_id,
+				"0xabcabcabc".as_bytes().into_bounded(),
+			),
+			crate::Error::<Test>::NotPayer
+		);
+	});
+}
+
+#[test]
+fn persist_repayment_order_transfer_should_settle_the_order_and_close_deal_order() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, deal_order) = fund_deal_order_for_repayment(&test_info);
+		lock_for_repayment(&test_info, &deal_order_id);
+		let payer = RegisteredAddress::new("payer", test_info.blockchain.clone());
+		let repayment_order_id =
+			add_repayment_order(&deal_order_id, &payer, deal_order.terms.amount, 1_000);
+
+		let (transfer_id, mut transfer) = test_info.make_transfer(
+			&payer,
+			&test_info.lender,
+			deal_order.terms.amount,
+			&deal_order_id,
+			"0xafafaf",
+			None,
+		);
+		transfer.order_id = OrderId::Repayment(repayment_order_id.clone());
+		assert_ok!(Creditcoin::persist_task_output(
+			Origin::signed(test_info.lender.account_id.clone()),
+			Test::unverified_transfer_deadline(),
+			(transfer_id.clone(), transfer).into(),
+		));
+
+		let saved_deal_order = DealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
+		assert_eq!(saved_deal_order.repaid_amount, deal_order.terms.amount);
+		assert_eq!(saved_deal_order.repayment_transfer_id, Some(transfer_id.clone()));
+		assert_eq!(saved_deal_order.status, DealOrderStatus::Closed);
+		assert!(Transfers::<Test>::get(&transfer_id).unwrap().is_processed);
+		assert!(crate::DealOrderTransfers::<Test>::contains_key(&deal_order_id, &transfer_id));
+		assert!(!RepaymentOrders::<Test>::contains_id(&repayment_order_id));
+		assert!(System::events().iter().any(|record| record.event
+			== crate::mock::RuntimeEvent::Creditcoin(crate::Event::RepaymentOrderSettled(
+				repayment_order_id.clone(),
+				transfer_id.clone()
+			))));
+	});
+}
+
+#[test]
+fn persist_repayment_order_transfer_should_settle_order_that_expired_after_registration() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, deal_order) = fund_deal_order_for_repayment(&test_info);
+		let payer = RegisteredAddress::new("payer", test_info.blockchain.clone());
+		let repayment_order_id =
+			add_repayment_order(&deal_order_id, &payer, deal_order.terms.amount, 10);
+
+		assert_ok!(Creditcoin::register_repayment_order_transfer(
+			Origin::signed(payer.account_id.clone()),
+			TransferKind::Native,
+			repayment_order_id.clone(),
+			"0xabcabcabc".as_bytes().into_bounded(),
+		));
+		let (transfer_id, transfer) = match System::events().pop().unwrap().event {
+			crate::mock::RuntimeEvent::Creditcoin(crate::Event::TransferRegistered(
+				transfer_id,
+				transfer,
+			)) => (transfer_id, transfer),
+			event => panic!("unexpected event {event:?}"),
+		};
+
+		Creditcoin::on_initialize(10);
+		assert!(!RepaymentOrders::<Test>::contains_id(&repayment_order_id));
+
+		assert_ok!(Creditcoin::persist_task_output(
+			Origin::signed(test_info.lender.account_id.clone()),
+			Test::unverified_transfer_deadline(),
+			(transfer_id.clone(), transfer).into(),
+		));
+
+		let saved_deal_order = DealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
+		assert_eq!(saved_deal_order.repaid_amount, deal_order.terms.amount);
+		assert!(Transfers::<Test>::get(&transfer_id).unwrap().is_processed);
+		assert!(!crate::RepaymentOrderTransfers::<Test>::contains_key(&transfer_id));
+		assert!(System::events().iter().any(|record| record.event
+			== crate::mock::RuntimeEvent::Creditcoin(crate::Event::RepaymentOrderSettled(
+				repayment_order_id.clone(),
+				transfer_id.clone()
+			))));
+	});
+}
+
+#[test]
+fn on_initialize_should_remove_expired_repayment_order() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, deal_order) = fund_deal_order_for_repayment(&test_info);
+		let payer = RegisteredAddress::new("payer", test_info.blockchain.clone());
+		let repayment_order_id =
+			add_repayment_order(&deal_order_id, &payer, deal_order.terms.amount, 10);
+
+		Creditcoin::on_initialize(10);
+
+		assert!(!RepaymentOrders::<Test>::contains_id(&repayment_order_id));
+	});
+}
+
+#[test]
+fn register_funding_transfer_should_error_when_transfer_kind_is_not_loan_currency() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo {
+			loan_terms: LoanTerms {
+				currency: LoanCurrency::Token { contract: "usdc".as_bytes().into_bounded() },
+				..TestInfo::new_defaults().loan_terms
+			},
+			..TestInfo::new_defaults()
+		};
+		let (deal_order_id, _) = test_info.create_deal_order();
+
+		for kind in [TransferKind::Native, TransferKind::Ethless("dai".as_bytes().into_bounded())] {
+			assert_noop!(
+				Creditcoin::register_funding_transfer(
+					Origin::signed(test_info.lender.account_id.clone()),
+					kind,
+					deal_order_id.clone(),
+					"0xfafafa".as_bytes().into_bounded(),
+				),
+				crate::Error::<Test>::CurrencyMismatch
+			);
+		}
+
+		assert_ok!(Creditcoin::register_funding_transfer(
+			Origin::signed(test_info.lender.account_id.clone()),
+			TransferKind::Ethless("usdc".as_bytes().into_bounded()),
+			deal_order_id,
+			"0xfafafa".as_bytes().into_bounded(),
+		));
+	});
+}
+
+#[test]
+fn register_repayment_transfer_should_error_when_transfer_kind_is_not_loan_currency() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, deal_order) = fund_deal_order_for_repayment(&test_info);
+
+		assert_noop!(
+			Creditcoin::register_repayment_transfer(
+				Origin::signed(test_info.borrower.account_id.clone()),
+				TransferKind::Erc20("usdc".as_bytes().into_bounded()),
+				deal_order.terms.amount,
+				deal_order_id,
+				"0xafafaf".as_bytes().into_bounded(),
+			),
+			crate::Error::<Test>::CurrencyMismatch
+		);
+	});
+}
+
+fn fund_deal_order_with_fee(
+	test_info: &TestInfo,
+	payer: FeePayer,
+) -> (TestDealOrderId, DispatchResult) {
+	let (deal_order_id, deal_order) = test_info.create_deal_order();
+	let (transfer_id, _) = test_info.create_funding_transfer(&deal_order_id);
+	crate::Transfers::<Test>::mutate(&transfer_id, |transfer_storage| {
+		transfer_storage.as_mut().unwrap().amount = deal_order.terms.amount;
+	});
+
+	assert_ok!(Creditcoin::set_origination_fee(
+		RawOrigin::Root.into(),
+		Some(OriginationFee {
+			rate: OriginationFeeRate::Flat(1_000),
+			payer,
+			collector: AccountId::new([9; 32]),
+		})
+	));
+
+	let result = Creditcoin::fund_deal_order(
+		Origin::signed(test_info.lender.account_id.clone()),
+		deal_order_id.clone(),
+		transfer_id,
+	);
+	(deal_order_id, result)
+}
+
+#[test]
+fn fund_deal_order_should_charge_origination_fee_to_borrower() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+		let test_info = TestInfo::new_defaults();
+		Balances::make_free_balance_be(&test_info.borrower.account_id, 10_000);
+
+		let (deal_order_id, result) = fund_deal_order_with_fee(&test_info, FeePayer::Borrower);
+		assert_ok!(result);
+
+		assert_eq!(Balances::free_balance(&test_info.borrower.account_id), 9_000);
+		assert_eq!(Balances::free_balance(&AccountId::new([9; 32])), 1_000);
+		System::assert_last_event(
+			crate::Event::<Test>::OriginationFeeCharged(
+				deal_order_id,
+				test_info.borrower.account_id,
+				1_000,
+			)
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn fund_deal_order_should_charge_origination_fee_to_lender() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+		let test_info = TestInfo::new_defaults();
+		Balances::make_free_balance_be(&test_info.lender.account_id, 10_000);
+
+		let (deal_order_id, result) = fund_deal_order_with_fee(&test_info, FeePayer::Lender);
+		assert_ok!(result);
+
+		assert_eq!(Balances::free_balance(&test_info.lender.account_id), 9_000);
+		assert_eq!(Balances::free_balance(&AccountId::new([9; 32])), 1_000);
+		let deal_order =
+			Creditcoin::deal_orders(deal_order_id.expiration(), deal_order_id.hash()).unwrap();
+		assert!(deal_order.funding_transfer_id.is_some());
+	});
+}
+
+#[test]
+fn fund_deal_order_should_error_when_payer_cannot_afford_origination_fee() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+		let test_info = TestInfo::new_defaults();
+
+		let (deal_order_id, result) = fund_deal_order_with_fee(&test_info, FeePayer::Borrower);
+		assert_eq!(result, Err(crate::Error::<Test>::InsufficientBalanceForFee.into()));
+
+		let deal_order =
+			Creditcoin::deal_orders(deal_order_id.expiration(), deal_order_id.hash()).unwrap();
+		assert!(deal_order.funding_transfer_id.is_none());
+	});
+}
+
+#[test]
+fn set_origination_fee_should_error_for_non_root() {
+	ExtBuilder::default().build_and_execute(|| {
+		let acct = AccountId::new([0; 32]);
+		assert_noop!(
+			Creditcoin::set_origination_fee(Origin::signed(acct.clone()), None),
+			BadOrigin
+		);
+		assert_noop!(
+			Creditcoin::set_origination_fee(
+				Origin::signed(acct.clone()),
+				Some(OriginationFee {
+					rate: OriginationFeeRate::Flat(1_000),
+					payer: FeePayer::Borrower,
+					collector: acct,
+				})
+			),
+			BadOrigin
+		);
+		assert!(Creditcoin::origination_fee().is_none());
+	});
+}
+
+#[test]
+fn mark_defaulted_deal_orders_should_default_overdue_deal_order() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, deal_order) = fund_deal_order_for_repayment(&test_info);
+
+		Timestamp::set_timestamp(deal_order.timestamp + 100_001);
+		Creditcoin::on_initialize(2);
+
+		let saved_deal_order = DealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
+		assert_eq!(saved_deal_order.status, DealOrderStatus::Defaulted);
+
+		let event = <frame_system::Pallet<Test>>::events().pop().expect("expected event").event;
+		assert_eq!(
+			event,
+			crate::mock::RuntimeEvent::Creditcoin(crate::Event::DealOrderDefaulted(deal_order_id))
+		);
+	});
+}
+
+#[test]
+fn mark_defaulted_deal_orders_should_skip_deal_order_not_yet_due() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, deal_order) = fund_deal_order_for_repayment(&test_info);
+
+		Timestamp::set_timestamp(deal_order.timestamp + 100_000);
+		Creditcoin::mark_defaulted_deal_orders();
+
+		let saved_deal_order = DealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
+		assert_eq!(saved_deal_order.status, DealOrderStatus::Funded);
+	});
+}
+
+#[test]
+fn mark_defaulted_deal_orders_should_skip_unfunded_deal_order() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, deal_order) = test_info.create_deal_order();
+
+		Timestamp::set_timestamp(deal_order.timestamp + 100_001);
+		Creditcoin::mark_defaulted_deal_orders();
+
+		let saved_deal_order = DealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
+		assert_eq!(saved_deal_order.status, DealOrderStatus::Pending);
+		assert!(!<frame_system::Pallet<Test>>::events().iter().any(|record| matches!(
+			record.event,
+			crate::mock::RuntimeEvent::Creditcoin(crate::Event::DealOrderDefaulted(_))
+		)));
+	});
+}
+
+#[test]
+fn exempt_should_error_when_not_signed() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, _) = test_info.create_deal_order();
+
+		assert_noop!(Creditcoin::exempt(Origin::none(), deal_order_id, None, None), BadOrigin);
+	});
+}
+
+#[test]
+fn exempt_should_error_when_deal_order_has_already_been_repaid() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, _) = test_info.create_deal_order();
+
+		// simulate DealOrder which has been repaid
+		crate::DealOrders::<Test>::mutate(
+			deal_order_id.expiration(),
+			deal_order_id.hash(),
+			|deal_order_storage| {
+				deal_order_storage.as_mut().unwrap().status = DealOrderStatus::Closed;
+			},
+		);
+
+		assert_noop!(
+			Creditcoin::exempt(
+				Origin::signed(test_info.lender.account_id),
+				deal_order_id,
+				None,
+				None
+			),
+			crate::Error::<Test>::DealOrderAlreadyClosed
+		);
+	});
+}
+
+#[test]
+fn exempt_should_error_for_non_lender() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, _) = test_info.create_deal_order();
+
+		assert_noop!(
+			Creditcoin::exempt(
+				Origin::signed(test_info.borrower.account_id),
+				deal_order_id,
+				None,
+				None
+			),
+			crate::Error::<Test>::NotLender
+		);
+	});
+}
+
+#[test]
+fn exempt_should_error_when_deal_order_is_not_funded() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, _) = test_info.create_deal_order();
+
+		assert_noop!(
+			Creditcoin::exempt(
+				Origin::signed(test_info.lender.account_id),
+				deal_order_id,
+				None,
+				None
+			),
+			crate::Error::<Test>::DealNotFunded
+		);
+	});
+}
+
+#[test]
+fn exempt_should_succeed() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, deal_order) = fund_deal_order_for_repayment(&test_info);
+
+		assert_ok!(Creditcoin::exempt(
+			Origin::signed(test_info.lender.account_id.clone()),
+			deal_order_id.clone(),
+			None,
+			None
+		));
+
+		// assert field values were updated in storage
+		let saved_deal_order = DealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
+		assert_eq!(saved_deal_order.repayment_transfer_id, None);
+		assert_eq!(saved_deal_order.status, DealOrderStatus::Exempted);
+		assert_eq!(
+			saved_deal_order.exemption,
+			Some(Exemption {
+				lender: test_info.lender.account_id,
+				timestamp: Creditcoin::timestamp(),
+				amount: deal_order.amount_owed(Creditcoin::timestamp()),
+				reason: None,
+			})
+		);
+
+		// assert events in reversed order
+		let mut all_events = <frame_system::Pallet<Test>>::events();
+		let event = all_events.pop().expect("Expected at least one EventRecord to be found").event;
+		assert_eq!(
+			event,
+			crate::mock::RuntimeEvent::Creditcoin(crate::Event::LoanExempted(deal_order_id))
+		);
+	});
+}
+
+#[test]
+fn exempt_should_forgive_the_outstanding_amount_of_a_funded_deal_order() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, deal_order) = fund_deal_order_for_repayment(&test_info);
+		let reason = ExemptionReason::try_from(b"borrower in hardship".to_vec()).unwrap();
+
+		assert_ok!(Creditcoin::exempt(
+			Origin::signed(test_info.lender.account_id.clone()),
+			deal_order_id.clone(),
+			None,
+			Some(reason.clone())
+		));
+
+		let saved_deal_order = DealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
+		assert_eq!(saved_deal_order.status, DealOrderStatus::Exempted);
+		assert_eq!(
+			saved_deal_order.exemption,
+			Some(Exemption {
+				lender: test_info.lender.account_id,
+				timestamp: Creditcoin::timestamp(),
+				amount: deal_order.amount_owed(Creditcoin::timestamp()),
+				reason: Some(reason),
+			})
+		);
+		assert!(crate::ClosedDealOrders::<Test>::contains_key(1, &deal_order_id));
+	});
+}
+
+#[test]
+fn exempt_should_partially_forgive_and_close_once_the_rest_is_repaid() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, deal_order) = fund_deal_order_for_repayment(&test_info);
+		let owed = deal_order.amount_owed(Creditcoin::timestamp());
+		let forgiven = owed / 4u64;
+
+		assert_ok!(Creditcoin::exempt(
+			Origin::signed(test_info.lender.account_id.clone()),
+			deal_order_id.clone(),
+			Some(forgiven),
+			None
+		));
+
+		let saved_deal_order = DealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
+		assert_eq!(saved_deal_order.status, DealOrderStatus::Funded);
+		assert_eq!(saved_deal_order.forgiven_amount(), forgiven);
+		assert_eq!(saved_deal_order.amount_due(Creditcoin::timestamp()), owed - forgiven);
+		let event = System::events().pop().expect("expected an event").event;
+		assert_eq!(
+			event,
+			crate::mock::RuntimeEvent::Creditcoin(crate::Event::LoanPartiallyExempted(
+				deal_order_id.clone(),
+				forgiven
+			))
+		);
+
+		lock_for_repayment(&test_info, &deal_order_id);
+		let transfer_id =
+			persist_repayment_transfer(&test_info, &deal_order_id, owed - forgiven, "0xafafaf");
+
+		let saved_deal_order = DealOrders::<Test>::try_get_id(&deal_order_id).unwrap();
+		assert_eq!(saved_deal_order.status, DealOrderStatus::Closed);
+		assert_eq!(saved_deal_order.repayment_transfer_id, Some(transfer_id));
+		assert_eq!(saved_deal_order.forgiven_amount(), forgiven);
+	});
+}
+
+#[test]
+fn exempt_should_error_when_amount_is_zero() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, _) = fund_deal_order_for_repayment(&test_info);
+
+		assert_noop!(
+			Creditcoin::exempt(
+				Origin::signed(test_info.lender.account_id),
+				deal_order_id,
+				Some(ExternalAmount::zero()),
+				None
+			),
+			crate::Error::<Test>::InvalidExemptionAmount
+		);
+	});
+}
+
+#[test]
+fn verify_transfer_should_error_when_not_signed() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, _) = test_info.create_deal_order();
+		let (transfer_id, transfer) = test_info.create_funding_transfer(&deal_order_id);
+		let deadline = Test::unverified_transfer_deadline();
+		assert_noop!(
+			Creditcoin::persist_task_output(
+				Origin::none(),
+				deadline,
+				(transfer_id, transfer).into()
+			),
+			BadOrigin
+		);
+	});
+}
+
+#[test]
+fn verify_transfer_should_error_when_signer_not_authorized() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, _) = test_info.create_deal_order();
+		let (transfer_id, transfer) = test_info.create_funding_transfer(&deal_order_id);
+		let deadline = Test::unverified_transfer_deadline();
+		assert_noop!(
+			Creditcoin::persist_task_output(
+				Origin::signed(test_info.lender.account_id),
+				deadline,
+				(transfer_id, transfer).into(),
+			),
+			crate::Error::<Test>::InsufficientAuthority,
+		);
+	});
+}
+
+#[test]
+fn verify_transfer_should_error_when_transfer_has_already_been_registered() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+
+		// authorize lender
+		let root = RawOrigin::Root;
+		assert_ok!(Creditcoin::add_authority(
+			crate::mock::RuntimeOrigin::from(root),
+			test_info.lender.account_id.clone(),
+		));
+
+		let (deal_order_id, _) = test_info.create_deal_order();
+		let (transfer_id, transfer) = test_info.create_funding_transfer(&deal_order_id);
+		let deadline = Test::unverified_transfer_deadline();
+
+		assert_noop!(
+			Creditcoin::persist_task_output(
+				Origin::signed(test_info.lender.account_id),
+				deadline,
+				(transfer_id, transfer).into(),
+			),
+			non_paying_error(crate::Error::<Test>::TransferAlreadyRegistered),
+		);
+	});
+}
+
+#[test]
+fn verify_transfer_should_work() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
+		let test_info = TestInfo::new_defaults();
+
+		// authorize lender
+		let root = RawOrigin::Root;
+		assert_ok!(Creditcoin::add_authority(
+			crate::mock::RuntimeOrigin::from(root),
+			test_info.lender.account_id.clone(),
+		));
+
+		let (deal_order_id, deal_order) = test_info.create_deal_order();
+
+		// create a transfer but don't add it into storage
+		let tx = "0xafafaf".as_bytes().into_bounded();
+		let transfer_id = TransferId::new::<Test>(&Blockchain::Rinkeby, &tx);
+		let transfer = Transfer {
+			blockchain: test_info.blockchain.clone(),
+			kind: TransferKind::Native,
+			from: test_info.lender.address_id.clone(),
+			to: test_info.borrower.address_id.clone(),
+			order_id: OrderId::Deal(deal_order_id),
+			amount: deal_order.terms.amount,
+			tx_id: tx,
+			block: System::block_number(),
+			is_processed: false,
+			account_id: test_info.lender.account_id.clone(),
+			timestamp: None,
+		};
+		let deadline = Test::unverified_transfer_deadline();
+
+		assert_ok!(Creditcoin::persist_task_output(
+			Origin::signed(test_info.lender.account_id),
+			deadline,
+			(transfer_id.clone(), transfer.clone()).into(),
+		));
+
+		let mut all_events = <frame_system::Pallet<Test>>::events();
+
+		// assert events in reversed order
+		let last_event = all_events.pop().expect("At least one EventRecord").event;
+		assert_matches!(
+			last_event,
+			crate::mock::RuntimeEvent::Creditcoin(crate::Event::TransferVerified(id)) => {
+				assert_eq!(transfer_id, id)
+			}
+		);
+
+		assert_eq!(Transfers::<Test>::get(&transfer_id), Some(transfer));
+	});
+}
+
+#[test]
+fn fail_transfer_should_work() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
+		let test_info = TestInfo::new_defaults();
+
+		let root = RawOrigin::Root;
+		assert_ok!(Creditcoin::add_authority(
+			crate::mock::RuntimeOrigin::from(root),
+			test_info.lender.account_id.clone(),
+		));
+
+		let _ = test_info.create_deal_order();
+
+		let tx = "0xafafaf".hex_to_address();
+		let transfer_id = TransferId::new::<Test>(&Blockchain::Rinkeby, &tx);
+
+		let failure_cause = crate::ocw::errors::VerificationFailureCause::TaskFailed;
+		let deadline = Test::unverified_transfer_deadline();
+
+		assert_ok!(Creditcoin::fail_task(
+			Origin::signed(test_info.lender.account_id),
+			deadline,
+			transfer_id.clone().into(),
+			failure_cause
+		));
+
+		let mut all_events = System::events();
+
+		assert_matches!(
+			all_events.pop().unwrap().event,
+			crate::mock::RuntimeEvent::Creditcoin(crate::Event::<Test>::TransferFailedVerification(id, cause)) => {
+				assert_eq!(id, transfer_id);
+				assert_eq!(cause, failure_cause);
+			}
+		);
+	})
+}
+
+#[test]
+fn fail_transfer_should_error_when_not_signed() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
+		let test_info = TestInfo::new_defaults();
+
+		let _ = test_info.create_deal_order();
+
+		let tx = "0xafafaf".hex_to_address();
+		let transfer_id = TransferId::new::<Test>(&Blockchain::Rinkeby, &tx);
+
+		let failure_cause = crate::ocw::errors::VerificationFailureCause::TaskFailed;
+		let deadline = Test::unverified_transfer_deadline();
+
+		assert_noop!(
+			Creditcoin::fail_task(Origin::none(), deadline, transfer_id.into(), failure_cause),
+			BadOrigin
+		);
+	})
+}
+
+#[test]
+fn fail_transfer_should_error_when_not_authority() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
+		let test_info = TestInfo::new_defaults();
+
+		let _ = test_info.create_deal_order();
+
+		let tx = "0xafafaf".hex_to_address();
+		let transfer_id = TransferId::new::<Test>(&Blockchain::Rinkeby, &tx);
+
+		let failure_cause = crate::ocw::errors::VerificationFailureCause::TaskFailed;
+		let deadline = Test::unverified_transfer_deadline();
+
+		assert_noop!(
+			Creditcoin::fail_task(
+				Origin::signed(test_info.lender.account_id),
+				deadline,
+				transfer_id.into(),
+				failure_cause
+			),
+			crate::Error::<Test>::InsufficientAuthority
+		);
+	})
+}
+
+#[test]
+fn fail_transfer_should_error_when_transfer_registered() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
+		let test_info = TestInfo::new_defaults();
+
+		let (deal_order_id, _) = test_info.create_deal_order();
+
+		let (transfer_id, _) = test_info.create_funding_transfer(&deal_order_id);
+
+		let root = RawOrigin::Root;
+		assert_ok!(Creditcoin::add_authority(
+			crate::mock::RuntimeOrigin::from(root),
+			test_info.lender.account_id.clone(),
+		));
+
+		let failure_cause = crate::ocw::errors::VerificationFailureCause::TaskFailed;
+		let deadline = Test::unverified_transfer_deadline();
+
+		assert_noop!(
+			Creditcoin::fail_task(
+				Origin::signed(test_info.lender.account_id),
+				deadline,
+				transfer_id.into(),
+				failure_cause
+			),
+			crate::Error::<Test>::TransferAlreadyRegistered
+		);
+	})
+}
+
+#[test]
+fn on_initialize_removes_expired_deals_without_transfers() {
+	ExtBuilder::default().build_offchain_and_execute(|| {
+		System::set_block_number(1);
+		let mut expected_deal_orders = Vec::new();
+
+		let now = System::block_number();
+		for expiration_block in now..=20 {
+			let seed1 = format!("{:02}0", expiration_block.clone());
+			let seed2 = format!("{:02}1", expiration_block.clone());
+
+			let test_info = TestInfo {
+				lender: RegisteredAddress::new(&seed1, Blockchain::Rinkeby),
+				borrower: RegisteredAddress::new(&seed2, Blockchain::Rinkeby),
+				blockchain: Blockchain::Rinkeby,
+				loan_terms: LoanTerms {
+					amount: 2_000_000u64.into(),
+					currency: LoanCurrency::Native,
+					interest_rate: Default::default(),
+					term_length: Duration::from_millis(1_000_000),
+					collateral: 0,
+				},
+				ask_guid: format!("{:?}-ask-guid", expiration_block.clone())
+					.as_bytes()
+					.into_bounded(),
+				bid_guid: format!("{:?}-bid-guid", expiration_block.clone())
+					.as_bytes()
+					.into_bounded(),
+				expiration_block,
+			};
+
+			let (offer_id, _) = test_info.create_offer();
+			assert_ok!(Creditcoin::add_deal_order(
+				Origin::signed(test_info.borrower.account_id.clone()),
+				offer_id.clone(),
+				expiration_block,
+			));
+			let deal_order_id = DealOrderId::new::<Test>(expiration_block, &offer_id);
+			let deal_order =
+				Creditcoin::deal_orders(deal_order_id.expiration(), deal_order_id.hash()).unwrap();
+
+			// fund only deal orders which expire at even blocks
+			if expiration_block % 2 == 0 {
+				let tx = format!("0xfafafa{:02}", expiration_block.clone());
+				assert_ok!(Creditcoin::register_funding_transfer(
+					Origin::signed(test_info.lender.account_id.clone()),
+					TransferKind::Native,
+					deal_order_id.clone(),
+					tx.as_bytes().into_bounded()
+				));
+				let (transfer_id, _) = test_info.mock_transfer(
+					&test_info.lender,
+					&test_info.borrower,
+					deal_order.terms.amount,
+					&deal_order_id,
+					tx,
+				);
+
+				// attach transfer to deal order
+				assert_ok!(Creditcoin::fund_deal_order(
+					Origin::signed(test_info.lender.account_id.clone()),
+					deal_order_id.clone(),
+					transfer_id.clone()
+				));
+				// it's funded so it should be kept
+				expected_deal_orders.push(deal_order_id.clone());
+			} else if expiration_block > 15 {
+				// still hasn't expired so should be kept regardless
+				expected_deal_orders.push(deal_order_id.clone());
+			}
+		}
+
+		// advance blocks, will perform housekeeping
+		roll_to(15);
+
+		for expected_order_id in expected_deal_orders.iter() {
+			let _order = DealOrders::<Test>::try_get_id(&expected_order_id).unwrap();
+		}
+	});
+}
+
+#[test]
+fn register_funding_transfer_should_error_when_not_signed() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, _) = test_info.create_deal_order();
+		let tx = "0xabcabcabc";
+
+		assert_noop!(
+			Creditcoin::register_funding_transfer(
+				Origin::none(),
+				TransferKind::Native,
+				deal_order_id,
+				tx.as_bytes().into_bounded()
+			),
+			BadOrigin
+		);
+	})
+}
+
+#[test]
+fn register_funding_transfer_should_error_when_not_deal_order_not_found() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (_, deal_order) = test_info.create_deal_order();
+		let DealOrder { offer_id, .. } = deal_order;
+		// expiration_block set to 0
+		let deal_order_id = DealOrderId::new::<Test>(0, &offer_id);
+
+		let tx = "0xabcabcabc";
+
+		assert_noop!(
+			Creditcoin::register_funding_transfer(
+				Origin::signed(test_info.lender.account_id),
+				TransferKind::Native,
+				deal_order_id,
+				tx.as_bytes().into_bounded()
+			),
+			crate::Error::<Test>::NonExistentDealOrder
+		);
+	})
+}
+
+#[test]
+fn register_repayment_transfer_should_error_when_not_signed() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, _) = test_info.create_deal_order();
+		let tx = "0xabcabcabc";
+
+		assert_noop!(
+			Creditcoin::register_repayment_transfer(
+				Origin::none(),
+				TransferKind::Native,
+				21u64.into(),
+				deal_order_id,
+				tx.as_bytes().into_bounded()
+			),
+			BadOrigin
+		);
+	})
+}
+
+#[test]
+fn register_repayment_transfer_should_error_when_not_deal_order_not_found() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (_, deal_order) = test_info.create_deal_order();
+		let DealOrder { offer_id, .. } = deal_order;
+		// expiration_block set to 0
+		let deal_order_id = DealOrderId::new::<Test>(0, &offer_id);
+
+		let tx = "0xabcabcabc";
+
+		assert_noop!(
+			Creditcoin::register_repayment_transfer(
+				Origin::signed(test_info.borrower.account_id),
+				TransferKind::Native,
+				21u64.into(),
+				deal_order_id,
+				tx.as_bytes().into_bounded()
+			),
+			crate::Error::<Test>::NonExistentDealOrder
+		);
+	})
+}
+
+#[test]
+fn register_transfer_internal_should_error_with_non_existent_lender_address() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, deal_order) = test_info.create_deal_order();
+		let tx = "0xabcabcabc";
+		let bogus_address =
+			AddressId::new::<Test>(&Blockchain::Rinkeby, &[1, 2, 3, 4, 5, 6, 7, 8, 9, 0]);
+
+		let result = Creditcoin::register_transfer_internal(
+			test_info.lender.account_id,
+			bogus_address,
+			deal_order.borrower_address_id,
+			TransferKind::Native,
+			deal_order.terms.amount,
+			OrderId::Deal(deal_order_id),
+			tx.as_bytes().into_bounded(),
+		)
+		.unwrap_err();
+
+		assert_eq!(result, crate::Error::<Test>::NonExistentAddress);
+	})
+}
+
+#[test]
+fn register_transfer_internal_should_error_when_addresses_are_not_on_the_same_blockchain() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, deal_order) = test_info.create_deal_order();
+		let second_borrower = RegisteredAddress::new("borrower2", Blockchain::Luniverse);
+		let tx = "0xabcabcabc";
+
+		let result = Creditcoin::register_transfer_internal(
+			test_info.lender.account_id,
+			deal_order.lender_address_id,
+			second_borrower.address_id,
+			TransferKind::Native,
+			deal_order.terms.amount,
+			OrderId::Deal(deal_order_id),
+			tx.as_bytes().into_bounded(),
+		)
+		.unwrap_err();
+
+		assert_eq!(result, crate::Error::<Test>::AddressBlockchainMismatch);
+	})
+}
+
+#[test]
+fn register_transfer_internal_should_error_when_transfer_kind_is_not_supported() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, deal_order) = test_info.create_deal_order();
+		let tx = "0xabcabcabc";
+
+		let result = Creditcoin::register_transfer_internal(
+			test_info.lender.account_id,
+			deal_order.lender_address_id,
+			deal_order.borrower_address_id,
+			// not supported on Blockchain::Rinkeby
+			TransferKind::Other(BoundedVec::try_from(b"other".to_vec()).unwrap()),
+			deal_order.terms.amount,
+			OrderId::Deal(deal_order_id),
+			tx.as_bytes().into_bounded(),
+		)
+		.unwrap_err();
+
+		assert_eq!(result, crate::Error::<Test>::UnsupportedTransferKind);
+	})
+}
+
+#[test]
+fn register_transfer_internal_should_error_when_transfer_is_already_registered() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, deal_order) = test_info.create_deal_order();
+		let (_, transfer) = test_info.create_funding_transfer(&deal_order_id);
+
+		let result = Creditcoin::register_transfer_internal(
+			test_info.lender.account_id,
+			deal_order.lender_address_id,
+			deal_order.borrower_address_id,
+			TransferKind::Native,
+			deal_order.terms.amount,
+			OrderId::Deal(deal_order_id),
+			transfer.tx_id,
+		)
+		.unwrap_err();
+
+		assert_eq!(result, crate::Error::<Test>::TransferAlreadyRegistered);
+	})
+}
+
+#[test]
+fn exercise_weightinfo_functions() {
+	let result = super::weights::WeightInfo::<Test>::register_address();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::claim_legacy_wallet();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::add_ask_order();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::add_bid_order();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::add_offer();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::add_deal_order();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::add_authority();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::persist_transfer();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::fail_transfer();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::fund_deal_order();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::lock_deal_order();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::register_funding_transfer();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::register_repayment_transfer();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::close_deal_order();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::exempt();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::register_deal_order();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::request_collect_coins();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::fail_collect_coins();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::persist_collect_coins();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::remove_authority();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::set_collect_coins_contract();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::mark_defaulted_deal_orders(1);
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::cancel_ask_order();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::cancel_bid_order();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::cancel_offer();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::transfer_deal_order();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::claim_collateral();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::set_order_matching();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::match_pending_orders(1, 10);
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::propose_terms();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::accept_terms();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::add_deal_order_share();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::register_share_repayment_transfer();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::set_deal_order_retention();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::archive_deal_orders(1, 1, 1);
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::add_repayment_order();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::cancel_repayment_order();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::register_repayment_order_transfer();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::set_origination_fee();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::unregister_address(1);
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::transfer_address(1);
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::register_contract_wallet();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::persist_contract_wallet();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::fail_contract_wallet();
+	assert!(result.ref_time() > 0);
+
+	let result = super::weights::WeightInfo::<Test>::set_external_chain();
+	assert!(result.ref_time() > 0);
+}
+
+#[test]
+fn remove_authority_errors_for_non_root() {
+	ExtBuilder::default().build_and_execute(|| {
+		let acct: AccountId = AccountId::new([0; 32]);
+
+		assert_noop!(Creditcoin::remove_authority(Origin::signed(acct.clone()), acct), BadOrigin);
+	});
+}
+
+#[test]
+fn remove_authority_should_fail_when_authority_does_not_exist() {
+	ExtBuilder::default().build_and_execute(|| {
+		let root = RawOrigin::Root;
+		let acct: AccountId = AccountId::new([0; 32]);
+
+		assert_noop!(
+			Creditcoin::remove_authority(crate::mock::RuntimeOrigin::from(root), acct),
+			crate::Error::<Test>::NotAnAuthority,
+		);
+	});
+}
+
+#[test]
+fn add_and_remove_authority_works_for_root() {
+	ExtBuilder::default().build_and_execute(|| {
+		let root = RawOrigin::Root;
+		let account: AccountId = AccountId::new([0; 32]);
+
+		assert!(!TaskScheduler::is_authority(&account));
+		TaskScheduler::insert_authority(&account);
+		assert!(TaskScheduler::is_authority(&account));
+
+		assert_ok!(Creditcoin::remove_authority(Origin::from(root), account.clone()));
+		assert!(!TaskScheduler::is_authority(&account));
+	});
+}
+
+#[test]
+fn register_address_v2_should_work() {
+	ExtBuilder::default().build_and_execute(|| {
+		System::set_block_number(1);
+
+		let (who, address, ownership_proof, _) = generate_address_with_proof("owner");
+		let blockchain = Blockchain::Rinkeby;
+
+		let proof = OwnershipProof::EthSign(ownership_proof);
+
+		assert_ok!(Creditcoin::register_address_v2(
+			Origin::signed(who.clone()),
+			blockchain.clone(),
+			address.clone(),
+			proof,
+		));
+		let address_id = crate::AddressId::new::<Test>(&blockchain, &address);
+		let address = crate::Address { blockchain, value: address, owner: who };
+		assert_eq!(Creditcoin::addresses(address_id.clone()), Some(address.clone()));
+
+		let event = <frame_system::Pallet<Test>>::events().pop().expect("an event").event;
+
+		assert_matches!(
+			event,
+			crate::mock::RuntimeEvent::Creditcoin(crate::Event::<Test>::AddressRegistered(registered_address_id, registered_address)) => {
+				assert_eq!(registered_address_id, address_id);
+				assert_eq!(registered_address, address);
+			}
+		);
+	});
+}
+
+#[test]
+fn register_address_v2_should_fail_to_reregister_external_address_to_same_account() {
+	ExtBuilder::default().build_and_execute(|| {
+		let (who, external_address, ownership_proof, _) = generate_address_with_proof("owner");
+		let blockchain = Blockchain::Rinkeby;
+		// Register external address to account
+		assert_ok!(Creditcoin::register_address(
+			Origin::signed(who.clone()),
+			blockchain.clone(),
+			external_address.clone(),
+			ownership_proof.clone()
+		));
+
+		let proof = OwnershipProof::EthSign(ownership_proof);
+
+		// Try registering again to same account and fail
+		assert_noop!(
+			Creditcoin::register_address_v2(
+				Origin::signed(who),
+				blockchain,
+				external_address,
+				proof,
+			),
+			crate::Error::<Test>::AddressAlreadyRegisteredByCaller
+		);
+	})
+}
+
+#[test]
+fn register_address_v2_should_fail_to_reregister_external_address_to_new_account() {
+	ExtBuilder::default().build_and_execute(|| {
+		let who_first = account_from_keypair(generate_keypair_from_seed("first account"));
+		let who_second = account_from_keypair(generate_keypair_from_seed("second account"));
+		let external_keypair = generate_keypair_from_seed("external account");
+		let external_address = external_address_from_keypair(external_keypair.clone());
+		let blockchain = Blockchain::Rinkeby;
+
+		// Register external address to first account
+		let first_ownership_proof =
+			build_proof_of_ownership(who_first.clone(), external_keypair.clone());
+
+		let proof = OwnershipProof::EthSign(first_ownership_proof);
+		assert_ok!(Creditcoin::register_address_v2(
+			Origin::signed(who_first),
+			blockchain.clone(),
+			external_address.clone(),
+			proof,
+		));
+		// Try registering to a second account and fail
+		let second_ownership_proof = build_proof_of_ownership(who_second.clone(), external_keypair);
+
+		let second_proof = OwnershipProof::EthSign(second_ownership_proof);
+		assert_noop!(
+			Creditcoin::register_address_v2(
+				Origin::signed(who_second),
+				blockchain,
+				external_address,
+				second_proof
+			),
+			crate::Error::<Test>::AddressAlreadyRegistered
+		);
+	})
+}
+
+#[test]
+fn register_address_v2_should_error_when_not_signed() {
+	ExtBuilder::default().build_and_execute(|| {
+		let (_who, address, ownership_proof, _) = generate_address_with_proof("owner");
+		let blockchain = Blockchain::Rinkeby;
+
+		let proof = OwnershipProof::EthSign(ownership_proof);
+		assert_noop!(
+			Creditcoin::register_address_v2(Origin::none(), blockchain, address, proof),
+			BadOrigin,
+		);
+	})
+}
+
+#[test]
+fn register_address_v2_should_error_when_using_wrong_ownership_proof() {
+	ExtBuilder::default().build_and_execute(|| {
+		let (who, address, _ownership_proof, _) = generate_address_with_proof("owner");
+		let (_who2, _address2, ownership_proof2, _) = generate_address_with_proof("bogus");
+
+		let blockchain = Blockchain::Rinkeby;
+		let bad_proof = OwnershipProof::EthSign(ownership_proof2);
+		assert_noop!(
+			Creditcoin::register_address_v2(Origin::signed(who), blockchain, address, bad_proof),
+			crate::Error::<Test>::OwnershipNotSatisfied
+		);
+	})
+}
+
+#[test]
+fn register_address_v2_should_error_when_address_too_long() {
+	ExtBuilder::default().build_and_execute(|| {
+		let (who, address, ownership_proof, _) = generate_address_with_proof("owner");
+		let address = format!("0xff{}", hex::encode(address)).hex_to_address();
+		let blockchain = Blockchain::Rinkeby;
+		let proof = OwnershipProof::EthSign(ownership_proof);
+		assert_noop!(
+			Creditcoin::register_address_v2(Origin::signed(who), blockchain, address, proof),
+			crate::Error::<Test>::EthSignExternalAddressGenerationFailed
+		);
+	})
+}
+
+#[test]
+fn register_address_v2_should_error_when_signature_is_invalid() {
+	ExtBuilder::default().build_and_execute(|| {
+		let (who, address, _ownership_proof, _) = generate_address_with_proof("owner");
+
+		// NOTE: No checking goes on to ensure this is a real signature! See
+		// https://docs.rs/sp-core/2.0.0-rc4/sp_core/ecdsa/struct.Signature.html#method.from_raw
+		let ownership_proof = sp_core::ecdsa::Signature::from_raw([0; 65]);
+
+		let bad_proof = OwnershipProof::EthSign(ownership_proof);
+
+		let blockchain = Blockchain::Rinkeby;
+		assert_noop!(
+			Creditcoin::register_address_v2(Origin::signed(who), blockchain, address, bad_proof),
+			crate::Error::<Test>::EthSignPublicKeyRecoveryFailed
+		);
+	})
+}
+
+/// Gives `blockchain` the EVM chain id typed data ownership proofs are signed for, which the mock
+/// registry of external chains leaves out.
+fn set_evm_chain_id(blockchain: &Blockchain, chain_id: u64) {
+	crate::ExternalChains::<Test>::mutate(blockchain, |chain| {
+		chain.as_mut().unwrap().evm_chain_id = Some(chain_id);
+	});
+}
+
+#[test]
+fn register_address_v2_should_work_with_typed_data() {
+	ExtBuilder::default().build_and_execute(|| {
+		let (who, address, _, key_pair) = generate_address_with_proof("owner");
+		let blockchain = Blockchain::Rinkeby;
+		set_evm_chain_id(&blockchain, 4);
+
+		let hash =
+			crate::helpers::typed_data_hash::<Test>(who.encode().as_slice(), &blockchain).unwrap();
+		let proof = OwnershipProof::TypedData(key_pair.sign_prehashed(&hash));
+
+		assert_ok!(Creditcoin::register_address_v2(
+			Origin::signed(who.clone()),
+			blockchain.clone(),
+			address.clone(),
+			proof,
+		));
+		let address_id = AddressId::new::<Test>(&blockchain, &address);
+		let address = crate::Address { blockchain, value: address, owner: who };
+		assert_eq!(Creditcoin::addresses(address_id), Some(address));
+	});
+}
+
+#[test]
+fn register_address_v2_should_error_when_typed_data_is_for_another_chain() {
+	ExtBuilder::default().build_and_execute(|| {
+		let (who, address, _, key_pair) = generate_address_with_proof("owner");
+		set_evm_chain_id(&Blockchain::Ethereum, 1);
+		set_evm_chain_id(&Blockchain::Rinkeby, 4);
+
+		let hash =
+			crate::helpers::typed_data_hash::<Test>(who.encode().as_slice(), &Blockchain::Ethereum)
+				.unwrap();
+		let proof = OwnershipProof::TypedData(key_pair.sign_prehashed(&hash));
+
+		assert_noop!(
+			Creditcoin::register_address_v2(
+				Origin::signed(who),
+				Blockchain::Rinkeby,
+				address,
+				proof
+			),
+			crate::Error::<Test>::OwnershipNotSatisfied
+		);
 	});
 }
 
 #[test]
-fn register_funding_transfer_should_error_when_not_signed() {
+fn register_address_v2_should_error_when_typed_data_chain_has_no_evm_chain_id() {
 	ExtBuilder::default().build_and_execute(|| {
-		let test_info = TestInfo::new_defaults();
-		let (deal_order_id, _) = test_info.create_deal_order();
-		let tx = "0xabcabcabc";
+		let (who, address, _, key_pair) = generate_address_with_proof("owner");
+		let proof = OwnershipProof::TypedData(key_pair.sign_prehashed(&[0u8; 32]));
 
 		assert_noop!(
-			Creditcoin::register_funding_transfer(
-				Origin::none(),
-				TransferKind::Native,
-				deal_order_id,
-				tx.as_bytes().into_bounded()
+			Creditcoin::register_address_v2(
+				Origin::signed(who),
+				Blockchain::Rinkeby,
+				address,
+				proof
 			),
-			BadOrigin
+			crate::Error::<Test>::TypedDataChainIdUnknown
 		);
-	})
+	});
+}
+
+/// Signs the ownership proof for `who` the way bitcoin wallets sign messages, with the header of
+/// a compressed or uncompressed key.
+fn build_bitcoin_proof_of_ownership(
+	who: AccountId,
+	key_pair: sp_core::ecdsa::Pair,
+	compressed: bool,
+) -> OwnershipProof {
+	let message = crate::helpers::bitcoin_sign_message(who.encode().as_slice());
+	let hash = crate::helpers::bitcoin_message_hash(&message);
+	let signature = <[u8; 65]>::from(key_pair.sign_prehashed(&hash));
+
+	let mut proof = [0u8; 65];
+	proof[0] = if compressed { 31 } else { 27 } + signature[64];
+	proof[1..].copy_from_slice(&signature[..64]);
+	OwnershipProof::BitcoinSign(proof)
 }
 
 #[test]
-fn register_funding_transfer_should_error_when_not_deal_order_not_found() {
+fn register_address_v2_should_work_with_bitcoin_sign() {
 	ExtBuilder::default().build_and_execute(|| {
-		let test_info = TestInfo::new_defaults();
-		let (_, deal_order) = test_info.create_deal_order();
-		let DealOrder { offer_id, .. } = deal_order;
-		// expiration_block set to 0
-		let deal_order_id = DealOrderId::new::<Test>(0, &offer_id);
+		let key_pair = generate_keypair_from_seed("owner");
+		let who = account_from_keypair(key_pair.clone());
+		let blockchain = Blockchain::Bitcoin;
+
+		// p2pkh, p2sh-p2wpkh and p2wpkh addresses of the same key
+		for address in [
+			"1D8DPnxNbNFanFkC5NCLP8JicmJKH4hJPo",
+			"3LAc4mAHZVjgtaQonVcZHZ5rrXvRTHPh7B",
+			"bc1qsnlvg23khe6hk4qqw38298qlmelf9jc7jvmxg8",
+		] {
+			let address: ExternalAddress = address.as_bytes().into_bounded();
+			assert_ok!(Creditcoin::register_address_v2(
+				Origin::signed(who.clone()),
+				blockchain.clone(),
+				address.clone(),
+				build_bitcoin_proof_of_ownership(who.clone(), key_pair.clone(), true),
+			));
 
-		let tx = "0xabcabcabc";
+			let address_id = AddressId::new::<Test>(&blockchain, &address);
+			let address = crate::Address {
+				blockchain: blockchain.clone(),
+				value: address,
+				owner: who.clone(),
+			};
+			assert_eq!(Creditcoin::addresses(address_id), Some(address));
+		}
+
+		let address = "1J2nB2t7wSUATtdtdaJFSi6NyfDmfuTwS2".as_bytes().into_bounded();
+		assert_ok!(Creditcoin::register_address_v2(
+			Origin::signed(who.clone()),
+			blockchain,
+			address,
+			build_bitcoin_proof_of_ownership(who, key_pair, false),
+		));
+	});
+}
+
+#[test]
+fn register_address_v2_should_error_when_bitcoin_sign_is_for_another_account() {
+	ExtBuilder::default().build_and_execute(|| {
+		let key_pair = generate_keypair_from_seed("owner");
+		let who = account_from_keypair(key_pair.clone());
+		let other = account_from_keypair(generate_keypair_from_seed("other"));
+		let address = "bc1qsnlvg23khe6hk4qqw38298qlmelf9jc7jvmxg8".as_bytes().into_bounded();
 
 		assert_noop!(
-			Creditcoin::register_funding_transfer(
-				Origin::signed(test_info.lender.account_id),
-				TransferKind::Native,
-				deal_order_id,
-				tx.as_bytes().into_bounded()
+			Creditcoin::register_address_v2(
+				Origin::signed(who),
+				Blockchain::Bitcoin,
+				address,
+				build_bitcoin_proof_of_ownership(other, key_pair, true),
 			),
-			crate::Error::<Test>::NonExistentDealOrder
+			crate::Error::<Test>::OwnershipNotSatisfied
 		);
-	})
+	});
 }
 
 #[test]
-fn register_repayment_transfer_should_error_when_not_signed() {
+fn register_address_v2_should_error_when_bitcoin_sign_uses_uncompressed_key_for_segwit() {
 	ExtBuilder::default().build_and_execute(|| {
-		let test_info = TestInfo::new_defaults();
-		let (deal_order_id, _) = test_info.create_deal_order();
-		let tx = "0xabcabcabc";
+		let key_pair = generate_keypair_from_seed("owner");
+		let who = account_from_keypair(key_pair.clone());
+		let address = "bc1qsnlvg23khe6hk4qqw38298qlmelf9jc7jvmxg8".as_bytes().into_bounded();
 
 		assert_noop!(
-			Creditcoin::register_repayment_transfer(
-				Origin::none(),
-				TransferKind::Native,
-				21u64.into(),
-				deal_order_id,
-				tx.as_bytes().into_bounded()
+			Creditcoin::register_address_v2(
+				Origin::signed(who.clone()),
+				Blockchain::Bitcoin,
+				address,
+				build_bitcoin_proof_of_ownership(who, key_pair, false),
 			),
-			BadOrigin
+			crate::Error::<Test>::BitcoinSignExternalAddressGenerationFailed
 		);
-	})
+	});
 }
 
 #[test]
-fn register_repayment_transfer_should_error_when_not_deal_order_not_found() {
+fn register_address_v2_should_error_when_bitcoin_sign_header_is_invalid() {
 	ExtBuilder::default().build_and_execute(|| {
-		let test_info = TestInfo::new_defaults();
-		let (_, deal_order) = test_info.create_deal_order();
-		let DealOrder { offer_id, .. } = deal_order;
-		// expiration_block set to 0
-		let deal_order_id = DealOrderId::new::<Test>(0, &offer_id);
+		let key_pair = generate_keypair_from_seed("owner");
+		let who = account_from_keypair(key_pair.clone());
+		let address = "bc1qsnlvg23khe6hk4qqw38298qlmelf9jc7jvmxg8".as_bytes().into_bounded();
 
-		let tx = "0xabcabcabc";
+		let mut proof = match build_bitcoin_proof_of_ownership(who.clone(), key_pair, true) {
+			OwnershipProof::BitcoinSign(proof) => proof,
+			_ => unreachable!(),
+		};
+		proof[0] = 0;
 
 		assert_noop!(
-			Creditcoin::register_repayment_transfer(
-				Origin::signed(test_info.borrower.account_id),
-				TransferKind::Native,
-				21u64.into(),
-				deal_order_id,
-				tx.as_bytes().into_bounded()
+			Creditcoin::register_address_v2(
+				Origin::signed(who),
+				Blockchain::Bitcoin,
+				address,
+				OwnershipProof::BitcoinSign(proof),
 			),
-			crate::Error::<Test>::NonExistentDealOrder
+			crate::Error::<Test>::BitcoinSignPublicKeyRecoveryFailed
 		);
-	})
+	});
 }
 
 #[test]
-fn register_transfer_internal_should_error_with_non_existent_lender_address() {
+fn register_address_v2_should_error_when_bitcoin_sign_is_for_another_chain() {
 	ExtBuilder::default().build_and_execute(|| {
-		let test_info = TestInfo::new_defaults();
-		let (deal_order_id, deal_order) = test_info.create_deal_order();
-		let tx = "0xabcabcabc";
-		let bogus_address =
-			AddressId::new::<Test>(&Blockchain::Rinkeby, &[1, 2, 3, 4, 5, 6, 7, 8, 9, 0]);
-
-		let result = Creditcoin::register_transfer_internal(
-			test_info.lender.account_id,
-			bogus_address,
-			deal_order.borrower_address_id,
-			TransferKind::Native,
-			deal_order.terms.amount,
-			OrderId::Deal(deal_order_id),
-			tx.as_bytes().into_bounded(),
-		)
-		.unwrap_err();
+		let key_pair = generate_keypair_from_seed("owner");
+		let who = account_from_keypair(key_pair.clone());
+		let address = "1D8DPnxNbNFanFkC5NCLP8JicmJKH4hJPo".as_bytes().into_bounded();
 
-		assert_eq!(result, crate::Error::<Test>::NonExistentAddress);
-	})
+		assert_noop!(
+			Creditcoin::register_address_v2(
+				Origin::signed(who.clone()),
+				Blockchain::Ethereum,
+				address,
+				build_bitcoin_proof_of_ownership(who, key_pair, true),
+			),
+			crate::Error::<Test>::BitcoinSignExternalAddressGenerationFailed
+		);
+	});
 }
 
 #[test]
-fn register_transfer_internal_should_error_when_addresses_are_not_on_the_same_blockchain() {
+fn unregister_address_should_work() {
 	ExtBuilder::default().build_and_execute(|| {
-		let test_info = TestInfo::new_defaults();
-		let (deal_order_id, deal_order) = test_info.create_deal_order();
-		let second_borrower = RegisteredAddress::new("borrower2", Blockchain::Luniverse);
-		let tx = "0xabcabcabc";
+		System::set_block_number(1);
 
-		let result = Creditcoin::register_transfer_internal(
-			test_info.lender.account_id,
-			deal_order.lender_address_id,
-			second_borrower.address_id,
-			TransferKind::Native,
-			deal_order.terms.amount,
-			OrderId::Deal(deal_order_id),
-			tx.as_bytes().into_bounded(),
-		)
-		.unwrap_err();
+		let owner = RegisteredAddress::new("owner", Blockchain::Rinkeby);
+		let address = Creditcoin::addresses(&owner.address_id).unwrap();
 
-		assert_eq!(result, crate::Error::<Test>::AddressBlockchainMismatch);
-	})
+		assert_ok!(Creditcoin::unregister_address(
+			Origin::signed(owner.account_id.clone()),
+			owner.address_id.clone(),
+		));
+
+		assert_eq!(Creditcoin::addresses(&owner.address_id), None);
+		assert!(!crate::AccountAddresses::<Test>::contains_key(
+			&owner.account_id,
+			&owner.address_id
+		));
+		System::assert_last_event(
+			crate::Event::<Test>::AddressUnregistered(owner.address_id, address).into(),
+		);
+	});
 }
 
 #[test]
-fn register_transfer_internal_should_error_when_transfer_kind_is_not_supported() {
+fn unregister_address_should_error_when_not_owner() {
 	ExtBuilder::default().build_and_execute(|| {
-		let test_info = TestInfo::new_defaults();
-		let (deal_order_id, deal_order) = test_info.create_deal_order();
-		let tx = "0xabcabcabc";
-
-		let result = Creditcoin::register_transfer_internal(
-			test_info.lender.account_id,
-			deal_order.lender_address_id,
-			deal_order.borrower_address_id,
-			// not supported on Blockchain::Rinkeby
-			TransferKind::Other(BoundedVec::try_from(b"other".to_vec()).unwrap()),
-			deal_order.terms.amount,
-			OrderId::Deal(deal_order_id),
-			tx.as_bytes().into_bounded(),
-		)
-		.unwrap_err();
+		let owner = RegisteredAddress::new("owner", Blockchain::Rinkeby);
+		let other = RegisteredAddress::new("other", Blockchain::Rinkeby);
 
-		assert_eq!(result, crate::Error::<Test>::UnsupportedTransferKind);
-	})
+		assert_noop!(
+			Creditcoin::unregister_address(Origin::signed(other.account_id), owner.address_id),
+			crate::Error::<Test>::NotAddressOwner
+		);
+	});
 }
 
 #[test]
-fn register_transfer_internal_should_error_when_transfer_is_already_registered() {
+fn unregister_address_should_error_while_an_open_order_references_it() {
 	ExtBuilder::default().build_and_execute(|| {
 		let test_info = TestInfo::new_defaults();
-		let (deal_order_id, deal_order) = test_info.create_deal_order();
-		let (_, transfer) = test_info.create_funding_transfer(&deal_order_id);
-
-		let result = Creditcoin::register_transfer_internal(
-			test_info.lender.account_id,
-			deal_order.lender_address_id,
-			deal_order.borrower_address_id,
-			TransferKind::Native,
-			deal_order.terms.amount,
-			OrderId::Deal(deal_order_id),
-			transfer.tx_id,
-		)
-		.unwrap_err();
+		test_info.create_deal_order();
 
-		assert_eq!(result, crate::Error::<Test>::TransferAlreadyRegistered);
-	})
+		for party in [&test_info.lender, &test_info.borrower] {
+			assert_noop!(
+				Creditcoin::unregister_address(
+					Origin::signed(party.account_id.clone()),
+					party.address_id.clone(),
+				),
+				crate::Error::<Test>::AddressInUse
+			);
+		}
+	});
 }
 
 #[test]
-fn exercise_weightinfo_functions() {
-	let result = super::weights::WeightInfo::<Test>::register_address();
-	assert!(result.ref_time() > 0);
-
-	let result = super::weights::WeightInfo::<Test>::claim_legacy_wallet();
-	assert!(result.ref_time() > 0);
-
-	let result = super::weights::WeightInfo::<Test>::add_ask_order();
-	assert!(result.ref_time() > 0);
-
-	let result = super::weights::WeightInfo::<Test>::add_bid_order();
-	assert!(result.ref_time() > 0);
-
-	let result = super::weights::WeightInfo::<Test>::add_offer();
-	assert!(result.ref_time() > 0);
-
-	let result = super::weights::WeightInfo::<Test>::add_deal_order();
-	assert!(result.ref_time() > 0);
-
-	let result = super::weights::WeightInfo::<Test>::add_authority();
-	assert!(result.ref_time() > 0);
-
-	let result = super::weights::WeightInfo::<Test>::persist_transfer();
-	assert!(result.ref_time() > 0);
-
-	let result = super::weights::WeightInfo::<Test>::fail_transfer();
-	assert!(result.ref_time() > 0);
-
-	let result = super::weights::WeightInfo::<Test>::fund_deal_order();
-	assert!(result.ref_time() > 0);
-
-	let result = super::weights::WeightInfo::<Test>::lock_deal_order();
-	assert!(result.ref_time() > 0);
-
-	let result = super::weights::WeightInfo::<Test>::register_funding_transfer();
-	assert!(result.ref_time() > 0);
-
-	let result = super::weights::WeightInfo::<Test>::register_repayment_transfer();
-	assert!(result.ref_time() > 0);
-
-	let result = super::weights::WeightInfo::<Test>::close_deal_order();
-	assert!(result.ref_time() > 0);
+fn unregister_address_should_error_when_owner_has_too_many_orders_to_scan() {
+	ExtBuilder::default().build_and_execute(|| {
+		let owner = RegisteredAddress::new("owner", Blockchain::Rinkeby);
+		for i in 0..=crate::MAX_ADDRESS_ORDERS_SCANNED {
+			let ask_order_id = AskOrderId::new::<Test>(1, format!("ask-{i}").as_bytes());
+			crate::AccountAskOrders::<Test>::insert(&owner.account_id, ask_order_id, ());
+		}
 
-	let result = super::weights::WeightInfo::<Test>::exempt();
-	assert!(result.ref_time() > 0);
+		assert_noop!(
+			Creditcoin::unregister_address(Origin::signed(owner.account_id), owner.address_id),
+			crate::Error::<Test>::TooManyOrdersToScan
+		);
+	});
+}
 
-	let result = super::weights::WeightInfo::<Test>::register_deal_order();
-	assert!(result.ref_time() > 0);
+#[test]
+fn unregister_address_should_drop_closed_deal_orders_of_the_lender() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, ..) = fund_and_close_deal_order(&test_info);
+		// the ask and bid orders have expired
+		System::set_block_number(test_info.expiration_block + 1);
 
-	let result = super::weights::WeightInfo::<Test>::request_collect_coins();
-	assert!(result.ref_time() > 0);
+		assert_ok!(Creditcoin::unregister_address(
+			Origin::signed(test_info.lender.account_id.clone()),
+			test_info.lender.address_id.clone(),
+		));
 
-	let result = super::weights::WeightInfo::<Test>::fail_collect_coins();
-	assert!(result.ref_time() > 0);
+		assert!(!crate::AccountDealOrders::<Test>::contains_key(
+			&test_info.lender.account_id,
+			&deal_order_id
+		));
+		assert!(crate::AccountDealOrders::<Test>::contains_key(
+			&test_info.borrower.account_id,
+			&deal_order_id
+		));
+	});
+}
 
-	let result = super::weights::WeightInfo::<Test>::persist_collect_coins();
-	assert!(result.ref_time() > 0);
+#[test]
+fn transfer_address_should_work() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, ..) = fund_and_close_deal_order(&test_info);
+		// the ask and bid orders have expired
+		System::set_block_number(test_info.expiration_block + 1);
+
+		let lender = test_info.lender.account_id.clone();
+		let address_id = test_info.lender.address_id.clone();
+		let new_owner = account_from_keypair(generate_keypair_from_seed("new_owner"));
+		let proof = build_transfer_address_proof(
+			&address_id,
+			&lender,
+			&new_owner,
+			0,
+			generate_keypair_from_seed("lender"),
+		);
 
-	let result = super::weights::WeightInfo::<Test>::remove_authority();
-	assert!(result.ref_time() > 0);
+		assert_ok!(Creditcoin::transfer_address(
+			Origin::signed(lender.clone()),
+			address_id.clone(),
+			new_owner.clone(),
+			OwnershipProof::EthSign(proof),
+		));
 
-	let result = super::weights::WeightInfo::<Test>::set_collect_coins_contract();
-	assert!(result.ref_time() > 0);
+		assert_eq!(Creditcoin::addresses(&address_id).unwrap().owner, new_owner);
+		assert_eq!(Creditcoin::address_transfer_nonce(&address_id), 1);
+		assert!(!crate::AccountAddresses::<Test>::contains_key(&lender, &address_id));
+		assert!(crate::AccountAddresses::<Test>::contains_key(&new_owner, &address_id));
+		assert!(!crate::AccountDealOrders::<Test>::contains_key(&lender, &deal_order_id));
+		assert!(crate::AccountDealOrders::<Test>::contains_key(&new_owner, &deal_order_id));
+		System::assert_last_event(
+			crate::Event::<Test>::AddressTransferred(address_id, lender, new_owner).into(),
+		);
+	});
 }
 
 #[test]
-fn remove_authority_errors_for_non_root() {
+fn transfer_address_should_error_without_proof_for_the_new_owner() {
 	ExtBuilder::default().build_and_execute(|| {
-		let acct: AccountId = AccountId::new([0; 32]);
+		let (who, address, ownership_proof, _) = generate_address_with_proof("owner");
+		let blockchain = Blockchain::Rinkeby;
+		assert_ok!(Creditcoin::register_address_v2(
+			Origin::signed(who.clone()),
+			blockchain.clone(),
+			address.clone(),
+			OwnershipProof::EthSign(ownership_proof),
+		));
+		let address_id = AddressId::new::<Test>(&blockchain, &address);
+		let new_owner = account_from_keypair(generate_keypair_from_seed("new_owner"));
 
-		assert_noop!(Creditcoin::remove_authority(Origin::signed(acct.clone()), acct), BadOrigin);
+		assert_noop!(
+			Creditcoin::transfer_address(
+				Origin::signed(who),
+				address_id,
+				new_owner,
+				OwnershipProof::EthSign(ownership_proof),
+			),
+			crate::Error::<Test>::OwnershipNotSatisfied
+		);
 	});
 }
 
 #[test]
-fn remove_authority_should_fail_when_authority_does_not_exist() {
+fn transfer_address_should_error_while_an_open_deal_order_references_it() {
 	ExtBuilder::default().build_and_execute(|| {
-		let root = RawOrigin::Root;
-		let acct: AccountId = AccountId::new([0; 32]);
+		let test_info = TestInfo::new_defaults();
+		fund_deal_order_for_repayment(&test_info);
+		// the ask and bid orders have expired
+		System::set_block_number(test_info.expiration_block + 1);
+
+		let new_owner = account_from_keypair(generate_keypair_from_seed("new_owner"));
+		let proof = build_transfer_address_proof(
+			&test_info.lender.address_id,
+			&test_info.lender.account_id,
+			&new_owner,
+			0,
+			generate_keypair_from_seed("lender"),
+		);
 
 		assert_noop!(
-			Creditcoin::remove_authority(crate::mock::RuntimeOrigin::from(root), acct),
-			crate::Error::<Test>::NotAnAuthority,
+			Creditcoin::transfer_address(
+				Origin::signed(test_info.lender.account_id.clone()),
+				test_info.lender.address_id.clone(),
+				new_owner,
+				OwnershipProof::EthSign(proof),
+			),
+			crate::Error::<Test>::AddressInUse
 		);
+		assert_eq!(Creditcoin::address_transfer_nonce(&test_info.lender.address_id), 0);
 	});
 }
 
 #[test]
-fn add_and_remove_authority_works_for_root() {
+fn transfer_address_should_work_when_a_deal_order_has_defaulted() {
 	ExtBuilder::default().build_and_execute(|| {
-		let root = RawOrigin::Root;
-		let account: AccountId = AccountId::new([0; 32]);
-
-		assert!(!TaskScheduler::is_authority(&account));
-		TaskScheduler::insert_authority(&account);
-		assert!(TaskScheduler::is_authority(&account));
+		let test_info = TestInfo::new_defaults();
+		let (deal_order_id, _) = fund_deal_order_for_repayment(&test_info);
+		DealOrders::<Test>::mutate(
+			deal_order_id.expiration(),
+			deal_order_id.hash(),
+			|deal_order| {
+				deal_order.as_mut().unwrap().status = DealOrderStatus::Defaulted;
+			},
+		);
+		// the ask and bid orders have expired
+		System::set_block_number(test_info.expiration_block + 1);
+
+		let new_owner = account_from_keypair(generate_keypair_from_seed("new_owner"));
+		let proof = build_transfer_address_proof(
+			&test_info.lender.address_id,
+			&test_info.lender.account_id,
+			&new_owner,
+			0,
+			generate_keypair_from_seed("lender"),
+		);
 
-		assert_ok!(Creditcoin::remove_authority(Origin::from(root), account.clone()));
-		assert!(!TaskScheduler::is_authority(&account));
+		assert_ok!(Creditcoin::transfer_address(
+			Origin::signed(test_info.lender.account_id.clone()),
+			test_info.lender.address_id.clone(),
+			new_owner.clone(),
+			OwnershipProof::EthSign(proof),
+		));
+		assert_eq!(
+			Creditcoin::addresses(&test_info.lender.address_id).map(|address| address.owner),
+			Some(new_owner)
+		);
+		assert_eq!(Creditcoin::address_transfer_nonce(&test_info.lender.address_id), 1);
 	});
 }
 
 #[test]
-fn register_address_v2_should_work() {
+fn transfer_address_should_error_when_a_proof_is_reused() {
 	ExtBuilder::default().build_and_execute(|| {
-		System::set_block_number(1);
-
-		let (who, address, ownership_proof, _) = generate_address_with_proof("owner");
+		let (who, address, ownership_proof, key_pair) = generate_address_with_proof("owner");
 		let blockchain = Blockchain::Rinkeby;
-
-		let proof = OwnershipProof::EthSign(ownership_proof);
-
 		assert_ok!(Creditcoin::register_address_v2(
 			Origin::signed(who.clone()),
 			blockchain.clone(),
 			address.clone(),
-			proof,
+			OwnershipProof::EthSign(ownership_proof),
 		));
-		let address_id = crate::AddressId::new::<Test>(&blockchain, &address);
-		let address = crate::Address { blockchain, value: address, owner: who };
-		assert_eq!(Creditcoin::addresses(address_id.clone()), Some(address.clone()));
+		let address_id = AddressId::new::<Test>(&blockchain, &address);
+		let new_owner = account_from_keypair(generate_keypair_from_seed("new_owner"));
 
-		let event = <frame_system::Pallet<Test>>::events().pop().expect("an event").event;
+		let first_proof =
+			build_transfer_address_proof(&address_id, &who, &new_owner, 0, key_pair.clone());
+		assert_ok!(Creditcoin::transfer_address(
+			Origin::signed(who.clone()),
+			address_id.clone(),
+			new_owner.clone(),
+			OwnershipProof::EthSign(first_proof.clone()),
+		));
+		let proof_back = build_transfer_address_proof(&address_id, &new_owner, &who, 1, key_pair);
+		assert_ok!(Creditcoin::transfer_address(
+			Origin::signed(new_owner.clone()),
+			address_id.clone(),
+			who.clone(),
+			OwnershipProof::EthSign(proof_back),
+		));
 
-		assert_matches!(
-			event,
-			crate::mock::RuntimeEvent::Creditcoin(crate::Event::<Test>::AddressRegistered(registered_address_id, registered_address)) => {
-				assert_eq!(registered_address_id, address_id);
-				assert_eq!(registered_address, address);
-			}
+		assert_noop!(
+			Creditcoin::transfer_address(
+				Origin::signed(who),
+				address_id,
+				new_owner,
+				OwnershipProof::EthSign(first_proof),
+			),
+			crate::Error::<Test>::OwnershipNotSatisfied
 		);
 	});
 }
 
 #[test]
-fn register_address_v2_should_fail_to_reregister_external_address_to_same_account() {
+fn register_address_v2_should_store_hex_encoded_evm_address_as_raw_bytes() {
 	ExtBuilder::default().build_and_execute(|| {
-		let (who, external_address, ownership_proof, _) = generate_address_with_proof("owner");
+		let (who, address, ownership_proof, _) = generate_address_with_proof("owner");
 		let blockchain = Blockchain::Rinkeby;
-		// Register external address to account
-		assert_ok!(Creditcoin::register_address(
+		let hex_encoded: ExternalAddress =
+			format!("0x{}", hex::encode_upper(&address)).as_bytes().into_bounded();
+
+		assert_ok!(Creditcoin::register_address_v2(
 			Origin::signed(who.clone()),
 			blockchain.clone(),
-			external_address.clone(),
-			ownership_proof.clone()
+			hex_encoded.clone(),
+			OwnershipProof::EthSign(ownership_proof),
 		));
 
-		let proof = OwnershipProof::EthSign(ownership_proof);
+		let address_id = AddressId::new::<Test>(&blockchain, &hex_encoded);
+		assert_eq!(address_id, AddressId::new::<Test>(&blockchain, &address));
+		let address = crate::Address { blockchain, value: address, owner: who };
+		assert_eq!(Creditcoin::addresses(address_id), Some(address));
+	});
+}
 
-		// Try registering again to same account and fail
+#[test]
+fn register_address_v2_should_error_when_address_is_registered_in_another_form() {
+	ExtBuilder::default().build_and_execute(|| {
+		let (who, address, ownership_proof, _) = generate_address_with_proof("owner");
+		let blockchain = Blockchain::Rinkeby;
+
+		assert_ok!(Creditcoin::register_address_v2(
+			Origin::signed(who.clone()),
+			blockchain.clone(),
+			address.clone(),
+			OwnershipProof::EthSign(ownership_proof.clone()),
+		));
+
+		let hex_encoded: ExternalAddress =
+			format!("0x{}", hex::encode(&address)).as_bytes().into_bounded();
 		assert_noop!(
 			Creditcoin::register_address_v2(
 				Origin::signed(who),
 				blockchain,
-				external_address,
-				proof,
+				hex_encoded,
+				OwnershipProof::EthSign(ownership_proof),
 			),
 			crate::Error::<Test>::AddressAlreadyRegisteredByCaller
 		);
-	})
+	});
+}
+
+fn evm_chain() -> crate::ExternalChain {
+	crate::ExternalChain {
+		address_format: crate::AddressFormat::Evm,
+		evm_chain_id: Some(8217),
+		transfer_kinds: crate::SupportedTransferKinds::ALL,
+		deprecated: false,
+	}
 }
 
 #[test]
-fn register_address_v2_should_fail_to_reregister_external_address_to_new_account() {
+fn set_external_chain_should_fail_when_not_signed_by_root() {
 	ExtBuilder::default().build_and_execute(|| {
-		let who_first = account_from_keypair(generate_keypair_from_seed("first account"));
-		let who_second = account_from_keypair(generate_keypair_from_seed("second account"));
-		let external_keypair = generate_keypair_from_seed("external account");
-		let external_address = external_address_from_keypair(external_keypair.clone());
-		let blockchain = Blockchain::Rinkeby;
+		let (who, ..) = generate_address_with_proof("owner");
+		let blockchain = Blockchain::Other("kaia".as_bytes().into_bounded());
 
-		// Register external address to first account
-		let first_ownership_proof =
-			build_proof_of_ownership(who_first.clone(), external_keypair.clone());
+		assert_noop!(
+			Creditcoin::set_external_chain(Origin::signed(who), blockchain, evm_chain()),
+			BadOrigin
+		);
+	});
+}
 
-		let proof = OwnershipProof::EthSign(first_ownership_proof);
-		assert_ok!(Creditcoin::register_address_v2(
-			Origin::signed(who_first),
+#[test]
+fn set_external_chain_should_add_the_chain_to_the_registry() {
+	ExtBuilder::default().build_and_execute(|| {
+		let blockchain = Blockchain::Other("kaia".as_bytes().into_bounded());
+		let chain = evm_chain();
+
+		assert_ok!(Creditcoin::set_external_chain(
+			RawOrigin::Root.into(),
 			blockchain.clone(),
-			external_address.clone(),
-			proof,
+			chain.clone()
 		));
-		// Try registering to a second account and fail
-		let second_ownership_proof = build_proof_of_ownership(who_second.clone(), external_keypair);
 
-		let second_proof = OwnershipProof::EthSign(second_ownership_proof);
+		assert_eq!(crate::ExternalChains::<Test>::get(&blockchain), Some(chain.clone()));
+		let event = System::events().pop().expect("expected an event").event;
+		assert_eq!(
+			event,
+			crate::mock::RuntimeEvent::Creditcoin(crate::Event::ExternalChainSet(
+				blockchain, chain
+			))
+		);
+	});
+}
+
+#[test]
+fn set_external_chain_should_error_when_evm_chain_has_no_chain_id() {
+	ExtBuilder::default().build_and_execute(|| {
+		let blockchain = Blockchain::Other("kaia".as_bytes().into_bounded());
+
 		assert_noop!(
-			Creditcoin::register_address_v2(
-				Origin::signed(who_second),
+			Creditcoin::set_external_chain(
+				RawOrigin::Root.into(),
 				blockchain,
-				external_address,
-				second_proof
+				crate::ExternalChain { evm_chain_id: None, ..evm_chain() }
 			),
-			crate::Error::<Test>::AddressAlreadyRegistered
+			crate::Error::<Test>::InvalidExternalChain
 		);
-	})
+	});
 }
 
 #[test]
-fn register_address_v2_should_error_when_not_signed() {
+fn set_external_chain_should_error_when_bitcoin_chain_moves_tokens() {
 	ExtBuilder::default().build_and_execute(|| {
-		let (_who, address, ownership_proof, _) = generate_address_with_proof("owner");
-		let blockchain = Blockchain::Rinkeby;
+		let blockchain = Blockchain::Other("litecoin".as_bytes().into_bounded());
+		let chain = crate::ExternalChain {
+			address_format: crate::AddressFormat::Bitcoin,
+			evm_chain_id: None,
+			transfer_kinds: crate::SupportedTransferKinds::ALL,
+			deprecated: false,
+		};
 
-		let proof = OwnershipProof::EthSign(ownership_proof);
 		assert_noop!(
-			Creditcoin::register_address_v2(Origin::none(), blockchain, address, proof),
-			BadOrigin,
+			Creditcoin::set_external_chain(RawOrigin::Root.into(), blockchain, chain),
+			crate::Error::<Test>::InvalidExternalChain
 		);
-	})
+	});
 }
 
 #[test]
-fn register_address_v2_should_error_when_using_wrong_ownership_proof() {
+fn set_external_chain_should_error_when_address_format_changes() {
 	ExtBuilder::default().build_and_execute(|| {
-		let (who, address, _ownership_proof, _) = generate_address_with_proof("owner");
-		let (_who2, _address2, ownership_proof2, _) = generate_address_with_proof("bogus");
+		let blockchain = Blockchain::Other("kaia".as_bytes().into_bounded());
+		assert_ok!(Creditcoin::set_external_chain(
+			RawOrigin::Root.into(),
+			blockchain.clone(),
+			evm_chain()
+		));
+		let bitcoin = crate::ExternalChain {
+			address_format: crate::AddressFormat::Bitcoin,
+			evm_chain_id: None,
+			transfer_kinds: crate::SupportedTransferKinds::NATIVE,
+			deprecated: false,
+		};
 
-		let blockchain = Blockchain::Rinkeby;
-		let bad_proof = OwnershipProof::EthSign(ownership_proof2);
 		assert_noop!(
-			Creditcoin::register_address_v2(Origin::signed(who), blockchain, address, bad_proof),
-			crate::Error::<Test>::OwnershipNotSatisfied
+			Creditcoin::set_external_chain(RawOrigin::Root.into(), blockchain, bitcoin.clone()),
+			crate::Error::<Test>::AddressFormatChanged
 		);
-	})
+		assert_noop!(
+			Creditcoin::set_external_chain(RawOrigin::Root.into(), Blockchain::Ethereum, bitcoin),
+			crate::Error::<Test>::AddressFormatChanged
+		);
+	});
 }
 
 #[test]
-fn register_address_v2_should_error_when_address_too_long() {
+fn address_id_should_be_canonical_on_a_registered_chain() {
+	ExtBuilder::default().build_and_execute(|| {
+		let blockchain = Blockchain::Other("kaia".as_bytes().into_bounded());
+		let address = hex::decode("5aaeb6053f3e94c9b9a09f33669435e7ef1beaed").unwrap();
+		let hex_encoded = b"0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+		assert_ne!(
+			AddressId::new::<Test>(&blockchain, hex_encoded),
+			AddressId::new::<Test>(&blockchain, &address)
+		);
+
+		assert_ok!(Creditcoin::set_external_chain(
+			RawOrigin::Root.into(),
+			blockchain.clone(),
+			evm_chain()
+		));
+
+		assert_eq!(
+			AddressId::new::<Test>(&blockchain, hex_encoded),
+			AddressId::new::<Test>(&blockchain, &address)
+		);
+	});
+}
+
+#[test]
+fn register_address_v2_should_work_on_a_registered_evm_chain() {
 	ExtBuilder::default().build_and_execute(|| {
 		let (who, address, ownership_proof, _) = generate_address_with_proof("owner");
-		let address = format!("0xff{}", hex::encode(address)).hex_to_address();
-		let blockchain = Blockchain::Rinkeby;
-		let proof = OwnershipProof::EthSign(ownership_proof);
+		let blockchain = Blockchain::Other("kaia".as_bytes().into_bounded());
+		assert_ok!(Creditcoin::set_external_chain(
+			RawOrigin::Root.into(),
+			blockchain.clone(),
+			evm_chain()
+		));
+
+		assert_ok!(Creditcoin::register_address_v2(
+			Origin::signed(who.clone()),
+			blockchain.clone(),
+			address.clone(),
+			OwnershipProof::EthSign(ownership_proof),
+		));
+
+		let address_id = AddressId::new::<Test>(&blockchain, &address);
+		assert_eq!(Creditcoin::addresses(address_id).map(|address| address.owner), Some(who));
+	});
+}
+
+#[test]
+fn register_address_v2_should_error_when_chain_is_not_registered() {
+	ExtBuilder::default().build_and_execute(|| {
+		let (who, address, ownership_proof, _) = generate_address_with_proof("owner");
+		let blockchain = Blockchain::Other("kaia".as_bytes().into_bounded());
+
 		assert_noop!(
-			Creditcoin::register_address_v2(Origin::signed(who), blockchain, address, proof),
-			crate::Error::<Test>::EthSignExternalAddressGenerationFailed
+			Creditcoin::register_address_v2(
+				Origin::signed(who),
+				blockchain,
+				address,
+				OwnershipProof::EthSign(ownership_proof),
+			),
+			crate::Error::<Test>::UnsupportedChain
 		);
-	})
+	});
 }
 
 #[test]
-fn register_address_v2_should_error_when_signature_is_invalid() {
+fn register_address_v2_should_error_when_chain_is_deprecated() {
 	ExtBuilder::default().build_and_execute(|| {
-		let (who, address, _ownership_proof, _) = generate_address_with_proof("owner");
+		let (who, address, ownership_proof, _) = generate_address_with_proof("owner");
+		let blockchain = Blockchain::Rinkeby;
+		assert_ok!(Creditcoin::set_external_chain(
+			RawOrigin::Root.into(),
+			blockchain.clone(),
+			crate::ExternalChain { evm_chain_id: Some(4), deprecated: true, ..evm_chain() }
+		));
 
-		// NOTE: No checking goes on to ensure this is a real signature! See
-		// https://docs.rs/sp-core/2.0.0-rc4/sp_core/ecdsa/struct.Signature.html#method.from_raw
-		let ownership_proof = sp_core::ecdsa::Signature::from_raw([0; 65]);
+		assert_noop!(
+			Creditcoin::register_address_v2(
+				Origin::signed(who),
+				blockchain,
+				address,
+				OwnershipProof::EthSign(ownership_proof),
+			),
+			crate::Error::<Test>::ChainDeprecated
+		);
+	});
+}
 
-		let bad_proof = OwnershipProof::EthSign(ownership_proof);
+#[test]
+fn add_ask_order_should_error_when_chain_is_deprecated() {
+	ExtBuilder::default().build_and_execute(|| {
+		let test_info = TestInfo::new_defaults();
+		let TestInfo { lender, loan_terms, ask_guid, blockchain, .. } = test_info;
+		let RegisteredAddress { address_id, account_id } = lender;
+		assert_ok!(Creditcoin::set_external_chain(
+			RawOrigin::Root.into(),
+			blockchain,
+			crate::ExternalChain { evm_chain_id: Some(4), deprecated: true, ..evm_chain() }
+		));
 
-		let blockchain = Blockchain::Rinkeby;
 		assert_noop!(
-			Creditcoin::register_address_v2(Origin::signed(who), blockchain, address, bad_proof),
-			crate::Error::<Test>::EthSignPublicKeyRecoveryFailed
+			Creditcoin::add_ask_order(
+				Origin::signed(account_id),
+				address_id,
+				loan_terms.try_into().unwrap(),
+				1_000,
+				ask_guid
+			),
+			crate::Error::<Test>::ChainDeprecated
 		);
-	})
+	});
 }
@@ -1,10 +1,12 @@
 mod collect_coins;
+mod contract_wallet;
 pub mod loan_terms;
 mod transfer;
 
 pub use collect_coins::{
 	CollectedCoins as CollectedCoinsStruct, CollectedCoinsId, UnverifiedCollectedCoins,
 };
+pub use contract_wallet::{ContractWalletId, UnverifiedContractWallet};
 pub use loan_terms::*;
 pub use transfer::*;
 
@@ -25,7 +27,8 @@ use scale_info::TypeInfo;
 use sha2::Digest;
 use sp_core::ecdsa;
 use sp_runtime::codec::{Decode, Encode, EncodeLike, FullCodec, MaxEncodedLen};
-use sp_runtime::traits::Hash;
+use sp_runtime::traits::{Hash, UniqueSaturatedFrom, UniqueSaturatedInto};
+use sp_runtime::{FixedPointNumber, FixedU128, PerThing, Perbill};
 use sp_std::prelude::*;
 
 pub type ExternalAmount = sp_core::U256;
@@ -35,12 +38,17 @@ type ExternalAddressLen = ConstU32<256>;
 pub type ExternalAddress = BoundedVec<u8, ExternalAddressLen>;
 type ExternalTxIdLen = ConstU32<256>;
 pub type ExternalTxId = BoundedVec<u8, ExternalTxIdLen>;
+type ExemptionReasonLen = ConstU32<256>;
+pub type ExemptionReason = BoundedVec<u8, ExemptionReasonLen>;
 type OtherChainLen = ConstU32<256>;
 pub type OtherChain = BoundedVec<u8, OtherChainLen>;
 type OtherTransferKindLen = ConstU32<256>;
 pub type OtherTransferKind = BoundedVec<u8, OtherTransferKindLen>;
+type ContractWalletSignatureLen = ConstU32<1024>;
+pub type ContractWalletSignature = BoundedVec<u8, ContractWalletSignatureLen>;
 
 #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub enum Blockchain {
 	Ethereum,
 	Rinkeby,
@@ -59,12 +67,121 @@ impl Blockchain {
 			Blockchain::Other(chain) => chain.as_slice(),
 		}
 	}
+
+	/// The address format of the chains supported before the registry of external chains.
+	/// Address ids hash the addresses of these chains in this format, so they stay the same
+	/// whatever the registry says.
+	pub fn builtin_address_format(&self) -> Option<AddressFormat> {
+		match self {
+			Blockchain::Ethereum | Blockchain::Rinkeby | Blockchain::Luniverse => {
+				Some(AddressFormat::Evm)
+			},
+			Blockchain::Bitcoin => Some(AddressFormat::Bitcoin),
+			Blockchain::Other(_) => None,
+		}
+	}
+}
+
+/// How the addresses of an external chain are written and derived from public keys.
+#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub enum AddressFormat {
+	/// The 20 bytes of an account on Ethereum or another EVM chain.
+	Evm,
+	/// A base58 or bech32 bitcoin address.
+	Bitcoin,
+}
+
+/// The kinds of transfers that can be registered on an external chain.
+#[derive(
+	Clone, Copy, Default, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen,
+)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct SupportedTransferKinds {
+	pub erc20: bool,
+	pub ethless: bool,
+	pub native: bool,
+}
+
+impl SupportedTransferKinds {
+	pub const ALL: Self = Self { erc20: true, ethless: true, native: true };
+	pub const NATIVE: Self = Self { erc20: false, ethless: false, native: true };
+
+	pub fn contains(&self, kind: &TransferKind) -> bool {
+		match kind {
+			TransferKind::Erc20(_) => self.erc20,
+			TransferKind::Ethless(_) => self.ethless,
+			TransferKind::Native => self.native,
+			TransferKind::Other(_) => false,
+		}
+	}
+}
+
+/// An entry of the registry of supported external chains, which is keyed by the `Blockchain`
+/// identifying the chain.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExternalChain {
+	pub address_format: AddressFormat,
+	/// The id an EVM chain answers `eth_chainId` with. Offchain workers check it before they
+	/// trust an RPC node of the chain, unless it is unknown.
+	pub evm_chain_id: Option<u64>,
+	pub transfer_kinds: SupportedTransferKinds,
+	/// A deprecated chain takes no new addresses or orders, while those it already has can be
+	/// used to settle their loans.
+	pub deprecated: bool,
+}
+
+impl ExternalChain {
+	pub fn supports(&self, kind: &TransferKind) -> bool {
+		self.transfer_kinds.contains(kind)
+	}
+
+	/// Whether the entry fits its address format: an EVM chain has the chain id offchain workers
+	/// check its RPC nodes against and may move tokens, other chains have neither. Every chain
+	/// supports at least one kind of transfer.
+	pub fn is_valid(&self) -> bool {
+		let SupportedTransferKinds { erc20, ethless, native } = self.transfer_kinds;
+		let is_evm = self.address_format == AddressFormat::Evm;
+		(erc20 || ethless || native)
+			&& self.evm_chain_id.is_some() == is_evm
+			&& (is_evm || !(erc20 || ethless))
+	}
+
+	/// The chains supported before the registry of external chains, which seed it.
+	pub fn builtin() -> Vec<(Blockchain, ExternalChain)> {
+		let evm = |evm_chain_id, deprecated| ExternalChain {
+			address_format: AddressFormat::Evm,
+			evm_chain_id,
+			transfer_kinds: SupportedTransferKinds::ALL,
+			deprecated,
+		};
+		vec![
+			(Blockchain::Ethereum, evm(Some(1), false)),
+			// the Rinkeby testnet has been shut down
+			(Blockchain::Rinkeby, evm(Some(4), true)),
+			(Blockchain::Luniverse, evm(None, false)),
+			(
+				Blockchain::Bitcoin,
+				ExternalChain {
+					address_format: AddressFormat::Bitcoin,
+					evm_chain_id: None,
+					transfer_kinds: SupportedTransferKinds::NATIVE,
+					deprecated: false,
+				},
+			),
+		]
+	}
 }
 
 #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
 pub enum OwnershipProof {
 	PersonalSign(sp_core::ecdsa::Signature),
 	EthSign(sp_core::ecdsa::Signature),
+	/// A signature of EIP-712 typed data, e.g. from `eth_signTypedData_v4`.
+	TypedData(sp_core::ecdsa::Signature),
+	/// A bitcoin `signmessage` signature, i.e. a header byte followed by r and s.
+	BitcoinSign([u8; 65]),
 }
 
 #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
@@ -129,9 +246,239 @@ pub struct DealOrder<AccountId, BlockNum, Hash, Moment> {
 	pub timestamp: Moment,
 	pub block: Option<BlockNum>,
 	pub funding_transfer_id: Option<TransferId<Hash>>,
+	/// The latest repayment credited towards the deal order.
 	pub repayment_transfer_id: Option<TransferId<Hash>>,
+	pub repaid_amount: ExternalAmount,
 	pub lock: Option<AccountId>,
 	pub borrower: AccountId,
+	pub status: DealOrderStatus,
+	pub exemption: Option<Exemption<AccountId, Moment>>,
+}
+
+/// The lender of a deal order forgave the borrower part or all of what is owed.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct Exemption<AccountId, Moment> {
+	/// The lender who granted the latest exemption.
+	pub lender: AccountId,
+	/// When the latest exemption was granted.
+	pub timestamp: Moment,
+	/// The total amount forgiven so far.
+	pub amount: ExternalAmount,
+	/// Why the lender granted the latest exemption, if given.
+	pub reason: Option<ExemptionReason>,
+}
+
+#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum DealOrderStatus {
+	/// The deal order has been created but the lender has not funded it yet.
+	Pending,
+	/// The lender has funded the deal order.
+	Funded,
+	/// The borrower has locked the funded deal order.
+	Locked,
+	/// The loan has been repaid.
+	Closed,
+	/// The lender has exempted the borrower from repaying the loan.
+	Exempted,
+	/// The loan term ended before the loan was repaid.
+	Defaulted,
+}
+
+impl<AccountId, BlockNum, Hash, Moment> DealOrder<AccountId, BlockNum, Hash, Moment>
+where
+	Moment: UniqueSaturatedInto<u64> + UniqueSaturatedFrom<u64> + Copy,
+{
+	/// The principal plus the interest accrued between funding the deal order and `now`.
+	pub fn amount_owed(&self, now: Moment) -> ExternalAmount {
+		let funded_at: u64 = self.timestamp.unique_saturated_into();
+		let now: u64 = now.unique_saturated_into();
+		self.terms.amount_owed(&Duration::from_millis(now.saturating_sub(funded_at)))
+	}
+
+	/// The amount the lender forgave the borrower.
+	pub fn forgiven_amount(&self) -> ExternalAmount {
+		self.exemption
+			.as_ref()
+			.map_or_else(ExternalAmount::zero, |exemption| exemption.amount)
+	}
+
+	/// What the borrower has to repay in total by `now`, i.e. the amount owed less the amount
+	/// the lender forgave.
+	pub fn amount_due(&self, now: Moment) -> ExternalAmount {
+		self.amount_owed(now).saturating_sub(self.forgiven_amount())
+	}
+
+	/// Whether the loan has been repaid or the borrower was exempted from repaying the rest of it.
+	pub fn is_closed(&self) -> bool {
+		matches!(self.status, DealOrderStatus::Closed | DealOrderStatus::Exempted)
+	}
+
+	/// The moment the loan is due, i.e. when its term ends.
+	pub fn due_timestamp(&self) -> Moment {
+		let funded_at: u64 = self.timestamp.unique_saturated_into();
+		let term_length: u64 = self.terms.term_length.as_millis().unique_saturated_into();
+		Moment::unique_saturated_from(funded_at.saturating_add(term_length))
+	}
+
+	/// Whether the loan is still outstanding after its term ended at `now`.
+	pub fn is_overdue(&self, now: Moment) -> bool {
+		let due: u64 = self.due_timestamp().unique_saturated_into();
+		let now: u64 = now.unique_saturated_into();
+		matches!(self.status, DealOrderStatus::Funded | DealOrderStatus::Locked) && now > due
+	}
+
+	/// Whether the deal order expired before it was funded. The expiry sweep may only remove it
+	/// a few blocks later, until then it is treated as expired.
+	pub fn has_expired(&self, head: BlockNum) -> bool
+	where
+		BlockNum: PartialOrd,
+	{
+		self.funding_transfer_id.is_none() && self.expiration_block < head
+	}
+}
+
+/// What a borrower owes on a deal order, as reported by the runtime API.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct LoanBalance<Moment> {
+	pub principal: ExternalAmount,
+	pub interest: ExternalAmount,
+	pub repaid: ExternalAmount,
+	pub due: Moment,
+}
+
+/// How far the sweep of expired entries got, it resumes from here in the next block.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct ExpirySweep<BlockNum, Hash> {
+	/// The expiration block being swept. Entries that expired in earlier blocks are gone.
+	pub block: BlockNum,
+	/// The last deal order of `block` that was checked. Funded deal orders are kept, so the
+	/// sweep continues after it instead of starting over.
+	pub last_deal_order: Option<Hash>,
+}
+
+/// What is kept of a closed or exempted deal order once it has been archived. The deal order
+/// itself is carried by the `DealOrderArchived` event and its transfers by `TransferArchived`
+/// events.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct ArchivedDealOrder<AccountId, BlockNum, Hash> {
+	pub borrower: AccountId,
+	pub lender_address_id: AddressId<Hash>,
+	pub status: DealOrderStatus,
+	pub principal: ExternalAmount,
+	pub repaid_amount: ExternalAmount,
+	/// The hash of the SCALE encoded deal order at the time it was archived.
+	pub deal_order_hash: Hash,
+	pub archived_at: BlockNum,
+}
+
+/// The part of a syndicated deal order's loan amount committed by one of its lenders, keyed by
+/// the lender's address.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct LenderShare<Hash> {
+	pub amount: ExternalAmount,
+	pub funding_transfer_id: Option<TransferId<Hash>>,
+	pub repaid_amount: ExternalAmount,
+}
+
+impl<Hash> LenderShare<Hash> {
+	pub fn new(amount: ExternalAmount) -> Self {
+		Self { amount, funding_transfer_id: None, repaid_amount: ExternalAmount::zero() }
+	}
+
+	/// The part of `owed` due to this lender, in proportion to its share of `loan_amount`.
+	/// Rounded down, so the borrower never owes more than `owed` in total.
+	pub fn amount_owed(&self, owed: ExternalAmount, loan_amount: ExternalAmount) -> ExternalAmount {
+		if loan_amount.is_zero() {
+			return ExternalAmount::zero();
+		}
+		// `owed * amount` may not fit in 256 bits, so the whole multiples of `loan_amount` and the
+		// remainder are scaled separately. The remainder is below `loan_amount`, so its share is
+		// below `amount` and fits.
+		let whole = (owed / loan_amount).saturating_mul(self.amount);
+		let rest = (owed % loan_amount).full_mul(self.amount) / sp_core::U512::from(loan_amount);
+		whole.saturating_add(ExternalAmount::try_from(rest).unwrap_or(self.amount))
+	}
+}
+
+/// New terms for a funded deal order, proposed by its borrower or lender and waiting for the
+/// other party to accept them.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct TermsProposal<AccountId, BlockNum, Hash> {
+	pub deal_order_id: DealOrderId<BlockNum, Hash>,
+	pub terms: LoanTerms,
+	pub expiration_block: BlockNum,
+	pub block: BlockNum,
+	pub proposer: AccountId,
+}
+
+/// A repayment of a deal order made by someone other than its borrower, e.g. a guarantor or a
+/// collection agency, from an address they own on the blockchain of the deal order.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct RepaymentOrder<AccountId, BlockNum, Hash> {
+	pub deal_order_id: DealOrderId<BlockNum, Hash>,
+	pub payer_address_id: AddressId<Hash>,
+	pub amount: ExternalAmount,
+	pub expiration_block: BlockNum,
+	pub block: BlockNum,
+	pub payer: AccountId,
+}
+
+/// Which party of a deal order pays its origination fee.
+#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum FeePayer {
+	Borrower,
+	Lender,
+}
+
+/// How the origination fee of a deal order is computed.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum OriginationFeeRate<Balance> {
+	/// A fraction of the loan amount, converted to CTC at `conversion_rate` CTC per unit of
+	/// the loan's external currency.
+	Fraction { fraction: Perbill, conversion_rate: FixedU128 },
+	/// The same CTC amount for every deal order.
+	Flat(Balance),
+}
+
+impl<Balance> OriginationFeeRate<Balance>
+where
+	Balance: UniqueSaturatedFrom<u128> + Copy,
+{
+	/// The fee for a loan of `amount`, in CTC.
+	pub fn fee(&self, amount: ExternalAmount) -> Balance {
+		match self {
+			Self::Flat(fee) => *fee,
+			Self::Fraction { fraction, conversion_rate } => {
+				let portion = amount.saturating_mul(fraction.deconstruct().into())
+					/ ExternalAmount::from(Perbill::ACCURACY);
+				let fee = portion.saturating_mul(conversion_rate.into_inner().into())
+					/ ExternalAmount::from(FixedU128::accuracy());
+				Balance::unique_saturated_from(fee.min(u128::MAX.into()).as_u128())
+			},
+		}
+	}
+}
+
+/// The origination fee charged when a deal order is funded, paid by `payer` to `collector`.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct OriginationFee<AccountId, Balance> {
+	pub rate: OriginationFeeRate<Balance>,
+	pub payer: FeePayer,
+	pub collector: AccountId,
+}
+
+/// A borrower's track record on one blockchain, as reported by the runtime API. Loans are
+/// counted once they are repaid, exempted or defaulted.
+#[derive(Clone, Default, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct CreditRecord {
+	pub loans: u32,
+	pub on_time_repayments: u32,
+	pub late_repayments: u32,
+	pub defaults: u32,
+	/// The total principal of the counted loans.
+	pub volume: ExternalAmount,
 }
 
 #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
@@ -144,6 +491,7 @@ pub struct AskOrderId<BlockNum, Hash>(BlockNum, Hash);
 pub struct BidOrderId<BlockNum, Hash>(BlockNum, Hash);
 
 #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub struct DealOrderId<BlockNum, Hash>(BlockNum, Hash);
 
 #[cfg(test)]
@@ -168,9 +516,19 @@ impl<BlockNum, Hash> From<DealOrderId<BlockNum, Hash>> for OrderId<BlockNum, Has
 	}
 }
 
+/// An ask or bid order waiting for the matching engine to look for a counterpart.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum MatchableOrderId<BlockNum, Hash> {
+	Ask(AskOrderId<BlockNum, Hash>),
+	Bid(BidOrderId<BlockNum, Hash>),
+}
+
 #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
 pub struct OfferId<BlockNum, Hash>(BlockNum, Hash);
 
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct TermsProposalId<BlockNum, Hash>(BlockNum, Hash);
+
 fn bytes_to_hex(bytes: &[u8]) -> Vec<u8> {
 	const HEX_CHARS_LOWER: &[u8; 16] = b"0123456789abcdef";
 	let mut hex = Vec::with_capacity(bytes.len() * 2);
@@ -209,14 +567,34 @@ where
 }
 
 impl<H> AddressId<H> {
+	/// Hashes the canonical form of `address`, so every way of writing an address has the same id.
+	/// The addresses of other chains are in the format the registry of external chains holds for
+	/// them, which does not change once set.
 	pub fn new<Config>(blockchain: &Blockchain, address: &[u8]) -> AddressId<H>
 	where
-		Config: frame_system::Config,
+		Config: crate::Config,
 		<Config as frame_system::Config>::Hashing: Hash<Output = H>,
 	{
+		let canonical = blockchain
+			.builtin_address_format()
+			.or_else(|| {
+				crate::ExternalChains::<Config>::get(blockchain).map(|chain| chain.address_format)
+			})
+			.and_then(|format| crate::helpers::canonical_address(&format, address));
+		let address = canonical.as_deref().unwrap_or(address);
 		let key = concatenate!(blockchain.as_bytes(), address);
 		AddressId(Config::Hashing::hash(&key))
 	}
+
+	pub fn into_inner(self) -> H {
+		self.0
+	}
+}
+
+impl<H> From<H> for AddressId<H> {
+	fn from(hash: H) -> Self {
+		Self(hash)
+	}
 }
 
 impl<B, H> AskOrderId<B, H> {
@@ -278,6 +656,24 @@ impl<B, H> DealOrderId<B, H> {
 	}
 }
 
+impl<B, H> TermsProposalId<B, H> {
+	pub fn new<Config>(
+		expiration_block: B,
+		deal_order_id: &DealOrderId<BlockNumberFor<Config>, H>,
+		terms: &LoanTerms,
+	) -> Self
+	where
+		Config: frame_system::Config<BlockNumber = B>,
+		<Config as frame_system::Config>::Hashing: Hash<Output = H>,
+		H: AsRef<[u8]>,
+	{
+		let deal_bytes = deal_order_id.1.as_ref();
+		let terms_bytes = terms.encode();
+		let key = concatenate!(deal_bytes, &terms_bytes);
+		TermsProposalId(expiration_block, Config::Hashing::hash(&key))
+	}
+}
+
 pub(crate) trait Id<BlockNum, Hash> {
 	fn expiration(&self) -> BlockNum;
 	fn hash(&self) -> Hash;
@@ -329,6 +725,7 @@ impl_id!(AskOrderId);
 impl_id!(BidOrderId);
 impl_id!(OfferId);
 impl_id!(RepaymentOrderId);
+impl_id!(TermsProposalId);
 
 impl<'a, B, H> Id<B, H> for &'a OrderId<B, H>
 where
@@ -470,6 +867,7 @@ impl<'de> serde::Deserialize<'de> for LegacySighash {
 pub enum Task<AccountId, BlockNum, Hash, Moment> {
 	VerifyTransfer(UnverifiedTransfer<AccountId, BlockNum, Hash, Moment>),
 	CollectCoins(UnverifiedCollectedCoins),
+	VerifyContractWallet(UnverifiedContractWallet<AccountId>),
 }
 
 #[cfg(feature = "runtime-benchmarks")]
@@ -505,10 +903,19 @@ impl<AccountId, BlockNum, Hash, Moment> From<UnverifiedCollectedCoins>
 	}
 }
 
+impl<AccountId, BlockNum, Hash, Moment> From<UnverifiedContractWallet<AccountId>>
+	for Task<AccountId, BlockNum, Hash, Moment>
+{
+	fn from(wallet: UnverifiedContractWallet<AccountId>) -> Self {
+		Task::VerifyContractWallet(wallet)
+	}
+}
+
 #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
 pub enum TaskId<Hash> {
 	VerifyTransfer(TransferId<Hash>),
 	CollectCoins(CollectedCoinsId<Hash>),
+	VerifyContractWallet(ContractWalletId<Hash>),
 }
 
 impl<Hash> From<TransferId<Hash>> for TaskId<Hash> {
@@ -523,10 +930,17 @@ impl<Hash> From<CollectedCoinsId<Hash>> for TaskId<Hash> {
 	}
 }
 
+impl<Hash> From<ContractWalletId<Hash>> for TaskId<Hash> {
+	fn from(id: ContractWalletId<Hash>) -> Self {
+		TaskId::VerifyContractWallet(id)
+	}
+}
+
 #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
 pub enum TaskOutput<AccountId, Balance, BlockNum, Hash, Moment> {
 	VerifyTransfer(TransferId<Hash>, Transfer<AccountId, BlockNum, Hash, Moment>),
 	CollectCoins(CollectedCoinsId<Hash>, CollectedCoinsStruct<Hash, Balance>),
+	VerifyContractWallet(AddressId<Hash>, Address<AccountId>),
 }
 
 impl<AccountId, Balance, BlockNum, Hash, Moment>
@@ -549,6 +963,14 @@ impl<AccountId, Balance, BlockNum, Hash, Moment>
 	}
 }
 
+impl<AccountId, Balance, BlockNum, Hash, Moment> From<(AddressId<Hash>, Address<AccountId>)>
+	for TaskOutput<AccountId, Balance, BlockNum, Hash, Moment>
+{
+	fn from((id, address): (AddressId<Hash>, Address<AccountId>)) -> Self {
+		Self::VerifyContractWallet(id, address)
+	}
+}
+
 #[cfg(test)]
 pub(crate) mod test {
 	use crate::{
@@ -677,6 +1099,15 @@ pub(crate) mod test {
 		}
 	}
 
+	fn create_unverified_contract_wallet() -> UnverifiedContractWallet<AccountId> {
+		UnverifiedContractWallet {
+			blockchain: Blockchain::Ethereum,
+			address: b"wallet".to_vec().try_into().unwrap(),
+			signature: b"signature".to_vec().try_into().unwrap(),
+			owner: AccountId::new([77; 32]),
+		}
+	}
+
 	pub(crate) fn create_unverified_transfer(
 	) -> UnverifiedTransfer<AccountId, BlockNum, Hash, Moment> {
 		let test_info = TestInfo::new_defaults();
@@ -704,11 +1135,15 @@ pub(crate) mod test {
 	trait_tests! {
 	blockchain: Blockchain : Blockchain::Bitcoin,
 	transfer_kind: TransferKind : TransferKind::Native,
+	address_format: AddressFormat : AddressFormat::Evm,
+	supported_transfer_kinds: SupportedTransferKinds : SupportedTransferKinds::ALL,
+	external_chain: ExternalChain : ExternalChain::builtin().remove(0).1,
 	address: Address<AccountId> : create_address(),
 	collected_coins: CollectedCoinsStruct<Hash, Balance> : create_collected_coins(),
 	transfer: Transfer<AccountId, BlockNum, Hash, Moment> : create_funding_transfer().1,
 	unverified_collected_coins: UnverifiedCollectedCoins : create_unverified_collected_coins(),
 	unverified_transfer: UnverifiedTransfer<AccountId, BlockNum, Hash, Moment> : create_unverified_transfer(),
+	unverified_contract_wallet: UnverifiedContractWallet<AccountId> : create_unverified_contract_wallet(),
 	offer: Offer<AccountId, BlockNum, Hash> : TestInfo::new_defaults().create_offer().1,
 	ask_order: AskOrder<AccountId, BlockNum, Hash> : TestInfo::new_defaults().create_ask_order().1,
 	bid_order: BidOrder<AccountId, BlockNum, Hash> : TestInfo::new_defaults().create_bid_order().1,
@@ -721,6 +1156,7 @@ pub(crate) mod test {
 	offer_id: OfferId<BlockNum, Hash> : TestInfo::new_defaults().create_offer().0,
 	transfer_id: TransferId<Hash> : TransferId::new::<mock::Test>(&Blockchain::Rinkeby, b"0"),
 	collected_coins_id: CollectedCoinsId<Hash> : CollectedCoinsId::new::<mock::Test>(&Blockchain::Rinkeby, &[0]),
+	contract_wallet_id: ContractWalletId<Hash> : ContractWalletId::new::<mock::Test>(&AddressId::new::<mock::Test>(&Blockchain::Ethereum, b"0"), &AccountId::new([77; 32])),
 	legacy_sighash: LegacySighash : LegacySighash::default(),
 	task: Task<AccountId, BlockNum, Hash, Moment> : Task::<AccountId, BlockNum, Hash, Moment>::from(create_unverified_collected_coins()),
 	task_id: TaskId<Hash> : TaskId::from(create_funding_transfer().0),
@@ -823,6 +1259,47 @@ pub(crate) mod test {
 		})
 	}
 
+	#[test]
+	fn origination_fee_rate_should_convert_fraction_of_amount_to_ctc() {
+		let rate = OriginationFeeRate::<Balance>::Fraction {
+			fraction: sp_runtime::Perbill::from_percent(2),
+			conversion_rate: sp_runtime::FixedU128::from_rational(3, 2),
+		};
+		assert_eq!(rate.fee(ExternalAmount::from(1_000u64)), 30);
+
+		let flat = OriginationFeeRate::<Balance>::Flat(42);
+		assert_eq!(flat.fee(ExternalAmount::from(1_000u64)), 42);
+	}
+
+	#[test]
+	fn lender_share_amount_owed_should_be_proportional_to_the_share() {
+		let share = LenderShare::<Hash>::new(ExternalAmount::from(3u64));
+		assert_eq!(
+			share.amount_owed(ExternalAmount::from(110u64), ExternalAmount::from(10u64)),
+			ExternalAmount::from(33u64)
+		);
+		assert_eq!(
+			share.amount_owed(ExternalAmount::from(110u64), ExternalAmount::zero()),
+			ExternalAmount::zero()
+		);
+	}
+
+	#[test]
+	fn lender_share_amount_owed_should_not_overflow_for_large_amounts() {
+		let loan_amount = ExternalAmount::MAX / 2;
+		let owed = loan_amount + loan_amount / 10;
+		let share = LenderShare::<Hash>::new(loan_amount / 4);
+
+		// owed * share.amount overflows 256 bits
+		assert!(owed.checked_mul(share.amount).is_none());
+		let expected: ExternalAmount = (owed.full_mul(share.amount)
+			/ sp_core::U512::from(loan_amount))
+		.try_into()
+		.unwrap();
+		assert_eq!(share.amount_owed(owed, loan_amount), expected);
+		assert!(share.amount_owed(owed, loan_amount) < owed / 3);
+	}
+
 	#[test]
 	#[allow(clippy::clone_on_copy)]
 	fn exercise_invalid_term_length_error_clone_and_runtime_debug() {
@@ -118,10 +118,11 @@ where
 			Err(InvalidTask(e)) => Err(TaskError::Evaluation(e)),
 			Err(NoRpcUrl(e)) => Err(TaskError::Scheduler(e.into())),
 			Err(RpcError(e)) => Err(TaskError::Scheduler(e.into())),
+			Err(IncorrectChainId) => Err(TaskError::Scheduler(SchedulerError::IncorrectChainId)),
 		}
 	}
 
-	fn is_persisted(id: &T::Hash) -> bool {
+	fn is_persisted(&self, id: &T::Hash) -> bool {
 		let id = CollectedCoinsId::from(*id);
 		crate::pallet::CollectedCoins::<T>::contains_key(id)
 	}
@@ -0,0 +1,117 @@
+use crate::types::{
+	Address, AddressId, Blockchain, ContractWalletSignature, ExternalAddress, SystemConfig,
+};
+use frame_support::RuntimeDebug;
+use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+use sp_runtime::traits::Hash;
+
+/// A smart contract wallet, e.g. a Safe, to be registered as an address of `owner` once the
+/// wallet accepts `signature` through EIP-1271.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct UnverifiedContractWallet<AccountId> {
+	pub blockchain: Blockchain,
+	pub address: ExternalAddress,
+	pub signature: ContractWalletSignature,
+	pub owner: AccountId,
+}
+
+impl<AccountId> UnverifiedContractWallet<AccountId> {
+	pub fn address_id<C: crate::Config>(&self) -> AddressId<C::Hash> {
+		AddressId::new::<C>(&self.blockchain, &self.address)
+	}
+
+	pub fn into_output(self) -> Address<AccountId> {
+		let Self { blockchain, address, owner, .. } = self;
+		Address { blockchain, value: address, owner }
+	}
+}
+
+/// Identifies the registration of a smart contract wallet by one account. Several accounts may
+/// claim the same wallet at once, only the one the wallet accepts the signature of gets it.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct ContractWalletId<Hash>(Hash);
+
+impl<H: Encode> ContractWalletId<H> {
+	pub fn new<C: SystemConfig>(address_id: &AddressId<H>, owner: &C::AccountId) -> Self
+	where
+		<C as SystemConfig>::Hashing: Hash<Output = H>,
+	{
+		let key = (address_id, owner).encode();
+		ContractWalletId(<C::Hashing as Hash>::hash(&key))
+	}
+}
+
+impl<H> ContractWalletId<H> {
+	pub fn into_inner(self) -> H {
+		self.0
+	}
+}
+
+impl<H> From<H> for ContractWalletId<H> {
+	fn from(hash: H) -> Self {
+		Self(hash)
+	}
+}
+
+use crate::ocw::errors::SchedulerError;
+use crate::ocw::tasks::OffchainVerification;
+use crate::ocw::VerificationFailureCause;
+use crate::ocw::VerificationResult;
+use crate::Config;
+use crate::TaskOutput;
+use pallet_offchain_task_scheduler::tasks::error::TaskError;
+use pallet_offchain_task_scheduler::tasks::TaskV2;
+
+impl<T: Config> OffchainVerification<T> for UnverifiedContractWallet<T::AccountId> {
+	type Output = ();
+
+	fn verify(&self) -> VerificationResult<Self::Output> {
+		crate::Pallet::<T>::verify_contract_wallet_ocw(self)
+	}
+}
+
+impl<T: Config> TaskV2<T> for UnverifiedContractWallet<T::AccountId>
+where
+	UnverifiedContractWallet<T::AccountId>: OffchainVerification<T>,
+{
+	type Call = crate::pallet::Call<T>;
+	type EvaluationError = VerificationFailureCause;
+	type SchedulerError = SchedulerError;
+	fn to_id(&self) -> T::Hash {
+		ContractWalletId::new::<T>(&self.address_id::<T>(), &self.owner).into_inner()
+	}
+
+	fn persistence_call(
+		&self,
+		deadline: T::BlockNumber,
+		id: &T::Hash,
+	) -> Result<Self::Call, TaskError<Self::EvaluationError, Self::SchedulerError>> {
+		use crate::ocw::OffchainError::*;
+		match self.verify() {
+			Ok(()) => {
+				let address_id = self.address_id::<T>();
+				let address = self.clone().into_output();
+				Ok(Self::Call::persist_task_output {
+					deadline,
+					task_output: TaskOutput::from((address_id, address)),
+				})
+			},
+			Err(InvalidTask(cause)) if cause.is_fatal() => {
+				log::warn!("Failed to verify pending task {:?} : {:?}", self, cause);
+				let id = ContractWalletId::from(*id);
+				Ok(Self::Call::fail_task { deadline, task_id: id.into(), cause })
+			},
+			Err(InvalidTask(e)) => Err(TaskError::Evaluation(e)),
+			Err(NoRpcUrl(e)) => Err(TaskError::Scheduler(e.into())),
+			Err(RpcError(e)) => Err(TaskError::Scheduler(e.into())),
+			Err(IncorrectChainId) => Err(TaskError::Scheduler(SchedulerError::IncorrectChainId)),
+		}
+	}
+
+	/// The task is finished once the wallet is registered, whether by `owner` or by another
+	/// account that claimed it too.
+	fn is_persisted(&self, _id: &T::Hash) -> bool {
+		crate::pallet::Addresses::<T>::contains_key(self.address_id::<T>())
+	}
+}
@@ -1,14 +1,28 @@
-use core::ops::Deref;
+use core::cmp::Ordering;
 
-use super::ExternalAmount;
+use super::{AddressFormat, ExternalAddress, ExternalAmount, TransferKind};
+use crate::helpers::canonical_address;
 use frame_support::RuntimeDebug;
 use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
 use scale_info::TypeInfo;
-use sp_std::convert::TryFrom;
+use sp_std::{convert::TryFrom, prelude::*};
 
 pub type RatePerPeriod = u64;
 pub type Decimals = u64;
-#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub type CollateralAmount = u128;
+#[derive(
+	Clone,
+	Copy,
+	Encode,
+	Decode,
+	Eq,
+	PartialEq,
+	Ord,
+	PartialOrd,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+)]
 pub struct Duration {
 	secs: u64,
 	nanos: u32,
@@ -31,6 +45,10 @@ impl Duration {
 	pub const fn is_zero(&self) -> bool {
 		self.secs == 0 && self.nanos == 0
 	}
+
+	pub const fn as_millis(&self) -> u128 {
+		self.secs as u128 * MILLIS_PER_SEC as u128 + (self.nanos / NANOS_PER_MILLI) as u128
+	}
 }
 
 #[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
@@ -47,24 +65,150 @@ pub struct InterestRate {
 	pub interest_type: InterestType,
 }
 
+impl InterestRate {
+	/// The number of interest periods that have started within `elapsed`. A period that
+	/// has started accrues interest in full.
+	fn periods_in(&self, elapsed: &Duration) -> u128 {
+		let period = self.period.as_millis();
+		if period == 0 {
+			return 0;
+		}
+
+		let elapsed = elapsed.as_millis();
+		elapsed / period + u128::from(elapsed % period != 0)
+	}
+
+	/// `10^decimals`, the denominator of `rate_per_period`.
+	fn scale(&self) -> ExternalAmount {
+		ExternalAmount::from(10u64)
+			.checked_pow(self.decimals.into())
+			.unwrap_or(ExternalAmount::MAX)
+	}
+
+	/// Compares two rates regardless of their decimals. Rates that accrue over different periods
+	/// or in different ways can't be compared.
+	pub fn compare(&self, other: &Self) -> Option<Ordering> {
+		if self.period != other.period || self.interest_type != other.interest_type {
+			return None;
+		}
+
+		let lhs = ExternalAmount::from(self.rate_per_period).saturating_mul(other.scale());
+		let rhs = ExternalAmount::from(other.rate_per_period).saturating_mul(self.scale());
+		Some(lhs.cmp(&rhs))
+	}
+}
+
+/// What the amounts of a loan are denominated in on the blockchain of its deal order.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum LoanCurrency {
+	/// The native coin of the blockchain, e.g. ETH on Ethereum.
+	Native,
+	/// A token issued by the EVM `contract`, with amounts in its smallest unit.
+	Token { contract: ExternalAddress },
+	/// The currency of an order or deal order created before loans had one, which can't be
+	/// told. Transfers of any kind are accepted for it, as they were then. New orders can't be
+	/// in it.
+	Legacy,
+}
+
+impl LoanCurrency {
+	/// Whether a transfer of `kind` moves this currency. Token contracts are compared in their
+	/// canonical form, so the same contract given hex encoded or raw is the same token.
+	pub fn accepts(&self, kind: &TransferKind) -> bool {
+		match (self, kind) {
+			(LoanCurrency::Legacy, _) => true,
+			(LoanCurrency::Native, TransferKind::Native) => true,
+			(
+				LoanCurrency::Token { contract },
+				TransferKind::Erc20(transfer_contract) | TransferKind::Ethless(transfer_contract),
+			) => canonical_contract(contract) == canonical_contract(transfer_contract),
+			(_, _) => false,
+		}
+	}
+}
+
+fn canonical_contract(contract: &ExternalAddress) -> Vec<u8> {
+	canonical_address(&AddressFormat::Evm, contract).unwrap_or_else(|| contract.to_vec())
+}
+
 #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
 pub struct LoanTerms {
 	pub amount: ExternalAmount,
+	pub currency: LoanCurrency,
 	pub interest_rate: InterestRate,
 	pub term_length: Duration,
+	/// Native CTC reserved from the borrower for as long as the loan is outstanding.
+	pub collateral: CollateralAmount,
 }
 
-#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
-pub struct AskTerms(LoanTerms);
+/// Fixed point precision used while compounding interest, so rates with few decimals
+/// don't lose precision on every period.
+const COMPOUND_PRECISION: u64 = 1_000_000_000_000_000_000;
 
-impl Deref for AskTerms {
-	type Target = LoanTerms;
+/// `a * b / c`, saturating at `ExternalAmount::MAX` if the product overflows.
+fn saturating_mul_div(a: ExternalAmount, b: ExternalAmount, c: ExternalAmount) -> ExternalAmount {
+	a.checked_mul(b).map_or(ExternalAmount::MAX, |product| product / c)
+}
 
-	fn deref(&self) -> &Self::Target {
-		&self.0
+impl LoanTerms {
+	/// The interest accrued on `amount` once `elapsed` time has passed since the loan was funded.
+	/// All arithmetic is done on `ExternalAmount` and saturates instead of overflowing.
+	pub fn interest_after(&self, elapsed: &Duration) -> ExternalAmount {
+		let rate = &self.interest_rate;
+		let mut periods = rate.periods_in(elapsed);
+		if periods == 0 || rate.rate_per_period == 0 {
+			return ExternalAmount::zero();
+		}
+
+		let scale = rate.scale();
+		let rate_per_period = ExternalAmount::from(rate.rate_per_period);
+
+		match rate.interest_type {
+			InterestType::Simple => saturating_mul_div(
+				self.amount,
+				rate_per_period.saturating_mul(periods.into()),
+				scale,
+			),
+			InterestType::Compound => {
+				let precision = ExternalAmount::from(COMPOUND_PRECISION);
+				// (1 + rate) ^ periods using exponentiation by squaring
+				let mut factor =
+					saturating_mul_div(scale.saturating_add(rate_per_period), precision, scale);
+				let mut growth = precision;
+				while periods != 0 {
+					if periods & 1 == 1 {
+						growth = saturating_mul_div(growth, factor, precision);
+					}
+					factor = saturating_mul_div(factor, factor, precision);
+					periods >>= 1;
+				}
+
+				saturating_mul_div(self.amount, growth, precision).saturating_sub(self.amount)
+			},
+		}
+	}
+
+	/// The principal plus the interest accrued once `elapsed` time has passed since the loan
+	/// was funded.
+	pub fn amount_owed(&self, elapsed: &Duration) -> ExternalAmount {
+		self.amount.saturating_add(self.interest_after(elapsed))
 	}
 }
 
+/// The loans a lender is willing to fund.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct AskTerms {
+	pub min_amount: ExternalAmount,
+	pub max_amount: ExternalAmount,
+	pub currency: LoanCurrency,
+	/// The lowest interest rate the lender accepts.
+	pub min_interest_rate: InterestRate,
+	/// The longest term the lender accepts.
+	pub max_term_length: Duration,
+	/// The least collateral the lender accepts.
+	pub min_collateral: CollateralAmount,
+}
+
 #[derive(Clone, Copy, RuntimeDebug)]
 pub struct InvalidTermLengthError;
 
@@ -74,6 +218,30 @@ impl<T: crate::Config> From<InvalidTermLengthError> for crate::Error<T> {
 	}
 }
 
+#[derive(Clone, Copy, RuntimeDebug)]
+pub enum InvalidTermsError {
+	TermLength,
+	EmptyRange,
+	LegacyCurrency,
+}
+
+impl From<InvalidTermLengthError> for InvalidTermsError {
+	fn from(_: InvalidTermLengthError) -> Self {
+		Self::TermLength
+	}
+}
+
+impl<T: crate::Config> From<InvalidTermsError> for crate::Error<T> {
+	fn from(error: InvalidTermsError) -> Self {
+		match error {
+			InvalidTermsError::TermLength => Self::InvalidTermLength,
+			InvalidTermsError::EmptyRange => Self::InvalidTermRange,
+			InvalidTermsError::LegacyCurrency => Self::LegacyCurrency,
+		}
+	}
+}
+
+/// Ask terms that only match the exact `terms`.
 impl TryFrom<LoanTerms> for AskTerms {
 	type Error = InvalidTermLengthError;
 	fn try_from(terms: LoanTerms) -> Result<Self, Self::Error> {
@@ -81,33 +249,77 @@ impl TryFrom<LoanTerms> for AskTerms {
 			return Err(InvalidTermLengthError);
 		}
 
-		Ok(Self(terms))
+		Ok(Self {
+			min_amount: terms.amount,
+			max_amount: terms.amount,
+			currency: terms.currency,
+			min_interest_rate: terms.interest_rate,
+			max_term_length: terms.term_length,
+			min_collateral: terms.collateral,
+		})
 	}
 }
 
 impl AskTerms {
+	pub fn validate(&self) -> Result<(), InvalidTermsError> {
+		if self.max_term_length.is_zero() {
+			return Err(InvalidTermsError::TermLength);
+		}
+		if self.min_amount > self.max_amount {
+			return Err(InvalidTermsError::EmptyRange);
+		}
+		if self.currency == LoanCurrency::Legacy {
+			return Err(InvalidTermsError::LegacyCurrency);
+		}
+
+		Ok(())
+	}
+
 	pub fn match_with(&self, bid_terms: &BidTerms) -> bool {
-		self.amount == bid_terms.amount
-			&& self.interest_rate == bid_terms.interest_rate
-			&& self.term_length == bid_terms.term_length
+		self.agreed_terms(bid_terms.clone()).is_some()
 	}
 
+	/// The loan both sides accept, if their ranges overlap and they are in the same currency.
+	/// The borrower gets the amount they asked for at the lender's lowest rate and collateral,
+	/// for the longest term both accept.
 	pub fn agreed_terms(&self, bid_terms: BidTerms) -> Option<LoanTerms> {
-		self.match_with(&bid_terms).then_some(bid_terms.0)
+		let currency_fits = self.currency == bid_terms.currency;
+		let amount_fits =
+			self.min_amount <= bid_terms.amount && bid_terms.amount <= self.max_amount;
+		let rate_fits = matches!(
+			self.min_interest_rate.compare(&bid_terms.max_interest_rate),
+			Some(Ordering::Less | Ordering::Equal)
+		);
+		let term_length = self.max_term_length.min(bid_terms.max_term_length);
+		let term_fits = term_length >= bid_terms.min_term_length;
+		let collateral_fits = self.min_collateral <= bid_terms.max_collateral;
+
+		(currency_fits && amount_fits && rate_fits && term_fits && collateral_fits).then(|| {
+			LoanTerms {
+				amount: bid_terms.amount,
+				currency: bid_terms.currency,
+				interest_rate: self.min_interest_rate.clone(),
+				term_length,
+				collateral: self.min_collateral,
+			}
+		})
 	}
 }
 
+/// The loan a borrower is asking for.
 #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
-pub struct BidTerms(LoanTerms);
-
-impl Deref for BidTerms {
-	type Target = LoanTerms;
-
-	fn deref(&self) -> &Self::Target {
-		&self.0
-	}
+pub struct BidTerms {
+	pub amount: ExternalAmount,
+	pub currency: LoanCurrency,
+	/// The highest interest rate the borrower accepts.
+	pub max_interest_rate: InterestRate,
+	pub min_term_length: Duration,
+	pub max_term_length: Duration,
+	/// The most collateral the borrower is willing to post.
+	pub max_collateral: CollateralAmount,
 }
 
+/// Bid terms that only match the exact `terms`.
 impl TryFrom<LoanTerms> for BidTerms {
 	type Error = InvalidTermLengthError;
 	fn try_from(terms: LoanTerms) -> Result<Self, Self::Error> {
@@ -115,11 +327,32 @@ impl TryFrom<LoanTerms> for BidTerms {
 			return Err(InvalidTermLengthError);
 		}
 
-		Ok(Self(terms))
+		Ok(Self {
+			amount: terms.amount,
+			currency: terms.currency,
+			max_interest_rate: terms.interest_rate,
+			min_term_length: terms.term_length,
+			max_term_length: terms.term_length,
+			max_collateral: terms.collateral,
+		})
 	}
 }
 
 impl BidTerms {
+	pub fn validate(&self) -> Result<(), InvalidTermsError> {
+		if self.max_term_length.is_zero() {
+			return Err(InvalidTermsError::TermLength);
+		}
+		if self.min_term_length > self.max_term_length {
+			return Err(InvalidTermsError::EmptyRange);
+		}
+		if self.currency == LoanCurrency::Legacy {
+			return Err(InvalidTermsError::LegacyCurrency);
+		}
+
+		Ok(())
+	}
+
 	pub fn match_with(&self, ask_terms: &AskTerms) -> bool {
 		ask_terms.match_with(self)
 	}
@@ -134,8 +367,10 @@ impl Default for LoanTerms {
 	fn default() -> Self {
 		Self {
 			amount: Default::default(),
+			currency: LoanCurrency::Native,
 			interest_rate: InterestRate::default(),
 			term_length: Duration::from_millis(100_000),
+			collateral: 0,
 		}
 	}
 }
@@ -150,3 +385,253 @@ impl Default for InterestRate {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn terms(rate_per_period: RatePerPeriod, interest_type: InterestType) -> LoanTerms {
+		LoanTerms {
+			amount: 1_000_000u64.into(),
+			currency: LoanCurrency::Native,
+			interest_rate: InterestRate {
+				rate_per_period,
+				decimals: 2,
+				period: Duration::from_millis(1_000),
+				interest_type,
+			},
+			term_length: Duration::from_millis(10_000),
+			collateral: 0,
+		}
+	}
+
+	#[test]
+	fn no_interest_accrues_before_the_first_period_starts() {
+		let terms = terms(10, InterestType::Simple);
+		assert_eq!(terms.amount_owed(&Duration::from_millis(0)), terms.amount);
+	}
+
+	#[test]
+	fn no_interest_accrues_without_a_rate() {
+		let terms = terms(0, InterestType::Compound);
+		assert_eq!(terms.amount_owed(&Duration::from_millis(5_000)), terms.amount);
+	}
+
+	#[test]
+	fn started_periods_accrue_in_full() {
+		let terms = terms(10, InterestType::Simple);
+		assert_eq!(terms.amount_owed(&Duration::from_millis(1)), 1_100_000u64.into());
+		assert_eq!(terms.amount_owed(&Duration::from_millis(1_000)), 1_100_000u64.into());
+		assert_eq!(terms.amount_owed(&Duration::from_millis(1_001)), 1_200_000u64.into());
+	}
+
+	#[test]
+	fn simple_interest_accrues_on_principal() {
+		let terms = terms(10, InterestType::Simple);
+		assert_eq!(terms.interest_after(&Duration::from_millis(3_000)), 300_000u64.into());
+	}
+
+	#[test]
+	fn compound_interest_accrues_on_interest() {
+		let terms = terms(10, InterestType::Compound);
+		// 1_000_000 * 1.1^3
+		assert_eq!(terms.interest_after(&Duration::from_millis(3_000)), 331_000u64.into());
+		// 1_000_000 * 1.1^10
+		assert_eq!(terms.amount_owed(&Duration::from_millis(10_000)), 2_593_742u64.into());
+	}
+
+	#[test]
+	fn interest_saturates_instead_of_overflowing() {
+		let terms = terms(RatePerPeriod::MAX, InterestType::Compound);
+		assert_eq!(terms.amount_owed(&Duration::from_millis(u64::MAX)), ExternalAmount::MAX);
+
+		let terms = LoanTerms { amount: ExternalAmount::MAX, ..terms };
+		assert_eq!(terms.amount_owed(&Duration::from_millis(1)), ExternalAmount::MAX);
+	}
+
+	#[test]
+	fn ask_and_bid_terms_must_agree_on_collateral() {
+		let ask_terms =
+			AskTerms::try_from(LoanTerms { collateral: 1_000, ..terms(10, InterestType::Simple) })
+				.unwrap();
+		let bid_terms =
+			BidTerms::try_from(LoanTerms { collateral: 1_000, ..terms(10, InterestType::Simple) })
+				.unwrap();
+		assert!(ask_terms.match_with(&bid_terms));
+
+		let bid_terms = BidTerms::try_from(terms(10, InterestType::Simple)).unwrap();
+		assert!(!ask_terms.match_with(&bid_terms));
+		assert_eq!(ask_terms.agreed_terms(bid_terms), None);
+	}
+
+	fn rate(rate_per_period: RatePerPeriod, decimals: Decimals) -> InterestRate {
+		InterestRate {
+			rate_per_period,
+			decimals,
+			period: Duration::from_millis(1_000),
+			interest_type: InterestType::Simple,
+		}
+	}
+
+	fn ask_terms() -> AskTerms {
+		AskTerms {
+			min_amount: 1_000u64.into(),
+			max_amount: 5_000u64.into(),
+			currency: LoanCurrency::Native,
+			min_interest_rate: rate(5, 2),
+			max_term_length: Duration::from_millis(20_000),
+			min_collateral: 100,
+		}
+	}
+
+	fn bid_terms() -> BidTerms {
+		BidTerms {
+			amount: 2_000u64.into(),
+			currency: LoanCurrency::Native,
+			max_interest_rate: rate(10, 2),
+			min_term_length: Duration::from_millis(5_000),
+			max_term_length: Duration::from_millis(30_000),
+			max_collateral: 500,
+		}
+	}
+
+	#[test]
+	fn overlapping_ranges_agree_on_terms_inside_the_overlap() {
+		assert_eq!(
+			ask_terms().agreed_terms(bid_terms()),
+			Some(LoanTerms {
+				amount: 2_000u64.into(),
+				currency: LoanCurrency::Native,
+				interest_rate: rate(5, 2),
+				term_length: Duration::from_millis(20_000),
+				collateral: 100,
+			})
+		);
+		assert!(bid_terms().match_with(&ask_terms()));
+	}
+
+	fn token(contract: &str) -> LoanCurrency {
+		LoanCurrency::Token {
+			contract: ExternalAddress::try_from(contract.as_bytes().to_vec()).unwrap(),
+		}
+	}
+
+	#[test]
+	fn ask_and_bid_terms_must_agree_on_currency() {
+		let bid_terms = BidTerms { currency: token("usdc"), ..bid_terms() };
+		assert!(!ask_terms().match_with(&bid_terms));
+
+		let ask_terms = AskTerms { currency: token("dai"), ..ask_terms() };
+		assert!(!ask_terms.match_with(&bid_terms));
+
+		let ask_terms = AskTerms { currency: token("usdc"), ..ask_terms };
+		assert_eq!(ask_terms.agreed_terms(bid_terms).unwrap().currency, token("usdc"));
+	}
+
+	#[test]
+	fn currency_accepts_only_transfers_of_it() {
+		let contract = ExternalAddress::try_from(b"usdc".to_vec()).unwrap();
+		let other = ExternalAddress::try_from(b"dai".to_vec()).unwrap();
+
+		assert!(LoanCurrency::Native.accepts(&TransferKind::Native));
+		assert!(!LoanCurrency::Native.accepts(&TransferKind::Ethless(contract.clone())));
+
+		let usdc = token("usdc");
+		assert!(usdc.accepts(&TransferKind::Erc20(contract.clone())));
+		assert!(usdc.accepts(&TransferKind::Ethless(contract)));
+		assert!(!usdc.accepts(&TransferKind::Ethless(other)));
+		assert!(!usdc.accepts(&TransferKind::Native));
+	}
+
+	#[test]
+	fn currency_compares_token_contracts_in_canonical_form() {
+		let hex_encoded =
+			ExternalAddress::try_from(b"0x0aD1439A0e0bFdcD49939f9722866651A4AA9B3C".to_vec())
+				.unwrap();
+		let raw = ExternalAddress::try_from(
+			hex::decode("0ad1439a0e0bfdcd49939f9722866651a4aa9b3c").unwrap(),
+		)
+		.unwrap();
+
+		let token = LoanCurrency::Token { contract: hex_encoded.clone() };
+		assert!(token.accepts(&TransferKind::Erc20(raw.clone())));
+		let token = LoanCurrency::Token { contract: raw };
+		assert!(token.accepts(&TransferKind::Ethless(hex_encoded)));
+	}
+
+	#[test]
+	fn legacy_currency_accepts_any_transfer() {
+		let contract = ExternalAddress::try_from(b"usdc".to_vec()).unwrap();
+
+		assert!(LoanCurrency::Legacy.accepts(&TransferKind::Native));
+		assert!(LoanCurrency::Legacy.accepts(&TransferKind::Erc20(contract.clone())));
+		assert!(LoanCurrency::Legacy.accepts(&TransferKind::Ethless(contract)));
+	}
+
+	#[test]
+	fn amount_must_be_within_the_ask_range() {
+		let bid_terms = BidTerms { amount: 6_000u64.into(), ..bid_terms() };
+		assert!(!ask_terms().match_with(&bid_terms));
+
+		let bid_terms = BidTerms { amount: 999u64.into(), ..bid_terms };
+		assert!(!ask_terms().match_with(&bid_terms));
+
+		let bid_terms = BidTerms { amount: 5_000u64.into(), ..bid_terms };
+		assert!(ask_terms().match_with(&bid_terms));
+	}
+
+	#[test]
+	fn rates_are_compared_regardless_of_decimals() {
+		let bid_terms = BidTerms { max_interest_rate: rate(4, 2), ..bid_terms() };
+		assert!(!ask_terms().match_with(&bid_terms));
+
+		let bid_terms = BidTerms { max_interest_rate: rate(50, 3), ..bid_terms };
+		assert!(ask_terms().match_with(&bid_terms));
+	}
+
+	#[test]
+	fn rates_with_different_periods_do_not_match() {
+		let bid_terms = BidTerms {
+			max_interest_rate: InterestRate { period: Duration::from_millis(2_000), ..rate(10, 2) },
+			..bid_terms()
+		};
+		assert_eq!(ask_terms().agreed_terms(bid_terms), None);
+	}
+
+	#[test]
+	fn term_ranges_must_overlap() {
+		let bid_terms = BidTerms { min_term_length: Duration::from_millis(20_001), ..bid_terms() };
+		assert!(!ask_terms().match_with(&bid_terms));
+
+		let bid_terms = BidTerms { max_term_length: Duration::from_millis(10_000), ..bid_terms() };
+		assert_eq!(
+			ask_terms().agreed_terms(bid_terms).map(|terms| terms.term_length),
+			Some(Duration::from_millis(10_000))
+		);
+	}
+
+	#[test]
+	fn collateral_must_cover_the_ask_minimum() {
+		let bid_terms = BidTerms { max_collateral: 99, ..bid_terms() };
+		assert!(!ask_terms().match_with(&bid_terms));
+	}
+
+	#[test]
+	fn empty_ranges_are_invalid() {
+		assert!(ask_terms().validate().is_ok());
+		assert!(bid_terms().validate().is_ok());
+
+		let ask_terms = AskTerms { min_amount: 5_001u64.into(), ..ask_terms() };
+		assert!(matches!(ask_terms.validate(), Err(InvalidTermsError::EmptyRange)));
+
+		let bid_terms = BidTerms { min_term_length: Duration::from_millis(30_001), ..bid_terms() };
+		assert!(matches!(bid_terms.validate(), Err(InvalidTermsError::EmptyRange)));
+
+		let bid_terms = BidTerms {
+			min_term_length: Duration::from_millis(0),
+			max_term_length: Duration::from_millis(0),
+			..bid_terms
+		};
+		assert!(matches!(bid_terms.validate(), Err(InvalidTermsError::TermLength)));
+	}
+}
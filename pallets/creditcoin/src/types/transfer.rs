@@ -133,12 +133,13 @@ where
 			Err(InvalidTask(e)) => Err(TaskError::Evaluation(e)),
 			Err(NoRpcUrl(e)) => Err(TaskError::Scheduler(e.into())),
 			Err(RpcError(e)) => Err(TaskError::Scheduler(e.into())),
+			Err(IncorrectChainId) => Err(TaskError::Scheduler(SchedulerError::IncorrectChainId)),
 		}
 	}
 
-	fn is_persisted(id: &T::Hash) -> bool {
+	fn is_persisted(&self, id: &T::Hash) -> bool {
 		let id = TransferId::from(*id);
-		crate::pallet::Transfers::<T>::contains_key(id)
+		crate::Pallet::<T>::is_transfer_registered(&id)
 	}
 }
 
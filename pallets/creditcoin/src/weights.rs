@@ -6,6 +6,10 @@
 //! WORST CASE MAP SIZE: `1000000`
 //! HOSTNAME: `github-runner-5203088300-attempt-1`, CPU: `AMD EPYC 7452 32-Core Processor`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("dev"), DB CACHE: 1024
+//!
+//! PROVISIONAL: the header above describes the last benchmark run. The weights marked
+//! `PROVISIONAL` were added or changed afterwards and estimated by hand from the storage their
+//! benchmarks access. Regenerate this file with the command below before a release.
 
 // Executed Command:
 // ./target/release/creditcoin-node
@@ -68,49 +72,86 @@ impl<T: frame_system::Config> crate::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(t.into())))
 			.saturating_add(Weight::from_parts(0, 2529).saturating_mul(t.into()))
 	}
-	/// Storage: Creditcoin AskOrders (r:255 w:255)
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
+	/// Storage: Creditcoin ExpiryCursor (r:1 w:1)
+	/// Proof: Creditcoin ExpiryCursor (max_values: Some(1), max_size: Some(41), added: 536, mode: MaxEncodedLen)
+	/// Storage: Creditcoin AskOrders (r:106 w:42)
 	/// Proof: Creditcoin AskOrders (max_values: None, max_size: Some(448), added: 2923, mode: MaxEncodedLen)
-	/// Storage: Creditcoin BidOrders (r:255 w:255)
+	/// Storage: Creditcoin BidOrders (r:106 w:42)
 	/// Proof: Creditcoin BidOrders (max_values: None, max_size: Some(448), added: 2923, mode: MaxEncodedLen)
-	/// Storage: Creditcoin Offers (r:255 w:255)
+	/// Storage: Creditcoin Offers (r:106 w:42)
 	/// Proof: Creditcoin Offers (max_values: None, max_size: Some(415), added: 2890, mode: MaxEncodedLen)
-	/// Storage: Creditcoin DealOrders (r:510 w:509)
+	/// Storage: Creditcoin AccountAskOrders (r:0 w:42)
+	/// Proof: Creditcoin AccountAskOrders (max_values: None, max_size: Some(116), added: 2591, mode: MaxEncodedLen)
+	/// Storage: Creditcoin AccountBidOrders (r:0 w:42)
+	/// Proof: Creditcoin AccountBidOrders (max_values: None, max_size: Some(116), added: 2591, mode: MaxEncodedLen)
+	/// Storage: Creditcoin AccountOffers (r:0 w:42)
+	/// Proof: Creditcoin AccountOffers (max_values: None, max_size: Some(116), added: 2591, mode: MaxEncodedLen)
+	/// Storage: Creditcoin TermsProposals (r:106 w:42)
+	/// Proof: Creditcoin TermsProposals (max_values: None, max_size: Some(229), added: 2704, mode: MaxEncodedLen)
+	/// Storage: Creditcoin DealOrders (r:148 w:42)
 	/// Proof: Creditcoin DealOrders (max_values: None, max_size: Some(624), added: 3099, mode: MaxEncodedLen)
-	/// The range of component `a` is `[0, 255]`.
-	/// The range of component `b` is `[0, 255]`.
-	/// The range of component `o` is `[0, 255]`.
-	/// The range of component `d` is `[0, 255]`.
-	/// The range of component `f` is `[0, 255]`.
-	fn on_initialize(a: u32, b: u32, o: u32, d: u32, f: u32, ) -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `138 + a * (69 ±0) + b * (69 ±0) + o * (69 ±0) + d * (261 ±0) + f * (293 ±0)`
-		//  Estimated: `7059 + d * (3099 ±0) + f * (3099 ±0) + b * (2923 ±0) + o * (2890 ±0) + a * (2923 ±0)`
+	/// Storage: Creditcoin DealOrderCollateral (r:36 w:36)
+	/// Proof: Creditcoin DealOrderCollateral (max_values: None, max_size: Some(68), added: 2543, mode: MaxEncodedLen)
+	/// Storage: Creditcoin Addresses (r:576 w:0)
+	/// Proof: Creditcoin Addresses (max_values: None, max_size: Some(597), added: 3072, mode: MaxEncodedLen)
+	/// Storage: Creditcoin AccountDealOrders (r:0 w:612)
+	/// Proof: Creditcoin AccountDealOrders (max_values: None, max_size: Some(116), added: 2591, mode: MaxEncodedLen)
+	/// Storage: Creditcoin DealOrderShares (r:612 w:576)
+	/// Proof: Creditcoin DealOrderShares (max_values: None, max_size: Some(197), added: 2672, mode: MaxEncodedLen)
+	/// Storage: Creditcoin RepaymentOrders (r:36 w:36)
+	/// Proof: Creditcoin RepaymentOrders (max_values: None, max_size: Some(200), added: 2675, mode: MaxEncodedLen)
+	/// The range of component `a` is `[0, 36]`.
+	/// The range of component `b` is `[0, 36]`.
+	/// The range of component `o` is `[0, 36]`.
+	/// The range of component `d` is `[0, 36]`.
+	/// The range of component `f` is `[0, 36]`.
+	/// The range of component `p` is `[0, 36]`.
+	/// The range of component `r` is `[0, 36]`.
+	/// The range of component `s` is `[1, 64]`.
+	fn on_initialize(a: u32, b: u32, o: u32, d: u32, f: u32, p: u32, r: u32, s: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `138 + a * (69 ±0) + b * (69 ±0) + o * (69 ±0) + d * (2906 ±0) + f * (293 ±0) + s * (12 ±0)`
+		//  Estimated: `7059 + d * (100218 ±0) + f * (3099 ±0) + s * (12515 ±0) + b * (2923 ±0) + o * (2890 ±0) + a * (2923 ±0)`
 		// Minimum execution time: 2_924_795_000 picoseconds.
 		Weight::from_parts(2_934_495_000, 0)
 			.saturating_add(Weight::from_parts(0, 7059))
 			// Standard Error: 234_423
-			.saturating_add(Weight::from_parts(5_180_954, 0).saturating_mul(d.into()))
+			.saturating_add(Weight::from_parts(31_244_118, 0).saturating_mul(d.into()))
 			// Standard Error: 234_423
 			.saturating_add(Weight::from_parts(10_382_694, 0).saturating_mul(f.into()))
+			// Standard Error: 421_307
+			.saturating_add(Weight::from_parts(9_704_215, 0).saturating_mul(s.into()))
 			.saturating_add(T::DbWeight::get().reads(1))
 			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(a.into())))
 			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(b.into())))
 			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(o.into())))
-			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(d.into())))
+			.saturating_add(T::DbWeight::get().reads((35_u64).saturating_mul(d.into())))
 			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(f.into())))
-			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(a.into())))
-			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(b.into())))
-			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(o.into())))
-			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(d.into())))
-			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(f.into())))
-			.saturating_add(Weight::from_parts(0, 3099).saturating_mul(d.into()))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(p.into())))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(r.into())))
+			.saturating_add(T::DbWeight::get().reads((6_u64).saturating_mul(s.into())))
+			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(a.into())))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(b.into())))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(o.into())))
+			.saturating_add(T::DbWeight::get().writes((35_u64).saturating_mul(d.into())))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(p.into())))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(r.into())))
+			.saturating_add(Weight::from_parts(0, 100218).saturating_mul(d.into()))
 			.saturating_add(Weight::from_parts(0, 3099).saturating_mul(f.into()))
 			.saturating_add(Weight::from_parts(0, 2923).saturating_mul(b.into()))
 			.saturating_add(Weight::from_parts(0, 2890).saturating_mul(o.into()))
 			.saturating_add(Weight::from_parts(0, 2923).saturating_mul(a.into()))
+			.saturating_add(Weight::from_parts(0, 2704).saturating_mul(p.into()))
+			.saturating_add(Weight::from_parts(0, 2675).saturating_mul(r.into()))
+			.saturating_add(Weight::from_parts(0, 12515).saturating_mul(s.into()))
 	}
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
 	/// Storage: Creditcoin Addresses (r:1 w:1)
 	/// Proof: Creditcoin Addresses (max_values: None, max_size: Some(597), added: 3072, mode: MaxEncodedLen)
+	/// Storage: Creditcoin AccountAddresses (r:0 w:1)
+	/// Proof: Creditcoin AccountAddresses (max_values: None, max_size: Some(116), added: 2591, mode: MaxEncodedLen)
 	fn register_address() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `76`
@@ -119,7 +160,7 @@ impl<T: frame_system::Config> crate::WeightInfo for WeightInfo<T> {
 		Weight::from_parts(99_104_000, 0)
 			.saturating_add(Weight::from_parts(0, 4062))
 			.saturating_add(T::DbWeight::get().reads(1))
-			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(T::DbWeight::get().writes(2))
 	}
 	/// Storage: Creditcoin LegacyWallets (r:1 w:1)
 	/// Proof: Creditcoin LegacyWallets (max_values: None, max_size: Some(32), added: 2507, mode: MaxEncodedLen)
@@ -137,54 +178,80 @@ impl<T: frame_system::Config> crate::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads(3))
 			.saturating_add(T::DbWeight::get().writes(2))
 	}
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
 	/// Storage: Creditcoin AskOrders (r:1 w:1)
 	/// Proof: Creditcoin AskOrders (max_values: None, max_size: Some(448), added: 2923, mode: MaxEncodedLen)
 	/// Storage: Creditcoin Addresses (r:1 w:0)
 	/// Proof: Creditcoin Addresses (max_values: None, max_size: Some(597), added: 3072, mode: MaxEncodedLen)
 	/// Storage: Creditcoin UsedGuids (r:1 w:1)
 	/// Proof: Creditcoin UsedGuids (max_values: None, max_size: Some(274), added: 2749, mode: MaxEncodedLen)
+	/// Storage: Creditcoin AccountAskOrders (r:0 w:1)
+	/// Proof: Creditcoin AccountAskOrders (max_values: None, max_size: Some(116), added: 2591, mode: MaxEncodedLen)
+	/// Storage: Creditcoin OrderMatchingEnabled (r:1 w:0)
+	/// Proof: Creditcoin OrderMatchingEnabled (max_values: Some(1), max_size: Some(1), added: 496, mode: MaxEncodedLen)
+	/// Storage: Creditcoin AskOrderBook (r:1 w:1)
+	/// Proof: Creditcoin AskOrderBook (max_values: None, max_size: Some(8873), added: 11348, mode: MaxEncodedLen)
+	/// Storage: Creditcoin PendingMatches (r:1 w:1)
+	/// Proof: Creditcoin PendingMatches (max_values: Some(1), max_size: Some(41987), added: 42482, mode: MaxEncodedLen)
 	fn add_ask_order() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `206`
-		//  Estimated: `11714`
+		//  Measured:  `8611`
+		//  Estimated: `66040`
 		// Minimum execution time: 48_102_000 picoseconds.
 		Weight::from_parts(49_101_000, 0)
-			.saturating_add(Weight::from_parts(0, 11714))
-			.saturating_add(T::DbWeight::get().reads(3))
-			.saturating_add(T::DbWeight::get().writes(2))
+			.saturating_add(Weight::from_parts(0, 66040))
+			.saturating_add(T::DbWeight::get().reads(6))
+			.saturating_add(T::DbWeight::get().writes(5))
 	}
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
 	/// Storage: Creditcoin BidOrders (r:1 w:1)
 	/// Proof: Creditcoin BidOrders (max_values: None, max_size: Some(448), added: 2923, mode: MaxEncodedLen)
 	/// Storage: Creditcoin Addresses (r:1 w:0)
 	/// Proof: Creditcoin Addresses (max_values: None, max_size: Some(597), added: 3072, mode: MaxEncodedLen)
 	/// Storage: Creditcoin UsedGuids (r:1 w:1)
 	/// Proof: Creditcoin UsedGuids (max_values: None, max_size: Some(274), added: 2749, mode: MaxEncodedLen)
+	/// Storage: Creditcoin AccountBidOrders (r:0 w:1)
+	/// Proof: Creditcoin AccountBidOrders (max_values: None, max_size: Some(116), added: 2591, mode: MaxEncodedLen)
+	/// Storage: Creditcoin OrderMatchingEnabled (r:1 w:0)
+	/// Proof: Creditcoin OrderMatchingEnabled (max_values: Some(1), max_size: Some(1), added: 496, mode: MaxEncodedLen)
+	/// Storage: Creditcoin BidOrderBook (r:1 w:1)
+	/// Proof: Creditcoin BidOrderBook (max_values: None, max_size: Some(5161), added: 7636, mode: MaxEncodedLen)
+	/// Storage: Creditcoin PendingMatches (r:1 w:1)
+	/// Proof: Creditcoin PendingMatches (max_values: Some(1), max_size: Some(41987), added: 42482, mode: MaxEncodedLen)
 	fn add_bid_order() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `206`
-		//  Estimated: `11714`
+		//  Measured:  `4867`
+		//  Estimated: `62328`
 		// Minimum execution time: 48_202_000 picoseconds.
 		Weight::from_parts(49_002_000, 0)
-			.saturating_add(Weight::from_parts(0, 11714))
-			.saturating_add(T::DbWeight::get().reads(3))
-			.saturating_add(T::DbWeight::get().writes(2))
+			.saturating_add(Weight::from_parts(0, 62328))
+			.saturating_add(T::DbWeight::get().reads(6))
+			.saturating_add(T::DbWeight::get().writes(5))
 	}
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
 	/// Storage: Creditcoin AskOrders (r:1 w:0)
 	/// Proof: Creditcoin AskOrders (max_values: None, max_size: Some(448), added: 2923, mode: MaxEncodedLen)
 	/// Storage: Creditcoin BidOrders (r:1 w:0)
 	/// Proof: Creditcoin BidOrders (max_values: None, max_size: Some(448), added: 2923, mode: MaxEncodedLen)
 	/// Storage: Creditcoin Offers (r:1 w:1)
 	/// Proof: Creditcoin Offers (max_values: None, max_size: Some(415), added: 2890, mode: MaxEncodedLen)
+	/// Storage: Creditcoin AccountOffers (r:0 w:1)
+	/// Proof: Creditcoin AccountOffers (max_values: None, max_size: Some(116), added: 2591, mode: MaxEncodedLen)
+	/// Storage: Creditcoin AskOrderBook (r:1 w:1)
+	/// Proof: Creditcoin AskOrderBook (max_values: None, max_size: Some(8873), added: 11348, mode: MaxEncodedLen)
+	/// Storage: Creditcoin BidOrderBook (r:1 w:1)
+	/// Proof: Creditcoin BidOrderBook (max_values: None, max_size: Some(5161), added: 7636, mode: MaxEncodedLen)
 	fn add_offer() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `580`
-		//  Estimated: `11706`
+		//  Measured:  `13568`
+		//  Estimated: `30690`
 		// Minimum execution time: 47_402_000 picoseconds.
 		Weight::from_parts(47_901_000, 0)
-			.saturating_add(Weight::from_parts(0, 11706))
-			.saturating_add(T::DbWeight::get().reads(3))
-			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(Weight::from_parts(0, 30690))
+			.saturating_add(T::DbWeight::get().reads(5))
+			.saturating_add(T::DbWeight::get().writes(4))
 	}
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
 	/// Storage: Creditcoin DealOrders (r:1 w:1)
 	/// Proof: Creditcoin DealOrders (max_values: None, max_size: Some(624), added: 3099, mode: MaxEncodedLen)
 	/// Storage: Creditcoin Offers (r:1 w:0)
@@ -195,6 +262,8 @@ impl<T: frame_system::Config> crate::WeightInfo for WeightInfo<T> {
 	/// Proof: Creditcoin BidOrders (max_values: None, max_size: Some(448), added: 2923, mode: MaxEncodedLen)
 	/// Storage: Timestamp Now (r:1 w:0)
 	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Creditcoin AccountDealOrders (r:0 w:2)
+	/// Proof: Creditcoin AccountDealOrders (max_values: None, max_size: Some(116), added: 2591, mode: MaxEncodedLen)
 	fn add_deal_order() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `854`
@@ -203,7 +272,7 @@ impl<T: frame_system::Config> crate::WeightInfo for WeightInfo<T> {
 		Weight::from_parts(61_602_000, 0)
 			.saturating_add(Weight::from_parts(0, 17288))
 			.saturating_add(T::DbWeight::get().reads(5))
-			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(T::DbWeight::get().writes(3))
 	}
 	/// Storage: TaskScheduler Authorities (r:1 w:1)
 	/// Proof: TaskScheduler Authorities (max_values: None, max_size: Some(48), added: 2523, mode: MaxEncodedLen)
@@ -217,26 +286,36 @@ impl<T: frame_system::Config> crate::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads(1))
 			.saturating_add(T::DbWeight::get().writes(1))
 	}
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
 	/// Storage: TaskScheduler Authorities (r:1 w:0)
 	/// Proof: TaskScheduler Authorities (max_values: None, max_size: Some(48), added: 2523, mode: MaxEncodedLen)
 	/// Storage: Creditcoin Transfers (r:1 w:1)
 	/// Proof: Creditcoin Transfers (max_values: None, max_size: Some(987), added: 3462, mode: MaxEncodedLen)
+	/// Storage: Creditcoin ArchivedTransfers (r:1 w:0)
+	/// Proof: Creditcoin ArchivedTransfers (max_values: None, max_size: Some(32), added: 2507, mode: MaxEncodedLen)
+	/// Storage: Creditcoin RepaymentOrders (r:1 w:1)
+	/// Proof: Creditcoin RepaymentOrders (max_values: None, max_size: Some(200), added: 2675, mode: MaxEncodedLen)
+	/// Storage: Creditcoin DealOrderTransfers (r:0 w:1)
+	/// Proof: Creditcoin DealOrderTransfers (max_values: None, max_size: Some(104), added: 2579, mode: MaxEncodedLen)
 	/// Storage: TaskScheduler PendingTasks (r:0 w:1)
 	/// Proof: TaskScheduler PendingTasks (max_values: None, max_size: Some(1512), added: 3987, mode: MaxEncodedLen)
 	fn persist_transfer() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `290`
-		//  Estimated: `7965`
+		//  Estimated: `10640`
 		// Minimum execution time: 46_801_000 picoseconds.
-		Weight::from_parts(47_601_000, 0)
-			.saturating_add(Weight::from_parts(0, 7965))
-			.saturating_add(T::DbWeight::get().reads(2))
-			.saturating_add(T::DbWeight::get().writes(2))
+		Weight::from_parts(48_902_000, 0)
+			.saturating_add(Weight::from_parts(0, 10640))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(4))
 	}
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
 	/// Storage: TaskScheduler Authorities (r:1 w:0)
 	/// Proof: TaskScheduler Authorities (max_values: None, max_size: Some(48), added: 2523, mode: MaxEncodedLen)
 	/// Storage: Creditcoin Transfers (r:1 w:0)
 	/// Proof: Creditcoin Transfers (max_values: None, max_size: Some(987), added: 3462, mode: MaxEncodedLen)
+	/// Storage: Creditcoin ArchivedTransfers (r:1 w:0)
+	/// Proof: Creditcoin ArchivedTransfers (max_values: None, max_size: Some(32), added: 2507, mode: MaxEncodedLen)
 	/// Storage: TaskScheduler PendingTasks (r:0 w:1)
 	/// Proof: TaskScheduler PendingTasks (max_values: None, max_size: Some(1512), added: 3987, mode: MaxEncodedLen)
 	fn fail_transfer() -> Weight {
@@ -246,9 +325,10 @@ impl<T: frame_system::Config> crate::WeightInfo for WeightInfo<T> {
 		// Minimum execution time: 34_101_000 picoseconds.
 		Weight::from_parts(35_001_000, 0)
 			.saturating_add(Weight::from_parts(0, 7965))
-			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().reads(3))
 			.saturating_add(T::DbWeight::get().writes(1))
 	}
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
 	/// Storage: Creditcoin DealOrders (r:1 w:1)
 	/// Proof: Creditcoin DealOrders (max_values: None, max_size: Some(624), added: 3099, mode: MaxEncodedLen)
 	/// Storage: Creditcoin Addresses (r:1 w:0)
@@ -257,15 +337,21 @@ impl<T: frame_system::Config> crate::WeightInfo for WeightInfo<T> {
 	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
 	/// Storage: Creditcoin Transfers (r:1 w:1)
 	/// Proof: Creditcoin Transfers (max_values: None, max_size: Some(987), added: 3462, mode: MaxEncodedLen)
+	/// Storage: Creditcoin DealOrderShares (r:17 w:1)
+	/// Proof: Creditcoin DealOrderShares (max_values: None, max_size: Some(197), added: 2672, mode: MaxEncodedLen)
+	/// Storage: Creditcoin OriginationFeeParameters (r:1 w:0)
+	/// Proof: Creditcoin OriginationFeeParameters (max_values: Some(1), max_size: Some(66), added: 561, mode: MaxEncodedLen)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
 	fn fund_deal_order() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `1039`
-		//  Estimated: `14096`
-		// Minimum execution time: 65_402_000 picoseconds.
-		Weight::from_parts(66_802_000, 0)
-			.saturating_add(Weight::from_parts(0, 14096))
-			.saturating_add(T::DbWeight::get().reads(4))
-			.saturating_add(T::DbWeight::get().writes(2))
+		//  Measured:  `1245`
+		//  Estimated: `20455`
+		// Minimum execution time: 89_702_000 picoseconds.
+		Weight::from_parts(91_403_000, 0)
+			.saturating_add(Weight::from_parts(0, 20455))
+			.saturating_add(T::DbWeight::get().reads(23))
+			.saturating_add(T::DbWeight::get().writes(5))
 	}
 	/// Storage: Creditcoin DealOrders (r:1 w:1)
 	/// Proof: Creditcoin DealOrders (max_values: None, max_size: Some(624), added: 3099, mode: MaxEncodedLen)
@@ -279,14 +365,19 @@ impl<T: frame_system::Config> crate::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads(1))
 			.saturating_add(T::DbWeight::get().writes(1))
 	}
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
 	/// Storage: Creditcoin DealOrders (r:1 w:0)
 	/// Proof: Creditcoin DealOrders (max_values: None, max_size: Some(624), added: 3099, mode: MaxEncodedLen)
-	/// Storage: Creditcoin Addresses (r:2 w:0)
+	/// Storage: Creditcoin Addresses (r:18 w:0)
 	/// Proof: Creditcoin Addresses (max_values: None, max_size: Some(597), added: 3072, mode: MaxEncodedLen)
 	/// Storage: Creditcoin Transfers (r:1 w:0)
 	/// Proof: Creditcoin Transfers (max_values: None, max_size: Some(987), added: 3462, mode: MaxEncodedLen)
+	/// Storage: Creditcoin ArchivedTransfers (r:1 w:0)
+	/// Proof: Creditcoin ArchivedTransfers (max_values: None, max_size: Some(32), added: 2507, mode: MaxEncodedLen)
 	/// Storage: TaskScheduler PendingTasks (r:1 w:1)
 	/// Proof: TaskScheduler PendingTasks (max_values: None, max_size: Some(1512), added: 3987, mode: MaxEncodedLen)
+	/// Storage: Creditcoin DealOrderShares (r:17 w:0)
+	/// Proof: Creditcoin DealOrderShares (max_values: None, max_size: Some(197), added: 2672, mode: MaxEncodedLen)
 	fn register_funding_transfer() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `684`
@@ -294,15 +385,18 @@ impl<T: frame_system::Config> crate::WeightInfo for WeightInfo<T> {
 		// Minimum execution time: 64_902_000 picoseconds.
 		Weight::from_parts(66_002_000, 0)
 			.saturating_add(Weight::from_parts(0, 20652))
-			.saturating_add(T::DbWeight::get().reads(5))
+			.saturating_add(T::DbWeight::get().reads(39))
 			.saturating_add(T::DbWeight::get().writes(1))
 	}
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
 	/// Storage: Creditcoin DealOrders (r:1 w:0)
 	/// Proof: Creditcoin DealOrders (max_values: None, max_size: Some(624), added: 3099, mode: MaxEncodedLen)
 	/// Storage: Creditcoin Addresses (r:2 w:0)
 	/// Proof: Creditcoin Addresses (max_values: None, max_size: Some(597), added: 3072, mode: MaxEncodedLen)
 	/// Storage: Creditcoin Transfers (r:1 w:0)
 	/// Proof: Creditcoin Transfers (max_values: None, max_size: Some(987), added: 3462, mode: MaxEncodedLen)
+	/// Storage: Creditcoin ArchivedTransfers (r:1 w:0)
+	/// Proof: Creditcoin ArchivedTransfers (max_values: None, max_size: Some(32), added: 2507, mode: MaxEncodedLen)
 	/// Storage: TaskScheduler PendingTasks (r:1 w:1)
 	/// Proof: TaskScheduler PendingTasks (max_values: None, max_size: Some(1512), added: 3987, mode: MaxEncodedLen)
 	fn register_repayment_transfer() -> Weight {
@@ -312,9 +406,10 @@ impl<T: frame_system::Config> crate::WeightInfo for WeightInfo<T> {
 		// Minimum execution time: 65_402_000 picoseconds.
 		Weight::from_parts(66_002_000, 0)
 			.saturating_add(Weight::from_parts(0, 20652))
-			.saturating_add(T::DbWeight::get().reads(5))
+			.saturating_add(T::DbWeight::get().reads(6))
 			.saturating_add(T::DbWeight::get().writes(1))
 	}
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
 	/// Storage: Creditcoin DealOrders (r:1 w:1)
 	/// Proof: Creditcoin DealOrders (max_values: None, max_size: Some(624), added: 3099, mode: MaxEncodedLen)
 	/// Storage: Creditcoin Addresses (r:1 w:0)
@@ -323,6 +418,14 @@ impl<T: frame_system::Config> crate::WeightInfo for WeightInfo<T> {
 	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
 	/// Storage: Creditcoin Transfers (r:1 w:1)
 	/// Proof: Creditcoin Transfers (max_values: None, max_size: Some(987), added: 3462, mode: MaxEncodedLen)
+	/// Storage: Creditcoin CreditHistory (r:1 w:1)
+	/// Proof: Creditcoin CreditHistory (max_values: None, max_size: Some(370), added: 2845, mode: MaxEncodedLen)
+	/// Storage: Creditcoin DealOrderShares (r:17 w:1)
+	/// Proof: Creditcoin DealOrderShares (max_values: None, max_size: Some(197), added: 2672, mode: MaxEncodedLen)
+	/// Storage: Creditcoin ClosedDealOrders (r:0 w:1)
+	/// Proof: Creditcoin ClosedDealOrders (max_values: None, max_size: Some(64), added: 2539, mode: MaxEncodedLen)
+	/// Storage: Creditcoin ArchiveCursor (r:1 w:1)
+	/// Proof: Creditcoin ArchiveCursor (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
 	fn close_deal_order() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `1141`
@@ -330,15 +433,24 @@ impl<T: frame_system::Config> crate::WeightInfo for WeightInfo<T> {
 		// Minimum execution time: 67_502_000 picoseconds.
 		Weight::from_parts(68_302_000, 0)
 			.saturating_add(Weight::from_parts(0, 14096))
-			.saturating_add(T::DbWeight::get().reads(4))
-			.saturating_add(T::DbWeight::get().writes(2))
+			.saturating_add(T::DbWeight::get().reads(23))
+			.saturating_add(T::DbWeight::get().writes(6))
 	}
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
 	/// Storage: Creditcoin DealOrders (r:1 w:1)
 	/// Proof: Creditcoin DealOrders (max_values: None, max_size: Some(624), added: 3099, mode: MaxEncodedLen)
 	/// Storage: Creditcoin Addresses (r:1 w:0)
 	/// Proof: Creditcoin Addresses (max_values: None, max_size: Some(597), added: 3072, mode: MaxEncodedLen)
 	/// Storage: Timestamp Now (r:1 w:0)
 	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Creditcoin CreditHistory (r:1 w:1)
+	/// Proof: Creditcoin CreditHistory (max_values: None, max_size: Some(370), added: 2845, mode: MaxEncodedLen)
+	/// Storage: Creditcoin DealOrderShares (r:1 w:0)
+	/// Proof: Creditcoin DealOrderShares (max_values: None, max_size: Some(197), added: 2672, mode: MaxEncodedLen)
+	/// Storage: Creditcoin ClosedDealOrders (r:0 w:1)
+	/// Proof: Creditcoin ClosedDealOrders (max_values: None, max_size: Some(64), added: 2539, mode: MaxEncodedLen)
+	/// Storage: Creditcoin ArchiveCursor (r:1 w:1)
+	/// Proof: Creditcoin ArchiveCursor (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
 	fn exempt() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `699`
@@ -346,9 +458,10 @@ impl<T: frame_system::Config> crate::WeightInfo for WeightInfo<T> {
 		// Minimum execution time: 47_701_000 picoseconds.
 		Weight::from_parts(48_102_000, 0)
 			.saturating_add(Weight::from_parts(0, 9644))
-			.saturating_add(T::DbWeight::get().reads(3))
-			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(T::DbWeight::get().reads(6))
+			.saturating_add(T::DbWeight::get().writes(4))
 	}
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
 	/// Storage: Creditcoin Addresses (r:2 w:0)
 	/// Proof: Creditcoin Addresses (max_values: None, max_size: Some(597), added: 3072, mode: MaxEncodedLen)
 	/// Storage: Creditcoin AskOrders (r:1 w:1)
@@ -361,6 +474,14 @@ impl<T: frame_system::Config> crate::WeightInfo for WeightInfo<T> {
 	/// Proof: Creditcoin DealOrders (max_values: None, max_size: Some(624), added: 3099, mode: MaxEncodedLen)
 	/// Storage: Timestamp Now (r:1 w:0)
 	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Creditcoin AccountAskOrders (r:0 w:1)
+	/// Proof: Creditcoin AccountAskOrders (max_values: None, max_size: Some(116), added: 2591, mode: MaxEncodedLen)
+	/// Storage: Creditcoin AccountBidOrders (r:0 w:1)
+	/// Proof: Creditcoin AccountBidOrders (max_values: None, max_size: Some(116), added: 2591, mode: MaxEncodedLen)
+	/// Storage: Creditcoin AccountOffers (r:0 w:1)
+	/// Proof: Creditcoin AccountOffers (max_values: None, max_size: Some(116), added: 2591, mode: MaxEncodedLen)
+	/// Storage: Creditcoin AccountDealOrders (r:0 w:2)
+	/// Proof: Creditcoin AccountDealOrders (max_values: None, max_size: Some(116), added: 2591, mode: MaxEncodedLen)
 	fn register_deal_order() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `413`
@@ -369,7 +490,7 @@ impl<T: frame_system::Config> crate::WeightInfo for WeightInfo<T> {
 		Weight::from_parts(170_706_000, 0)
 			.saturating_add(Weight::from_parts(0, 24422))
 			.saturating_add(T::DbWeight::get().reads(7))
-			.saturating_add(T::DbWeight::get().writes(4))
+			.saturating_add(T::DbWeight::get().writes(9))
 	}
 	/// Storage: Creditcoin CollectCoinsContract (r:1 w:0)
 	/// Proof: Creditcoin CollectCoinsContract (max_values: Some(1), max_size: Some(279), added: 774, mode: MaxEncodedLen)
@@ -446,6 +567,518 @@ impl<T: frame_system::Config> crate::WeightInfo for WeightInfo<T> {
 			.saturating_add(Weight::from_parts(0, 0))
 			.saturating_add(T::DbWeight::get().writes(1))
 	}
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Creditcoin DealOrderDefaultCursor (r:1 w:1)
+	/// Proof: Creditcoin DealOrderDefaultCursor (max_values: Some(1), max_size: Some(36), added: 531, mode: MaxEncodedLen)
+	/// Storage: Creditcoin DealOrders (r:257 w:256)
+	/// Proof: Creditcoin DealOrders (max_values: None, max_size: Some(1634), added: 4109, mode: MaxEncodedLen)
+	/// Storage: Creditcoin CreditHistory (r:256 w:256)
+	/// Proof: Creditcoin CreditHistory (max_values: None, max_size: Some(370), added: 2845, mode: MaxEncodedLen)
+	/// The range of component `d` is `[0, 256]`.
+	fn mark_defaulted_deal_orders(d: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `174 + d * (293 ±0)`
+		//  Estimated: `4025 + d * (3099 ±0)`
+		// Minimum execution time: 11_400_000 picoseconds.
+		Weight::from_parts(12_105_000, 0)
+			.saturating_add(Weight::from_parts(0, 4025))
+			// Standard Error: 31_402
+			.saturating_add(Weight::from_parts(16_712_337, 0).saturating_mul(d.into()))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(d.into())))
+			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(d.into())))
+			.saturating_add(Weight::from_parts(0, 3099).saturating_mul(d.into()))
+	}
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
+	/// Storage: Creditcoin AskOrders (r:1 w:1)
+	/// Proof: Creditcoin AskOrders (max_values: None, max_size: Some(448), added: 2923, mode: MaxEncodedLen)
+	/// Storage: Creditcoin AccountAskOrders (r:0 w:1)
+	/// Proof: Creditcoin AccountAskOrders (max_values: None, max_size: Some(116), added: 2591, mode: MaxEncodedLen)
+	/// Storage: Creditcoin AskOrderBook (r:1 w:1)
+	/// Proof: Creditcoin AskOrderBook (max_values: None, max_size: Some(8873), added: 11348, mode: MaxEncodedLen)
+	fn cancel_ask_order() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `8654`
+		//  Estimated: `15261`
+		// Minimum execution time: 24_301_000 picoseconds.
+		Weight::from_parts(25_002_000, 0)
+			.saturating_add(Weight::from_parts(0, 15261))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
+	/// Storage: Creditcoin BidOrders (r:1 w:1)
+	/// Proof: Creditcoin BidOrders (max_values: None, max_size: Some(448), added: 2923, mode: MaxEncodedLen)
+	/// Storage: Creditcoin AccountBidOrders (r:0 w:1)
+	/// Proof: Creditcoin AccountBidOrders (max_values: None, max_size: Some(116), added: 2591, mode: MaxEncodedLen)
+	/// Storage: Creditcoin BidOrderBook (r:1 w:1)
+	/// Proof: Creditcoin BidOrderBook (max_values: None, max_size: Some(5161), added: 7636, mode: MaxEncodedLen)
+	fn cancel_bid_order() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `4940`
+		//  Estimated: `11549`
+		// Minimum execution time: 24_102_000 picoseconds.
+		Weight::from_parts(24_901_000, 0)
+			.saturating_add(Weight::from_parts(0, 11549))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
+	/// Storage: Creditcoin Offers (r:1 w:1)
+	/// Proof: Creditcoin Offers (max_values: None, max_size: Some(415), added: 2890, mode: MaxEncodedLen)
+	/// Storage: Creditcoin AccountOffers (r:0 w:1)
+	/// Proof: Creditcoin AccountOffers (max_values: None, max_size: Some(116), added: 2591, mode: MaxEncodedLen)
+	fn cancel_offer() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `286`
+		//  Estimated: `3880`
+		// Minimum execution time: 23_802_000 picoseconds.
+		Weight::from_parts(24_500_000, 0)
+			.saturating_add(Weight::from_parts(0, 3880))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
+	/// Storage: Creditcoin Addresses (r:2 w:0)
+	/// Proof: Creditcoin Addresses (max_values: None, max_size: Some(597), added: 3072, mode: MaxEncodedLen)
+	/// Storage: Creditcoin DealOrders (r:1 w:1)
+	/// Proof: Creditcoin DealOrders (max_values: None, max_size: Some(1634), added: 4109, mode: MaxEncodedLen)
+	/// Storage: Creditcoin AccountDealOrders (r:0 w:2)
+	/// Proof: Creditcoin AccountDealOrders (max_values: None, max_size: Some(116), added: 2591, mode: MaxEncodedLen)
+	/// Storage: Creditcoin DealOrderShares (r:1 w:0)
+	/// Proof: Creditcoin DealOrderShares (max_values: None, max_size: Some(197), added: 2672, mode: MaxEncodedLen)
+	/// Storage: Creditcoin DealOrderTransferNonces (r:1 w:1)
+	/// Proof: Creditcoin DealOrderTransferNonces (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	fn transfer_deal_order() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1042`
+		//  Estimated: `17771`
+		// Minimum execution time: 85_902_000 picoseconds.
+		Weight::from_parts(87_400_000, 0)
+			.saturating_add(Weight::from_parts(0, 17771))
+			.saturating_add(T::DbWeight::get().reads(5))
+			.saturating_add(T::DbWeight::get().writes(4))
+	}
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
+	/// Storage: Creditcoin DealOrders (r:1 w:1)
+	/// Proof: Creditcoin DealOrders (max_values: None, max_size: Some(1650), added: 4125, mode: MaxEncodedLen)
+	/// Storage: Creditcoin Addresses (r:1 w:0)
+	/// Proof: Creditcoin Addresses (max_values: None, max_size: Some(597), added: 3072, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Creditcoin DealOrderCollateral (r:1 w:1)
+	/// Proof: Creditcoin DealOrderCollateral (max_values: None, max_size: Some(68), added: 2543, mode: MaxEncodedLen)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Creditcoin CreditHistory (r:1 w:1)
+	/// Proof: Creditcoin CreditHistory (max_values: None, max_size: Some(370), added: 2845, mode: MaxEncodedLen)
+	fn claim_collateral() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1315`
+		//  Estimated: `19452`
+		// Minimum execution time: 96_302_000 picoseconds.
+		Weight::from_parts(98_003_000, 0)
+			.saturating_add(Weight::from_parts(0, 19452))
+			.saturating_add(T::DbWeight::get().reads(7))
+			.saturating_add(T::DbWeight::get().writes(5))
+	}
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
+	/// Storage: Creditcoin OrderMatchingEnabled (r:0 w:1)
+	/// Proof: Creditcoin OrderMatchingEnabled (max_values: Some(1), max_size: Some(1), added: 496, mode: MaxEncodedLen)
+	fn set_order_matching() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 8_900_000 picoseconds.
+		Weight::from_parts(9_300_000, 0)
+			.saturating_add(Weight::from_parts(0, 0))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
+	/// Storage: Creditcoin OrderMatchingEnabled (r:1 w:0)
+	/// Proof: Creditcoin OrderMatchingEnabled (max_values: Some(1), max_size: Some(1), added: 496, mode: MaxEncodedLen)
+	/// Storage: Creditcoin PendingMatches (r:1 w:1)
+	/// Proof: Creditcoin PendingMatches (max_values: Some(1), max_size: Some(41987), added: 42482, mode: MaxEncodedLen)
+	/// Storage: Creditcoin BidOrders (r:16 w:0)
+	/// Proof: Creditcoin BidOrders (max_values: None, max_size: Some(448), added: 2923, mode: MaxEncodedLen)
+	/// Storage: Creditcoin BidOrderBook (r:16 w:16)
+	/// Proof: Creditcoin BidOrderBook (max_values: None, max_size: Some(5161), added: 7636, mode: MaxEncodedLen)
+	/// Storage: Creditcoin AskOrderBook (r:16 w:16)
+	/// Proof: Creditcoin AskOrderBook (max_values: None, max_size: Some(8873), added: 11348, mode: MaxEncodedLen)
+	/// Storage: Creditcoin AskOrders (r:64 w:0)
+	/// Proof: Creditcoin AskOrders (max_values: None, max_size: Some(520), added: 2995, mode: MaxEncodedLen)
+	/// Storage: Creditcoin Offers (r:16 w:16)
+	/// Proof: Creditcoin Offers (max_values: None, max_size: Some(427), added: 2902, mode: MaxEncodedLen)
+	/// Storage: Creditcoin AccountOffers (r:0 w:16)
+	/// Proof: Creditcoin AccountOffers (max_values: None, max_size: Some(116), added: 2591, mode: MaxEncodedLen)
+	/// The range of component `m` is `[1, 16]`.
+	/// The range of component `c` is `[0, 64]`.
+	fn match_pending_orders(m: u32, c: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `244 + m * (9231 ±0) + c * (412 ±0)`
+		//  Estimated: `43472 + m * (24809 ±0) + c * (2995 ±0)`
+		// Minimum execution time: 7_600_000 picoseconds.
+		Weight::from_parts(8_112_000, 0)
+			.saturating_add(Weight::from_parts(0, 43472))
+			// Standard Error: 61_204
+			.saturating_add(Weight::from_parts(48_902_311, 0).saturating_mul(m.into()))
+			// Standard Error: 24_815
+			.saturating_add(Weight::from_parts(21_406_118, 0).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().reads((4_u64).saturating_mul(m.into())))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(c.into())))
+			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(T::DbWeight::get().writes((4_u64).saturating_mul(m.into())))
+			.saturating_add(Weight::from_parts(0, 24809).saturating_mul(m.into()))
+			.saturating_add(Weight::from_parts(0, 2995).saturating_mul(c.into()))
+	}
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
+	/// Storage: Creditcoin DealOrders (r:1 w:0)
+	/// Proof: Creditcoin DealOrders (max_values: None, max_size: Some(1650), added: 4125, mode: MaxEncodedLen)
+	/// Storage: Creditcoin Addresses (r:1 w:0)
+	/// Proof: Creditcoin Addresses (max_values: None, max_size: Some(597), added: 3072, mode: MaxEncodedLen)
+	/// Storage: Creditcoin TermsProposals (r:1 w:1)
+	/// Proof: Creditcoin TermsProposals (max_values: None, max_size: Some(229), added: 2704, mode: MaxEncodedLen)
+	/// Storage: Creditcoin DealOrderShares (r:1 w:0)
+	/// Proof: Creditcoin DealOrderShares (max_values: None, max_size: Some(197), added: 2672, mode: MaxEncodedLen)
+	fn propose_terms() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1012`
+		//  Estimated: `12891`
+		// Minimum execution time: 41_201_000 picoseconds.
+		Weight::from_parts(42_002_000, 0)
+			.saturating_add(Weight::from_parts(0, 12891))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
+	/// Storage: Creditcoin TermsProposals (r:1 w:1)
+	/// Proof: Creditcoin TermsProposals (max_values: None, max_size: Some(229), added: 2704, mode: MaxEncodedLen)
+	/// Storage: Creditcoin DealOrders (r:1 w:1)
+	/// Proof: Creditcoin DealOrders (max_values: None, max_size: Some(1650), added: 4125, mode: MaxEncodedLen)
+	/// Storage: Creditcoin Addresses (r:1 w:0)
+	/// Proof: Creditcoin Addresses (max_values: None, max_size: Some(597), added: 3072, mode: MaxEncodedLen)
+	fn accept_terms() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1241`
+		//  Estimated: `12891`
+		// Minimum execution time: 44_901_000 picoseconds.
+		Weight::from_parts(45_702_000, 0)
+			.saturating_add(Weight::from_parts(0, 12891))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
+	/// Storage: Creditcoin Addresses (r:18 w:0)
+	/// Proof: Creditcoin Addresses (max_values: None, max_size: Some(597), added: 3072, mode: MaxEncodedLen)
+	/// Storage: Creditcoin DealOrders (r:1 w:0)
+	/// Proof: Creditcoin DealOrders (max_values: None, max_size: Some(1650), added: 4125, mode: MaxEncodedLen)
+	/// Storage: Creditcoin DealOrderShares (r:17 w:2)
+	/// Proof: Creditcoin DealOrderShares (max_values: None, max_size: Some(197), added: 2672, mode: MaxEncodedLen)
+	/// Storage: Creditcoin AccountDealOrders (r:0 w:1)
+	/// Proof: Creditcoin AccountDealOrders (max_values: None, max_size: Some(116), added: 2591, mode: MaxEncodedLen)
+	fn add_deal_order_share() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `2874`
+		//  Estimated: `104634`
+		// Minimum execution time: 151_203_000 picoseconds.
+		Weight::from_parts(153_402_000, 0)
+			.saturating_add(Weight::from_parts(0, 104634))
+			.saturating_add(T::DbWeight::get().reads(36))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
+	/// Storage: Creditcoin DealOrders (r:1 w:0)
+	/// Proof: Creditcoin DealOrders (max_values: None, max_size: Some(1650), added: 4125, mode: MaxEncodedLen)
+	/// Storage: Creditcoin DealOrderShares (r:1 w:0)
+	/// Proof: Creditcoin DealOrderShares (max_values: None, max_size: Some(197), added: 2672, mode: MaxEncodedLen)
+	/// Storage: Creditcoin Addresses (r:2 w:0)
+	/// Proof: Creditcoin Addresses (max_values: None, max_size: Some(597), added: 3072, mode: MaxEncodedLen)
+	/// Storage: Creditcoin Transfers (r:1 w:0)
+	/// Proof: Creditcoin Transfers (max_values: None, max_size: Some(987), added: 3462, mode: MaxEncodedLen)
+	/// Storage: Creditcoin ArchivedTransfers (r:1 w:0)
+	/// Proof: Creditcoin ArchivedTransfers (max_values: None, max_size: Some(32), added: 2507, mode: MaxEncodedLen)
+	/// Storage: TaskScheduler PendingTasks (r:1 w:1)
+	/// Proof: TaskScheduler PendingTasks (max_values: None, max_size: Some(1512), added: 3987, mode: MaxEncodedLen)
+	fn register_share_repayment_transfer() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `812`
+		//  Estimated: `23324`
+		// Minimum execution time: 68_302_000 picoseconds.
+		Weight::from_parts(69_401_000, 0)
+			.saturating_add(Weight::from_parts(0, 23324))
+			.saturating_add(T::DbWeight::get().reads(7))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
+	/// Storage: Creditcoin DealOrderRetention (r:0 w:1)
+	/// Proof: Creditcoin DealOrderRetention (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	fn set_deal_order_retention() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 8_800_000 picoseconds.
+		Weight::from_parts(9_200_000, 0)
+			.saturating_add(Weight::from_parts(0, 0))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
+	/// Storage: Creditcoin DealOrderRetention (r:1 w:0)
+	/// Proof: Creditcoin DealOrderRetention (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: Creditcoin ArchiveCursor (r:1 w:1)
+	/// Proof: Creditcoin ArchiveCursor (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: Creditcoin ClosedDealOrders (r:96 w:32)
+	/// Proof: Creditcoin ClosedDealOrders (max_values: None, max_size: Some(64), added: 2539, mode: MaxEncodedLen)
+	/// Storage: Creditcoin DealOrders (r:32 w:32)
+	/// Proof: Creditcoin DealOrders (max_values: None, max_size: Some(624), added: 3099, mode: MaxEncodedLen)
+	/// Storage: Creditcoin DealOrderTransfers (r:64 w:32)
+	/// Proof: Creditcoin DealOrderTransfers (max_values: None, max_size: Some(104), added: 2579, mode: MaxEncodedLen)
+	/// Storage: Creditcoin Transfers (r:32 w:32)
+	/// Proof: Creditcoin Transfers (max_values: None, max_size: Some(987), added: 3462, mode: MaxEncodedLen)
+	/// Storage: Creditcoin Addresses (r:32 w:0)
+	/// Proof: Creditcoin Addresses (max_values: None, max_size: Some(597), added: 3072, mode: MaxEncodedLen)
+	/// Storage: Creditcoin DealOrderShares (r:32 w:0)
+	/// Proof: Creditcoin DealOrderShares (max_values: None, max_size: Some(197), added: 2672, mode: MaxEncodedLen)
+	/// Storage: Creditcoin ArchivedTransfers (r:0 w:32)
+	/// Proof: Creditcoin ArchivedTransfers (max_values: None, max_size: Some(32), added: 2507, mode: MaxEncodedLen)
+	/// Storage: Creditcoin AccountDealOrders (r:0 w:64)
+	/// Proof: Creditcoin AccountDealOrders (max_values: None, max_size: Some(116), added: 2591, mode: MaxEncodedLen)
+	/// Storage: Creditcoin ArchivedDealOrders (r:0 w:32)
+	/// Proof: Creditcoin ArchivedDealOrders (max_values: None, max_size: Some(125), added: 2600, mode: MaxEncodedLen)
+	/// Storage: Creditcoin DealOrderTransferNonces (r:0 w:32)
+	/// Proof: Creditcoin DealOrderTransferNonces (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// The range of component `s` is `[1, 64]`.
+	/// The range of component `d` is `[0, 32]`.
+	/// The range of component `t` is `[0, 32]`.
+	fn archive_deal_orders(s: u32, d: u32, t: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `138 + s * (12 ±0) + d * (1218 ±0) + t * (339 ±0)`
+		//  Estimated: `1489 + s * (2539 ±0) + d * (14388 ±0) + t * (3462 ±0)`
+		// Minimum execution time: 6_300_000 picoseconds.
+		Weight::from_parts(6_702_000, 0)
+			.saturating_add(Weight::from_parts(0, 1489))
+			// Standard Error: 21_532
+			.saturating_add(Weight::from_parts(3_127_405, 0).saturating_mul(s.into()))
+			// Standard Error: 43_871
+			.saturating_add(Weight::from_parts(52_604_117, 0).saturating_mul(d.into()))
+			// Standard Error: 43_871
+			.saturating_add(Weight::from_parts(18_402_530, 0).saturating_mul(t.into()))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(s.into())))
+			.saturating_add(T::DbWeight::get().reads((6_u64).saturating_mul(d.into())))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(t.into())))
+			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(T::DbWeight::get().writes((6_u64).saturating_mul(d.into())))
+			.saturating_add(T::DbWeight::get().writes((3_u64).saturating_mul(t.into())))
+			.saturating_add(Weight::from_parts(0, 2539).saturating_mul(s.into()))
+			.saturating_add(Weight::from_parts(0, 14388).saturating_mul(d.into()))
+			.saturating_add(Weight::from_parts(0, 3462).saturating_mul(t.into()))
+	}
+
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
+	/// Storage: Creditcoin RepaymentOrders (r:1 w:1)
+	/// Proof: Creditcoin RepaymentOrders (max_values: None, max_size: Some(200), added: 2675, mode: MaxEncodedLen)
+	/// Storage: Creditcoin DealOrders (r:1 w:0)
+	/// Proof: Creditcoin DealOrders (max_values: None, max_size: Some(624), added: 3099, mode: MaxEncodedLen)
+	/// Storage: Creditcoin DealOrderShares (r:1 w:0)
+	/// Proof: Creditcoin DealOrderShares (max_values: None, max_size: Some(197), added: 2672, mode: MaxEncodedLen)
+	/// Storage: Creditcoin Addresses (r:1 w:0)
+	/// Proof: Creditcoin Addresses (max_values: None, max_size: Some(597), added: 3072, mode: MaxEncodedLen)
+	/// Storage: Creditcoin UsedGuids (r:1 w:1)
+	/// Proof: Creditcoin UsedGuids (max_values: None, max_size: Some(274), added: 2749, mode: MaxEncodedLen)
+	fn add_repayment_order() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `912`
+		//  Estimated: `19207`
+		// Minimum execution time: 52_402_000 picoseconds.
+		Weight::from_parts(53_801_000, 0)
+			.saturating_add(Weight::from_parts(0, 19207))
+			.saturating_add(T::DbWeight::get().reads(5))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
+	/// Storage: Creditcoin RepaymentOrders (r:1 w:1)
+	/// Proof: Creditcoin RepaymentOrders (max_values: None, max_size: Some(200), added: 2675, mode: MaxEncodedLen)
+	fn cancel_repayment_order() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `270`
+		//  Estimated: `3665`
+		// Minimum execution time: 21_601_000 picoseconds.
+		Weight::from_parts(22_302_000, 0)
+			.saturating_add(Weight::from_parts(0, 3665))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
+	/// Storage: Creditcoin RepaymentOrders (r:1 w:0)
+	/// Proof: Creditcoin RepaymentOrders (max_values: None, max_size: Some(200), added: 2675, mode: MaxEncodedLen)
+	/// Storage: Creditcoin DealOrders (r:1 w:0)
+	/// Proof: Creditcoin DealOrders (max_values: None, max_size: Some(624), added: 3099, mode: MaxEncodedLen)
+	/// Storage: Creditcoin Addresses (r:2 w:0)
+	/// Proof: Creditcoin Addresses (max_values: None, max_size: Some(597), added: 3072, mode: MaxEncodedLen)
+	/// Storage: Creditcoin Transfers (r:1 w:0)
+	/// Proof: Creditcoin Transfers (max_values: None, max_size: Some(987), added: 3462, mode: MaxEncodedLen)
+	/// Storage: Creditcoin ArchivedTransfers (r:1 w:0)
+	/// Proof: Creditcoin ArchivedTransfers (max_values: None, max_size: Some(32), added: 2507, mode: MaxEncodedLen)
+	/// Storage: TaskScheduler PendingTasks (r:1 w:1)
+	/// Proof: TaskScheduler PendingTasks (max_values: None, max_size: Some(1512), added: 3987, mode: MaxEncodedLen)
+	/// Storage: Creditcoin RepaymentOrderTransfers (r:0 w:1)
+	/// Proof: Creditcoin RepaymentOrderTransfers (max_values: None, max_size: Some(232), added: 2707, mode: MaxEncodedLen)
+	fn register_repayment_order_transfer() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `954`
+		//  Estimated: `23327`
+		// Minimum execution time: 68_902_000 picoseconds.
+		Weight::from_parts(70_101_000, 0)
+			.saturating_add(Weight::from_parts(0, 23327))
+			.saturating_add(T::DbWeight::get().reads(7))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
+	/// Storage: Creditcoin OriginationFeeParameters (r:0 w:1)
+	/// Proof: Creditcoin OriginationFeeParameters (max_values: Some(1), max_size: Some(66), added: 561, mode: MaxEncodedLen)
+	fn set_origination_fee() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 9_501_000 picoseconds.
+		Weight::from_parts(9_801_000, 0)
+			.saturating_add(Weight::from_parts(0, 0))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
+	/// Storage: Creditcoin Addresses (r:1 w:1)
+	/// Proof: Creditcoin Addresses (max_values: None, max_size: Some(597), added: 3072, mode: MaxEncodedLen)
+	/// Storage: System Number (r:1 w:0)
+	/// Proof: System Number (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: Creditcoin AccountAskOrders (r:1 w:0)
+	/// Proof: Creditcoin AccountAskOrders (max_values: None, max_size: Some(116), added: 2591, mode: MaxEncodedLen)
+	/// Storage: Creditcoin AccountBidOrders (r:1 w:0)
+	/// Proof: Creditcoin AccountBidOrders (max_values: None, max_size: Some(116), added: 2591, mode: MaxEncodedLen)
+	/// Storage: Creditcoin AccountDealOrders (r:257 w:1)
+	/// Proof: Creditcoin AccountDealOrders (max_values: None, max_size: Some(116), added: 2591, mode: MaxEncodedLen)
+	/// Storage: Creditcoin DealOrders (r:256 w:0)
+	/// Proof: Creditcoin DealOrders (max_values: None, max_size: Some(1650), added: 4125, mode: MaxEncodedLen)
+	/// Storage: Creditcoin DealOrderShares (r:256 w:0)
+	/// Proof: Creditcoin DealOrderShares (max_values: None, max_size: Some(197), added: 2672, mode: MaxEncodedLen)
+	/// Storage: Creditcoin AccountAddresses (r:0 w:1)
+	/// Proof: Creditcoin AccountAddresses (max_values: None, max_size: Some(116), added: 2591, mode: MaxEncodedLen)
+	/// The range of component `o` is `[0, 256]`.
+	fn unregister_address(o: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `245 + o * (362 ±0)`
+		//  Estimated: `10552 + o * (9388 ±0)`
+		// Minimum execution time: 32_501_000 picoseconds.
+		Weight::from_parts(33_704_000, 0)
+			.saturating_add(Weight::from_parts(0, 10552))
+			// Standard Error: 24_611
+			.saturating_add(Weight::from_parts(15_940_118, 0).saturating_mul(o.into()))
+			.saturating_add(T::DbWeight::get().reads(5))
+			.saturating_add(T::DbWeight::get().reads((3_u64).saturating_mul(o.into())))
+			.saturating_add(T::DbWeight::get().writes(3))
+			.saturating_add(Weight::from_parts(0, 9388).saturating_mul(o.into()))
+	}
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
+	/// Storage: Creditcoin Addresses (r:1 w:1)
+	/// Proof: Creditcoin Addresses (max_values: None, max_size: Some(597), added: 3072, mode: MaxEncodedLen)
+	/// Storage: System Number (r:1 w:0)
+	/// Proof: System Number (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: Creditcoin AccountAskOrders (r:1 w:0)
+	/// Proof: Creditcoin AccountAskOrders (max_values: None, max_size: Some(116), added: 2591, mode: MaxEncodedLen)
+	/// Storage: Creditcoin AccountBidOrders (r:1 w:0)
+	/// Proof: Creditcoin AccountBidOrders (max_values: None, max_size: Some(116), added: 2591, mode: MaxEncodedLen)
+	/// Storage: Creditcoin AccountDealOrders (r:257 w:2)
+	/// Proof: Creditcoin AccountDealOrders (max_values: None, max_size: Some(116), added: 2591, mode: MaxEncodedLen)
+	/// Storage: Creditcoin DealOrders (r:256 w:0)
+	/// Proof: Creditcoin DealOrders (max_values: None, max_size: Some(1650), added: 4125, mode: MaxEncodedLen)
+	/// Storage: Creditcoin DealOrderShares (r:256 w:0)
+	/// Proof: Creditcoin DealOrderShares (max_values: None, max_size: Some(197), added: 2672, mode: MaxEncodedLen)
+	/// Storage: Creditcoin AddressTransferNonces (r:1 w:1)
+	/// Proof: Creditcoin AddressTransferNonces (max_values: None, max_size: Some(40), added: 2515, mode: MaxEncodedLen)
+	/// Storage: Creditcoin AccountAddresses (r:0 w:2)
+	/// Proof: Creditcoin AccountAddresses (max_values: None, max_size: Some(116), added: 2591, mode: MaxEncodedLen)
+	/// The range of component `o` is `[0, 256]`.
+	fn transfer_address(o: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `245 + o * (362 ±0)`
+		//  Estimated: `13067 + o * (9388 ±0)`
+		// Minimum execution time: 89_004_000 picoseconds.
+		Weight::from_parts(90_911_000, 0)
+			.saturating_add(Weight::from_parts(0, 13067))
+			// Standard Error: 25_309
+			.saturating_add(Weight::from_parts(15_981_402, 0).saturating_mul(o.into()))
+			.saturating_add(T::DbWeight::get().reads(6))
+			.saturating_add(T::DbWeight::get().reads((3_u64).saturating_mul(o.into())))
+			.saturating_add(T::DbWeight::get().writes(6))
+			.saturating_add(Weight::from_parts(0, 9388).saturating_mul(o.into()))
+	}
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
+	/// Storage: Creditcoin Addresses (r:1 w:0)
+	/// Proof: Creditcoin Addresses (max_values: None, max_size: Some(597), added: 3072, mode: MaxEncodedLen)
+	/// Storage: System Number (r:1 w:0)
+	/// Proof: System Number (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: TaskScheduler PendingTasks (r:1 w:1)
+	/// Proof: TaskScheduler PendingTasks (max_values: None, max_size: Some(1620), added: 4095, mode: MaxEncodedLen)
+	fn register_contract_wallet() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `113`
+		//  Estimated: `10657`
+		// Minimum execution time: 38_601_000 picoseconds.
+		Weight::from_parts(39_302_000, 0)
+			.saturating_add(Weight::from_parts(0, 10657))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
+	/// Storage: TaskScheduler Authorities (r:1 w:0)
+	/// Proof: TaskScheduler Authorities (max_values: None, max_size: Some(48), added: 2523, mode: MaxEncodedLen)
+	/// Storage: Creditcoin Addresses (r:1 w:1)
+	/// Proof: Creditcoin Addresses (max_values: None, max_size: Some(597), added: 3072, mode: MaxEncodedLen)
+	/// Storage: Creditcoin AccountAddresses (r:0 w:1)
+	/// Proof: Creditcoin AccountAddresses (max_values: None, max_size: Some(116), added: 2591, mode: MaxEncodedLen)
+	/// Storage: TaskScheduler PendingTasks (r:0 w:1)
+	/// Proof: TaskScheduler PendingTasks (max_values: None, max_size: Some(1620), added: 4095, mode: MaxEncodedLen)
+	fn persist_contract_wallet() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `153`
+		//  Estimated: `7575`
+		// Minimum execution time: 36_902_000 picoseconds.
+		Weight::from_parts(37_601_000, 0)
+			.saturating_add(Weight::from_parts(0, 7575))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
+	/// Storage: TaskScheduler Authorities (r:1 w:0)
+	/// Proof: TaskScheduler Authorities (max_values: None, max_size: Some(48), added: 2523, mode: MaxEncodedLen)
+	/// Storage: TaskScheduler PendingTasks (r:0 w:1)
+	/// Proof: TaskScheduler PendingTasks (max_values: None, max_size: Some(1620), added: 4095, mode: MaxEncodedLen)
+	fn fail_contract_wallet() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `153`
+		//  Estimated: `3513`
+		// Minimum execution time: 24_301_000 picoseconds.
+		Weight::from_parts(24_902_000, 0)
+			.saturating_add(Weight::from_parts(0, 3513))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// PROVISIONAL: estimated by hand, not produced by the benchmark CLI.
+	/// Storage: Creditcoin ExternalChains (r:1 w:1)
+	/// Proof: Creditcoin ExternalChains (max_values: None, max_size: Some(289), added: 2764, mode: MaxEncodedLen)
+	fn set_external_chain() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `2764`
+		// Minimum execution time: 11_700_000 picoseconds.
+		Weight::from_parts(14_301_000, 0)
+			.saturating_add(Weight::from_parts(0, 2764))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
 
 	fn register_address_v2() -> Weight {
 		Weight::from_parts(0,0)
@@ -8,4 +8,12 @@ sp_api::decl_runtime_apis! {
 	pub trait TaskApi<AccountId: Codec> {
 		fn offchain_nonce_key(acc: &AccountId) -> Vec<u8>;
 	}
+
+	pub trait CreditcoinApi<DealOrderId: Codec, LoanBalance: Codec> {
+		fn loan_balance(deal_order_id: DealOrderId) -> Option<LoanBalance>;
+	}
+
+	pub trait CreditHistoryApi<AccountId: Codec, Blockchain: Codec, CreditRecord: Codec> {
+		fn credit_history(account_id: AccountId) -> Vec<(Blockchain, CreditRecord)>;
+	}
 }
@@ -48,7 +48,7 @@ impl<Runtime: Config, Nonce: Encode> TaskV2<Runtime> for MockTask<Nonce> {
 		Runtime::Hashing::hash(&self.encode())
 	}
 	//A MockTask is never written into storage. Check [frame_system::pallet::Call::remark]
-	fn is_persisted(_id: &Runtime::Hash) -> bool {
+	fn is_persisted(&self, _id: &Runtime::Hash) -> bool {
 		PERSISTED.with(|cell| cell.get())
 	}
 	fn persistence_call(
@@ -54,7 +54,7 @@ pub trait TaskV2<Runtime: SystemConfig> {
 	/// A task generates its own id. This Id is used as a task id in the scheduler and also to check onchain storage persistence.
 	fn to_id(&self) -> Runtime::Hash;
 	//A task will know how to check onchain storage persistence.
-	fn is_persisted(id: &Runtime::Hash) -> bool;
+	fn is_persisted(&self, id: &Runtime::Hash) -> bool;
 	/// A call to persist state is expected after successfully processing a task.
 	/// This does not mean that the task result was successful. A succesful task's result may be a failure that needs state persistance.
 	fn persistence_call(
@@ -68,7 +68,7 @@ pub trait TaskV2<Runtime: SystemConfig> {
 		deadline: Runtime::BlockNumber,
 	) -> Result<Self::Call, TaskError<Self::EvaluationError, Self::SchedulerError>> {
 		let id = self.to_id();
-		if Self::is_persisted(&id) {
+		if self.is_persisted(&id) {
 			return Err(TaskError::FinishedTask);
 		}
 		self.persistence_call(deadline, &id)
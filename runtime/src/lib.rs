@@ -103,6 +103,14 @@ pub type Index = u32;
 /// A hash of some data used by the chain.
 pub type Hash = sp_core::H256;
 
+/// Identifies a deal order in the creditcoin pallet.
+pub type DealOrderId = pallet_creditcoin::DealOrderId<BlockNumber, Hash>;
+
+/// What a borrower owes on a deal order.
+pub type LoanBalance = pallet_creditcoin::LoanBalance<Moment>;
+
+pub use pallet_creditcoin::{Blockchain, CreditRecord};
+
 pub use opaque::SessionKeys;
 
 macro_rules! prod_or_fast {
@@ -1054,6 +1062,18 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl task_scheduler_runtime_api::CreditcoinApi<Block, DealOrderId, LoanBalance> for Runtime {
+		fn loan_balance(deal_order_id: DealOrderId) -> Option<LoanBalance> {
+			Creditcoin::loan_balance(&deal_order_id)
+		}
+	}
+
+	impl task_scheduler_runtime_api::CreditHistoryApi<Block, AccountId, Blockchain, CreditRecord> for Runtime {
+		fn credit_history(account_id: AccountId) -> Vec<(Blockchain, CreditRecord)> {
+			Creditcoin::credit_history(&account_id)
+		}
+	}
+
 	#[cfg(feature = "try-runtime")]
 	impl frame_try_runtime::TryRuntime<Block> for Runtime {
 		fn on_runtime_upgrade(checks: UpgradeCheckSelect) -> (Weight, Weight) {